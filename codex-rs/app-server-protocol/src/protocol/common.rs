@@ -109,6 +109,10 @@ client_request_definitions! {
         params: v2::ThreadResumeParams,
         response: v2::ThreadResumeResponse,
     },
+    ThreadFork => "thread/fork" {
+        params: v2::ThreadForkParams,
+        response: v2::ThreadForkResponse,
+    },
     ThreadArchive => "thread/archive" {
         params: v2::ThreadArchiveParams,
         response: v2::ThreadArchiveResponse,
@@ -117,10 +121,30 @@ client_request_definitions! {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
     },
+    ThreadSearch => "thread/search" {
+        params: v2::ThreadSearchParams,
+        response: v2::ThreadSearchResponse,
+    },
     ThreadCompact => "thread/compact" {
         params: v2::ThreadCompactParams,
         response: v2::ThreadCompactResponse,
     },
+    ThreadUpdate => "thread/update" {
+        params: v2::ThreadUpdateParams,
+        response: v2::ThreadUpdateResponse,
+    },
+    ThreadPin => "thread/pin" {
+        params: v2::ThreadPinParams,
+        response: v2::ThreadPinResponse,
+    },
+    ThreadUnpin => "thread/unpin" {
+        params: v2::ThreadUnpinParams,
+        response: v2::ThreadUnpinResponse,
+    },
+    ThreadContextRead => "thread/context/read" {
+        params: v2::ThreadContextReadParams,
+        response: v2::ThreadContextReadResponse,
+    },
     TurnStart => "turn/start" {
         params: v2::TurnStartParams,
         response: v2::TurnStartResponse,
@@ -469,6 +493,14 @@ server_request_definitions! {
         response: v2::FileChangeRequestApprovalResponse,
     },
 
+    /// Sent when a sandboxed command tries to reach a host outside the
+    /// network proxy's allowlist. The connection waits for the answer.
+    /// This request is used for Turns started via turn/start.
+    NetworkAccessRequestApproval => "turn/networkAccess/requestApproval" {
+        params: v2::NetworkAccessRequestApprovalParams,
+        response: v2::NetworkAccessRequestApprovalResponse,
+    },
+
     /// DEPRECATED APIs below
     /// Request to approve a patch.
     /// This request is used for Turns started via the legacy APIs (i.e. SendUserTurn, SendUserMessage).
@@ -530,6 +562,7 @@ server_notification_definitions! {
     ReasoningSummaryPartAdded => "item/reasoning/summaryPartAdded" (v2::ReasoningSummaryPartAddedNotification),
     ReasoningTextDelta => "item/reasoning/textDelta" (v2::ReasoningTextDeltaNotification),
     ContextCompacted => "thread/compacted" (v2::ContextCompactedNotification),
+    ThreadPinnedContextUpdated => "thread/pinnedContext/updated" (v2::ThreadPinnedContextUpdatedNotification),
 
     /// Notifies the user of world-writable directories on Windows, which cannot be protected by the sandbox.
     WindowsWorldWritableWarning => "windows/worldWritableWarning" (v2::WindowsWorldWritableWarningNotification),
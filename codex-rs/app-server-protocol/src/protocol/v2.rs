@@ -13,7 +13,10 @@ use codex_protocol::parse_command::ParsedCommand as CoreParsedCommand;
 use codex_protocol::plan_tool::PlanItemArg as CorePlanItemArg;
 use codex_protocol::plan_tool::StepStatus as CorePlanStepStatus;
 use codex_protocol::protocol::CodexErrorInfo as CoreCodexErrorInfo;
+use codex_protocol::protocol::ContextBreakdownEvent as CoreContextBreakdownEvent;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
+use codex_protocol::protocol::McpServerContextUsage as CoreMcpServerContextUsage;
+use codex_protocol::protocol::PinnedNote as CorePinnedNote;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
 use codex_protocol::protocol::SessionSource as CoreSessionSource;
@@ -297,7 +300,14 @@ pub enum ApprovalDecision {
 #[ts(export_to = "v2/")]
 pub enum SandboxPolicy {
     DangerFullAccess,
-    ReadOnly,
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    ReadOnly {
+        #[serde(default)]
+        readable_roots: Vec<PathBuf>,
+        #[serde(default)]
+        deny_read: Vec<PathBuf>,
+    },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
     WorkspaceWrite {
@@ -309,6 +319,10 @@ pub enum SandboxPolicy {
         exclude_tmpdir_env_var: bool,
         #[serde(default)]
         exclude_slash_tmp: bool,
+        #[serde(default)]
+        readable_roots: Vec<PathBuf>,
+        #[serde(default)]
+        deny_read: Vec<PathBuf>,
     },
 }
 
@@ -318,17 +332,27 @@ impl SandboxPolicy {
             SandboxPolicy::DangerFullAccess => {
                 codex_protocol::protocol::SandboxPolicy::DangerFullAccess
             }
-            SandboxPolicy::ReadOnly => codex_protocol::protocol::SandboxPolicy::ReadOnly,
+            SandboxPolicy::ReadOnly {
+                readable_roots,
+                deny_read,
+            } => codex_protocol::protocol::SandboxPolicy::ReadOnly {
+                readable_roots: readable_roots.clone(),
+                deny_read: deny_read.clone(),
+            },
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                readable_roots,
+                deny_read,
             } => codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots: writable_roots.clone(),
                 network_access: *network_access,
                 exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                 exclude_slash_tmp: *exclude_slash_tmp,
+                readable_roots: readable_roots.clone(),
+                deny_read: deny_read.clone(),
             },
         }
     }
@@ -340,17 +364,27 @@ impl From<codex_protocol::protocol::SandboxPolicy> for SandboxPolicy {
             codex_protocol::protocol::SandboxPolicy::DangerFullAccess => {
                 SandboxPolicy::DangerFullAccess
             }
-            codex_protocol::protocol::SandboxPolicy::ReadOnly => SandboxPolicy::ReadOnly,
+            codex_protocol::protocol::SandboxPolicy::ReadOnly {
+                readable_roots,
+                deny_read,
+            } => SandboxPolicy::ReadOnly {
+                readable_roots,
+                deny_read,
+            },
             codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                readable_roots,
+                deny_read,
             } => SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                readable_roots,
+                deny_read,
             },
         }
     }
@@ -775,6 +809,48 @@ pub struct ThreadResumeResponse {
     pub reasoning_effort: Option<ReasoningEffort>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadForkParams {
+    pub thread_id: String,
+
+    /// Number of leading turns the new thread keeps. When neither this nor
+    /// `afterItemId` is set, every turn is kept.
+    pub keep_turns: Option<u32>,
+    /// Keep every turn up to and including the one containing this item. Takes
+    /// an item id from the `thread/resume` history or a tool call id.
+    pub after_item_id: Option<String>,
+    /// Restore the working tree the thread ran in (its recorded `cwd`) to the
+    /// ghost snapshot taken when the first dropped turn started.
+    #[serde(default)]
+    pub restore_snapshot: bool,
+
+    /// Configuration overrides for the forked thread, if any.
+    pub model: Option<String>,
+    pub model_provider: Option<String>,
+    pub cwd: Option<String>,
+    pub approval_policy: Option<AskForApproval>,
+    pub sandbox: Option<SandboxMode>,
+    pub config: Option<HashMap<String, serde_json::Value>>,
+    pub base_instructions: Option<String>,
+    pub developer_instructions: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadForkResponse {
+    /// The new thread, including the turns it kept.
+    pub thread: Thread,
+    pub model: String,
+    pub model_provider: String,
+    pub cwd: PathBuf,
+    pub approval_policy: AskForApproval,
+    pub sandbox: SandboxPolicy,
+    pub reasoning_effort: Option<ReasoningEffort>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -810,6 +886,52 @@ pub struct ThreadListResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchParams {
+    /// Words to search for. A thread matches when every word occurs in it;
+    /// double quotes group words into a phrase.
+    pub query: String,
+    /// Optional maximum number of results; defaults to a reasonable server-side value.
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResponse {
+    /// Matching threads, best match first.
+    pub data: Vec<ThreadSearchResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResult {
+    pub thread: Thread,
+    /// Excerpts of the thread that matched the query.
+    pub matches: Vec<ThreadSearchMatch>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchMatch {
+    pub field: ThreadSearchField,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ThreadSearchField {
+    UserMessage,
+    AgentMessage,
+    Command,
+    File,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -822,6 +944,156 @@ pub struct ThreadCompactParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadCompactResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUpdateParams {
+    pub thread_id: String,
+    /// New title for the thread; an empty string clears it.
+    pub title: Option<String>,
+    /// Replaces all tags of the thread.
+    pub tags: Option<Vec<String>>,
+    /// Tags to add. Unlike `tags`, this keeps tags added concurrently by
+    /// other clients.
+    pub add_tags: Option<Vec<String>>,
+    /// Tags to remove, ignoring case.
+    pub remove_tags: Option<Vec<String>>,
+    pub starred: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUpdateResponse {
+    pub thread: Thread,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinParams {
+    pub thread_id: String,
+    /// Note to keep verbatim across compaction.
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUnpinParams {
+    pub thread_id: String,
+    /// Id of the note, as reported by `thread/pinnedContext/updated`.
+    pub id: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUnpinResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextReadParams {
+    pub thread_id: String,
+}
+
+/// Estimated size of the thread's next prompt, by category. Counts are
+/// byte-based estimates, not tokenizer counts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextReadResponse {
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+    /// Sum of every category below.
+    #[ts(type = "number")]
+    pub total_tokens: i64,
+    /// Base and developer instructions.
+    #[ts(type = "number")]
+    pub base_instructions: i64,
+    /// AGENTS.md and other project docs.
+    #[ts(type = "number")]
+    pub project_docs: i64,
+    #[ts(type = "number")]
+    pub skills: i64,
+    #[ts(type = "number")]
+    pub builtin_tools: i64,
+    /// Specs of MCP tools, per server.
+    pub mcp_servers: Vec<McpServerContextUsage>,
+    #[ts(type = "number")]
+    pub messages: i64,
+    #[ts(type = "number")]
+    pub reasoning: i64,
+    #[ts(type = "number")]
+    pub tool_calls: i64,
+    #[ts(type = "number")]
+    pub tool_outputs: i64,
+    #[ts(type = "number")]
+    pub images: i64,
+}
+
+impl From<CoreContextBreakdownEvent> for ThreadContextReadResponse {
+    fn from(value: CoreContextBreakdownEvent) -> Self {
+        Self {
+            model_context_window: value.model_context_window,
+            total_tokens: value.total_tokens,
+            base_instructions: value.base_instructions,
+            project_docs: value.project_docs,
+            skills: value.skills,
+            builtin_tools: value.builtin_tools,
+            mcp_servers: value.mcp_servers.into_iter().map(Into::into).collect(),
+            messages: value.messages,
+            reasoning: value.reasoning,
+            tool_calls: value.tool_calls,
+            tool_outputs: value.tool_outputs,
+            images: value.images,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerContextUsage {
+    pub server: String,
+    pub tool_count: usize,
+    #[ts(type = "number")]
+    pub tokens: i64,
+}
+
+impl From<CoreMcpServerContextUsage> for McpServerContextUsage {
+    fn from(value: CoreMcpServerContextUsage) -> Self {
+        Self {
+            server: value.server,
+            tool_count: value.tool_count,
+            tokens: value.tokens,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PinnedNote {
+    pub id: u32,
+    pub text: String,
+}
+
+impl From<CorePinnedNote> for PinnedNote {
+    fn from(value: CorePinnedNote) -> Self {
+        Self {
+            id: value.id,
+            text: value.text,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -829,6 +1101,12 @@ pub struct Thread {
     pub id: String,
     /// Usually the first user message in the thread, if available.
     pub preview: String,
+    /// Title set by the user, if any.
+    pub title: Option<String>,
+    /// Tags set by the user.
+    pub tags: Vec<String>,
+    /// Whether the user starred the thread.
+    pub starred: bool,
     /// Model provider used for this thread (for example, 'openai').
     pub model_provider: String,
     /// Unix timestamp (in seconds) when the thread was created.
@@ -1156,12 +1434,42 @@ pub enum ThreadItem {
     ImageView { id: String, path: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
+    SubAgent {
+        id: String,
+        /// The task delegated to the sub-agent.
+        task: String,
+        /// The model used by the sub-agent.
+        model: String,
+        status: SubAgentStatus,
+        /// The sub-agent's final message, once it has finished.
+        result: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
     EnteredReviewMode { id: String, review: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
     ExitedReviewMode { id: String, review: String },
 }
 
+impl ThreadItem {
+    pub fn id(&self) -> &str {
+        match self {
+            ThreadItem::UserMessage { id, .. }
+            | ThreadItem::AgentMessage { id, .. }
+            | ThreadItem::Reasoning { id, .. }
+            | ThreadItem::CommandExecution { id, .. }
+            | ThreadItem::FileChange { id, .. }
+            | ThreadItem::McpToolCall { id, .. }
+            | ThreadItem::WebSearch { id, .. }
+            | ThreadItem::ImageView { id, .. }
+            | ThreadItem::SubAgent { id, .. }
+            | ThreadItem::EnteredReviewMode { id, .. }
+            | ThreadItem::ExitedReviewMode { id, .. } => id,
+        }
+    }
+}
+
 impl From<CoreTurnItem> for ThreadItem {
     fn from(value: CoreTurnItem) -> Self {
         match value {
@@ -1240,6 +1548,15 @@ pub enum McpToolCallStatus {
     Failed,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum SubAgentStatus {
+    InProgress,
+    Completed,
+    Failed,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1457,6 +1774,15 @@ pub struct ContextCompactedNotification {
     pub turn_id: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinnedContextUpdatedNotification {
+    pub thread_id: String,
+    /// All notes currently pinned to the thread, oldest first.
+    pub notes: Vec<PinnedNote>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1510,6 +1836,42 @@ pub struct FileChangeRequestApprovalResponse {
     pub decision: ApprovalDecision,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NetworkAccessRequestApprovalParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Identifies the blocked connection; several can be pending at once.
+    pub call_id: String,
+    /// Host outside `network_proxy.allowed_domains` a sandboxed command tried to reach.
+    pub host: String,
+    pub port: u16,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NetworkAccessRequestAcceptSettings {
+    /// If true, allow the host for the rest of the session.
+    #[serde(default)]
+    pub for_session: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NetworkAccessRequestApprovalResponse {
+    /// `decline` blocks the host for the rest of the session; `cancel` only
+    /// refuses this connection.
+    pub decision: ApprovalDecision,
+    /// Optional approval settings for when the decision is `accept`.
+    /// Ignored if the decision is `decline` or `cancel`.
+    #[serde(default)]
+    pub accept_settings: Option<NetworkAccessRequestAcceptSettings>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -172,7 +172,10 @@ fn trigger_cmd_approval(codex_bin: String, user_message: Option<String>) -> Resu
         codex_bin,
         message,
         Some(AskForApproval::OnRequest),
-        Some(SandboxPolicy::ReadOnly),
+        Some(SandboxPolicy::ReadOnly {
+            readable_roots: Vec::new(),
+            deny_read: Vec::new(),
+        }),
     )
 }
 
@@ -184,7 +187,10 @@ fn trigger_patch_approval(codex_bin: String, user_message: Option<String>) -> Re
         codex_bin,
         message,
         Some(AskForApproval::OnRequest),
-        Some(SandboxPolicy::ReadOnly),
+        Some(SandboxPolicy::ReadOnly {
+            readable_roots: Vec::new(),
+            deny_read: Vec::new(),
+        }),
     )
 }
 
@@ -1,4 +1,5 @@
 use crate::codex_message_processor::ApiVersion;
+use crate::codex_message_processor::PendingContextReads;
 use crate::codex_message_processor::PendingInterrupts;
 use crate::codex_message_processor::TurnSummary;
 use crate::codex_message_processor::TurnSummaryStore;
@@ -28,6 +29,8 @@ use codex_app_server_protocol::ItemStartedNotification;
 use codex_app_server_protocol::McpToolCallError;
 use codex_app_server_protocol::McpToolCallResult;
 use codex_app_server_protocol::McpToolCallStatus;
+use codex_app_server_protocol::NetworkAccessRequestApprovalParams;
+use codex_app_server_protocol::NetworkAccessRequestApprovalResponse;
 use codex_app_server_protocol::PatchApplyStatus;
 use codex_app_server_protocol::PatchChangeKind as V2PatchChangeKind;
 use codex_app_server_protocol::ReasoningSummaryPartAddedNotification;
@@ -36,7 +39,10 @@ use codex_app_server_protocol::ReasoningTextDeltaNotification;
 use codex_app_server_protocol::SandboxCommandAssessment as V2SandboxCommandAssessment;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
+use codex_app_server_protocol::SubAgentStatus;
+use codex_app_server_protocol::ThreadContextReadResponse;
 use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadPinnedContextUpdatedNotification;
 use codex_app_server_protocol::ThreadTokenUsage;
 use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
 use codex_app_server_protocol::Turn;
@@ -57,6 +63,7 @@ use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange as CoreFileChange;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::NetworkApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::TokenCountEvent;
@@ -75,12 +82,14 @@ use tracing::error;
 
 type JsonValue = serde_json::Value;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn apply_bespoke_event_handling(
     event: Event,
     conversation_id: ConversationId,
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
+    pending_context_reads: PendingContextReads,
     turn_summary_store: TurnSummaryStore,
     api_version: ApiVersion,
 ) {
@@ -281,6 +290,17 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ContextCompacted(notification))
                 .await;
         }
+        EventMsg::PinnedContextUpdated(event) => {
+            let notification = ThreadPinnedContextUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                notes: event.notes.into_iter().map(Into::into).collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadPinnedContextUpdated(
+                    notification,
+                ))
+                .await;
+        }
         EventMsg::ReasoningContentDelta(event) => {
             let notification = ReasoningSummaryTextDeltaNotification {
                 thread_id: conversation_id.to_string(),
@@ -377,6 +397,45 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ItemCompleted(completed))
                 .await;
         }
+        EventMsg::SubAgentBegin(begin_event) => {
+            let item = ThreadItem::SubAgent {
+                id: begin_event.call_id,
+                task: begin_event.task,
+                model: begin_event.model,
+                status: SubAgentStatus::InProgress,
+                result: None,
+            };
+            let notification = ItemStartedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                item,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemStarted(notification))
+                .await;
+        }
+        EventMsg::SubAgentEnd(end_event) => {
+            let status = if end_event.last_agent_message.is_some() {
+                SubAgentStatus::Completed
+            } else {
+                SubAgentStatus::Failed
+            };
+            let item = ThreadItem::SubAgent {
+                id: end_event.call_id,
+                task: end_event.task,
+                model: end_event.model,
+                status,
+                result: end_event.last_agent_message,
+            };
+            let notification = ItemCompletedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id.clone(),
+                item,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemCompleted(notification))
+                .await;
+        }
         EventMsg::EnteredReviewMode(review_request) => {
             let review = review_request
                 .user_facing_hint
@@ -621,6 +680,16 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ItemCompleted(notification))
                 .await;
         }
+        EventMsg::ContextBreakdown(breakdown) => {
+            let pending = {
+                let mut map = pending_context_reads.lock().await;
+                map.remove(&conversation_id).unwrap_or_default()
+            };
+            for request_id in pending {
+                let response = ThreadContextReadResponse::from(breakdown.clone());
+                outgoing.send_response(request_id, response).await;
+            }
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -672,6 +741,43 @@ pub(crate) async fn apply_bespoke_event_handling(
             )
             .await;
         }
+        EventMsg::NetworkApprovalRequest(NetworkApprovalRequestEvent {
+            call_id,
+            turn_id,
+            host,
+            port,
+            reason,
+        }) => match api_version {
+            ApiVersion::V1 => {
+                // The legacy APIs have no request for this, so hosts outside
+                // the allowlist stay blocked.
+                if let Err(err) = conversation
+                    .submit(Op::NetworkApproval {
+                        id: call_id,
+                        decision: ReviewDecision::Denied,
+                    })
+                    .await
+                {
+                    error!("failed to submit denied NetworkApproval: {err}");
+                }
+            }
+            ApiVersion::V2 => {
+                let params = NetworkAccessRequestApprovalParams {
+                    thread_id: conversation_id.to_string(),
+                    turn_id,
+                    call_id: call_id.clone(),
+                    host,
+                    port,
+                    reason,
+                };
+                let rx = outgoing
+                    .send_request(ServerRequestPayload::NetworkAccessRequestApproval(params))
+                    .await;
+                tokio::spawn(async move {
+                    on_network_access_request_approval_response(call_id, rx, conversation).await;
+                });
+            }
+        },
 
         _ => {}
     }
@@ -1166,6 +1272,47 @@ async fn on_command_execution_request_approval_response(
     }
 }
 
+async fn on_network_access_request_approval_response(
+    call_id: String,
+    receiver: oneshot::Receiver<JsonValue>,
+    conversation: Arc<CodexConversation>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => {
+            let response = serde_json::from_value::<NetworkAccessRequestApprovalResponse>(value)
+                .unwrap_or_else(|err| {
+                    error!("failed to deserialize NetworkAccessRequestApprovalResponse: {err}");
+                    NetworkAccessRequestApprovalResponse {
+                        decision: ApprovalDecision::Cancel,
+                        accept_settings: None,
+                    }
+                });
+            match (response.decision, response.accept_settings) {
+                (ApprovalDecision::Accept, Some(settings)) if settings.for_session => {
+                    ReviewDecision::ApprovedForSession
+                }
+                (ApprovalDecision::Accept, _) => ReviewDecision::Approved,
+                (ApprovalDecision::Decline, _) => ReviewDecision::Denied,
+                (ApprovalDecision::Cancel, _) => ReviewDecision::Abort,
+            }
+        }
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Abort
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::NetworkApproval {
+            id: call_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit NetworkApproval: {err}");
+    }
+}
+
 /// similar to handle_mcp_tool_call_begin in exec
 async fn construct_mcp_tool_call_notification(
     begin_event: McpToolCallBeginEvent,
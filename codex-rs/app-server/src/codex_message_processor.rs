@@ -80,14 +80,28 @@ use codex_app_server_protocol::SetDefaultModelResponse;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadContextReadParams;
+use codex_app_server_protocol::ThreadForkParams;
+use codex_app_server_protocol::ThreadForkResponse;
 use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
+use codex_app_server_protocol::ThreadPinParams;
+use codex_app_server_protocol::ThreadPinResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
+use codex_app_server_protocol::ThreadSearchField;
+use codex_app_server_protocol::ThreadSearchMatch;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use codex_app_server_protocol::ThreadSearchResult;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
+use codex_app_server_protocol::ThreadUnpinParams;
+use codex_app_server_protocol::ThreadUnpinResponse;
+use codex_app_server_protocol::ThreadUpdateParams;
+use codex_app_server_protocol::ThreadUpdateResponse;
 use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptParams;
@@ -104,6 +118,7 @@ use codex_core::AuthManager;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::Cursor as RolloutCursor;
+use codex_core::ForkPoint;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::InitialHistory;
 use codex_core::NewConversation;
@@ -117,11 +132,15 @@ use codex_core::config::ConfigToml;
 use codex_core::config::edit::ConfigEditsBuilder;
 use codex_core::config_loader::load_config_as_toml;
 use codex_core::default_client::get_codex_user_agent;
+use codex_core::error::CodexErr;
 use codex_core::exec::ExecParams;
 use codex_core::exec_env::create_env;
 use codex_core::features::Feature;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::git_info::git_diff_to_remote;
+use codex_core::labels::SessionLabels;
+use codex_core::labels::append_session_labels;
+use codex_core::labels::read_session_labels;
 use codex_core::mcp::collect_mcp_snapshot;
 use codex_core::mcp::group_tools_by_server;
 use codex_core::parse_cursor;
@@ -132,6 +151,8 @@ use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget as CoreReviewTarget;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::read_head_for_summary;
+use codex_core::search::SearchField;
+use codex_core::search::SessionSearcher;
 use codex_feedback::CodexFeedback;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
@@ -144,6 +165,7 @@ use codex_protocol::protocol::GitInfo as CoreGitInfo;
 use codex_protocol::protocol::McpAuthStatus as CoreMcpAuthStatus;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionLabelsItem;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_protocol::user_input::UserInput as CoreInputItem;
@@ -168,6 +190,7 @@ use uuid::Uuid;
 
 type PendingInterruptQueue = Vec<(RequestId, ApiVersion)>;
 pub(crate) type PendingInterrupts = Arc<Mutex<HashMap<ConversationId, PendingInterruptQueue>>>;
+pub(crate) type PendingContextReads = Arc<Mutex<HashMap<ConversationId, Vec<RequestId>>>>;
 
 /// Per-conversation accumulation of the latest states e.g. error message while a turn runs.
 #[derive(Default, Clone)]
@@ -202,8 +225,13 @@ pub(crate) struct CodexMessageProcessor {
     active_login: Arc<Mutex<Option<ActiveLogin>>>,
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: PendingInterrupts,
+    // Queue of pending `thread/context/read` requests per conversation. We reply when
+    // ContextBreakdown arrives.
+    pending_context_reads: PendingContextReads,
     turn_summary_store: TurnSummaryStore,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    // Shared by all `thread/search` requests so the index is loaded once.
+    session_searcher: Arc<Mutex<SessionSearcher>>,
     feedback: CodexFeedback,
 }
 
@@ -246,6 +274,8 @@ impl CodexMessageProcessor {
         config: Arc<Config>,
         feedback: CodexFeedback,
     ) -> Self {
+        let session_searcher =
+            Arc::new(Mutex::new(SessionSearcher::new(config.codex_home.clone())));
         Self {
             auth_manager,
             conversation_manager,
@@ -255,8 +285,10 @@ impl CodexMessageProcessor {
             conversation_listeners: HashMap::new(),
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
+            pending_context_reads: Arc::new(Mutex::new(HashMap::new())),
             turn_summary_store: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
+            session_searcher,
             feedback,
         }
     }
@@ -332,12 +364,18 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadResume { request_id, params } => {
                 self.thread_resume(request_id, params).await;
             }
+            ClientRequest::ThreadFork { request_id, params } => {
+                self.thread_fork(request_id, params).await;
+            }
             ClientRequest::ThreadArchive { request_id, params } => {
                 self.thread_archive(request_id, params).await;
             }
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
+            ClientRequest::ThreadSearch { request_id, params } => {
+                self.thread_search(request_id, params).await;
+            }
             ClientRequest::ThreadCompact {
                 request_id,
                 params: _,
@@ -345,6 +383,18 @@ impl CodexMessageProcessor {
                 self.send_unimplemented_error(request_id, "thread/compact")
                     .await;
             }
+            ClientRequest::ThreadUpdate { request_id, params } => {
+                self.thread_update(request_id, params).await;
+            }
+            ClientRequest::ThreadPin { request_id, params } => {
+                self.thread_pin(request_id, params).await;
+            }
+            ClientRequest::ThreadUnpin { request_id, params } => {
+                self.thread_unpin(request_id, params).await;
+            }
+            ClientRequest::ThreadContextRead { request_id, params } => {
+                self.thread_context_read(request_id, params).await;
+            }
             ClientRequest::TurnStart { request_id, params } => {
                 self.turn_start(request_id, params).await;
             }
@@ -1335,7 +1385,7 @@ impl CodexMessageProcessor {
                 )
                 .await
                 {
-                    Ok(summary) => summary_to_thread(summary),
+                    Ok(summary) => summary_to_thread(summary, SessionLabels::default()),
                     Err(err) => {
                         self.send_internal_error(
                             request_id,
@@ -1498,12 +1548,177 @@ impl CodexMessageProcessor {
             }
         };
 
-        let data = summaries.into_iter().map(summary_to_thread).collect();
+        let data = summaries
+            .into_iter()
+            .map(|(summary, labels)| summary_to_thread(summary, labels))
+            .collect();
 
         let response = ThreadListResponse { data, next_cursor };
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn thread_search(&self, request_id: RequestId, params: ThreadSearchParams) {
+        let ThreadSearchParams { query, limit } = params;
+        let limit = limit.unwrap_or(25).max(1) as usize;
+
+        let hits = match self
+            .session_searcher
+            .lock()
+            .await
+            .search(&query, limit)
+            .await
+        {
+            Ok(hits) => hits,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to search threads: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let fallback_provider = self.config.model_provider_id.as_str();
+        let mut data = Vec::with_capacity(hits.len());
+        for hit in hits {
+            // The rollout may have been archived or removed since it was indexed.
+            let Ok(summary) = read_summary_from_rollout(&hit.path, fallback_provider).await else {
+                continue;
+            };
+            let matches = hit
+                .matches
+                .into_iter()
+                .map(|m| ThreadSearchMatch {
+                    field: match m.field {
+                        SearchField::UserMessage => ThreadSearchField::UserMessage,
+                        SearchField::AgentMessage => ThreadSearchField::AgentMessage,
+                        SearchField::Command => ThreadSearchField::Command,
+                        SearchField::File => ThreadSearchField::File,
+                    },
+                    snippet: m.snippet,
+                })
+                .collect();
+            data.push(ThreadSearchResult {
+                thread: summary_to_thread(summary, hit.labels),
+                matches,
+            });
+        }
+
+        let response = ThreadSearchResponse { data };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_update(&self, request_id: RequestId, params: ThreadUpdateParams) {
+        let ThreadUpdateParams {
+            thread_id,
+            title,
+            tags,
+            add_tags,
+            remove_tags,
+            starred,
+        } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+        let edit = SessionLabelsItem {
+            title,
+            tags,
+            add_tags,
+            remove_tags,
+            starred,
+        };
+
+        let updated = match self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        {
+            // A loaded thread records the edit through its own rollout recorder.
+            Ok(conversation) => {
+                let rollout_path = conversation.rollout_path();
+                match read_session_labels(&rollout_path).await {
+                    Ok(mut labels) => {
+                        labels.apply(&edit);
+                        conversation
+                            .submit(Op::UpdateSessionLabels { labels: edit })
+                            .await
+                            .map(|_| (rollout_path, labels))
+                            .map_err(|err| err.to_string())
+                    }
+                    Err(err) => Err(err.to_string()),
+                }
+            }
+            Err(_) => {
+                let rollout_path = match find_conversation_path_by_id_str(
+                    &self.config.codex_home,
+                    &conversation_id.to_string(),
+                )
+                .await
+                {
+                    Ok(Some(path)) => path,
+                    Ok(None) => {
+                        self.send_invalid_request_error(
+                            request_id,
+                            format!("no rollout found for conversation id {conversation_id}"),
+                        )
+                        .await;
+                        return;
+                    }
+                    Err(err) => {
+                        self.send_invalid_request_error(
+                            request_id,
+                            format!("failed to locate conversation id {conversation_id}: {err}"),
+                        )
+                        .await;
+                        return;
+                    }
+                };
+                append_session_labels(&rollout_path, edit)
+                    .await
+                    .map(|labels| (rollout_path, labels))
+                    .map_err(|err| err.to_string())
+            }
+        };
+        let (rollout_path, labels) = match updated {
+            Ok(updated) => updated,
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to update thread {conversation_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        let fallback_provider = self.config.model_provider_id.as_str();
+        match read_summary_from_rollout(&rollout_path, fallback_provider).await {
+            Ok(summary) => {
+                let response = ThreadUpdateResponse {
+                    thread: summary_to_thread(summary, labels),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to load rollout `{}` for conversation {conversation_id}: {err}",
+                        rollout_path.display()
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+
     async fn thread_resume(&mut self, request_id: RequestId, params: ThreadResumeParams) {
         let ThreadResumeParams {
             thread_id,
@@ -1669,7 +1884,12 @@ impl CodexMessageProcessor {
                 )
                 .await
                 {
-                    Ok(summary) => summary_to_thread(summary),
+                    Ok(summary) => {
+                        let labels = read_session_labels(rollout_path.as_path())
+                            .await
+                            .unwrap_or_default();
+                        summary_to_thread(summary, labels)
+                    }
                     Err(err) => {
                         self.send_internal_error(
                             request_id,
@@ -1709,6 +1929,173 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn thread_fork(&mut self, request_id: RequestId, params: ThreadForkParams) {
+        let ThreadForkParams {
+            thread_id,
+            keep_turns,
+            after_item_id,
+            restore_snapshot,
+            model,
+            model_provider,
+            cwd,
+            approval_policy,
+            sandbox,
+            config: cli_overrides,
+            base_instructions,
+            developer_instructions,
+        } = params;
+
+        if keep_turns.is_some() && after_item_id.is_some() {
+            self.send_invalid_request_error(
+                request_id,
+                "keepTurns and afterItemId are mutually exclusive".to_string(),
+            )
+            .await;
+            return;
+        }
+
+        let overrides = self.build_thread_config_overrides(
+            model,
+            model_provider,
+            cwd,
+            approval_policy,
+            sandbox,
+            base_instructions,
+            developer_instructions,
+        );
+        let config = match derive_config_from_params(overrides, cli_overrides).await {
+            Ok(config) => config,
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("error deriving config: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        let path = match find_conversation_path_by_id_str(&self.config.codex_home, &thread_id).await
+        {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("no rollout found for thread id {thread_id}"),
+                )
+                .await;
+                return;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate thread id {thread_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        let fork_point = match (keep_turns, after_item_id) {
+            (Some(turns), _) => ForkPoint::AfterTurns(turns as usize),
+            (None, Some(item_id)) => {
+                // Ids from the `thread/resume` history are assigned when the
+                // history is rebuilt, so translate them to a turn count here.
+                let events = match RolloutRecorder::get_rollout_history(&path).await {
+                    Ok(history) => history.get_event_msgs().unwrap_or_default(),
+                    Err(err) => {
+                        self.send_invalid_request_error(
+                            request_id,
+                            format!("failed to load rollout `{}`: {err}", path.display()),
+                        )
+                        .await;
+                        return;
+                    }
+                };
+                let turns = build_turns_from_event_msgs(&events);
+                match turns_through_item(&turns, &item_id) {
+                    Some(kept) => ForkPoint::AfterTurns(kept),
+                    None => ForkPoint::AfterItem(item_id),
+                }
+            }
+            (None, None) => ForkPoint::Latest,
+        };
+
+        let fallback_model_provider = config.model_provider_id.clone();
+        let NewConversation {
+            conversation_id,
+            session_configured,
+            ..
+        } = match self
+            .conversation_manager
+            .fork_conversation_at(&fork_point, config, path, restore_snapshot)
+            .await
+        {
+            Ok(new_conversation) => new_conversation,
+            Err(CodexErr::InvalidRequest(message)) => {
+                self.send_invalid_request_error(request_id, message).await;
+                return;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("error forking thread: {err}"))
+                    .await;
+                return;
+            }
+        };
+
+        if let Err(err) = self
+            .attach_conversation_listener(conversation_id, false, ApiVersion::V2)
+            .await
+        {
+            tracing::warn!(
+                "failed to attach listener for conversation {}: {}",
+                conversation_id,
+                err.message
+            );
+        }
+
+        let SessionConfiguredEvent {
+            rollout_path,
+            initial_messages,
+            ..
+        } = &session_configured;
+        let mut thread =
+            match read_summary_from_rollout(rollout_path.as_path(), &fallback_model_provider).await
+            {
+                Ok(summary) => summary_to_thread(summary, SessionLabels::default()),
+                Err(err) => {
+                    self.send_internal_error(
+                        request_id,
+                        format!(
+                            "failed to load rollout `{}` for conversation {conversation_id}: {err}",
+                            rollout_path.display()
+                        ),
+                    )
+                    .await;
+                    return;
+                }
+            };
+        thread.turns = initial_messages
+            .as_deref()
+            .map_or_else(Vec::new, build_turns_from_event_msgs);
+
+        let response = ThreadForkResponse {
+            thread: thread.clone(),
+            model: session_configured.model,
+            model_provider: session_configured.model_provider_id,
+            cwd: session_configured.cwd,
+            approval_policy: session_configured.approval_policy.into(),
+            sandbox: session_configured.sandbox_policy.into(),
+            reasoning_effort: session_configured.reasoning_effort,
+        };
+        self.outgoing.send_response(request_id, response).await;
+
+        let notif = ThreadStartedNotification { thread };
+        self.outgoing
+            .send_server_notification(ServerNotification::ThreadStarted(notif))
+            .await;
+    }
+
     async fn get_conversation_summary(
         &self,
         request_id: RequestId,
@@ -1784,6 +2171,7 @@ impl CodexMessageProcessor {
             .await
         {
             Ok((items, next_cursor)) => {
+                let items = items.into_iter().map(|(summary, _)| summary).collect();
                 let response = ListConversationsResponse { items, next_cursor };
                 self.outgoing.send_response(request_id, response).await;
             }
@@ -1798,7 +2186,8 @@ impl CodexMessageProcessor {
         page_size: usize,
         cursor: Option<String>,
         model_providers: Option<Vec<String>>,
-    ) -> Result<(Vec<ConversationSummary>, Option<String>), JSONRPCErrorError> {
+    ) -> Result<(Vec<(ConversationSummary, SessionLabels)>, Option<String>), JSONRPCErrorError>
+    {
         let cursor_obj: Option<RolloutCursor> = cursor.as_ref().and_then(|s| parse_cursor(s));
         let cursor_ref = cursor_obj.as_ref();
 
@@ -1848,6 +2237,7 @@ impl CodexMessageProcessor {
                     session_meta_line.git.as_ref(),
                     fallback_provider.as_str(),
                 )
+                .map(|summary| (summary, it.labels))
             })
             .collect::<Vec<_>>();
 
@@ -2266,8 +2656,10 @@ impl CodexMessageProcessor {
             });
         };
 
-        // Verify file name matches conversation id.
+        // Verify file name matches conversation id. Closed rollouts may be
+        // compressed.
         let required_suffix = format!("{conversation_id}.jsonl");
+        let compressed_suffix = format!("{required_suffix}.zst");
         let Some(file_name) = canonical_rollout_path.file_name().map(OsStr::to_owned) else {
             return Err(JSONRPCErrorError {
                 code: INVALID_REQUEST_ERROR_CODE,
@@ -2278,9 +2670,9 @@ impl CodexMessageProcessor {
                 data: None,
             });
         };
-        if !file_name
-            .to_string_lossy()
-            .ends_with(required_suffix.as_str())
+        let file_name_str = file_name.to_string_lossy();
+        if !file_name_str.ends_with(required_suffix.as_str())
+            && !file_name_str.ends_with(compressed_suffix.as_str())
         {
             return Err(JSONRPCErrorError {
                 code: INVALID_REQUEST_ERROR_CODE,
@@ -2701,7 +3093,7 @@ impl CodexMessageProcessor {
         let fallback_provider = self.config.model_provider_id.as_str();
         match read_summary_from_rollout(rollout_path.as_path(), fallback_provider).await {
             Ok(summary) => {
-                let thread = summary_to_thread(summary);
+                let thread = summary_to_thread(summary, SessionLabels::default());
                 let notif = ThreadStartedNotification { thread };
                 self.outgoing
                     .send_server_notification(ServerNotification::ThreadStarted(notif))
@@ -2812,6 +3204,96 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
+    async fn thread_pin(&mut self, request_id: RequestId, params: ThreadPinParams) {
+        let ThreadPinParams { thread_id, text } = params;
+        if text.trim().is_empty() {
+            self.send_invalid_request_error(request_id, "text must not be empty".to_string())
+                .await;
+            return;
+        }
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // The updated notes are announced via `thread/pinnedContext/updated`.
+        match conversation.submit(Op::PinContext { text }).await {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, ThreadPinResponse {})
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to pin note: {err}"))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_unpin(&mut self, request_id: RequestId, params: ThreadUnpinParams) {
+        let ThreadUnpinParams { thread_id, id } = params;
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match conversation.submit(Op::UnpinContext { id }).await {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, ThreadUnpinResponse {})
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to unpin note: {err}"))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_context_read(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadContextReadParams,
+    ) {
+        let ThreadContextReadParams { thread_id } = params;
+
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
+
+        // Record the pending read so we can reply when ContextBreakdown arrives.
+        {
+            let mut map = self.pending_context_reads.lock().await;
+            map.entry(conversation_id)
+                .or_default()
+                .push(request_id.clone());
+        }
+
+        if let Err(err) = conversation.submit(Op::GetContextBreakdown).await {
+            {
+                let mut map = self.pending_context_reads.lock().await;
+                if let Some(pending) = map.get_mut(&conversation_id) {
+                    pending.retain(|pending_id| pending_id != &request_id);
+                }
+            }
+            self.send_internal_error(request_id, format!("failed to read context: {err}"))
+                .await;
+        }
+    }
+
     async fn add_conversation_listener(
         &mut self,
         request_id: RequestId,
@@ -2887,6 +3369,7 @@ impl CodexMessageProcessor {
 
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
+        let pending_context_reads = self.pending_context_reads.clone();
         let turn_summary_store = self.turn_summary_store.clone();
         let api_version_for_task = api_version;
         tokio::spawn(async move {
@@ -2944,6 +3427,7 @@ impl CodexMessageProcessor {
                             conversation.clone(),
                             outgoing_for_task.clone(),
                             pending_interrupts.clone(),
+                            pending_context_reads.clone(),
                             turn_summary_store.clone(),
                             api_version_for_task,
                         )
@@ -3239,7 +3723,20 @@ fn parse_datetime(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
     })
 }
 
-fn summary_to_thread(summary: ConversationSummary) -> Thread {
+/// Number of user turns up to and including the turn containing `item_id`.
+fn turns_through_item(turns: &[Turn], item_id: &str) -> Option<usize> {
+    let idx = turns
+        .iter()
+        .position(|turn| turn.items.iter().any(|item| item.id() == item_id))?;
+    Some(
+        turns[..=idx]
+            .iter()
+            .filter(|turn| matches!(turn.items.first(), Some(ThreadItem::UserMessage { .. })))
+            .count(),
+    )
+}
+
+fn summary_to_thread(summary: ConversationSummary, labels: SessionLabels) -> Thread {
     let ConversationSummary {
         conversation_id,
         path,
@@ -3262,6 +3759,9 @@ fn summary_to_thread(summary: ConversationSummary) -> Thread {
     Thread {
         id: conversation_id.to_string(),
         preview,
+        title: labels.title,
+        tags: labels.tags,
+        starred: labels.starred,
         model_provider,
         created_at: created_at.map(|dt| dt.timestamp()).unwrap_or(0),
         path,
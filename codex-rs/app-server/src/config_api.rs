@@ -214,6 +214,7 @@ impl ConfigApi {
             base,
             managed_config,
             managed_preferences,
+            ..
         } = load_config_layers_with_overrides(&self.codex_home, self.loader_overrides.clone())
             .await?;
 
@@ -751,6 +752,7 @@ mod tests {
             vec![],
             LoaderOverrides {
                 managed_config_path: Some(managed_path),
+                project_cwd: None,
                 #[cfg(target_os = "macos")]
                 managed_preferences_base64: None,
             },
@@ -799,6 +801,7 @@ mod tests {
             vec![],
             LoaderOverrides {
                 managed_config_path: Some(managed_path),
+                project_cwd: None,
                 #[cfg(target_os = "macos")]
                 managed_preferences_base64: None,
             },
@@ -900,6 +903,7 @@ mod tests {
             vec![],
             LoaderOverrides {
                 managed_config_path: Some(managed_path),
+                project_cwd: None,
                 #[cfg(target_os = "macos")]
                 managed_preferences_base64: None,
             },
@@ -949,6 +953,7 @@ mod tests {
             cli_overrides,
             LoaderOverrides {
                 managed_config_path: Some(managed_path),
+                project_cwd: None,
                 #[cfg(target_os = "macos")]
                 managed_preferences_base64: None,
             },
@@ -985,6 +990,7 @@ mod tests {
             vec![],
             LoaderOverrides {
                 managed_config_path: Some(managed_path),
+                project_cwd: None,
                 #[cfg(target_os = "macos")]
                 managed_preferences_base64: None,
             },
@@ -4,8 +4,13 @@ mod model_list;
 mod rate_limits;
 mod review;
 mod thread_archive;
+mod thread_context;
+mod thread_fork;
 mod thread_list;
+mod thread_pin;
 mod thread_resume;
+mod thread_search;
 mod thread_start;
+mod thread_update;
 mod turn_interrupt;
 mod turn_start;
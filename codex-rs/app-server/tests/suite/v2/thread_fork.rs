@@ -0,0 +1,183 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::create_mock_chat_completions_server;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadForkParams;
+use codex_app_server_protocol::ThreadForkResponse;
+use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::UserInput;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_fork_keeps_leading_turns() -> Result<()> {
+    let server = create_mock_chat_completions_server(vec![]).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let thread_id = create_two_turn_rollout(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let fork_id = mcp
+        .send_thread_fork_request(ThreadForkParams {
+            thread_id: thread_id.clone(),
+            keep_turns: Some(1),
+            ..Default::default()
+        })
+        .await?;
+    let fork_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(fork_id)),
+    )
+    .await??;
+    let ThreadForkResponse { thread, .. } = to_response::<ThreadForkResponse>(fork_resp)?;
+
+    assert_ne!(thread.id, thread_id);
+    assert_eq!(user_messages(&thread.turns), vec!["first question"]);
+
+    // An item id from the rebuilt history keeps the turn that contains it.
+    let fork_id = mcp
+        .send_thread_fork_request(ThreadForkParams {
+            thread_id: thread_id.clone(),
+            after_item_id: Some("item-3".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let fork_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(fork_id)),
+    )
+    .await??;
+    let ThreadForkResponse { thread, .. } = to_response::<ThreadForkResponse>(fork_resp)?;
+    assert_eq!(
+        user_messages(&thread.turns),
+        vec!["first question", "second question"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_fork_rejects_out_of_range_turns() -> Result<()> {
+    let server = create_mock_chat_completions_server(vec![]).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let thread_id = create_two_turn_rollout(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let fork_id = mcp
+        .send_thread_fork_request(ThreadForkParams {
+            thread_id,
+            keep_turns: Some(5),
+            ..Default::default()
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(fork_id)),
+    )
+    .await??;
+    assert_eq!(
+        error.error.message,
+        "cannot fork after turn 5: the session has 2 turns"
+    );
+
+    Ok(())
+}
+
+fn user_messages(turns: &[codex_app_server_protocol::Turn]) -> Vec<String> {
+    turns
+        .iter()
+        .flat_map(|turn| &turn.items)
+        .filter_map(|item| match item {
+            ThreadItem::UserMessage { content, .. } => match content.first() {
+                Some(UserInput::Text { text }) => Some(text.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Writes a rollout with two turns; the rebuilt history numbers its items
+/// `item-1` (first question), `item-2` (answer) and `item-3` (second question).
+fn create_two_turn_rollout(codex_home: &Path) -> Result<String> {
+    let thread_id = create_fake_rollout(
+        codex_home,
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "first question",
+        Some("mock_provider"),
+        None,
+    )?;
+    let dir = codex_home.join("sessions/2025/01/05");
+    let path = std::fs::read_dir(&dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.to_string_lossy().contains(&thread_id))
+        .ok_or_else(|| anyhow::anyhow!("rollout for {thread_id} not found"))?;
+
+    let timestamp = "2025-01-05T12:01:00Z";
+    let lines = [
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": { "type": "agent_message", "message": "first answer" }
+        }),
+        json!({
+            "timestamp": timestamp,
+            "type": "response_item",
+            "payload": {
+                "type": "message",
+                "role": "user",
+                "content": [{ "type": "input_text", "text": "second question" }]
+            }
+        }),
+        json!({
+            "timestamp": timestamp,
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": "second question", "kind": "plain" }
+        }),
+    ];
+    let mut file = std::fs::OpenOptions::new().append(true).open(path)?;
+    for line in lines {
+        writeln!(file, "{line}")?;
+    }
+    Ok(thread_id)
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
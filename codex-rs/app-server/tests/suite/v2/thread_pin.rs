@@ -0,0 +1,136 @@
+use anyhow::Context;
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_mock_chat_completions_server;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCNotification;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::PinnedNote;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadPinParams;
+use codex_app_server_protocol::ThreadPinResponse;
+use codex_app_server_protocol::ThreadPinnedContextUpdatedNotification;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::ThreadUnpinParams;
+use codex_app_server_protocol::ThreadUnpinResponse;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_pin_and_unpin_emit_pinned_context_updates() -> Result<()> {
+    let server = create_mock_chat_completions_server(vec![]).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams::default())
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+
+    let pin_id = mcp
+        .send_thread_pin_request(ThreadPinParams {
+            thread_id: thread.id.clone(),
+            text: "Never edit files under migrations/.".to_string(),
+        })
+        .await?;
+    let pin_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(pin_id)),
+    )
+    .await??;
+    let ThreadPinResponse {} = to_response::<ThreadPinResponse>(pin_resp)?;
+
+    let updated = read_pinned_context_updated(&mut mcp).await?;
+    assert_eq!(
+        updated,
+        ThreadPinnedContextUpdatedNotification {
+            thread_id: thread.id.clone(),
+            notes: vec![PinnedNote {
+                id: 1,
+                text: "Never edit files under migrations/.".to_string(),
+            }],
+        }
+    );
+
+    let unpin_id = mcp
+        .send_thread_unpin_request(ThreadUnpinParams {
+            thread_id: thread.id.clone(),
+            id: 1,
+        })
+        .await?;
+    let unpin_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(unpin_id)),
+    )
+    .await??;
+    let ThreadUnpinResponse {} = to_response::<ThreadUnpinResponse>(unpin_resp)?;
+
+    let updated = read_pinned_context_updated(&mut mcp).await?;
+    assert_eq!(updated.notes, Vec::new());
+
+    // Empty notes are rejected up front.
+    let empty_id = mcp
+        .send_thread_pin_request(ThreadPinParams {
+            thread_id: thread.id,
+            text: "  ".to_string(),
+        })
+        .await?;
+    let empty_err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(empty_id)),
+    )
+    .await??;
+    assert_eq!(empty_err.error.message, "text must not be empty");
+
+    Ok(())
+}
+
+async fn read_pinned_context_updated(
+    mcp: &mut McpProcess,
+) -> Result<ThreadPinnedContextUpdatedNotification> {
+    let notification: JSONRPCNotification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("thread/pinnedContext/updated"),
+    )
+    .await??;
+    let params = notification.params.context("params must be present")?;
+    Ok(serde_json::from_value(params)?)
+}
+
+// Helper to create a config.toml pointing at the mock model server.
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
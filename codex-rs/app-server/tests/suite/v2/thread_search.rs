@@ -0,0 +1,73 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadSearchField;
+use codex_app_server_protocol::ThreadSearchMatch;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_search_returns_matching_threads() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+"#,
+    )?;
+
+    let migration = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "Fix the flaky migration test",
+        Some("mock_provider"),
+        None,
+    )?;
+    let _other = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-01T12-00-00",
+        "2025-01-01T12:00:00Z",
+        "Update the README",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let search_id = mcp
+        .send_thread_search_request(ThreadSearchParams {
+            query: "flaky MIGRATION".to_string(),
+            limit: None,
+        })
+        .await?;
+    let search_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(search_id)),
+    )
+    .await??;
+    let ThreadSearchResponse { data } = to_response::<ThreadSearchResponse>(search_resp)?;
+
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].thread.id, migration);
+    assert_eq!(data[0].thread.preview, "Fix the flaky migration test");
+    assert_eq!(
+        data[0].matches,
+        vec![ThreadSearchMatch {
+            field: ThreadSearchField::UserMessage,
+            snippet: "Fix the flaky migration test".to_string(),
+        }]
+    );
+
+    Ok(())
+}
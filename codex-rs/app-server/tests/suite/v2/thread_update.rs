@@ -0,0 +1,100 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadListParams;
+use codex_app_server_protocol::ThreadListResponse;
+use codex_app_server_protocol::ThreadUpdateParams;
+use codex_app_server_protocol::ThreadUpdateResponse;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_update_sets_title_tags_and_star() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+"#,
+    )?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "Prepare the release",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let update_id = mcp
+        .send_thread_update_request(ThreadUpdateParams {
+            thread_id: thread_id.clone(),
+            title: Some("release prep".to_string()),
+            tags: Some(vec!["release".to_string(), "v2".to_string()]),
+            add_tags: None,
+            remove_tags: None,
+            starred: None,
+        })
+        .await?;
+    let update_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(update_id)),
+    )
+    .await??;
+    let ThreadUpdateResponse { thread } = to_response::<ThreadUpdateResponse>(update_resp)?;
+    assert_eq!(thread.id, thread_id);
+    assert_eq!(thread.title.as_deref(), Some("release prep"));
+    assert_eq!(thread.tags, vec!["release", "v2"]);
+    assert!(!thread.starred);
+
+    // Fields left out keep their previous values.
+    let star_id = mcp
+        .send_thread_update_request(ThreadUpdateParams {
+            thread_id: thread_id.clone(),
+            title: None,
+            tags: None,
+            add_tags: Some(vec!["db".to_string()]),
+            remove_tags: Some(vec!["V2".to_string()]),
+            starred: Some(true),
+        })
+        .await?;
+    let star_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(star_id)),
+    )
+    .await??;
+    let ThreadUpdateResponse { thread } = to_response::<ThreadUpdateResponse>(star_resp)?;
+    assert_eq!(thread.title.as_deref(), Some("release prep"));
+    assert_eq!(thread.tags, vec!["release", "db"]);
+    assert!(thread.starred);
+
+    let list_id = mcp
+        .send_thread_list_request(ThreadListParams {
+            cursor: None,
+            limit: Some(10),
+            model_providers: Some(vec!["mock_provider".to_string()]),
+        })
+        .await?;
+    let list_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await??;
+    let ThreadListResponse { data, .. } = to_response::<ThreadListResponse>(list_resp)?;
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].title.as_deref(), Some("release prep"));
+    assert_eq!(data[0].tags, vec!["release", "db"]);
+    assert!(data[0].starred);
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::io::BufRead;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::approvals::PrefixRuleSuggestion;
+use codex_core::approvals::suggest_prefix_rules;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::default_policy_path;
+use codex_core::exec_env::create_env;
+use codex_core::explain_command;
+use codex_core::load_exec_policy_for_features;
+use codex_core::sandboxing::SandboxPermissions;
+use codex_execpolicy::Decision;
+use codex_execpolicy::blocking_append_prefix_rule;
+use codex_execpolicy::execpolicycheck::load_policies;
+
+/// Explain how execpolicy rules and safety heuristics decide whether a command needs approval.
+#[derive(Debug, clap::Parser)]
+pub struct ExecPolicyExplainCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Policy files to use instead of the ones Codex loads from `$CODEX_HOME/policy`
+    /// (repeatable).
+    #[arg(short = 'p', long = "policy", value_name = "PATH")]
+    pub policies: Vec<PathBuf>,
+
+    /// Directory the command would run in. Defaults to the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Output the explanation as JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Command tokens to explain.
+    #[arg(
+        value_name = "COMMAND",
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    pub command: Vec<String>,
+}
+
+pub async fn run_execpolicy_explain(cmd: ExecPolicyExplainCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(
+        overrides,
+        ConfigOverrides {
+            cwd: cmd.cwd,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+
+    let policy = if cmd.policies.is_empty() {
+        load_exec_policy_for_features(&config.features, &config.codex_home).await?
+    } else {
+        load_policies(&cmd.policies)?
+    };
+    let explanation = explain_command(
+        &policy,
+        &config.features,
+        &cmd.command,
+        &config.cwd,
+        &create_env(&config.shell_environment_policy),
+        config.approval_policy,
+        &config.sandbox_policy,
+        SandboxPermissions::UseDefault,
+    );
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&explanation)?);
+    } else {
+        println!("{explanation}");
+    }
+    Ok(())
+}
+
+/// Suggest execpolicy rules from the approval decisions recorded in past sessions.
+#[derive(Debug, clap::Parser)]
+pub struct ExecPolicySuggestCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Only suggest rules backed by at least this many decisions.
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    pub min_count: usize,
+
+    /// Policy file accepted rules are appended to. Defaults to
+    /// `$CODEX_HOME/policy/default.codexpolicy`.
+    #[arg(long, value_name = "PATH")]
+    pub policy_file: Option<PathBuf>,
+
+    /// Print the suggestions as JSON instead of asking about each one.
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub async fn run_execpolicy_suggest(cmd: ExecPolicySuggestCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")?;
+
+    let policy = load_exec_policy_for_features(&config.features, &config.codex_home).await?;
+    let suggestions = suggest_prefix_rules(&config.codex_home, &policy, cmd.min_count)
+        .await
+        .context("failed to read recorded sessions")?;
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&suggestions)?);
+        return Ok(());
+    }
+    if suggestions.is_empty() {
+        println!(
+            "No suggestions: no command was approved or denied at least {} time(s).",
+            cmd.min_count
+        );
+        return Ok(());
+    }
+
+    let policy_path = cmd
+        .policy_file
+        .unwrap_or_else(|| default_policy_path(&config.codex_home));
+    let mut lines = std::io::stdin().lock().lines();
+    let mut added = 0;
+    for (index, suggestion) in suggestions.iter().enumerate() {
+        println!();
+        println!(
+            "[{}/{}] {}",
+            index + 1,
+            suggestions.len(),
+            describe_suggestion(suggestion)
+        );
+        for example in &suggestion.examples {
+            println!("    {example}");
+        }
+        print!("Add to {}? [y/N/q] ", policy_path.display());
+        std::io::stdout().flush()?;
+        let answer = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+        match answer.trim() {
+            "y" | "yes" => {
+                blocking_append_prefix_rule(&policy_path, &suggestion.prefix, suggestion.decision)?;
+                added += 1;
+            }
+            "q" | "quit" => break,
+            _ => {}
+        }
+    }
+    println!();
+    println!("Added {added} rule(s) to {}", policy_path.display());
+    Ok(())
+}
+
+fn describe_suggestion(suggestion: &PrefixRuleSuggestion) -> String {
+    // Suggested prefixes only hold plain words, so they need no quoting.
+    let prefix = suggestion.prefix.join(" ");
+    let decisions = match suggestion.decision {
+        Decision::Allow => "approvals",
+        Decision::Prompt | Decision::Forbidden => "denials",
+    };
+    format!(
+        "{} `{prefix}`: {} {decisions} in {} session(s)",
+        suggestion.decision.as_str(),
+        suggestion.count,
+        suggestion.sessions
+    )
+}
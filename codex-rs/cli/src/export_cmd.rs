@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_core::config::find_codex_home;
+use codex_core::export::ExportFormat;
+use codex_core::export::ExportOptions;
+use codex_core::export::export_rollout;
+use codex_core::find_conversation_path_by_id_str;
+
+/// Export a recorded session as a Markdown, HTML or JSON transcript.
+#[derive(Debug, clap::Parser)]
+pub struct ExportCommand {
+    /// Session id (UUID) or path to a rollout `.jsonl` file.
+    #[arg(value_name = "SESSION_ID|PATH")]
+    pub session: String,
+
+    /// Output format.
+    #[arg(long, short = 'f', value_enum, default_value_t = ExportFormatArg::Md)]
+    pub format: ExportFormatArg,
+
+    /// Write the transcript to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Omit reasoning summaries from the transcript.
+    #[arg(long = "no-reasoning", default_value_t = false)]
+    pub no_reasoning: bool,
+
+    /// Omit tool call output from the transcript.
+    #[arg(long = "no-tool-output", default_value_t = false)]
+    pub no_tool_output: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormatArg {
+    Md,
+    Html,
+    Json,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(value: ExportFormatArg) -> Self {
+        match value {
+            ExportFormatArg::Md => ExportFormat::Markdown,
+            ExportFormatArg::Html => ExportFormat::Html,
+            ExportFormatArg::Json => ExportFormat::Json,
+        }
+    }
+}
+
+pub async fn run_export_command(cmd: ExportCommand) -> Result<()> {
+    let path = resolve_rollout_path(&cmd.session).await?;
+    let options = ExportOptions {
+        include_reasoning: !cmd.no_reasoning,
+        include_tool_output: !cmd.no_tool_output,
+    };
+    let transcript = export_rollout(&path, cmd.format.into(), options)
+        .await
+        .with_context(|| format!("failed to export {}", path.display()))?;
+
+    match cmd.output {
+        Some(output) => {
+            tokio::fs::write(&output, transcript)
+                .await
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            eprintln!("Exported session to {}", output.display());
+        }
+        None => print!("{transcript}"),
+    }
+    Ok(())
+}
+
+/// Resolves a session id or a path to a rollout file.
+pub(crate) async fn resolve_rollout_path(session: &str) -> Result<PathBuf> {
+    let candidate = PathBuf::from(session);
+    if tokio::fs::try_exists(&candidate).await.unwrap_or(false) {
+        return Ok(candidate);
+    }
+    let codex_home = find_codex_home().context("failed to locate codex home")?;
+    find_conversation_path_by_id_str(&codex_home, session)
+        .await?
+        .ok_or_else(|| anyhow!("no recorded session found for {session}"))
+}
@@ -21,6 +21,7 @@ use codex_exec::Cli as ExecCli;
 use codex_exec::Command as ExecCommand;
 use codex_exec::ReviewArgs;
 use codex_execpolicy::ExecPolicyCheckCommand;
+use codex_execpolicy::ExecPolicyTestCommand;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
 use codex_tui::Cli as TuiCli;
@@ -29,12 +30,27 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod execpolicy_cmd;
+mod export_cmd;
 mod mcp_cmd;
+mod sessions_cmd;
+mod usage_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::execpolicy_cmd::ExecPolicyExplainCommand;
+use crate::execpolicy_cmd::ExecPolicySuggestCommand;
+use crate::execpolicy_cmd::run_execpolicy_explain;
+use crate::execpolicy_cmd::run_execpolicy_suggest;
+use crate::export_cmd::ExportCommand;
+use crate::export_cmd::run_export_command;
 use crate::mcp_cmd::McpCli;
+use crate::sessions_cmd::SessionsCli;
+use crate::sessions_cmd::run_sessions_command;
+use crate::usage_cmd::UsageCommand;
+use crate::usage_cmd::run_usage_command;
 
+use codex_core::ForkPoint;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::features::is_known_feature_key;
@@ -110,6 +126,18 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Start a new interactive session from an earlier point of a recorded session.
+    Fork(ForkCommand),
+
+    /// Export a recorded session as a Markdown, HTML or JSON transcript.
+    Export(ExportCommand),
+
+    /// Search and prune recorded sessions.
+    Sessions(SessionsCli),
+
+    /// Report token usage and estimated cost across recorded sessions.
+    Usage(UsageCommand),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -148,6 +176,35 @@ struct ResumeCommand {
     #[arg(long = "all", default_value_t = false)]
     all: bool,
 
+    /// Only consider sessions carrying this tag (see `codex sessions tag`).
+    #[arg(long = "tag", value_name = "TAG", conflicts_with = "session_id")]
+    tag: Option<String>,
+
+    #[clap(flatten)]
+    config_overrides: TuiCli,
+}
+
+#[derive(Debug, Parser)]
+struct ForkCommand {
+    /// Session id (UUID) or path to a rollout `.jsonl` file to fork.
+    #[arg(value_name = "SESSION_ID|PATH")]
+    session: String,
+
+    /// Keep the first N turns of the session and drop the rest. Without
+    /// --at-turn or --at-item every turn is kept.
+    #[arg(long = "at-turn", value_name = "N", conflicts_with = "at_item")]
+    at_turn: Option<usize>,
+
+    /// Keep every turn up to and including the one containing this item or
+    /// tool call id.
+    #[arg(long = "at-item", value_name = "ITEM_ID")]
+    at_item: Option<String>,
+
+    /// Restore the working tree to the ghost snapshot taken at the start of
+    /// the first dropped turn.
+    #[arg(long = "restore-snapshot", default_value_t = false)]
+    restore_snapshot: bool,
+
     #[clap(flatten)]
     config_overrides: TuiCli,
 }
@@ -183,6 +240,18 @@ enum ExecpolicySubcommand {
     /// Check execpolicy files against a command.
     #[clap(name = "check")]
     Check(ExecPolicyCheckCommand),
+
+    /// Check a policy directory against its rule examples and fixture files.
+    #[clap(name = "test")]
+    Test(ExecPolicyTestCommand),
+
+    /// Explain which rules and heuristics decide whether a command needs approval.
+    #[clap(name = "explain")]
+    Explain(ExecPolicyExplainCommand),
+
+    /// Suggest rules from the commands you approved or denied in past sessions.
+    #[clap(name = "suggest")]
+    Suggest(ExecPolicySuggestCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -489,6 +558,7 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             session_id,
             last,
             all,
+            tag,
             config_overrides,
         })) => {
             interactive = finalize_resume_interactive(
@@ -497,11 +567,35 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 session_id,
                 last,
                 all,
+                tag,
                 config_overrides,
             );
             let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
             handle_app_exit(exit_info)?;
         }
+        Some(Subcommand::Fork(fork_cli)) => {
+            interactive =
+                finalize_fork_interactive(interactive, root_config_overrides.clone(), fork_cli);
+            let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
+            handle_app_exit(exit_info)?;
+        }
+        Some(Subcommand::Export(export_cli)) => {
+            run_export_command(export_cli).await?;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            run_sessions_command(sessions_cli).await?;
+        }
+        Some(Subcommand::Usage(mut usage_cli)) => {
+            prepend_config_flags(
+                &mut usage_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            run_usage_command(usage_cli).await?;
+        }
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
@@ -587,6 +681,15 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
         },
         Some(Subcommand::Execpolicy(ExecpolicyCommand { sub })) => match sub {
             ExecpolicySubcommand::Check(cmd) => run_execpolicycheck(cmd)?,
+            ExecpolicySubcommand::Test(cmd) => cmd.run()?,
+            ExecpolicySubcommand::Explain(mut cmd) => {
+                prepend_config_flags(&mut cmd.config_overrides, root_config_overrides.clone());
+                run_execpolicy_explain(cmd).await?;
+            }
+            ExecpolicySubcommand::Suggest(mut cmd) => {
+                prepend_config_flags(&mut cmd.config_overrides, root_config_overrides.clone());
+                run_execpolicy_suggest(cmd).await?;
+            }
         },
         Some(Subcommand::Apply(mut apply_cli)) => {
             prepend_config_flags(
@@ -657,6 +760,7 @@ fn finalize_resume_interactive(
     session_id: Option<String>,
     last: bool,
     show_all: bool,
+    tag: Option<String>,
     resume_cli: TuiCli,
 ) -> TuiCli {
     // Start with the parsed interactive CLI so resume shares the same
@@ -666,6 +770,7 @@ fn finalize_resume_interactive(
     interactive.resume_last = last;
     interactive.resume_session_id = resume_session_id;
     interactive.resume_show_all = show_all;
+    interactive.resume_tag = tag;
 
     // Merge resume-scoped flags and overrides with highest precedence.
     merge_resume_cli_flags(&mut interactive, resume_cli);
@@ -676,7 +781,34 @@ fn finalize_resume_interactive(
     interactive
 }
 
-/// Merge flags provided to `codex resume` so they take precedence over any
+/// Build the final `TuiCli` for a `codex fork` invocation.
+fn finalize_fork_interactive(
+    mut interactive: TuiCli,
+    root_config_overrides: CliConfigOverrides,
+    fork_cli: ForkCommand,
+) -> TuiCli {
+    let ForkCommand {
+        session,
+        at_turn,
+        at_item,
+        restore_snapshot,
+        config_overrides,
+    } = fork_cli;
+    interactive.fork_session = Some(session);
+    interactive.fork_point = Some(match (at_turn, at_item) {
+        (Some(turns), _) => ForkPoint::AfterTurns(turns),
+        (None, Some(item_id)) => ForkPoint::AfterItem(item_id),
+        (None, None) => ForkPoint::Latest,
+    });
+    interactive.fork_restore_snapshot = restore_snapshot;
+
+    merge_resume_cli_flags(&mut interactive, config_overrides);
+    prepend_config_flags(&mut interactive.config_overrides, root_config_overrides);
+
+    interactive
+}
+
+/// Merge flags provided to `codex resume` or `codex fork` so they take precedence over any
 /// root-level flags. Only overrides fields explicitly set on the resume-scoped
 /// CLI. Also appends `-c key=value` overrides with highest precedence.
 fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
@@ -750,6 +882,7 @@ mod tests {
             session_id,
             last,
             all,
+            tag,
             config_overrides: resume_cli,
         }) = subcommand.expect("resume present")
         else {
@@ -762,6 +895,7 @@ mod tests {
             session_id,
             last,
             all,
+            tag,
             resume_cli,
         )
     }
@@ -858,6 +992,45 @@ mod tests {
         assert!(interactive.resume_show_all);
     }
 
+    #[test]
+    fn resume_tag_flag_sets_resume_tag() {
+        let interactive =
+            finalize_from_args(["codex", "resume", "--last", "--tag", "release"].as_ref());
+        assert!(interactive.resume_last);
+        assert_eq!(interactive.resume_tag.as_deref(), Some("release"));
+    }
+
+    #[test]
+    fn fork_sets_fork_point_and_merges_flags() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "fork",
+            "sid",
+            "--at-turn",
+            "2",
+            "--restore-snapshot",
+            "-m",
+            "gpt-5.1-test",
+        ])
+        .expect("parse");
+        let MultitoolCli {
+            interactive,
+            config_overrides: root_overrides,
+            subcommand,
+            feature_toggles: _,
+        } = cli;
+        let Some(Subcommand::Fork(fork_cli)) = subcommand else {
+            panic!("expected fork subcommand");
+        };
+
+        let interactive = finalize_fork_interactive(interactive, root_overrides, fork_cli);
+        assert_eq!(interactive.fork_session.as_deref(), Some("sid"));
+        assert_eq!(interactive.fork_point, Some(ForkPoint::AfterTurns(2)));
+        assert!(interactive.fork_restore_snapshot);
+        assert_eq!(interactive.model.as_deref(), Some("gpt-5.1-test"));
+        assert!(!interactive.resume_picker);
+    }
+
     #[test]
     fn resume_merges_option_flags_and_full_auto() {
         let interactive = finalize_from_args(
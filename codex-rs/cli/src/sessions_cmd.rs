@@ -0,0 +1,302 @@
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::labels::SessionLabels;
+use codex_core::labels::append_session_labels;
+use codex_core::labels::read_session_labels;
+use codex_core::protocol::SessionLabelsItem;
+use codex_core::retention::PruneReason;
+use codex_core::retention::prune_sessions;
+use codex_core::search::SearchField;
+use codex_core::search::search_sessions;
+
+use crate::export_cmd::resolve_rollout_path;
+
+/// Inspect and manage recorded sessions.
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Full-text search across user messages, assistant messages, commands
+    /// run and files touched in all recorded sessions.
+    Search(SearchArgs),
+
+    /// Delete old sessions and compress closed ones according to the
+    /// `[sessions]` policy in config.toml. Flags override the configured
+    /// values.
+    Prune(PruneArgs),
+
+    /// Set the title, tags or star of a session. Without any edit flags,
+    /// prints the current labels.
+    Tag(TagArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Words to search for. All words must match; wrap a phrase in double
+    /// quotes to match it exactly.
+    #[arg(value_name = "QUERY", required = true, num_args = 1..)]
+    pub query: Vec<String>,
+
+    /// Maximum number of sessions to show.
+    #[arg(long, short = 'n', default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output the results as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct PruneArgs {
+    /// Show what would be deleted or compressed without changing anything.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Delete sessions not updated for this many days.
+    #[arg(long, value_name = "DAYS")]
+    pub max_age_days: Option<u64>,
+
+    /// Delete the oldest sessions until all sessions fit in this many MiB.
+    #[arg(long, value_name = "MB")]
+    pub max_total_mb: Option<u64>,
+
+    /// Never delete this many of the most recent sessions.
+    #[arg(long, value_name = "N")]
+    pub keep_recent: Option<usize>,
+
+    /// Compress closed sessions with zstd.
+    #[arg(long)]
+    pub compress: bool,
+
+    /// Also delete starred sessions.
+    #[arg(long)]
+    pub include_starred: bool,
+
+    /// Output the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct TagArgs {
+    /// Session id (UUID) or path to a rollout `.jsonl` file.
+    #[arg(value_name = "SESSION_ID|PATH")]
+    pub session: String,
+
+    /// Add a tag. May be repeated.
+    #[arg(long = "add", value_name = "TAG")]
+    pub add: Vec<String>,
+
+    /// Remove a tag. May be repeated.
+    #[arg(long = "remove", value_name = "TAG")]
+    pub remove: Vec<String>,
+
+    /// Set the session title. Pass an empty string to clear it.
+    #[arg(long, value_name = "TITLE")]
+    pub title: Option<String>,
+
+    /// Star the session so `codex sessions prune` keeps it.
+    #[arg(long, conflicts_with = "unstar")]
+    pub star: bool,
+
+    /// Remove the star from the session.
+    #[arg(long)]
+    pub unstar: bool,
+
+    /// Output the resulting labels as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub async fn run_sessions_command(cli: SessionsCli) -> Result<()> {
+    match cli.subcommand {
+        SessionsSubcommand::Search(args) => run_search(args).await,
+        SessionsSubcommand::Prune(args) => run_prune(&cli.config_overrides, args).await,
+        SessionsSubcommand::Tag(args) => run_tag(args).await,
+    }
+}
+
+async fn run_search(args: SearchArgs) -> Result<()> {
+    let codex_home = find_codex_home().context("failed to locate codex home")?;
+    let query = args.query.join(" ");
+    let hits = search_sessions(&codex_home, &query, args.limit)
+        .await
+        .context("failed to search sessions")?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&hits)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No sessions match {query:?}.");
+        return Ok(());
+    }
+
+    for (i, hit) in hits.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}  {}  {}", hit.id, hit.created_at, hit.cwd.display());
+        if let Some(title) = &hit.labels.title {
+            println!("  {title}");
+        } else if let Some(preview) = hit.preview.lines().next() {
+            println!("  {preview}");
+        }
+        for m in &hit.matches {
+            println!("    {:<9} {}", field_label(m.field), m.snippet);
+        }
+    }
+    Ok(())
+}
+
+async fn run_prune(config_overrides: &CliConfigOverrides, args: PruneArgs) -> Result<()> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")?;
+
+    let mut policy = config.sessions;
+    if args.max_age_days.is_some() {
+        policy.max_age_days = args.max_age_days;
+    }
+    if args.max_total_mb.is_some() {
+        policy.max_total_mb = args.max_total_mb;
+    }
+    if let Some(keep_recent) = args.keep_recent {
+        policy.keep_recent = keep_recent;
+    }
+    policy.compress |= args.compress;
+    if args.include_starred {
+        policy.keep_starred = false;
+    }
+    if !policy.is_enabled() {
+        anyhow::bail!(
+            "nothing to do: set a limit under [sessions] in config.toml or pass --max-age-days, --max-total-mb or --compress"
+        );
+    }
+
+    let report = prune_sessions(&config.codex_home, &policy, args.dry_run)
+        .await
+        .context("failed to prune sessions")?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let (delete_verb, compress_verb) = if args.dry_run {
+        ("Would delete", "Would compress")
+    } else {
+        ("Deleted", "Compressed")
+    };
+    for pruned in &report.removed {
+        let reason = match pruned.reason {
+            PruneReason::MaxAge => "older than max age",
+            PruneReason::MaxTotalSize => "over total size",
+        };
+        println!(
+            "{delete_verb} {} ({}, {reason})",
+            pruned.path.display(),
+            format_bytes(pruned.bytes)
+        );
+    }
+    for path in &report.compressed {
+        println!("{compress_verb} {}", path.display());
+    }
+    let freed: u64 = report.removed.iter().map(|pruned| pruned.bytes).sum();
+    println!(
+        "{delete_verb} {} sessions ({}), {} {} sessions; {} remain.",
+        report.removed.len(),
+        format_bytes(freed),
+        compress_verb.to_lowercase(),
+        report.compressed.len(),
+        format_bytes(report.remaining_bytes)
+    );
+    Ok(())
+}
+
+async fn run_tag(args: TagArgs) -> Result<()> {
+    let path = resolve_rollout_path(&args.session).await?;
+    let current = read_session_labels(&path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    let starred = if args.star {
+        Some(true)
+    } else if args.unstar {
+        Some(false)
+    } else {
+        None
+    };
+    // Record tag changes as additions and removals rather than a new list, so
+    // they don't drop tags the running session adds in the meantime.
+    let edit = SessionLabelsItem {
+        title: args.title,
+        tags: None,
+        add_tags: (!args.add.is_empty()).then_some(args.add),
+        remove_tags: (!args.remove.is_empty()).then_some(args.remove),
+        starred,
+    };
+
+    let labels = if edit == SessionLabelsItem::default() {
+        current
+    } else {
+        append_session_labels(&path, edit)
+            .await
+            .with_context(|| format!("failed to update {}", path.display()))?
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&labels)?);
+    } else {
+        print_labels(&labels);
+    }
+    Ok(())
+}
+
+fn print_labels(labels: &SessionLabels) {
+    println!("title:   {}", labels.title.as_deref().unwrap_or("-"));
+    if labels.tags.is_empty() {
+        println!("tags:    -");
+    } else {
+        println!("tags:    {}", labels.tags.join(", "));
+    }
+    println!("starred: {}", if labels.starred { "yes" } else { "no" });
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= KIB * KIB * KIB {
+        format!("{:.1} GiB", bytes / (KIB * KIB * KIB))
+    } else if bytes >= KIB * KIB {
+        format!("{:.1} MiB", bytes / (KIB * KIB))
+    } else if bytes >= KIB {
+        format!("{:.1} KiB", bytes / KIB)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+fn field_label(field: SearchField) -> &'static str {
+    match field {
+        SearchField::UserMessage => "user",
+        SearchField::AgentMessage => "assistant",
+        SearchField::Command => "command",
+        SearchField::File => "file",
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::Context;
+use anyhow::Result;
+use chrono::NaiveDate;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::usage::UsageGroup;
+use codex_core::usage::UsageQuery;
+use codex_core::usage::UsageReport;
+use codex_core::usage::summarize_usage;
+
+/// Report token usage and estimated cost across recorded sessions.
+#[derive(Debug, clap::Parser)]
+pub struct UsageCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// How to group the usage.
+    #[arg(long = "by", value_enum, default_value_t = GroupBy::Day)]
+    pub group_by: GroupBy,
+
+    /// Only count usage on or after this day (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<NaiveDate>,
+
+    /// Only count usage on or before this day (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<NaiveDate>,
+
+    /// Output the report as JSON.
+    #[arg(long, conflicts_with = "csv")]
+    pub json: bool,
+
+    /// Output the rows as CSV.
+    #[arg(long)]
+    pub csv: bool,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum GroupBy {
+    Day,
+    Week,
+    Model,
+    /// Working directory of the session.
+    Project,
+    Session,
+}
+
+impl From<GroupBy> for UsageGroup {
+    fn from(group_by: GroupBy) -> Self {
+        match group_by {
+            GroupBy::Day => UsageGroup::Day,
+            GroupBy::Week => UsageGroup::Week,
+            GroupBy::Model => UsageGroup::Model,
+            GroupBy::Project => UsageGroup::Project,
+            GroupBy::Session => UsageGroup::Session,
+        }
+    }
+}
+
+pub async fn run_usage_command(cmd: UsageCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")?;
+
+    let query = UsageQuery {
+        group_by: cmd.group_by.into(),
+        since: cmd.since,
+        until: cmd.until,
+    };
+    let report = summarize_usage(&config.codex_home, &config.usage, &query)
+        .await
+        .context("failed to read session usage")?;
+
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if cmd.csv {
+        print_csv(&report);
+    } else {
+        print_table(&report, !config.usage.prices.is_empty());
+    }
+    Ok(())
+}
+
+fn print_csv(report: &UsageReport) {
+    println!(
+        "{},sessions,input_tokens,cached_input_tokens,output_tokens,reasoning_output_tokens,total_tokens,cost_usd",
+        group_label(report.group_by)
+    );
+    for row in &report.rows {
+        println!(
+            "{},{},{},{},{},{},{},{:.6}",
+            csv_field(&row.key),
+            row.sessions,
+            row.input_tokens,
+            row.cached_input_tokens,
+            row.output_tokens,
+            row.reasoning_output_tokens,
+            row.total_tokens,
+            row.cost_usd
+        );
+    }
+}
+
+fn print_table(report: &UsageReport, priced: bool) {
+    if report.rows.is_empty() {
+        println!("No token usage recorded.");
+        return;
+    }
+
+    let key_width = report
+        .rows
+        .iter()
+        .map(|row| row.key.chars().count())
+        .chain([group_label(report.group_by).len()])
+        .max()
+        .unwrap_or_default();
+    let mut header = format!(
+        "{:<key_width$}  {:>8}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+        group_label(report.group_by).to_uppercase(),
+        "SESSIONS",
+        "INPUT",
+        "CACHED",
+        "OUTPUT",
+        "REASONING",
+        "TOTAL"
+    );
+    if priced {
+        header.push_str(&format!("  {:>10}", "COST"));
+    }
+    println!("{header}");
+    for row in report.rows.iter().chain([&report.total]) {
+        let mut line = format!(
+            "{:<key_width$}  {:>8}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+            row.key,
+            row.sessions,
+            row.input_tokens,
+            row.cached_input_tokens,
+            row.output_tokens,
+            row.reasoning_output_tokens,
+            row.total_tokens
+        );
+        if priced {
+            line.push_str(&format!("  {:>10}", format!("${:.2}", row.cost_usd)));
+        }
+        println!("{line}");
+    }
+    if priced && !report.unpriced_models.is_empty() {
+        println!(
+            "\nNo price configured for {}; their usage is not included in the cost.",
+            report.unpriced_models.join(", ")
+        );
+    }
+}
+
+fn group_label(group_by: UsageGroup) -> &'static str {
+    match group_by {
+        UsageGroup::Day => "day",
+        UsageGroup::Week => "week",
+        UsageGroup::Model => "model",
+        UsageGroup::Project => "project",
+        UsageGroup::Session => "session",
+    }
+}
+
+/// Quotes `value` when it contains characters that are special in CSV.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
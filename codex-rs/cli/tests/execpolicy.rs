@@ -54,3 +54,114 @@ prefix_rule(
 
     Ok(())
 }
+
+#[test]
+fn execpolicy_explain_lists_rules_for_each_inner_command() -> Result<(), Box<dyn std::error::Error>>
+{
+    let codex_home = TempDir::new()?;
+    let policy_dir = codex_home.path().join("policy");
+    fs::create_dir(&policy_dir)?;
+    let policy_path = policy_dir.join("default.codexpolicy");
+    fs::write(
+        &policy_path,
+        r#"prefix_rule(pattern = ["npm", "test"], decision = "prompt")
+"#,
+    )?;
+
+    let output = Command::cargo_bin("codex")?
+        .env("CODEX_HOME", codex_home.path())
+        .args([
+            "execpolicy",
+            "explain",
+            "-c",
+            "approval_policy=\"on-request\"",
+            "-c",
+            "sandbox_mode=\"workspace-write\"",
+            "--json",
+            "bash",
+            "-lc",
+            "npm test && ls",
+        ])
+        .output()?;
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let policy_path = policy_path
+        .to_str()
+        .expect("policy path should be valid UTF-8");
+    assert_eq!(result["splitShellScript"], json!(true));
+    assert_eq!(result["decision"], json!("prompt"));
+    assert_eq!(
+        result["innerCommands"][0]["rules"],
+        json!([{
+            "source": { "file": policy_path, "line": 1 },
+            "ruleMatch": {
+                "prefixRuleMatch": {
+                    "matchedPrefix": ["npm", "test"],
+                    "decision": "prompt"
+                }
+            }
+        }])
+    );
+    assert_eq!(
+        result["innerCommands"][1]["heuristics"],
+        json!({ "knownSafe": true, "mightBeDangerous": false, "decision": "allow" })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn execpolicy_suggest_appends_accepted_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let codex_home = TempDir::new()?;
+    let sessions_dir = codex_home.path().join("sessions/2025/01/06");
+    fs::create_dir_all(&sessions_dir)?;
+    let decisions: Vec<String> = ["codex-core", "codex-tui", "codex-cli"]
+        .iter()
+        .enumerate()
+        .map(|(index, package)| {
+            json!({
+                "timestamp": "2025-01-06T09:00:00.000Z",
+                "type": "exec_approval_decision",
+                "payload": {
+                    "call_id": format!("call-{index}"),
+                    "command": ["cargo", "nextest", "run", "-p", package],
+                    "cwd": "/repo",
+                    "decision": "approved"
+                }
+            })
+            .to_string()
+        })
+        .collect();
+    fs::write(
+        sessions_dir.join("rollout-2025-01-06T09-00-00-5973b6c0-94b8-487b-a530-2aeb6098ae0e.jsonl"),
+        decisions.join("\n") + "\n",
+    )?;
+
+    let output = Command::cargo_bin("codex")?
+        .env("CODEX_HOME", codex_home.path())
+        .args(["execpolicy", "suggest"])
+        .write_stdin("y\n")
+        .output()?;
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(
+        stdout.contains("[1/1] allow `cargo nextest`: 3 approvals in 1 session(s)"),
+        "stdout: {stdout}"
+    );
+    assert_eq!(
+        fs::read_to_string(codex_home.path().join("policy/default.codexpolicy"))?,
+        "prefix_rule(pattern=[\"cargo\", \"nextest\"], decision=\"allow\")\n"
+    );
+
+    Ok(())
+}
@@ -8,6 +8,9 @@ pub mod requests;
 pub mod sse;
 pub mod telemetry;
 
+pub use codex_client::Cassette;
+pub use codex_client::CassetteMode;
+pub use codex_client::CassetteTransport;
 pub use codex_client::RequestTelemetry;
 pub use codex_client::ReqwestTransport;
 pub use codex_client::TransportError;
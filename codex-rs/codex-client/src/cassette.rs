@@ -0,0 +1,609 @@
+//! Record/replay of HTTP traffic for offline, deterministic runs.
+//!
+//! A [`Cassette`] is a JSON file holding a list of request/response
+//! interactions. [`CassetteTransport`] wraps another transport and, depending
+//! on the cassette's mode, either forwards every request while appending the
+//! exchange to the cassette, or serves responses from the cassette without
+//! touching the network.
+//!
+//! Replayed requests are matched on their *shape* rather than their exact
+//! bytes: the method, the URL path and the JSON body with free-form string
+//! values blanked out. Keys such as `model`, `type`, `role` and `name` keep
+//! their values, so the sequence of messages and tool calls has to line up
+//! while text that naturally differs between runs (paths, command output,
+//! instructions) does not. Each recorded interaction is served at most once,
+//! in recording order.
+//!
+//! Request headers are never written to a cassette, so credentials stay out of
+//! the file.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
+use http::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::TransportError;
+use crate::request::Request;
+use crate::request::Response;
+use crate::transport::HttpTransport;
+use crate::transport::StreamResponse;
+
+const CASSETTE_VERSION: u32 = 1;
+
+/// Body keys whose string values are part of a request's shape.
+const SHAPE_KEYS: &[&str] = &["model", "type", "role", "name"];
+
+/// Response headers that are never recorded.
+const SKIPPED_RESPONSE_HEADERS: &[&str] = &["set-cookie"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Forward requests and append every exchange to the cassette.
+    Record,
+    /// Serve responses from the cassette; never touch the network.
+    Replay,
+}
+
+/// A cassette file shared by every transport that records to or replays from it.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// Whether each interaction has already been replayed.
+    used: Vec<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteFile {
+    version: u32,
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Cassette {
+    /// Starts a new, empty cassette at `path`, replacing any existing file.
+    pub fn record(path: PathBuf) -> std::io::Result<Self> {
+        let cassette = Self {
+            path,
+            mode: CassetteMode::Record,
+            state: Mutex::new(CassetteState::default()),
+        };
+        cassette.save(&[])?;
+        Ok(cassette)
+    }
+
+    /// Loads the cassette at `path` for replay.
+    pub fn replay(path: PathBuf) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(&path)?;
+        let file: CassetteFile = serde_json::from_str(&contents).map_err(std::io::Error::other)?;
+        if file.version != CASSETTE_VERSION {
+            return Err(std::io::Error::other(format!(
+                "unsupported cassette version {} in {}",
+                file.version,
+                path.display()
+            )));
+        }
+        let used = vec![false; file.interactions.len()];
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            state: Mutex::new(CassetteState {
+                interactions: file.interactions,
+                used,
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    fn push(&self, interaction: Interaction) -> Result<(), TransportError> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| TransportError::Cassette("cassette lock poisoned".to_string()))?;
+        state.interactions.push(interaction);
+        state.used.push(false);
+        self.save(&state.interactions)
+            .map_err(|err| TransportError::Cassette(format!("failed to write cassette: {err}")))
+    }
+
+    fn save(&self, interactions: &[Interaction]) -> std::io::Result<()> {
+        let file = CassetteFile {
+            version: CASSETTE_VERSION,
+            interactions: interactions.to_vec(),
+        };
+        let contents = serde_json::to_string_pretty(&file).map_err(std::io::Error::other)?;
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, contents)
+    }
+
+    /// Claims the first unused interaction matching `request`.
+    fn take(&self, request: &RecordedRequest) -> Result<RecordedResponse, TransportError> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| TransportError::Cassette("cassette lock poisoned".to_string()))?;
+        let wanted = request_shape(request);
+        let CassetteState { interactions, used } = &mut *state;
+        let position = interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| {
+                !*used && request_shape(&interaction.request) == wanted
+            });
+        match position {
+            Some(idx) => {
+                used[idx] = true;
+                Ok(interactions[idx].response.clone())
+            }
+            None => Err(TransportError::Cassette(format!(
+                "cassette {} has no unused recording matching {} {}",
+                self.path.display(),
+                request.method,
+                request.path
+            ))),
+        }
+    }
+}
+
+/// Transport that records to or replays from a [`Cassette`]. Without a
+/// cassette, requests go straight to the inner transport.
+#[derive(Debug, Clone)]
+pub struct CassetteTransport<T: HttpTransport> {
+    inner: T,
+    cassette: Option<Arc<Cassette>>,
+}
+
+impl<T: HttpTransport> CassetteTransport<T> {
+    pub fn new(inner: T, cassette: Option<Arc<Cassette>>) -> Self {
+        Self { inner, cassette }
+    }
+}
+
+#[async_trait]
+impl<T: HttpTransport> HttpTransport for CassetteTransport<T> {
+    async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+        let Some(cassette) = &self.cassette else {
+            return self.inner.execute(req).await;
+        };
+        let recorded_request = RecordedRequest::from_request(&req);
+        match cassette.mode {
+            CassetteMode::Replay => {
+                let recorded = cassette.take(&recorded_request)?;
+                let (status, headers) = recorded.status_and_headers()?;
+                if !status.is_success() {
+                    return Err(recorded.into_http_error(status, headers));
+                }
+                Ok(Response {
+                    status,
+                    headers,
+                    body: Bytes::from(recorded.body),
+                })
+            }
+            CassetteMode::Record => match self.inner.execute(req).await {
+                Ok(response) => {
+                    cassette.push(Interaction {
+                        request: recorded_request,
+                        response: RecordedResponse::new(
+                            response.status,
+                            &response.headers,
+                            String::from_utf8_lossy(&response.body).into_owned(),
+                        ),
+                    })?;
+                    Ok(response)
+                }
+                Err(err) => {
+                    record_http_error(cassette, recorded_request, &err)?;
+                    Err(err)
+                }
+            },
+        }
+    }
+
+    async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError> {
+        let Some(cassette) = &self.cassette else {
+            return self.inner.stream(req).await;
+        };
+        let recorded_request = RecordedRequest::from_request(&req);
+        match cassette.mode {
+            CassetteMode::Replay => {
+                let recorded = cassette.take(&recorded_request)?;
+                let (status, headers) = recorded.status_and_headers()?;
+                if !status.is_success() {
+                    return Err(recorded.into_http_error(status, headers));
+                }
+                let body = Bytes::from(recorded.body);
+                Ok(StreamResponse {
+                    status,
+                    headers,
+                    bytes: Box::pin(futures::stream::once(async move { Ok(body) })),
+                })
+            }
+            CassetteMode::Record => {
+                let response = match self.inner.stream(req).await {
+                    Ok(response) => response,
+                    Err(err) => {
+                        record_http_error(cassette, recorded_request, &err)?;
+                        return Err(err);
+                    }
+                };
+                let StreamResponse {
+                    status,
+                    headers,
+                    bytes,
+                } = response;
+                let recorder = StreamRecorder {
+                    cassette: Arc::clone(cassette),
+                    request: Some(recorded_request),
+                    status,
+                    headers: headers.clone(),
+                    body: Vec::new(),
+                };
+                // Tee the stream into the recorder; the exchange is written to
+                // the cassette once the stream ends (or is dropped early).
+                let bytes = bytes.scan(recorder, |recorder, chunk| {
+                    if let Ok(chunk) = &chunk {
+                        recorder.body.extend_from_slice(chunk);
+                    }
+                    futures::future::ready(Some(chunk))
+                });
+                Ok(StreamResponse {
+                    status,
+                    headers,
+                    bytes: Box::pin(bytes),
+                })
+            }
+        }
+    }
+}
+
+/// Accumulates a streamed response body and records it when dropped.
+struct StreamRecorder {
+    cassette: Arc<Cassette>,
+    request: Option<RecordedRequest>,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl Drop for StreamRecorder {
+    fn drop(&mut self) {
+        let Some(request) = self.request.take() else {
+            return;
+        };
+        let body = String::from_utf8_lossy(&self.body).into_owned();
+        let interaction = Interaction {
+            request,
+            response: RecordedResponse::new(self.status, &self.headers, body),
+        };
+        if let Err(err) = self.cassette.push(interaction) {
+            tracing::warn!("{err}");
+        }
+    }
+}
+
+/// HTTP error responses are part of the conversation (e.g. rate limits that
+/// trigger retries), so they are recorded too.
+fn record_http_error(
+    cassette: &Cassette,
+    request: RecordedRequest,
+    err: &TransportError,
+) -> Result<(), TransportError> {
+    if let TransportError::Http {
+        status,
+        headers,
+        body,
+    } = err
+    {
+        cassette.push(Interaction {
+            request,
+            response: RecordedResponse::new(
+                *status,
+                &headers.clone().unwrap_or_default(),
+                body.clone().unwrap_or_default(),
+            ),
+        })?;
+    }
+    Ok(())
+}
+
+impl RecordedRequest {
+    fn from_request(req: &Request) -> Self {
+        let path = reqwest::Url::parse(&req.url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| req.url.clone());
+        Self {
+            method: req.method.to_string(),
+            path,
+            body: req.body.clone(),
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn new(status: StatusCode, headers: &HeaderMap, body: String) -> Self {
+        let headers = headers
+            .iter()
+            .filter(|(name, _)| !SKIPPED_RESPONSE_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+        Self {
+            status: status.as_u16(),
+            headers,
+            body,
+        }
+    }
+
+    fn status_and_headers(&self) -> Result<(StatusCode, HeaderMap), TransportError> {
+        let status = StatusCode::from_u16(self.status)
+            .map_err(|err| TransportError::Cassette(format!("invalid recorded status: {err}")))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        Ok((status, headers))
+    }
+
+    fn into_http_error(self, status: StatusCode, headers: HeaderMap) -> TransportError {
+        TransportError::Http {
+            status,
+            headers: Some(headers),
+            body: Some(self.body),
+        }
+    }
+}
+
+fn request_shape(request: &RecordedRequest) -> (String, String, Option<Value>) {
+    (
+        request.method.clone(),
+        request.path.clone(),
+        request.body.as_ref().map(|body| value_shape(body, None)),
+    )
+}
+
+/// Blanks out string values except those under [`SHAPE_KEYS`].
+fn value_shape(value: &Value, key: Option<&str>) -> Value {
+    match value {
+        Value::String(s) => {
+            if key.is_some_and(|key| SHAPE_KEYS.contains(&key)) {
+                Value::String(s.clone())
+            } else {
+                Value::String(String::new())
+            }
+        }
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|item| value_shape(item, key)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), value_shape(v, Some(k))))
+                .collect(),
+        ),
+        Value::Null | Value::Bool(_) | Value::Number(_) => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use http::Method;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    /// Transport that answers every request with a fixed SSE body.
+    #[derive(Clone)]
+    struct FixedTransport {
+        body: &'static str,
+    }
+
+    #[async_trait]
+    impl HttpTransport for FixedTransport {
+        async fn execute(&self, _req: Request) -> Result<Response, TransportError> {
+            Ok(Response {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from_static(self.body.as_bytes()),
+            })
+        }
+
+        async fn stream(&self, _req: Request) -> Result<StreamResponse, TransportError> {
+            let chunks = self
+                .body
+                .split_inclusive("\n\n")
+                .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))
+                .collect::<Vec<_>>();
+            let mut headers = HeaderMap::new();
+            headers.insert("x-request-id", HeaderValue::from_static("req-1"));
+            headers.insert("set-cookie", HeaderValue::from_static("secret"));
+            Ok(StreamResponse {
+                status: StatusCode::OK,
+                headers,
+                bytes: Box::pin(futures::stream::iter(chunks)),
+            })
+        }
+    }
+
+    /// Transport that must never be reached.
+    struct OfflineTransport;
+
+    #[async_trait]
+    impl HttpTransport for OfflineTransport {
+        async fn execute(&self, _req: Request) -> Result<Response, TransportError> {
+            Err(TransportError::Network("offline".to_string()))
+        }
+
+        async fn stream(&self, _req: Request) -> Result<StreamResponse, TransportError> {
+            Err(TransportError::Network("offline".to_string()))
+        }
+    }
+
+    fn request(text: &str) -> Request {
+        Request::new(
+            Method::POST,
+            "https://api.example.com/v1/responses".to_string(),
+        )
+        .with_json(&json!({
+            "model": "gpt-test",
+            "input": [{"type": "message", "role": "user", "content": text}],
+        }))
+    }
+
+    async fn read_body(response: StreamResponse) -> String {
+        let chunks: Vec<Bytes> = response.bytes.try_collect().await.expect("stream");
+        String::from_utf8(chunks.concat()).expect("utf8")
+    }
+
+    #[tokio::test]
+    async fn records_and_replays_streams() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("run.json");
+        let body = "event: response.created\ndata: {}\n\nevent: response.completed\ndata: {}\n\n";
+
+        let recorder = CassetteTransport::new(
+            FixedTransport { body },
+            Some(Arc::new(Cassette::record(path.clone()).expect("record"))),
+        );
+        let response = recorder.stream(request("hello")).await.expect("stream");
+        assert_eq!(read_body(response).await, body);
+
+        let recorded: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).expect("read")).expect("json");
+        assert_eq!(
+            recorded["interactions"][0]["request"]["path"],
+            "/v1/responses"
+        );
+        assert_eq!(
+            recorded["interactions"][0]["response"]["headers"],
+            json!([["x-request-id", "req-1"]])
+        );
+
+        let replayer = CassetteTransport::new(
+            OfflineTransport,
+            Some(Arc::new(Cassette::replay(path).expect("replay"))),
+        );
+        // Message text differs but the shape of the request matches.
+        let response = replayer.stream(request("hi there")).await.expect("replay");
+        assert_eq!(response.headers["x-request-id"], "req-1");
+        assert_eq!(read_body(response).await, body);
+
+        // Each recording is served once.
+        let err = replayer
+            .stream(request("hello"))
+            .await
+            .err()
+            .expect("cassette exhausted");
+        assert!(matches!(err, TransportError::Cassette(_)));
+    }
+
+    #[tokio::test]
+    async fn replay_requires_matching_shape() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("run.json");
+        let recorder = CassetteTransport::new(
+            FixedTransport { body: "{}" },
+            Some(Arc::new(Cassette::record(path.clone()).expect("record"))),
+        );
+        recorder.execute(request("hello")).await.expect("execute");
+
+        let replayer = CassetteTransport::new(
+            OfflineTransport,
+            Some(Arc::new(Cassette::replay(path).expect("replay"))),
+        );
+        let other_model = Request::new(
+            Method::POST,
+            "https://api.example.com/v1/responses".to_string(),
+        )
+        .with_json(&json!({
+            "model": "other-model",
+            "input": [{"type": "message", "role": "user", "content": "hello"}],
+        }));
+        assert!(replayer.execute(other_model).await.is_err());
+        let response = replayer.execute(request("hello")).await.expect("replay");
+        assert_eq!(response.body, Bytes::from_static(b"{}"));
+    }
+
+    #[test]
+    fn shape_keeps_structural_values() {
+        let shape = value_shape(
+            &json!({
+                "model": "gpt-test",
+                "input": [
+                    {"type": "function_call", "name": "shell", "arguments": "{\"command\":[\"ls\"]}"},
+                    {"type": "function_call_output", "call_id": "call_1", "output": "a\nb"},
+                ],
+                "stream": true,
+            }),
+            None,
+        );
+        assert_eq!(
+            shape,
+            json!({
+                "model": "gpt-test",
+                "input": [
+                    {"type": "function_call", "name": "shell", "arguments": ""},
+                    {"type": "function_call_output", "call_id": "", "output": ""},
+                ],
+                "stream": true,
+            })
+        );
+    }
+}
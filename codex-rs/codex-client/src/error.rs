@@ -18,6 +18,10 @@ pub enum TransportError {
     Network(String),
     #[error("request build error: {0}")]
     Build(String),
+    /// A cassette could not be read, written or had no recording for a
+    /// replayed request.
+    #[error("cassette error: {0}")]
+    Cassette(String),
 }
 
 #[derive(Debug, Error)]
@@ -1,3 +1,4 @@
+mod cassette;
 mod error;
 mod request;
 mod retry;
@@ -5,6 +6,9 @@ mod sse;
 mod telemetry;
 mod transport;
 
+pub use crate::cassette::Cassette;
+pub use crate::cassette::CassetteMode;
+pub use crate::cassette::CassetteTransport;
 pub use crate::error::StreamError;
 pub use crate::error::TransportError;
 pub use crate::request::Request;
@@ -1,14 +1,25 @@
+use std::path::PathBuf;
+
 use codex_core::protocol::SandboxPolicy;
 
 pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
     match sandbox_policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-        SandboxPolicy::ReadOnly => "read-only".to_string(),
+        SandboxPolicy::ReadOnly {
+            readable_roots,
+            deny_read,
+        } => {
+            let mut summary = "read-only".to_string();
+            push_read_restrictions(&mut summary, readable_roots, deny_read);
+            summary
+        }
         SandboxPolicy::WorkspaceWrite {
             writable_roots,
             network_access,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            readable_roots,
+            deny_read,
         } => {
             let mut summary = "workspace-write".to_string();
 
@@ -27,6 +38,7 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             );
 
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
+            push_read_restrictions(&mut summary, readable_roots, deny_read);
             if *network_access {
                 summary.push_str(" (network access enabled)");
             }
@@ -34,3 +46,28 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
         }
     }
 }
+
+fn push_read_restrictions(summary: &mut String, readable_roots: &[PathBuf], deny_read: &[PathBuf]) {
+    if !readable_roots.is_empty() {
+        let mut readable_entries = vec!["workdir".to_string()];
+        readable_entries.extend(
+            readable_roots
+                .iter()
+                .map(|p| p.to_string_lossy().to_string()),
+        );
+        summary.push_str(&format!(
+            " (read access limited to {})",
+            readable_entries.join(", ")
+        ));
+    }
+    if !deny_read.is_empty() {
+        let denied_entries: Vec<String> = deny_read
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        summary.push_str(&format!(
+            " (read access denied to {})",
+            denied_entries.join(", ")
+        ));
+    }
+}
@@ -83,6 +83,7 @@ pub(crate) fn map_api_error(err: ApiError) -> CodexErr {
             TransportError::Network(msg) | TransportError::Build(msg) => {
                 CodexErr::Stream(msg, None)
             }
+            TransportError::Cassette(msg) => CodexErr::Fatal(msg),
         },
         ApiError::RateLimit(msg) => CodexErr::Stream(msg, None),
     }
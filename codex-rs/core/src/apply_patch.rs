@@ -1,6 +1,8 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookEvent;
+use crate::hooks::HookOutcome;
 use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
 use crate::safety::SafetyCheck;
@@ -39,6 +41,19 @@ pub(crate) async fn apply_patch(
     call_id: &str,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    let pre_patch_apply = HookEvent::PrePatchApply {
+        thread_id: sess.conversation_id().to_string(),
+        turn_id: turn_context.sub_id.clone(),
+        cwd: turn_context.cwd.display().to_string(),
+        call_id: call_id.to_string(),
+        changes: convert_apply_patch_to_protocol(&action),
+    };
+    if let HookOutcome::Deny { reason } = sess.hooks().run(pre_patch_apply).await {
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            format!("patch rejected by hook: {reason}"),
+        )));
+    }
+
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
@@ -60,6 +75,31 @@ pub(crate) async fn apply_patch(
             // give the user the option to expand the set of writable roots so
             // that similar patches can be auto-approved in the future during
             // this session.
+            let approval_requested = HookEvent::ApprovalRequested {
+                thread_id: sess.conversation_id().to_string(),
+                turn_id: turn_context.sub_id.clone(),
+                cwd: turn_context.cwd.display().to_string(),
+                call_id: call_id.to_string(),
+                tool_name: "apply_patch".to_string(),
+                command: None,
+                reason: None,
+            };
+            match sess.hooks().run(approval_requested).await {
+                HookOutcome::Allow => {
+                    return InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                        action,
+                        user_explicitly_approved_this_action: true,
+                    });
+                }
+                HookOutcome::Deny { reason } => {
+                    return InternalApplyPatchInvocation::Output(Err(
+                        FunctionCallError::RespondToModel(format!(
+                            "patch rejected by hook: {reason}"
+                        )),
+                    ));
+                }
+                HookOutcome::Continue | HookOutcome::Modify { .. } => {}
+            }
             let rx_approve = sess
                 .request_patch_approval(
                     turn_context,
@@ -0,0 +1,426 @@
+//! Turn and session budgets configured under `[budget]`.
+//!
+//! The tracker accumulates token usage and tool calls as they happen; the
+//! task loop calls [`BudgetTracker::check`] between model requests to decide
+//! whether to warn or to abort the turn.
+//!
+//! Session durations only count time spent in active turns, so a session that
+//! sits idle between user messages does not run out of time. Sub-agents share
+//! their parent's tracker through [`SharedBudget::for_sub_agent`], so their
+//! usage counts against the parent's limits.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use tokio::sync::Mutex;
+use tokio::sync::MutexGuard;
+
+use codex_protocol::protocol::BudgetLimit;
+use codex_protocol::protocol::BudgetScope;
+use codex_protocol::protocol::BudgetWarningEvent;
+use codex_protocol::protocol::TokenUsage;
+
+use crate::config::types::BudgetConfig;
+use crate::config::types::BudgetLimits;
+use crate::config::types::TokenPricing;
+
+const DEFAULT_WARN_AT: f64 = 0.8;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Usage {
+    tokens: i64,
+    tool_calls: u64,
+    cost_usd: f64,
+}
+
+impl Usage {
+    fn add_tokens(&mut self, usage: &TokenUsage, pricing: Option<&TokenPricing>) {
+        self.tokens += usage.total_tokens.max(0);
+        if let Some(pricing) = pricing {
+            self.cost_usd += estimate_cost(usage, pricing);
+        }
+    }
+}
+
+/// A session's handle on its [`BudgetTracker`]. Sub-agents get a handle on
+/// the same tracker that leaves turn boundaries to the parent, since a
+/// sub-agent's work happens inside one of the parent's turns.
+#[derive(Clone)]
+pub(crate) struct SharedBudget {
+    tracker: Arc<Mutex<BudgetTracker>>,
+    owns_turns: bool,
+}
+
+impl SharedBudget {
+    pub(crate) fn new(config: BudgetConfig) -> Self {
+        Self {
+            tracker: Arc::new(Mutex::new(BudgetTracker::new(config))),
+            owns_turns: true,
+        }
+    }
+
+    pub(crate) fn for_sub_agent(&self) -> Self {
+        Self {
+            tracker: Arc::clone(&self.tracker),
+            owns_turns: false,
+        }
+    }
+
+    pub(crate) async fn lock(&self) -> MutexGuard<'_, BudgetTracker> {
+        self.tracker.lock().await
+    }
+
+    pub(crate) async fn start_turn(&self) {
+        if self.owns_turns {
+            self.tracker.lock().await.start_turn();
+        }
+    }
+
+    pub(crate) async fn end_turn(&self) {
+        if self.owns_turns {
+            self.tracker.lock().await.end_turn();
+        }
+    }
+}
+
+pub(crate) struct BudgetTracker {
+    config: BudgetConfig,
+    session: Usage,
+    /// Time spent in turns that already ended.
+    session_active: Duration,
+    /// Start of the running turn, if any.
+    active_since: Option<Instant>,
+    turn: Usage,
+    turn_started: Instant,
+    /// Limits that already produced a soft warning; each warns only once per
+    /// turn (for turn limits) or per session (for session limits).
+    warned: HashSet<(BudgetScope, BudgetLimit)>,
+}
+
+impl BudgetTracker {
+    pub(crate) fn new(config: BudgetConfig) -> Self {
+        let now = Instant::now();
+        Self {
+            config,
+            session: Usage::default(),
+            session_active: Duration::ZERO,
+            active_since: None,
+            turn: Usage::default(),
+            turn_started: now,
+            warned: HashSet::new(),
+        }
+    }
+
+    /// Resets the per-turn counters and starts the session clock. Called when
+    /// a user turn starts.
+    pub(crate) fn start_turn(&mut self) {
+        self.start_turn_at(Instant::now());
+    }
+
+    /// Stops the session clock until the next turn starts.
+    pub(crate) fn end_turn(&mut self) {
+        self.end_turn_at(Instant::now());
+    }
+
+    fn start_turn_at(&mut self, now: Instant) {
+        self.end_turn_at(now);
+        self.turn = Usage::default();
+        self.turn_started = now;
+        self.active_since = Some(now);
+        self.warned
+            .retain(|(scope, _)| *scope == BudgetScope::Session);
+    }
+
+    fn end_turn_at(&mut self, now: Instant) {
+        if let Some(since) = self.active_since.take() {
+            self.session_active += now.saturating_duration_since(since);
+        }
+    }
+
+    pub(crate) fn record_token_usage(&mut self, usage: &TokenUsage) {
+        let pricing = self.config.pricing.as_ref();
+        self.turn.add_tokens(usage, pricing);
+        self.session.add_tokens(usage, pricing);
+    }
+
+    pub(crate) fn record_tool_call(&mut self) {
+        self.turn.tool_calls += 1;
+        self.session.tool_calls += 1;
+    }
+
+    /// Returns the limits that were exhausted, plus any that crossed the
+    /// warning threshold since the last check.
+    pub(crate) fn check(&mut self) -> Vec<BudgetWarningEvent> {
+        self.check_at(Instant::now())
+    }
+
+    fn check_at(&mut self, now: Instant) -> Vec<BudgetWarningEvent> {
+        let warn_at = self.config.warn_at.unwrap_or(DEFAULT_WARN_AT);
+        let session_elapsed = self.session_active
+            + self
+                .active_since
+                .map_or(Duration::ZERO, |since| now.saturating_duration_since(since));
+        let turn_elapsed = now.saturating_duration_since(self.turn_started);
+        let scopes = [
+            (
+                BudgetScope::Session,
+                measurements(&self.config.session, self.session, session_elapsed),
+            ),
+            (
+                BudgetScope::Turn,
+                measurements(&self.config.turn, self.turn, turn_elapsed),
+            ),
+        ];
+
+        let mut events = Vec::new();
+        for (scope, measurements) in scopes {
+            for (limit, used, max) in measurements {
+                let exceeded = used >= max;
+                if !exceeded && (used < max * warn_at || self.warned.contains(&(scope, limit))) {
+                    continue;
+                }
+                if !exceeded {
+                    self.warned.insert((scope, limit));
+                }
+                events.push(BudgetWarningEvent {
+                    scope,
+                    limit,
+                    used,
+                    max,
+                    exceeded,
+                });
+            }
+        }
+        events
+    }
+}
+
+fn measurements(
+    limits: &BudgetLimits,
+    usage: Usage,
+    elapsed: Duration,
+) -> Vec<(BudgetLimit, f64, f64)> {
+    [
+        (
+            BudgetLimit::Tokens,
+            usage.tokens as f64,
+            limits.max_tokens.map(|max| max as f64),
+        ),
+        (
+            BudgetLimit::ToolCalls,
+            usage.tool_calls as f64,
+            limits.max_tool_calls.map(|max| max as f64),
+        ),
+        (
+            BudgetLimit::Duration,
+            elapsed.as_secs_f64(),
+            limits.max_duration_sec.map(|max| max.as_secs_f64()),
+        ),
+        (BudgetLimit::Cost, usage.cost_usd, limits.max_cost_usd),
+    ]
+    .into_iter()
+    .filter_map(|(limit, used, max)| max.map(|max| (limit, used, max)))
+    .collect()
+}
+
+fn estimate_cost(usage: &TokenUsage, pricing: &TokenPricing) -> f64 {
+    let cached_price = pricing
+        .cached_input_per_million
+        .unwrap_or(pricing.input_per_million);
+    let cost = usage.non_cached_input() as f64 * pricing.input_per_million
+        + usage.cached_input() as f64 * cached_price
+        + usage.output_tokens.max(0) as f64 * pricing.output_per_million;
+    cost / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input: i64, cached: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    fn summarize(events: &[BudgetWarningEvent]) -> Vec<(BudgetScope, BudgetLimit, bool)> {
+        events
+            .iter()
+            .map(|event| (event.scope, event.limit, event.exceeded))
+            .collect()
+    }
+
+    #[test]
+    fn warns_once_then_reports_exhaustion() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            turn: BudgetLimits {
+                max_tool_calls: Some(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        for _ in 0..3 {
+            tracker.record_tool_call();
+        }
+        assert_eq!(tracker.check(), Vec::new());
+
+        tracker.record_tool_call();
+        assert_eq!(
+            summarize(&tracker.check()),
+            vec![(BudgetScope::Turn, BudgetLimit::ToolCalls, false)]
+        );
+        assert_eq!(tracker.check(), Vec::new());
+
+        tracker.record_tool_call();
+        assert_eq!(
+            summarize(&tracker.check()),
+            vec![(BudgetScope::Turn, BudgetLimit::ToolCalls, true)]
+        );
+    }
+
+    #[test]
+    fn turn_limits_reset_but_session_limits_accumulate() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            turn: BudgetLimits {
+                max_tokens: Some(1_000),
+                ..Default::default()
+            },
+            session: BudgetLimits {
+                max_tokens: Some(1_500),
+                ..Default::default()
+            },
+            warn_at: Some(1.0),
+            pricing: None,
+        });
+
+        tracker.record_token_usage(&usage(800, 0, 200));
+        assert_eq!(
+            summarize(&tracker.check()),
+            vec![(BudgetScope::Turn, BudgetLimit::Tokens, true)]
+        );
+
+        tracker.start_turn();
+        tracker.record_token_usage(&usage(400, 0, 100));
+        assert_eq!(
+            summarize(&tracker.check()),
+            vec![(BudgetScope::Session, BudgetLimit::Tokens, true)]
+        );
+    }
+
+    #[test]
+    fn estimates_cost_from_pricing() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            session: BudgetLimits {
+                max_cost_usd: Some(1.0),
+                ..Default::default()
+            },
+            pricing: Some(TokenPricing {
+                input_per_million: 2.0,
+                cached_input_per_million: Some(0.5),
+                output_per_million: 8.0,
+            }),
+            ..Default::default()
+        });
+
+        // 100k uncached input, 100k cached input and 50k output:
+        // 0.2 + 0.05 + 0.4 = $0.65.
+        tracker.record_token_usage(&usage(200_000, 100_000, 50_000));
+        let events = tracker.check();
+        assert_eq!(events, Vec::new());
+
+        tracker.record_token_usage(&usage(100_000, 0, 0));
+        let events = tracker.check();
+        assert_eq!(
+            summarize(&events),
+            vec![(BudgetScope::Session, BudgetLimit::Cost, false)]
+        );
+        assert!((events[0].used - 0.85).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reports_elapsed_time() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            session: BudgetLimits {
+                max_duration_sec: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let started = Instant::now();
+        tracker.start_turn_at(started);
+        assert_eq!(
+            tracker.check_at(started + Duration::from_secs(10)),
+            Vec::new()
+        );
+        assert_eq!(
+            summarize(&tracker.check_at(started + Duration::from_secs(61))),
+            vec![(BudgetScope::Session, BudgetLimit::Duration, true)]
+        );
+    }
+
+    #[test]
+    fn session_duration_excludes_idle_time() {
+        let mut tracker = BudgetTracker::new(BudgetConfig {
+            session: BudgetLimits {
+                max_duration_sec: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+            warn_at: Some(1.0),
+            ..Default::default()
+        });
+
+        let started = Instant::now();
+        tracker.start_turn_at(started);
+        tracker.end_turn_at(started + Duration::from_secs(40));
+
+        // An hour of idle time before the next turn does not count.
+        let resumed = started + Duration::from_secs(3_600);
+        assert_eq!(tracker.check_at(resumed), Vec::new());
+        tracker.start_turn_at(resumed);
+        assert_eq!(
+            tracker.check_at(resumed + Duration::from_secs(15)),
+            Vec::new()
+        );
+        assert_eq!(
+            summarize(&tracker.check_at(resumed + Duration::from_secs(21))),
+            vec![(BudgetScope::Session, BudgetLimit::Duration, true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn sub_agents_share_the_parent_budget() {
+        let parent = SharedBudget::new(BudgetConfig {
+            turn: BudgetLimits {
+                max_tokens: Some(1_000),
+                ..Default::default()
+            },
+            warn_at: Some(1.0),
+            ..Default::default()
+        });
+        parent.start_turn().await;
+        parent.lock().await.record_token_usage(&usage(400, 0, 100));
+
+        let sub_agent = parent.for_sub_agent();
+        // The sub-agent's own turn does not reset the parent's turn counters.
+        sub_agent.start_turn().await;
+        sub_agent
+            .lock()
+            .await
+            .record_token_usage(&usage(400, 0, 100));
+        sub_agent.end_turn().await;
+
+        assert_eq!(
+            summarize(&parent.lock().await.check()),
+            vec![(BudgetScope::Turn, BudgetLimit::Tokens, true)]
+        );
+    }
+}
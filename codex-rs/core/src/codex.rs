@@ -1,19 +1,25 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Weak;
 use std::sync::atomic::AtomicU64;
 
 use crate::AuthManager;
 use crate::SandboxState;
+use crate::budget::SharedBudget;
 use crate::client_common::REVIEW_PROMPT;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
-use crate::compact::should_use_remote_compact_task;
-use crate::compact_remote::run_inline_remote_auto_compact_task;
 use crate::exec_policy::load_exec_policy_for_features;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::hooks::HookEvent;
+use crate::hooks::Hooks;
+use crate::lsp::LspManager;
+use crate::network_proxy::HostApprovalRequest;
+use crate::network_proxy::NetworkProxy;
 use crate::openai_models::model_family::ModelFamily;
 use crate::openai_models::models_manager::ModelsManager;
 use crate::parse_command::parse_command;
@@ -34,6 +40,8 @@ use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
+use codex_protocol::protocol::PinnedContextEvent;
+use codex_protocol::protocol::PinnedNote;
 use codex_protocol::protocol::RawResponseItemEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
@@ -57,12 +65,14 @@ use serde_json;
 use serde_json::Value;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
+use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
+use uuid::Uuid;
 
 use crate::ModelProviderInfo;
 use crate::client::ModelClient;
@@ -75,12 +85,15 @@ use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::exec::SandboxType;
 #[cfg(test)]
 use crate::exec::StreamOutput;
 use crate::exec_policy::ExecPolicyUpdateError;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::nested_project_docs::nested_project_doc_items;
 use crate::openai_model_info::get_model_info;
+use crate::pinned_context::append_pinned_context;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
@@ -90,7 +103,9 @@ use crate::protocol::BackgroundEventEvent;
 use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
+use crate::protocol::ExecApprovalDecisionItem;
 use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::NetworkApprovalRequestEvent;
 use crate::protocol::Op;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
@@ -109,6 +124,7 @@ use crate::protocol::WarningEvent;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
+use crate::safety::get_platform_sandbox;
 use crate::shell;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
@@ -170,6 +186,27 @@ impl Codex {
         models_manager: Arc<ModelsManager>,
         conversation_history: InitialHistory,
         session_source: SessionSource,
+    ) -> CodexResult<CodexSpawnOk> {
+        Self::spawn_with_budget(
+            config,
+            auth_manager,
+            models_manager,
+            conversation_history,
+            session_source,
+            None,
+        )
+        .await
+    }
+
+    /// Like [`Codex::spawn`], but charges the session's usage to `budget`
+    /// instead of a fresh tracker. Used for sub-agents.
+    pub(crate) async fn spawn_with_budget(
+        config: Config,
+        auth_manager: Arc<AuthManager>,
+        models_manager: Arc<ModelsManager>,
+        conversation_history: InitialHistory,
+        session_source: SessionSource,
+        budget: Option<SharedBudget>,
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
@@ -210,6 +247,7 @@ impl Codex {
             tx_event.clone(),
             conversation_history,
             session_source_clone,
+            budget,
         )
         .await
         .map_err(|e| {
@@ -377,7 +415,10 @@ impl SessionConfiguration {
         if let Some(approval_policy) = updates.approval_policy {
             next_configuration.approval_policy = approval_policy;
         }
-        if let Some(sandbox_policy) = updates.sandbox_policy.clone() {
+        if let Some(mut sandbox_policy) = updates.sandbox_policy.clone() {
+            // Read restrictions come from config and survive switching modes.
+            sandbox_policy
+                .inherit_read_restrictions(&self.original_config_do_not_use.sandbox_policy);
             next_configuration.sandbox_policy = sandbox_policy;
         }
         if let Some(cwd) = updates.cwd.clone() {
@@ -442,10 +483,13 @@ impl Session {
             session_configuration.session_source.clone(),
         );
 
-        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
             features: &per_turn_config.features,
         });
+        tools_config.enabled_tools = per_turn_config.enabled_tools.clone();
+        tools_config.command_tools = per_turn_config.command_tools.clone();
+        tools_config.include_lsp_tool = !per_turn_config.lsp_servers.is_empty();
 
         TurnContext {
             sub_id,
@@ -470,6 +514,7 @@ impl Session {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn new(
         session_configuration: SessionConfiguration,
         config: Arc<Config>,
@@ -478,6 +523,7 @@ impl Session {
         tx_event: Sender<Event>,
         initial_history: InitialHistory,
         session_source: SessionSource,
+        budget: Option<SharedBudget>,
     ) -> anyhow::Result<Arc<Self>> {
         debug!(
             "Configuring session: model={}; provider={:?}",
@@ -531,6 +577,7 @@ impl Session {
             anyhow::Error::from(e)
         })?;
         let rollout_path = rollout_recorder.rollout_path.clone();
+        crate::rollout::retention::spawn_sweep(&config);
 
         let mut post_session_configured_events = Vec::<Event>::new();
 
@@ -580,11 +627,20 @@ impl Session {
         // Create the mutable state for the Session.
         let state = SessionState::new(session_configuration.clone());
 
+        let (network_proxy, network_approvals) = match start_network_proxy(&config).await {
+            Some((proxy, approvals)) => (Some(proxy), Some(approvals)),
+            None => (None, None),
+        };
+
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(config.notify.clone()),
+            hooks: Hooks::new(config.hooks.clone()),
+            lsp_manager: LspManager::new(config.lsp_servers.clone()),
+            budget: budget.unwrap_or_else(|| SharedBudget::new(config.budget.clone())),
+            network_proxy,
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -604,6 +660,10 @@ impl Session {
             next_internal_sub_id: AtomicU64::new(0),
         });
 
+        if let Some(approvals) = network_approvals {
+            tokio::spawn(handle_network_approvals(Arc::downgrade(&sess), approvals));
+        }
+
         // Dispatch the SessionConfiguredEvent first and then report any errors.
         // If resuming, include converted initial messages in the payload so UIs can render them immediately.
         let initial_messages = initial_history.get_event_msgs();
@@ -660,6 +720,16 @@ impl Session {
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
 
+        let hook_sess = Arc::clone(&sess);
+        let session_start = HookEvent::SessionStart {
+            thread_id: conversation_id.to_string(),
+            cwd: session_configuration.cwd.display().to_string(),
+            model: session_configuration.model.clone(),
+        };
+        tokio::spawn(async move {
+            hook_sess.hooks().run(session_start).await;
+        });
+
         Ok(sess)
     }
 
@@ -734,6 +804,16 @@ impl Session {
                     }
                 }
 
+                // Restore the notes pinned before the session was saved.
+                if let Some(notes) = rollout_items.iter().rev().find_map(|item| match item {
+                    RolloutItem::EventMsg(EventMsg::PinnedContextUpdated(event)) => {
+                        Some(event.notes.clone())
+                    }
+                    _ => None,
+                }) {
+                    self.state.lock().await.pinned_notes = notes;
+                }
+
                 // Always add response items to conversation history
                 let reconstructed_history =
                     self.reconstruct_history_from_rollout(&turn_context, &rollout_items);
@@ -969,17 +1049,31 @@ impl Session {
 
         let parsed_cmd = parse_command(&command);
         let event = EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
-            call_id,
+            call_id: call_id.clone(),
             turn_id: turn_context.sub_id.clone(),
-            command,
-            cwd,
+            command: command.clone(),
+            cwd: cwd.clone(),
             reason,
             risk,
             proposed_execpolicy_amendment,
             parsed_cmd,
         });
         self.send_event(turn_context, event).await;
-        rx_approve.await.unwrap_or_default()
+        let Ok(decision) = rx_approve.await else {
+            return ReviewDecision::default();
+        };
+        // Only decisions the user actually made are kept, so aborted turns do
+        // not show up as denials in `codex execpolicy suggest`.
+        self.persist_rollout_items(&[RolloutItem::ExecApprovalDecision(
+            ExecApprovalDecisionItem {
+                call_id,
+                command,
+                cwd,
+                decision: decision.clone(),
+            },
+        )])
+        .await;
+        decision
     }
 
     pub async fn request_patch_approval(
@@ -1066,12 +1160,34 @@ impl Session {
         self.send_raw_response_items(turn_context, items).await;
     }
 
+    /// Records the project docs for the subdirectories touched by the items
+    /// added to the history since `turn_start`; see
+    /// [`crate::nested_project_docs`].
+    async fn record_nested_project_docs(&self, turn_context: &TurnContext, turn_start: usize) {
+        let history = self.clone_history().await.get_history_for_prompt();
+        let Some(turn_items) = history.get(turn_start..) else {
+            return;
+        };
+        let items = nested_project_doc_items(
+            &turn_context.client.config(),
+            &turn_context.cwd,
+            &turn_context.sandbox_policy,
+            &history,
+            turn_items,
+        )
+        .await;
+        if !items.is_empty() {
+            self.record_conversation_items(turn_context, &items).await;
+        }
+    }
+
     fn reconstruct_history_from_rollout(
         &self,
         turn_context: &TurnContext,
         rollout_items: &[RolloutItem],
     ) -> Vec<ResponseItem> {
         let mut history = ContextManager::new();
+        let mut pinned_notes: &[PinnedNote] = &[];
         for item in rollout_items {
             match item {
                 RolloutItem::ResponseItem(response_item) => {
@@ -1087,14 +1203,18 @@ impl Session {
                         history.replace(replacement.clone());
                     } else {
                         let user_messages = collect_user_messages(&snapshot);
-                        let rebuilt = compact::build_compacted_history(
+                        let mut rebuilt = compact::build_compacted_history(
                             self.build_initial_context(turn_context),
                             &user_messages,
                             &compacted.message,
                         );
+                        append_pinned_context(&mut rebuilt, pinned_notes);
                         history.replace(rebuilt);
                     }
                 }
+                RolloutItem::EventMsg(EventMsg::PinnedContextUpdated(event)) => {
+                    pinned_notes = &event.notes;
+                }
                 _ => {}
             }
         }
@@ -1194,6 +1314,43 @@ impl Session {
         }
     }
 
+    pub(crate) async fn pinned_notes(&self) -> Vec<PinnedNote> {
+        let state = self.state.lock().await;
+        state.pinned_notes.clone()
+    }
+
+    /// Pins `text` to the session and announces the updated notes.
+    pub(crate) async fn pin_note(&self, sub_id: &str, text: String) -> PinnedNote {
+        let (note, notes) = {
+            let mut state = self.state.lock().await;
+            let note = state.pin_note(text);
+            (note, state.pinned_notes.clone())
+        };
+        self.send_pinned_context_updated(sub_id, notes).await;
+        note
+    }
+
+    /// Unpins the note with `id`, returning whether it existed.
+    pub(crate) async fn unpin_note(&self, sub_id: &str, id: u32) -> bool {
+        let (removed, notes) = {
+            let mut state = self.state.lock().await;
+            let removed = state.unpin_note(id);
+            (removed, state.pinned_notes.clone())
+        };
+        if removed {
+            self.send_pinned_context_updated(sub_id, notes).await;
+        }
+        removed
+    }
+
+    async fn send_pinned_context_updated(&self, sub_id: &str, notes: Vec<PinnedNote>) {
+        self.send_event_raw(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::PinnedContextUpdated(PinnedContextEvent { notes }),
+        })
+        .await;
+    }
+
     pub(crate) async fn clone_history(&self) -> ContextManager {
         let state = self.state.lock().await;
         state.clone_history()
@@ -1207,6 +1364,11 @@ impl Session {
         {
             let mut state = self.state.lock().await;
             if let Some(token_usage) = token_usage {
+                self.services
+                    .budget
+                    .lock()
+                    .await
+                    .record_token_usage(token_usage);
                 state.update_token_info_from_usage(
                     token_usage,
                     turn_context.client.get_model_context_window(),
@@ -1452,6 +1614,66 @@ impl Session {
         &self.services.notifier
     }
 
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
+    pub(crate) fn hooks(&self) -> &Hooks {
+        &self.services.hooks
+    }
+
+    pub(crate) fn budget(&self) -> &SharedBudget {
+        &self.services.budget
+    }
+
+    pub(crate) fn network_proxy_addr(&self) -> Option<SocketAddr> {
+        self.services.network_proxy.as_ref().map(NetworkProxy::addr)
+    }
+
+    /// Asks the user whether sandboxed commands may connect to a host that is
+    /// not allowlisted. Denied when no turn is running or the approval policy
+    /// never prompts. The pending approval is keyed by its own call id, so
+    /// prompts for several hosts and the turn's command approvals do not
+    /// replace each other.
+    pub(crate) async fn request_network_approval(&self, host: &str, port: u16) -> ReviewDecision {
+        let call_id = format!("network-{}", Uuid::new_v4());
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let turn_context = {
+            let active = self.active_turn.lock().await;
+            let Some(at) = active.as_ref() else {
+                return ReviewDecision::Denied;
+            };
+            let Some(turn_context) = at
+                .tasks
+                .values()
+                .next()
+                .map(|task| Arc::clone(&task.turn_context))
+            else {
+                return ReviewDecision::Denied;
+            };
+            if turn_context.approval_policy == AskForApproval::Never {
+                return ReviewDecision::Denied;
+            }
+            at.turn_state
+                .lock()
+                .await
+                .insert_pending_approval(call_id.clone(), tx_approve);
+            turn_context
+        };
+
+        let event = EventMsg::NetworkApprovalRequest(NetworkApprovalRequestEvent {
+            call_id,
+            turn_id: turn_context.sub_id.clone(),
+            host: host.to_string(),
+            port,
+            reason: Some(format!(
+                "A sandboxed command wants to connect to {host}:{port}, which is not in network_proxy.allowed_domains."
+            )),
+        });
+        self.send_event(&turn_context, event).await;
+        rx_approve.await.unwrap_or_default()
+    }
+
     pub(crate) fn user_shell(&self) -> &shell::Shell {
         &self.services.user_shell
     }
@@ -1465,6 +1687,48 @@ impl Session {
     }
 }
 
+/// Starts the network proxy when `[network_proxy]` is configured and the
+/// platform sandbox can confine commands to it.
+async fn start_network_proxy(
+    config: &Config,
+) -> Option<(NetworkProxy, mpsc::Receiver<HostApprovalRequest>)> {
+    let proxy_config = config.network_proxy.as_ref()?;
+    if !matches!(
+        get_platform_sandbox(),
+        Some(SandboxType::LinuxSeccomp | SandboxType::MacosSeatbelt)
+    ) {
+        warn!("network_proxy is not supported by this platform's sandbox");
+        return None;
+    }
+    match NetworkProxy::start(proxy_config).await {
+        Ok(started) => Some(started),
+        Err(err) => {
+            warn!("failed to start network proxy: {err}");
+            None
+        }
+    }
+}
+
+async fn handle_network_approvals(
+    sess: Weak<Session>,
+    mut approvals: mpsc::Receiver<HostApprovalRequest>,
+) {
+    while let Some(request) = approvals.recv().await {
+        let sess = sess.clone();
+        // Prompts for different hosts may be pending at the same time.
+        tokio::spawn(async move {
+            let decision = match sess.upgrade() {
+                Some(sess) => {
+                    sess.request_network_approval(&request.host, request.port)
+                        .await
+                }
+                None => ReviewDecision::Denied,
+            };
+            let _ = request.reply.send(decision);
+        });
+    }
+}
+
 async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiver<Submission>) {
     // Seed with context in case there is an OverrideTurnContext first.
     let mut previous_context: Option<Arc<TurnContext>> =
@@ -1509,6 +1773,9 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
+            Op::NetworkApproval { id, decision } => {
+                handlers::network_approval(&sess, id, decision).await;
+            }
             Op::AddToHistory { text } => {
                 handlers::add_to_history(&sess, &config, text).await;
             }
@@ -1522,11 +1789,14 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::GetContextBreakdown => {
+                handlers::get_context_breakdown(&sess, sub.id.clone()).await;
+            }
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
-            Op::Compact => {
-                handlers::compact(&sess, sub.id.clone()).await;
+            Op::Compact { strategy } => {
+                handlers::compact(&sess, sub.id.clone(), strategy).await;
             }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(
@@ -1552,6 +1822,16 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Review { review_request } => {
                 handlers::review(&sess, &config, sub.id.clone(), review_request).await;
             }
+            Op::UpdateSessionLabels { labels } => {
+                handlers::update_session_labels(&sess, labels).await;
+            }
+            Op::PinContext { text } => {
+                handlers::pin_context(&sess, sub.id.clone(), text, previous_context.as_deref())
+                    .await;
+            }
+            Op::UnpinContext { id } => {
+                handlers::unpin_context(&sess, sub.id.clone(), id).await;
+            }
             _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
         }
     }
@@ -1566,13 +1846,17 @@ mod handlers {
 
     use crate::codex::spawn_review_thread;
     use crate::config::Config;
+    use crate::context_manager::estimate_context_breakdown;
     use crate::mcp::auth::compute_auth_statuses;
     use crate::mcp::collect_mcp_snapshot_from_manager;
+    use crate::pinned_context::pinned_context_item;
     use crate::review_prompts::resolve_review_request;
     use crate::tasks::CompactTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
+    use crate::tools::ToolRouter;
+    use codex_protocol::config_types::CompactionStrategy;
     use codex_protocol::custom_prompts::CustomPrompt;
     use codex_protocol::protocol::CodexErrorInfo;
     use codex_protocol::protocol::ErrorEvent;
@@ -1582,6 +1866,8 @@ mod handlers {
     use codex_protocol::protocol::Op;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
+    use codex_protocol::protocol::RolloutItem;
+    use codex_protocol::protocol::SessionLabelsItem;
     use codex_protocol::protocol::TurnAbortReason;
     use codex_protocol::protocol::WarningEvent;
 
@@ -1734,6 +2020,15 @@ mod handlers {
         }
     }
 
+    pub async fn network_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
+        match decision {
+            ReviewDecision::Abort => {
+                sess.interrupt_task().await;
+            }
+            other => sess.notify_approval(&id, other).await,
+        }
+    }
+
     pub async fn add_to_history(sess: &Arc<Session>, config: &Arc<Config>, text: String) {
         let id = sess.conversation_id;
         let config = Arc::clone(config);
@@ -1816,6 +2111,87 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn get_context_breakdown(sess: &Session, sub_id: String) {
+        let turn_context = sess
+            .new_turn_with_sub_id(sub_id.clone(), SessionSettingsUpdate::default())
+            .await;
+        let mcp_tools = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .list_all_tools()
+            .await;
+        let router = ToolRouter::from_config(
+            &turn_context.tools_config,
+            Some(
+                mcp_tools
+                    .iter()
+                    .map(|(name, tool)| (name.clone(), tool.tool.clone()))
+                    .collect(),
+            ),
+        );
+        let base_instructions = turn_context
+            .base_instructions
+            .clone()
+            .unwrap_or_else(|| turn_context.client.get_model_family().base_instructions);
+        let items = sess.clone_history().await.get_history_for_prompt();
+        let mut breakdown =
+            estimate_context_breakdown(&base_instructions, &items, &router.specs(), &mcp_tools);
+        breakdown.model_context_window = turn_context.client.get_model_context_window();
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ContextBreakdown(breakdown),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn update_session_labels(sess: &Session, labels: SessionLabelsItem) {
+        sess.persist_rollout_items(&[RolloutItem::SessionLabels(labels)])
+            .await;
+    }
+
+    pub async fn pin_context(
+        sess: &Session,
+        sub_id: String,
+        text: String,
+        turn_context: Option<&TurnContext>,
+    ) {
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Warning(WarningEvent {
+                    message: "Cannot pin an empty note.".to_string(),
+                }),
+            })
+            .await;
+            return;
+        }
+
+        let note = sess.pin_note(&sub_id, text).await;
+        // Make the note visible to the model right away; compaction re-injects
+        // it later from session state.
+        if let Some(turn_context) = turn_context
+            && let Some(item) = pinned_context_item(std::slice::from_ref(&note))
+        {
+            sess.record_conversation_items(turn_context, &[item]).await;
+        }
+    }
+
+    pub async fn unpin_context(sess: &Session, sub_id: String, id: u32) {
+        if !sess.unpin_note(&sub_id, id).await {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Warning(WarningEvent {
+                    message: format!("No pinned note with id {id}."),
+                }),
+            })
+            .await;
+        }
+    }
+
     pub async fn undo(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1824,7 +2200,11 @@ mod handlers {
             .await;
     }
 
-    pub async fn compact(sess: &Arc<Session>, sub_id: String) {
+    pub async fn compact(
+        sess: &Arc<Session>,
+        sub_id: String,
+        strategy: Option<CompactionStrategy>,
+    ) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
             .await;
@@ -1834,7 +2214,7 @@ mod handlers {
             vec![UserInput::Text {
                 text: turn_context.compact_prompt().to_string(),
             }],
-            CompactTask,
+            CompactTask::new(strategy),
         )
         .await;
     }
@@ -1845,6 +2225,7 @@ mod handlers {
             .unified_exec_manager
             .terminate_all_sessions()
             .await;
+        sess.services.lsp_manager.shutdown().await;
         info!("Shutting down Codex instance");
 
         // Gracefully flush and shutdown rollout recorder on session end so tests
@@ -2044,8 +2425,22 @@ pub(crate) async fn run_task(
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+    sess.budget().start_turn().await;
 
     loop {
+        if budget_exhausted(&sess, &turn_context).await {
+            // Aborting waits for this task to wind down, so it has to be
+            // driven from outside of it.
+            let abort_sess = Arc::clone(&sess);
+            tokio::spawn(async move {
+                abort_sess
+                    .abort_all_tasks(TurnAbortReason::BudgetExceeded)
+                    .await;
+            });
+            cancellation_token.cancelled().await;
+            return None;
+        }
+
         // Note that pending_input would be something like a message the user
         // submitted through the UI while the model was running. Though the UI
         // may support this, the model might not.
@@ -2071,6 +2466,7 @@ pub(crate) async fn run_task(
             })
             .map(|user_message| user_message.message())
             .collect::<Vec<String>>();
+        let turn_start = turn_input.len();
         match run_turn(
             Arc::clone(&sess),
             Arc::clone(&turn_context),
@@ -2092,13 +2488,16 @@ pub(crate) async fn run_task(
                 let total_usage_tokens = sess.get_total_token_usage().await;
                 let token_limit_reached = total_usage_tokens >= limit;
 
-                // as long as compaction works well in getting us way below the token limit, we shouldn't worry about being in an infinite loop.
                 if token_limit_reached {
-                    if should_use_remote_compact_task(&sess) {
-                        run_inline_remote_auto_compact_task(sess.clone(), turn_context.clone())
-                            .await;
-                    } else {
-                        run_inline_auto_compact_task(sess.clone(), turn_context.clone()).await;
+                    let history_before = sess.clone_history().await.get_history();
+                    run_inline_auto_compact_task(sess.clone(), turn_context.clone()).await;
+                    // If compaction left the history as it was, the next
+                    // request would hit the limit again and loop forever.
+                    if sess.clone_history().await.get_history() == history_before {
+                        let event =
+                            EventMsg::Error(CodexErr::ContextWindowExceeded.to_error_event(None));
+                        sess.send_event(&turn_context, event).await;
+                        break;
                     }
                     continue;
                 }
@@ -2110,11 +2509,26 @@ pub(crate) async fn run_task(
                             thread_id: sess.conversation_id.to_string(),
                             turn_id: turn_context.sub_id.clone(),
                             cwd: turn_context.cwd.display().to_string(),
-                            input_messages: turn_input_messages,
+                            input_messages: turn_input_messages.clone(),
                             last_assistant_message: last_agent_message.clone(),
                         });
+                    let hook_sess = Arc::clone(&sess);
+                    let turn_complete = HookEvent::TurnComplete {
+                        thread_id: sess.conversation_id.to_string(),
+                        turn_id: turn_context.sub_id.clone(),
+                        cwd: turn_context.cwd.display().to_string(),
+                        input_messages: turn_input_messages,
+                        last_assistant_message: last_agent_message.clone(),
+                    };
+                    tokio::spawn(async move {
+                        hook_sess.hooks().run(turn_complete).await;
+                    });
                     break;
                 }
+                if sess.enabled(Feature::NestedProjectDocs) {
+                    sess.record_nested_project_docs(&turn_context, turn_start)
+                        .await;
+                }
                 continue;
             }
             Err(CodexErr::TurnAborted) => {
@@ -2141,6 +2555,19 @@ pub(crate) async fn run_task(
     last_agent_message
 }
 
+/// Emits a [`EventMsg::BudgetWarning`] for every budget that crossed its
+/// warning threshold or was exhausted, and reports whether any was exhausted.
+async fn budget_exhausted(sess: &Session, turn_context: &TurnContext) -> bool {
+    let events = sess.budget().lock().await.check();
+    let mut exhausted = false;
+    for event in events {
+        exhausted |= event.exceeded;
+        sess.send_event(turn_context, EventMsg::BudgetWarning(event))
+            .await;
+    }
+    exhausted
+}
+
 async fn run_turn(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
@@ -2494,6 +2921,7 @@ mod tests {
     use crate::config::ConfigOverrides;
     use crate::config::ConfigToml;
     use crate::exec::ExecToolCallOutput;
+    use crate::exec::ResourceLimitKind;
     use crate::function_tool::FunctionCallError;
     use crate::shell::default_user_shell;
     use crate::tools::format_exec_output_str;
@@ -2560,6 +2988,56 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn record_initial_history_restores_pinned_notes_after_compaction() {
+        let (session, turn_context) = make_session_and_context();
+        let note = PinnedNote {
+            id: 1,
+            text: "Never edit files under migrations/.".to_string(),
+        };
+        let user = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "first user".to_string(),
+            }],
+        };
+        let rollout_items = vec![
+            RolloutItem::EventMsg(EventMsg::PinnedContextUpdated(PinnedContextEvent {
+                notes: vec![note.clone()],
+            })),
+            RolloutItem::ResponseItem(user.clone()),
+            RolloutItem::Compacted(CompactedItem {
+                message: "summary".to_string(),
+                replacement_history: None,
+            }),
+        ];
+
+        tokio_test::block_on(session.record_initial_history(InitialHistory::Resumed(
+            ResumedHistory {
+                conversation_id: ConversationId::default(),
+                history: rollout_items,
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            },
+        )));
+
+        let mut expected = compact::build_compacted_history(
+            session.build_initial_context(&turn_context),
+            &collect_user_messages(&[user]),
+            "summary",
+        );
+        append_pinned_context(&mut expected, std::slice::from_ref(&note));
+        let (history, pinned_notes) = tokio_test::block_on(async {
+            let state = session.state.lock().await;
+            (
+                state.clone_history().get_history(),
+                state.pinned_notes.clone(),
+            )
+        });
+        assert_eq!(history, expected);
+        assert_eq!(pinned_notes, vec![note]);
+    }
+
     #[test]
     fn record_initial_history_reconstructs_forked_transcript() {
         let (session, turn_context) = make_session_and_context();
@@ -2750,6 +3228,7 @@ mod tests {
             aggregated_output: StreamOutput::new("Command output".to_string()),
             duration: StdDuration::from_secs(1),
             timed_out: true,
+            resource_limit_exceeded: None,
         };
         let (_, turn_context) = make_session_and_context();
 
@@ -2761,6 +3240,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn includes_resource_limit_message() {
+        let exec = ExecToolCallOutput {
+            exit_code: 137,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new("Command output".to_string()),
+            duration: StdDuration::from_secs(1),
+            timed_out: false,
+            resource_limit_exceeded: Some(ResourceLimitKind::Memory),
+        };
+        let (_, turn_context) = make_session_and_context();
+
+        let out = format_exec_output_str(&exec, turn_context.truncation_policy);
+
+        assert_eq!(out, "command exceeded its memory limit\nCommand output");
+    }
+
     #[test]
     fn falls_back_to_content_when_structured_is_null() {
         let ctr = CallToolResult {
@@ -2884,6 +3381,10 @@ mod tests {
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: Hooks::default(),
+            lsp_manager: LspManager::new(config.lsp_servers.clone()),
+            budget: SharedBudget::new(config.budget.clone()),
+            network_proxy: None,
             rollout: Mutex::new(None),
             user_shell: default_user_shell(),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -2966,6 +3467,10 @@ mod tests {
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: Hooks::default(),
+            lsp_manager: LspManager::new(config.lsp_servers.clone()),
+            budget: SharedBudget::new(config.budget.clone()),
+            network_proxy: None,
             rollout: Mutex::new(None),
             user_shell: default_user_shell(),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -8,6 +8,7 @@ use codex_protocol::protocol::ApplyPatchApprovalRequestEvent;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExecApprovalRequestEvent;
+use codex_protocol::protocol::NetworkApprovalRequestEvent;
 use codex_protocol::protocol::Op;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
@@ -33,8 +34,10 @@ use codex_protocol::protocol::InitialHistory;
 /// The returned `events_rx` yields non-approval events emitted by the sub-agent.
 /// Approval requests are handled via `parent_session` and are not surfaced.
 /// The returned `ops_tx` allows the caller to submit additional `Op`s to the sub-agent.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_codex_conversation_interactive(
     config: Config,
+    source: SubAgentSource,
     auth_manager: Arc<AuthManager>,
     models_manager: Arc<ModelsManager>,
     parent_session: Arc<Session>,
@@ -45,12 +48,13 @@ pub(crate) async fn run_codex_conversation_interactive(
     let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
     let (tx_ops, rx_ops) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
 
-    let CodexSpawnOk { codex, .. } = Codex::spawn(
+    let CodexSpawnOk { codex, .. } = Codex::spawn_with_budget(
         config,
         auth_manager,
         models_manager,
         initial_history.unwrap_or(InitialHistory::New),
-        SessionSource::SubAgent(SubAgentSource::Review),
+        SessionSource::SubAgent(source),
+        Some(parent_session.budget().for_sub_agent()),
     )
     .await?;
     let codex = Arc::new(codex);
@@ -94,6 +98,7 @@ pub(crate) async fn run_codex_conversation_interactive(
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_codex_conversation_one_shot(
     config: Config,
+    source: SubAgentSource,
     auth_manager: Arc<AuthManager>,
     models_manager: Arc<ModelsManager>,
     input: Vec<UserInput>,
@@ -107,6 +112,7 @@ pub(crate) async fn run_codex_conversation_one_shot(
     let child_cancel = cancel_token.child_token();
     let io = run_codex_conversation_interactive(
         config,
+        source,
         auth_manager,
         models_manager,
         parent_session,
@@ -216,6 +222,13 @@ async fn forward_events(
                         )
                         .await;
                     }
+                    Event {
+                        id: _,
+                        msg: EventMsg::NetworkApprovalRequest(event),
+                    } => {
+                        handle_network_approval(&codex, &parent_session, event, &cancel_token)
+                            .await;
+                    }
                     other => {
                         match tx_sub.send(other).or_cancel(&cancel_token).await {
                             Ok(Ok(())) => {}
@@ -322,6 +335,26 @@ async fn handle_patch_approval(
     let _ = codex.submit(Op::PatchApproval { id, decision }).await;
 }
 
+/// Handle a NetworkApprovalRequest by consulting the parent session and replying.
+async fn handle_network_approval(
+    codex: &Codex,
+    parent_session: &Session,
+    event: NetworkApprovalRequestEvent,
+    cancel_token: &CancellationToken,
+) {
+    let decision = parent_session
+        .request_network_approval(&event.host, event.port)
+        .or_cancel(cancel_token)
+        .await
+        .unwrap_or(codex_protocol::protocol::ReviewDecision::Abort);
+    let _ = codex
+        .submit(Op::NetworkApproval {
+            id: event.call_id,
+            decision,
+        })
+        .await;
+}
+
 /// Await an approval decision, aborting on cancellation.
 async fn await_approval_with_cancel<F>(
     fut: F,
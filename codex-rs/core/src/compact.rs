@@ -5,9 +5,13 @@ use crate::client_common::ResponseEvent;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::codex::get_last_assistant_message_from_turn;
+use crate::compact_local::fall_back_to_heuristic_compaction;
+use crate::compact_local::run_inline_local_compact_task;
+use crate::compact_remote::run_inline_remote_auto_compact_task;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::features::Feature;
+use crate::pinned_context::append_pinned_context;
 use crate::protocol::CompactedItem;
 use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
@@ -19,6 +23,7 @@ use crate::truncate::approx_token_count;
 use crate::truncate::truncate_text;
 use crate::util::backoff;
 use codex_app_server_protocol::AuthMode;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
@@ -45,10 +50,18 @@ pub(crate) async fn run_inline_auto_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
 ) {
-    let prompt = turn_context.compact_prompt().to_string();
-    let input = vec![UserInput::Text { text: prompt }];
-
-    run_compact_task_inner(sess, turn_context, input).await;
+    match turn_context.client.config().compaction.strategy {
+        CompactionStrategy::Summarize => {
+            if should_use_remote_compact_task(&sess) {
+                run_inline_remote_auto_compact_task(sess, turn_context).await;
+            } else {
+                let prompt = turn_context.compact_prompt().to_string();
+                let input = vec![UserInput::Text { text: prompt }];
+                run_compact_task_inner(sess, turn_context, input).await;
+            }
+        }
+        strategy => run_inline_local_compact_task(sess, turn_context, strategy).await,
+    }
 }
 
 pub(crate) async fn run_compact_task(
@@ -126,9 +139,7 @@ async fn run_compact_task_inner(
                     retries = 0;
                     continue;
                 }
-                sess.set_total_tokens_full(turn_context.as_ref()).await;
-                let event = EventMsg::Error(e.to_error_event(None));
-                sess.send_event(&turn_context, event).await;
+                fall_back_to_heuristic_compaction(&sess, &turn_context, &e).await;
                 return;
             }
             Err(e) => {
@@ -144,8 +155,7 @@ async fn run_compact_task_inner(
                     tokio::time::sleep(delay).await;
                     continue;
                 } else {
-                    let event = EventMsg::Error(e.to_error_event(None));
-                    sess.send_event(&turn_context, event).await;
+                    fall_back_to_heuristic_compaction(&sess, &turn_context, &e).await;
                     return;
                 }
             }
@@ -166,6 +176,7 @@ async fn run_compact_task_inner(
         .cloned()
         .collect();
     new_history.extend(ghost_snapshots);
+    append_pinned_context(&mut new_history, &sess.pinned_notes().await);
     sess.replace_history(new_history).await;
     sess.recompute_token_usage(&turn_context).await;
 
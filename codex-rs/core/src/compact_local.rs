@@ -0,0 +1,469 @@
+//! Compaction strategies that rewrite history locally instead of asking the
+//! model for a summary.
+
+use std::sync::Arc;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::compact::SUMMARY_PREFIX;
+use crate::compact::content_items_to_text;
+use crate::compact::is_summary_message;
+use crate::context_manager::ContextManager;
+use crate::error::CodexErr;
+use crate::parse_command::shlex_join;
+use crate::pinned_context::append_pinned_context;
+use crate::protocol::CompactedItem;
+use crate::protocol::ContextCompactedEvent;
+use crate::protocol::EventMsg;
+use crate::protocol::TaskStartedEvent;
+use crate::protocol::WarningEvent;
+use crate::tool_call_text::SHELL_TOOL_NAMES;
+use crate::tool_call_text::command_from_arguments;
+use crate::tool_call_text::files_in_patch;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+use codex_protocol::config_types::CompactionStrategy;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
+use serde_json::Value;
+
+/// Tool outputs at least this long are replaced by a placeholder.
+const ELIDE_OUTPUT_MIN_BYTES: usize = 512;
+pub(crate) const ELIDED_OUTPUT_PLACEHOLDER: &str = "[output elided during compaction]";
+
+/// Caps for the sections of a heuristic summary.
+const SUMMARY_MAX_ENTRIES: usize = 20;
+const SUMMARY_ENTRY_MAX_BYTES: usize = 200;
+const SUMMARY_LAST_MESSAGE_MAX_BYTES: usize = 2_000;
+
+pub(crate) async fn run_local_compact_task(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    strategy: CompactionStrategy,
+) {
+    let start_event = EventMsg::TaskStarted(TaskStartedEvent {
+        model_context_window: turn_context.client.get_model_context_window(),
+    });
+    sess.send_event(&turn_context, start_event).await;
+    run_local_compact_task_inner(&sess, &turn_context, strategy).await;
+}
+
+pub(crate) async fn run_inline_local_compact_task(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    strategy: CompactionStrategy,
+) {
+    run_local_compact_task_inner(&sess, &turn_context, strategy).await;
+}
+
+/// Compacts with the heuristic strategy after the model failed to produce a
+/// summary, so the conversation can continue.
+pub(crate) async fn fall_back_to_heuristic_compaction(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    err: &CodexErr,
+) {
+    let warning = EventMsg::Warning(WarningEvent {
+        message: format!(
+            "Summarizing the conversation failed ({err}); compacted it locally instead."
+        ),
+    });
+    sess.send_event(turn_context, warning).await;
+    run_local_compact_task_inner(sess, turn_context, CompactionStrategy::Heuristic).await;
+}
+
+async fn run_local_compact_task_inner(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    strategy: CompactionStrategy,
+) {
+    let mut current = sess.clone_history().await;
+    let tokens_before = current.estimate_token_count(turn_context);
+    let history = current.get_history();
+    let initial_context = sess.build_initial_context(turn_context.as_ref());
+    let keep_recent_turns = turn_context.client.config().compaction.keep_recent_turns;
+    let mut new_history =
+        compact_history_locally(strategy, &history, initial_context, keep_recent_turns);
+    append_pinned_context(&mut new_history, &sess.pinned_notes().await);
+    if !shrinks(&new_history, tokens_before, turn_context) {
+        // Everything worth dropping is in the kept turns, typically a single
+        // turn with large tool outputs, so elide those as well.
+        new_history.iter_mut().for_each(elide_tool_output);
+    }
+    if new_history == history {
+        let warning = EventMsg::Warning(WarningEvent {
+            message: "Compaction could not make the conversation any smaller.".to_string(),
+        });
+        sess.send_event(turn_context, warning).await;
+        return;
+    }
+    sess.replace_history(new_history.clone()).await;
+    sess.recompute_token_usage(turn_context).await;
+
+    let compacted_item = CompactedItem {
+        message: String::new(),
+        replacement_history: Some(new_history),
+    };
+    sess.persist_rollout_items(&[RolloutItem::Compacted(compacted_item)])
+        .await;
+
+    let event = EventMsg::ContextCompacted(ContextCompactedEvent {});
+    sess.send_event(turn_context, event).await;
+}
+
+/// Whether `items` are estimated to take fewer tokens than `tokens_before`.
+fn shrinks(items: &[ResponseItem], tokens_before: Option<i64>, turn_context: &TurnContext) -> bool {
+    let mut candidate = ContextManager::new();
+    candidate.replace(items.to_vec());
+    match (candidate.estimate_token_count(turn_context), tokens_before) {
+        (Some(after), Some(before)) => after < before,
+        _ => true,
+    }
+}
+
+/// Builds the compacted history for `strategy` without calling the model.
+/// `Summarize` needs the model, so it is treated like `Heuristic`.
+pub(crate) fn compact_history_locally(
+    strategy: CompactionStrategy,
+    history: &[ResponseItem],
+    initial_context: Vec<ResponseItem>,
+    keep_recent_turns: usize,
+) -> Vec<ResponseItem> {
+    let split = kept_region_start(history, keep_recent_turns);
+    match strategy {
+        CompactionStrategy::ElideToolOutputs => {
+            let mut new_history = history.to_vec();
+            new_history[..split].iter_mut().for_each(elide_tool_output);
+            new_history
+        }
+        CompactionStrategy::KeepRecent => {
+            let dropped = &history[..split];
+            let note = format!(
+                "{SUMMARY_PREFIX}\n{} earlier turn(s) were dropped during compaction; only the most recent turns are kept verbatim.",
+                count_turns(dropped)
+            );
+            rebuild_history(initial_context, history, split, note)
+        }
+        CompactionStrategy::Summarize | CompactionStrategy::Heuristic => {
+            let summary = heuristic_summary(&history[..split]);
+            rebuild_history(initial_context, history, split, summary)
+        }
+    }
+}
+
+/// Index of the first item of the last `keep_recent_turns` turns, or
+/// `history.len()` when nothing is kept.
+fn kept_region_start(history: &[ResponseItem], keep_recent_turns: usize) -> usize {
+    if keep_recent_turns == 0 {
+        return history.len();
+    }
+    let turn_starts: Vec<usize> = history
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| user_turn_message(item).is_some())
+        .map(|(idx, _)| idx)
+        .collect();
+    match turn_starts.len().checked_sub(keep_recent_turns) {
+        Some(first_kept) => turn_starts[first_kept],
+        None => turn_starts.first().copied().unwrap_or(history.len()),
+    }
+}
+
+/// Text of a real user turn, skipping session prefixes and earlier summaries.
+fn user_turn_message(item: &ResponseItem) -> Option<String> {
+    match crate::event_mapping::parse_turn_item(item) {
+        Some(TurnItem::UserMessage(user)) if !is_summary_message(&user.message()) => {
+            Some(user.message())
+        }
+        _ => None,
+    }
+}
+
+fn count_turns(items: &[ResponseItem]) -> usize {
+    items
+        .iter()
+        .filter(|item| user_turn_message(item).is_some())
+        .count()
+}
+
+/// Fresh initial context, the most recent earlier summary, `summary`, the
+/// kept turns and every ghost snapshot (so `/undo` keeps working).
+fn rebuild_history(
+    mut new_history: Vec<ResponseItem>,
+    history: &[ResponseItem],
+    split: usize,
+    summary: String,
+) -> Vec<ResponseItem> {
+    let (dropped, kept) = history.split_at(split);
+    if let Some(previous_summary) = dropped.iter().rev().find(|item| is_summary_item(item)) {
+        new_history.push(previous_summary.clone());
+    }
+    if count_turns(dropped) > 0 {
+        new_history.push(user_text_message(summary));
+    }
+    new_history.extend(
+        kept.iter()
+            .filter(|item| !matches!(item, ResponseItem::GhostSnapshot { .. }))
+            .cloned(),
+    );
+    new_history.extend(
+        history
+            .iter()
+            .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
+            .cloned(),
+    );
+    new_history
+}
+
+fn is_summary_item(item: &ResponseItem) -> bool {
+    match item {
+        ResponseItem::Message { role, content, .. } if role == "user" => {
+            content_items_to_text(content).is_some_and(|text| is_summary_message(&text))
+        }
+        ResponseItem::CompactionSummary { .. } => true,
+        _ => false,
+    }
+}
+
+fn user_text_message(text: String) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText { text }],
+    }
+}
+
+fn elide_tool_output(item: &mut ResponseItem) {
+    match item {
+        ResponseItem::FunctionCallOutput { output, .. }
+            if output.content.len() >= ELIDE_OUTPUT_MIN_BYTES || output.content_items.is_some() =>
+        {
+            output.content = ELIDED_OUTPUT_PLACEHOLDER.to_string();
+            output.content_items = None;
+        }
+        ResponseItem::CustomToolCallOutput { output, .. }
+            if output.len() >= ELIDE_OUTPUT_MIN_BYTES =>
+        {
+            *output = ELIDED_OUTPUT_PLACEHOLDER.to_string();
+        }
+        _ => {}
+    }
+}
+
+/// Summarizes `items` from what can be read off the history: the user's
+/// requests, the commands that ran, the files patched and the last reply.
+fn heuristic_summary(items: &[ResponseItem]) -> String {
+    let mut requests = Vec::new();
+    let mut commands = Vec::new();
+    let mut files = Vec::new();
+    let mut last_reply = None;
+
+    for item in items {
+        if let Some(message) = user_turn_message(item) {
+            requests.push(message);
+            continue;
+        }
+        match item {
+            ResponseItem::Message { role, content, .. } if role == "assistant" => {
+                if let Some(text) = content_items_to_text(content) {
+                    last_reply = Some(text);
+                }
+            }
+            ResponseItem::FunctionCall {
+                name, arguments, ..
+            } => {
+                let Ok(args) = serde_json::from_str::<Value>(arguments) else {
+                    continue;
+                };
+                if SHELL_TOOL_NAMES.contains(&name.as_str())
+                    && let Some(command) = command_from_arguments(&args)
+                {
+                    collect_patched_files(&command, &mut files);
+                    commands.push(command);
+                } else {
+                    let mut strings = Vec::new();
+                    string_leaves(&args, &mut strings);
+                    for text in strings {
+                        collect_patched_files(text, &mut files);
+                    }
+                }
+            }
+            ResponseItem::CustomToolCall { input, .. } => collect_patched_files(input, &mut files),
+            ResponseItem::LocalShellCall {
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => {
+                let command = shlex_join(&exec.command);
+                collect_patched_files(&command, &mut files);
+                commands.push(command);
+            }
+            _ => {}
+        }
+    }
+
+    let mut summary = format!(
+        "{SUMMARY_PREFIX}\nThis summary was built locally from {} earlier turn(s) without calling the model.\n",
+        requests.len()
+    );
+    push_section(&mut summary, "User requests", &requests);
+    push_section(&mut summary, "Commands run", &commands);
+    push_section(&mut summary, "Files changed", &files);
+    if let Some(reply) = last_reply {
+        summary.push_str("\nLast assistant message:\n");
+        summary.push_str(&truncate_text(
+            &reply,
+            TruncationPolicy::Bytes(SUMMARY_LAST_MESSAGE_MAX_BYTES),
+        ));
+        summary.push('\n');
+    }
+    summary
+}
+
+fn push_section(summary: &mut String, title: &str, entries: &[String]) {
+    if entries.is_empty() {
+        return;
+    }
+    summary.push_str(&format!("\n{title}:\n"));
+    let skipped = entries.len().saturating_sub(SUMMARY_MAX_ENTRIES);
+    if skipped > 0 {
+        summary.push_str(&format!("- ({skipped} earlier entries omitted)\n"));
+    }
+    for entry in &entries[skipped..] {
+        let line = entry.split_whitespace().collect::<Vec<_>>().join(" ");
+        let line = truncate_text(&line, TruncationPolicy::Bytes(SUMMARY_ENTRY_MAX_BYTES));
+        summary.push_str(&format!("- {line}\n"));
+    }
+}
+
+fn string_leaves<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => out.push(text),
+        Value::Array(values) => values.iter().for_each(|value| string_leaves(value, out)),
+        Value::Object(map) => map.values().for_each(|value| string_leaves(value, out)),
+        _ => {}
+    }
+}
+
+/// Adds the paths named by `apply_patch` headers in `text` to `files`.
+fn collect_patched_files(text: &str, files: &mut Vec<String>) {
+    for path in files_in_patch(text) {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn user(text: &str) -> ResponseItem {
+        user_text_message(text.to_string())
+    }
+
+    fn assistant(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn shell_call(call_id: &str, command: &[&str]) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: serde_json::json!({ "command": command }).to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn output(call_id: &str, content: String) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn history() -> Vec<ResponseItem> {
+        vec![
+            user("fix the build"),
+            shell_call("c1", &["cargo", "build"]),
+            output("c1", "x".repeat(2_000)),
+            ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "c2".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch\n*** Update File: src/lib.rs\n@@\n-a\n+b\n*** End Patch"
+                    .to_string(),
+            },
+            ResponseItem::CustomToolCallOutput {
+                call_id: "c2".to_string(),
+                output: "ok".to_string(),
+            },
+            assistant("build fixed"),
+            user("now run the tests"),
+            shell_call("c3", &["cargo", "test"]),
+            output("c3", "y".repeat(2_000)),
+            assistant("tests pass"),
+        ]
+    }
+
+    #[test]
+    fn elide_tool_outputs_only_touches_long_outputs_before_kept_turns() {
+        let history = history();
+        let compacted =
+            compact_history_locally(CompactionStrategy::ElideToolOutputs, &history, vec![], 1);
+
+        let mut expected = history;
+        expected[2] = output("c1", ELIDED_OUTPUT_PLACEHOLDER.to_string());
+        assert_eq!(compacted, expected);
+    }
+
+    #[test]
+    fn keep_recent_drops_earlier_turns_behind_a_note() {
+        let history = history();
+        let initial_context = vec![user("<environment_context>ctx</environment_context>")];
+        let compacted = compact_history_locally(
+            CompactionStrategy::KeepRecent,
+            &history,
+            initial_context.clone(),
+            1,
+        );
+
+        let mut expected = initial_context;
+        expected.push(user(&format!(
+            "{SUMMARY_PREFIX}\n1 earlier turn(s) were dropped during compaction; only the most recent turns are kept verbatim."
+        )));
+        expected.extend(history[6..].iter().cloned());
+        assert_eq!(compacted, expected);
+    }
+
+    #[test]
+    fn heuristic_summary_lists_requests_commands_and_files() {
+        let history = history();
+        let compacted = compact_history_locally(CompactionStrategy::Heuristic, &history, vec![], 1);
+
+        let expected_summary = format!(
+            "{SUMMARY_PREFIX}\nThis summary was built locally from 1 earlier turn(s) without calling the model.\n\
+             \nUser requests:\n- fix the build\n\
+             \nCommands run:\n- cargo build\n\
+             \nFiles changed:\n- src/lib.rs\n\
+             \nLast assistant message:\nbuild fixed\n"
+        );
+        let mut expected = vec![user(&expected_summary)];
+        expected.extend(history[6..].iter().cloned());
+        assert_eq!(compacted, expected);
+    }
+}
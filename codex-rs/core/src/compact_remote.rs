@@ -3,7 +3,10 @@ use std::sync::Arc;
 use crate::Prompt;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::compact_local::fall_back_to_heuristic_compaction;
+use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::pinned_context::append_pinned_context;
 use crate::protocol::CompactedItem;
 use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
@@ -28,11 +31,9 @@ pub(crate) async fn run_remote_compact_task(sess: Arc<Session>, turn_context: Ar
 }
 
 async fn run_remote_compact_task_inner(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
-    if let Err(err) = run_remote_compact_task_inner_impl(sess, turn_context).await {
-        let event = EventMsg::Error(
-            err.to_error_event(Some("Error running remote compact task".to_string())),
-        );
-        sess.send_event(turn_context, event).await;
+    match run_remote_compact_task_inner_impl(sess, turn_context).await {
+        Ok(()) | Err(CodexErr::Interrupted) => {}
+        Err(err) => fall_back_to_heuristic_compaction(sess, turn_context, &err).await,
     }
 }
 
@@ -64,6 +65,7 @@ async fn run_remote_compact_task_inner_impl(
     if !ghost_snapshots.is_empty() {
         new_history.extend(ghost_snapshots);
     }
+    append_pinned_context(&mut new_history, &sess.pinned_notes().await);
     sess.replace_history(new_history.clone()).await;
     sess.recompute_token_usage(turn_context).await;
 
@@ -1,18 +1,29 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::types::BudgetConfig;
+use crate::config::types::CommandToolConfig;
+use crate::config::types::CompactionConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
+use crate::config::types::HooksConfig;
+use crate::config::types::LspServerConfig;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelCassette;
+use crate::config::types::NetworkProxyConfig;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
 use crate::config::types::ReasoningSummaryFormat;
+use crate::config::types::ResourceLimits;
+use crate::config::types::SandboxRead;
 use crate::config::types::SandboxWorkspaceWrite;
+use crate::config::types::SessionsConfig;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::UsageConfig;
 use crate::config_loader::LoadedConfigLayers;
 use crate::config_loader::load_config_as_toml;
 use crate::config_loader::load_config_layers_with_overrides;
@@ -96,6 +107,22 @@ pub struct Config {
 
     pub sandbox_policy: SandboxPolicy,
 
+    /// When set, sandboxed commands without network access can still reach
+    /// the allowlisted domains through a local proxy.
+    pub network_proxy: Option<NetworkProxyConfig>,
+
+    /// Memory, CPU, process and file size limits for commands the agent runs.
+    pub resource_limits: ResourceLimits,
+
+    /// Retention and compression policy for recorded sessions.
+    pub sessions: SessionsConfig,
+
+    /// Per-model prices used by `codex usage` to estimate spend.
+    pub usage: UsageConfig,
+
+    /// Strategy and parameters used to compact conversation history.
+    pub compaction: CompactionConfig,
+
     /// True if the user passed in an override or set a value in config.toml
     /// for either of approval_policy or sandbox_mode.
     pub did_user_set_custom_approval_policy_or_sandbox_mode: bool,
@@ -124,6 +151,14 @@ pub struct Config {
     /// Developer instructions override injected as a separate message.
     pub developer_instructions: Option<String>,
 
+    /// When set, only tools with these names are exposed to the model. Not
+    /// read from `config.toml`; used to restrict sub-agents spawned with the
+    /// `spawn_agent` tool.
+    pub enabled_tools: Option<Vec<String>>,
+
+    /// Command-backed function tools declared under `[tools.custom.<name>]`.
+    pub command_tools: BTreeMap<String, CommandToolConfig>,
+
     /// Compact prompt override.
     pub compact_prompt: Option<String>,
 
@@ -149,6 +184,11 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Lifecycle hooks that run external commands on session, turn, tool and
+    /// approval events. See [`crate::hooks`] for the payload and response
+    /// formats.
+    pub hooks: HooksConfig,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -173,6 +213,12 @@ pub struct Config {
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
+    /// Language servers backing the `lsp` tool, keyed by name.
+    pub lsp_servers: BTreeMap<String, LspServerConfig>,
+
+    /// Token, tool-call, time and cost limits for turns and the session.
+    pub budget: BudgetConfig,
+
     /// Preferred store for MCP OAuth credentials.
     /// keyring: Use an OS-specific keyring service.
     ///          Credentials stored in the keyring will only be readable by Codex unless the user explicitly grants access via OS-level keyring access.
@@ -285,6 +331,10 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
+
+    /// When set, model traffic is recorded to or replayed from this cassette.
+    /// The path is absolute.
+    pub model_cassette: Option<ModelCassette>,
 }
 
 impl Config {
@@ -297,7 +347,10 @@ impl Config {
         let root_value = load_resolved_config(
             &codex_home,
             cli_overrides,
-            crate::config_loader::LoaderOverrides::default(),
+            crate::config_loader::LoaderOverrides {
+                project_cwd: Some(resolve_cwd(overrides.cwd.clone())?),
+                ..Default::default()
+            },
         )
         .await?;
 
@@ -338,16 +391,40 @@ async fn load_resolved_config(
     Ok(apply_overlays(layers, cli_overrides))
 }
 
+fn resolve_cwd(cwd: Option<PathBuf>) -> std::io::Result<PathBuf> {
+    use std::env;
+
+    match cwd {
+        None => {
+            tracing::info!("cwd not set, using current dir");
+            env::current_dir()
+        }
+        Some(p) if p.is_absolute() => Ok(p),
+        Some(p) => {
+            // Resolve relative path against the current working directory.
+            tracing::info!("cwd is relative, resolving against current dir");
+            let mut current = env::current_dir()?;
+            current.push(p);
+            Ok(current)
+        }
+    }
+}
+
 fn apply_overlays(
     layers: LoadedConfigLayers,
     cli_overrides: Vec<(String, TomlValue)>,
 ) -> TomlValue {
     let LoadedConfigLayers {
         mut base,
+        project,
         managed_config,
         managed_preferences,
     } = layers;
 
+    if let Some(project) = project {
+        merge_toml_values(&mut base, &project);
+    }
+
     for (path, value) in cli_overrides.into_iter() {
         apply_toml_override(&mut base, &path, value);
     }
@@ -593,10 +670,34 @@ pub struct ConfigToml {
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Read restrictions applied to the `read-only` and `workspace-write`
+    /// sandbox modes.
+    pub sandbox_read: Option<SandboxRead>,
+
+    /// Domain allowlist for the local network proxy used by sandboxed
+    /// commands that have no network access of their own.
+    pub network_proxy: Option<NetworkProxyConfig>,
+
+    /// Resource limits for commands the agent runs.
+    pub resource_limits: Option<ResourceLimits>,
+
+    /// Retention and compression policy for recorded sessions.
+    pub sessions: Option<SessionsConfig>,
+
+    /// Per-model prices used by `codex usage` to estimate spend.
+    pub usage: Option<UsageConfig>,
+
+    /// Strategy and parameters used to compact conversation history.
+    pub compaction: Option<CompactionConfig>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Lifecycle hooks keyed by event.
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -626,6 +727,14 @@ pub struct ConfigToml {
     #[serde(default)]
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
+    /// Language servers backing the `lsp` tool, keyed by name.
+    #[serde(default)]
+    pub lsp_servers: BTreeMap<String, LspServerConfig>,
+
+    /// Token, tool-call, time and cost limits for turns and the session.
+    #[serde(default)]
+    pub budget: Option<BudgetConfig>,
+
     /// Preferred backend for storing MCP OAuth credentials.
     /// keyring: Use an OS-specific keyring service.
     ///          https://github.com/openai/codex/blob/main/codex-rs/rmcp-client/src/oauth.rs#L2
@@ -752,6 +861,29 @@ impl From<ConfigToml> for UserSavedConfig {
     }
 }
 
+/// Looks up the `[projects]` entry for `resolved_cwd`, falling back to the
+/// root git project so worktrees inherit trust from the main checkout.
+pub(crate) fn find_active_project(
+    projects: &HashMap<String, ProjectConfig>,
+    resolved_cwd: &Path,
+) -> Option<ProjectConfig> {
+    if let Some(project_config) = projects.get(&resolved_cwd.to_string_lossy().to_string()) {
+        return Some(project_config.clone());
+    }
+
+    // If cwd lives inside a git repo/worktree, check whether the root git project
+    // (the primary repository working directory) is trusted. This lets
+    // worktrees inherit trust from the main project.
+    if let Some(repo_root) = resolve_root_git_project_for_trust(resolved_cwd)
+        && let Some(project_config_for_root) =
+            projects.get(&repo_root.to_string_lossy().to_string_lossy().to_string())
+    {
+        return Some(project_config_for_root.clone());
+    }
+
+    None
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ProjectConfig {
     pub trust_level: Option<TrustLevel>,
@@ -775,6 +907,10 @@ pub struct ToolsToml {
     /// Enable the `view_image` tool that lets the agent attach local images.
     #[serde(default)]
     pub view_image: Option<bool>,
+
+    /// Command-backed function tools keyed by tool name.
+    #[serde(default)]
+    pub custom: BTreeMap<String, CommandToolConfig>,
 }
 
 impl From<ToolsToml> for Tools {
@@ -827,6 +963,8 @@ impl ConfigToml {
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
+                    readable_roots: Vec::new(),
+                    deny_read: Vec::new(),
                 },
                 None => SandboxPolicy::new_workspace_write_policy(),
             },
@@ -841,6 +979,18 @@ impl ConfigToml {
             sandbox_policy = SandboxPolicy::new_read_only_policy();
             forced_auto_mode_downgraded_on_windows = true;
         }
+        if let Some(sandbox_read) = self.sandbox_read.as_ref() {
+            let resolve = |paths: &[PathBuf]| -> Vec<PathBuf> {
+                paths
+                    .iter()
+                    .map(|path| resolve_path(resolved_cwd, &expand_home(path)))
+                    .collect()
+            };
+            sandbox_policy.restrict_reads(
+                &resolve(&sandbox_read.readable_roots),
+                &resolve(&sandbox_read.deny),
+            );
+        }
         SandboxPolicyResolution {
             policy: sandbox_policy,
             forced_auto_mode_downgraded_on_windows,
@@ -850,23 +1000,7 @@ impl ConfigToml {
     /// Resolves the cwd to an existing project, or returns None if ConfigToml
     /// does not contain a project corresponding to cwd or a git repo for cwd
     pub fn get_active_project(&self, resolved_cwd: &Path) -> Option<ProjectConfig> {
-        let projects = self.projects.clone().unwrap_or_default();
-
-        if let Some(project_config) = projects.get(&resolved_cwd.to_string_lossy().to_string()) {
-            return Some(project_config.clone());
-        }
-
-        // If cwd lives inside a git repo/worktree, check whether the root git project
-        // (the primary repository working directory) is trusted. This lets
-        // worktrees inherit trust from the main project.
-        if let Some(repo_root) = resolve_root_git_project_for_trust(resolved_cwd)
-            && let Some(project_config_for_root) =
-                projects.get(&repo_root.to_string_lossy().to_string_lossy().to_string())
-        {
-            return Some(project_config_for_root.clone());
-        }
-
-        None
+        find_active_project(&self.projects.clone().unwrap_or_default(), resolved_cwd)
     }
 
     pub fn get_config_profile(
@@ -911,6 +1045,8 @@ pub struct ConfigOverrides {
     pub experimental_sandbox_command_assessment: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Record model traffic to, or replay it from, a cassette file.
+    pub model_cassette: Option<ModelCassette>,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -969,6 +1105,7 @@ impl Config {
             tools_web_search_request: override_tools_web_search_request,
             experimental_sandbox_command_assessment: sandbox_command_assessment_override,
             additional_writable_roots,
+            model_cassette,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1001,24 +1138,7 @@ impl Config {
             crate::safety::set_windows_sandbox_enabled(features.enabled(Feature::WindowsSandbox));
         }
 
-        let resolved_cwd = {
-            use std::env;
-
-            match cwd {
-                None => {
-                    tracing::info!("cwd not set, using current dir");
-                    env::current_dir()?
-                }
-                Some(p) if p.is_absolute() => p,
-                Some(p) => {
-                    // Resolve relative path against the current working directory.
-                    tracing::info!("cwd is relative, resolving against current dir");
-                    let mut current = env::current_dir()?;
-                    current.push(p);
-                    current
-                }
-            }
-        };
+        let resolved_cwd = resolve_cwd(cwd)?;
         let additional_writable_roots: Vec<PathBuf> = additional_writable_roots
             .into_iter()
             .map(|path| {
@@ -1132,6 +1252,16 @@ impl Config {
             }
         });
 
+        let budget = cfg.budget.unwrap_or_default();
+        if budget.pricing.is_none()
+            && (budget.turn.max_cost_usd.is_some() || budget.session.max_cost_usd.is_some())
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "budget.max_cost_usd requires [budget.pricing] to estimate cost",
+            ));
+        }
+
         // Load base instructions override from a file if specified. If the
         // path is relative, resolve it against the effective cwd so the
         // behaviour matches other path-like config values.
@@ -1175,18 +1305,32 @@ impl Config {
             cwd: resolved_cwd,
             approval_policy,
             sandbox_policy,
+            network_proxy: cfg.network_proxy,
+            resource_limits: cfg.resource_limits.unwrap_or_default(),
+            sessions: cfg.sessions.unwrap_or_default(),
+            usage: cfg.usage.unwrap_or_default(),
+            compaction: cfg.compaction.unwrap_or_default(),
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
             notify: cfg.notify,
+            hooks: cfg.hooks.unwrap_or_default(),
             user_instructions,
             base_instructions,
             developer_instructions,
+            enabled_tools: None,
+            command_tools: cfg
+                .tools
+                .as_ref()
+                .map(|tools| tools.custom.clone())
+                .unwrap_or_default(),
             compact_prompt,
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
             mcp_servers: cfg.mcp_servers,
+            lsp_servers: cfg.lsp_servers,
+            budget,
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
@@ -1264,6 +1408,7 @@ impl Config {
                     exporter,
                 }
             },
+            model_cassette,
         };
         Ok(config)
     }
@@ -1358,6 +1503,14 @@ pub fn find_codex_home() -> std::io::Result<PathBuf> {
     Ok(p)
 }
 
+/// Expands a leading `~` to the user's home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
 /// Returns the path to the folder where Codex logs are stored. Does not verify
 /// that the directory exists.
 pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
@@ -1371,9 +1524,12 @@ mod tests {
     use crate::config::edit::ConfigEdit;
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
+    use crate::config::types::BudgetLimits;
     use crate::config::types::HistoryPersistence;
     use crate::config::types::McpServerTransportConfig;
+    use crate::config::types::ModelCassetteMode;
     use crate::config::types::Notifications;
+    use crate::config::types::TokenPricing;
     use crate::features::Feature;
 
     use super::*;
@@ -1428,6 +1584,155 @@ persistence = "none"
         assert!(tui.show_tooltips);
     }
 
+    #[test]
+    fn lsp_servers_are_parsed() -> anyhow::Result<()> {
+        let cfg = r#"
+[lsp_servers.python]
+command = "pyright-langserver"
+args = ["--stdio"]
+file_extensions = ["py", "pyi"]
+request_timeout_sec = 5
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg)?;
+
+        assert_eq!(
+            parsed.lsp_servers,
+            BTreeMap::from([(
+                "python".to_string(),
+                LspServerConfig {
+                    command: "pyright-langserver".to_string(),
+                    args: vec!["--stdio".to_string()],
+                    env: None,
+                    file_extensions: vec!["py".to_string(), "pyi".to_string()],
+                    language_id: None,
+                    initialization_options: None,
+                    startup_timeout_sec: None,
+                    request_timeout_sec: Some(Duration::from_secs(5)),
+                },
+            )])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn budget_is_parsed() -> anyhow::Result<()> {
+        let cfg = r#"
+[budget]
+warn_at = 0.9
+
+[budget.turn]
+max_tool_calls = 50
+max_duration_sec = 600
+
+[budget.session]
+max_cost_usd = 5.0
+
+[budget.pricing]
+input_per_million = 1.25
+output_per_million = 10.0
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg)?;
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.budget,
+            BudgetConfig {
+                turn: BudgetLimits {
+                    max_tool_calls: Some(50),
+                    max_duration_sec: Some(Duration::from_secs(600)),
+                    ..Default::default()
+                },
+                session: BudgetLimits {
+                    max_cost_usd: Some(5.0),
+                    ..Default::default()
+                },
+                warn_at: Some(0.9),
+                pricing: Some(TokenPricing {
+                    input_per_million: 1.25,
+                    cached_input_per_million: None,
+                    output_per_million: 10.0,
+                }),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn budget_cost_limit_requires_pricing() -> anyhow::Result<()> {
+        let cfg = r#"
+[budget.turn]
+max_cost_usd = 1.0
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg)?;
+        let codex_home = TempDir::new()?;
+        let err = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("cost limit without pricing should be rejected");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        Ok(())
+    }
+
+    #[test]
+    fn command_tools_are_parsed_from_tools_table() -> anyhow::Result<()> {
+        let cfg = r#"
+[tools]
+view_image = false
+
+[tools.custom.lint]
+description = "Run the linter"
+command = ["make", "lint", "TARGET={target}"]
+
+[tools.custom.lint.parameters]
+type = "object"
+properties = { target = { type = "string" } }
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg)?;
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            parsed,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.command_tools,
+            BTreeMap::from([(
+                "lint".to_string(),
+                CommandToolConfig {
+                    description: "Run the linter".to_string(),
+                    parameters: Some(serde_json::json!({
+                        "type": "object",
+                        "properties": { "target": { "type": "string" } }
+                    })),
+                    command: vec![
+                        "make".to_string(),
+                        "lint".to_string(),
+                        "TARGET={target}".to_string(),
+                    ],
+                    timeout_ms: None,
+                },
+            )])
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -1470,7 +1775,7 @@ network_access = true  # This should be ignored.
         assert_eq!(
             resolution,
             SandboxPolicyResolution {
-                policy: SandboxPolicy::ReadOnly,
+                policy: SandboxPolicy::new_read_only_policy(),
                 forced_auto_mode_downgraded_on_windows: false,
             }
         );
@@ -1498,7 +1803,7 @@ exclude_slash_tmp = true
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1511,6 +1816,8 @@ exclude_slash_tmp = true
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        readable_roots: Vec::new(),
+                        deny_read: Vec::new(),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1543,7 +1850,7 @@ trust_level = "trusted"
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1556,6 +1863,8 @@ trust_level = "trusted"
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        readable_roots: Vec::new(),
+                        deny_read: Vec::new(),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1563,6 +1872,107 @@ trust_level = "trusted"
         }
     }
 
+    #[test]
+    fn sandbox_read_restricts_derived_policy() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "read-only"
+
+[sandbox_read]
+readable_roots = ["/opt/data"]
+deny = ["/home/user/.ssh", ".env"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let resolution = cfg.derive_sandbox_policy(None, None, &PathBuf::from("/tmp/test"));
+
+        assert_eq!(
+            resolution.policy,
+            SandboxPolicy::ReadOnly {
+                readable_roots: vec![PathBuf::from("/opt/data")],
+                deny_read: vec![
+                    PathBuf::from("/home/user/.ssh"),
+                    PathBuf::from("/tmp/test/.env"),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn network_proxy_allowed_domains_deserialize() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[network_proxy]
+allowed_domains = ["crates.io", "*.crates.io"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        assert_eq!(
+            cfg.network_proxy,
+            Some(NetworkProxyConfig {
+                allowed_domains: vec!["crates.io".to_string(), "*.crates.io".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn resource_limits_deserialize() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[resource_limits]
+memory_mb = 4096
+cpus = 2.5
+max_processes = 512
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        assert_eq!(
+            cfg.resource_limits,
+            Some(ResourceLimits {
+                memory_mb: Some(4096),
+                cpu_time_secs: None,
+                cpus: Some(2.5),
+                max_processes: Some(512),
+                max_file_size_mb: None,
+            })
+        );
+    }
+
+    #[test]
+    fn model_cassette_is_only_set_by_overrides() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let toml = r#"
+[model_cassette]
+mode = "replay"
+path = "fixtures/run.json"
+"#;
+
+        let config = Config::load_from_base_config_with_overrides(
+            toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed"),
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+        assert_eq!(config.model_cassette, None);
+
+        let cassette = ModelCassette {
+            mode: ModelCassetteMode::Replay,
+            path: temp_dir.path().join("run.json"),
+        };
+        let config = Config::load_from_base_config_with_overrides(
+            toml::from_str::<ConfigToml>(toml).expect("TOML deserialization should succeed"),
+            ConfigOverrides {
+                model_cassette: Some(cassette.clone()),
+                ..Default::default()
+            },
+            temp_dir.path().to_path_buf(),
+        )?;
+        assert_eq!(config.model_cassette, Some(cassette));
+        Ok(())
+    }
+
     #[test]
     fn add_dir_override_extends_workspace_writable_roots() -> std::io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -1591,7 +2001,7 @@ trust_level = "trusted"
                 "expected workspace-write request to be downgraded on Windows"
             );
             match config.sandbox_policy {
-                SandboxPolicy::ReadOnly => {}
+                SandboxPolicy::ReadOnly { .. } => {}
                 other => panic!("expected read-only policy on Windows, got {other:?}"),
             }
         } else {
@@ -1764,7 +2174,10 @@ trust_level = "trusted"
         )?;
 
         if cfg!(target_os = "windows") {
-            assert!(matches!(config.sandbox_policy, SandboxPolicy::ReadOnly));
+            assert!(matches!(
+                config.sandbox_policy,
+                SandboxPolicy::ReadOnly { .. }
+            ));
             assert!(config.forced_auto_mode_downgraded_on_windows);
         } else {
             assert!(matches!(
@@ -1860,6 +2273,7 @@ trust_level = "trusted"
 
         let overrides = crate::config_loader::LoaderOverrides {
             managed_config_path: Some(managed_path.clone()),
+            project_cwd: None,
             #[cfg(target_os = "macos")]
             managed_preferences_base64: None,
         };
@@ -1974,6 +2388,7 @@ trust_level = "trusted"
 
         let overrides = crate::config_loader::LoaderOverrides {
             managed_config_path: Some(managed_path),
+            project_cwd: None,
             #[cfg(target_os = "macos")]
             managed_preferences_base64: None,
         };
@@ -2956,14 +3371,22 @@ model_verbosity = "high"
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                network_proxy: None,
+                resource_limits: ResourceLimits::default(),
+                sessions: SessionsConfig::default(),
+                usage: UsageConfig::default(),
+                compaction: CompactionConfig::default(),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: HooksConfig::default(),
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: HashMap::new(),
+                lsp_servers: BTreeMap::new(),
+                budget: BudgetConfig::default(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 model_providers: fixture.model_provider_map.clone(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
@@ -2983,6 +3406,8 @@ model_verbosity = "high"
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
                 base_instructions: None,
                 developer_instructions: None,
+                enabled_tools: None,
+                command_tools: BTreeMap::new(),
                 compact_prompt: None,
                 forced_chatgpt_workspace_id: None,
                 forced_login_method: None,
@@ -3002,6 +3427,7 @@ model_verbosity = "high"
                 animations: true,
                 show_tooltips: true,
                 otel: OtelConfig::default(),
+                model_cassette: None,
             },
             o3_profile_config
         );
@@ -3031,14 +3457,22 @@ model_verbosity = "high"
             model_provider: fixture.openai_chat_completions_provider.clone(),
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            network_proxy: None,
+            resource_limits: ResourceLimits::default(),
+            sessions: SessionsConfig::default(),
+            usage: UsageConfig::default(),
+            compaction: CompactionConfig::default(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
+            lsp_servers: BTreeMap::new(),
+            budget: BudgetConfig::default(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
@@ -3058,6 +3492,8 @@ model_verbosity = "high"
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
+            enabled_tools: None,
+            command_tools: BTreeMap::new(),
             compact_prompt: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
@@ -3077,6 +3513,7 @@ model_verbosity = "high"
             animations: true,
             show_tooltips: true,
             otel: OtelConfig::default(),
+            model_cassette: None,
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -3121,14 +3558,22 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            network_proxy: None,
+            resource_limits: ResourceLimits::default(),
+            sessions: SessionsConfig::default(),
+            usage: UsageConfig::default(),
+            compaction: CompactionConfig::default(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
+            lsp_servers: BTreeMap::new(),
+            budget: BudgetConfig::default(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
@@ -3148,6 +3593,8 @@ model_verbosity = "high"
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
+            enabled_tools: None,
+            command_tools: BTreeMap::new(),
             compact_prompt: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
@@ -3167,6 +3614,7 @@ model_verbosity = "high"
             animations: true,
             show_tooltips: true,
             otel: OtelConfig::default(),
+            model_cassette: None,
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -3197,14 +3645,22 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            network_proxy: None,
+            resource_limits: ResourceLimits::default(),
+            sessions: SessionsConfig::default(),
+            usage: UsageConfig::default(),
+            compaction: CompactionConfig::default(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
+            lsp_servers: BTreeMap::new(),
+            budget: BudgetConfig::default(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
@@ -3224,6 +3680,8 @@ model_verbosity = "high"
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
+            enabled_tools: None,
+            command_tools: BTreeMap::new(),
             compact_prompt: None,
             forced_chatgpt_workspace_id: None,
             forced_login_method: None,
@@ -3243,6 +3701,7 @@ model_verbosity = "high"
             animations: true,
             show_tooltips: true,
             otel: OtelConfig::default(),
+            model_cassette: None,
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
@@ -3414,7 +3873,7 @@ trust_level = "untrusted"
         // Verify that untrusted projects get WorkspaceWrite (or ReadOnly on Windows due to downgrade)
         if cfg!(target_os = "windows") {
             assert!(
-                matches!(resolution.policy, SandboxPolicy::ReadOnly),
+                matches!(resolution.policy, SandboxPolicy::ReadOnly { .. }),
                 "Expected ReadOnly on Windows, got {:?}",
                 resolution.policy
             );
@@ -3546,7 +4005,7 @@ trust_level = "untrusted"
         // Verify that untrusted projects still get WorkspaceWrite sandbox (or ReadOnly on Windows)
         if cfg!(target_os = "windows") {
             assert!(
-                matches!(config.sandbox_policy, SandboxPolicy::ReadOnly),
+                matches!(config.sandbox_policy, SandboxPolicy::ReadOnly { .. }),
                 "Expected ReadOnly on Windows"
             );
         } else {
@@ -3,6 +3,7 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use codex_protocol::config_types::CompactionStrategy;
 use serde::Deserializer;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -193,6 +194,91 @@ pub enum McpServerTransportConfig {
     },
 }
 
+/// A language server the `lsp` tool talks to over stdio, declared under
+/// `[lsp_servers.<name>]`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct LspServerConfig {
+    /// Program that starts the server, e.g. `rust-analyzer`.
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
+
+    /// File extensions (without the leading dot) routed to this server.
+    pub file_extensions: Vec<String>,
+
+    /// `languageId` sent when opening documents. Defaults to the server name.
+    #[serde(default)]
+    pub language_id: Option<String>,
+
+    /// Passed verbatim as `initializationOptions` in the `initialize` request.
+    #[serde(default)]
+    pub initialization_options: Option<serde_json::Value>,
+
+    /// Time allowed for the `initialize` handshake.
+    #[serde(default, with = "option_duration_secs")]
+    pub startup_timeout_sec: Option<Duration>,
+
+    /// Time allowed for each query.
+    #[serde(default, with = "option_duration_secs")]
+    pub request_timeout_sec: Option<Duration>,
+}
+
+/// Spending limits configured under `[budget]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct BudgetConfig {
+    /// Limits for a single turn, i.e. one user submission and all the model
+    /// requests and tool calls it triggers.
+    #[serde(default)]
+    pub turn: BudgetLimits,
+
+    /// Limits for the whole session.
+    #[serde(default)]
+    pub session: BudgetLimits,
+
+    /// Fraction of a limit at which a warning is emitted. Defaults to 0.8.
+    #[serde(default)]
+    pub warn_at: Option<f64>,
+
+    /// Token prices used to estimate cost. Required for `max_cost_usd`.
+    #[serde(default)]
+    pub pricing: Option<TokenPricing>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct BudgetLimits {
+    /// Total tokens reported by the model provider, summed over requests.
+    #[serde(default)]
+    pub max_tokens: Option<i64>,
+
+    #[serde(default)]
+    pub max_tool_calls: Option<u64>,
+
+    /// Wall-clock time.
+    #[serde(default, with = "option_duration_secs")]
+    pub max_duration_sec: Option<Duration>,
+
+    /// Estimated cost in US dollars, derived from `pricing`.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+}
+
+/// Prices in US dollars per million tokens.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct TokenPricing {
+    pub input_per_million: f64,
+
+    /// Defaults to `input_per_million`.
+    #[serde(default)]
+    pub cached_input_per_million: Option<f64>,
+
+    /// Applies to reasoning tokens as well.
+    pub output_per_million: f64,
+}
+
 mod option_duration_secs {
     use serde::Deserialize;
     use serde::Deserializer;
@@ -271,6 +357,81 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// A single lifecycle hook: an external command that receives a JSON payload
+/// describing the event on stdin.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HookConfig {
+    /// Program to run, broken into argv tokens.
+    pub command: Vec<String>,
+
+    /// Restrict tool-related hooks to these tool names. When unset the hook
+    /// runs for every tool.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+
+    /// Maximum time to wait for the hook to exit. Defaults to 60 seconds.
+    pub timeout_ms: Option<u64>,
+}
+
+impl HookConfig {
+    pub fn matches_tool(&self, tool_name: &str) -> bool {
+        match &self.tools {
+            Some(tools) => tools.iter().any(|tool| tool == tool_name),
+            None => true,
+        }
+    }
+}
+
+/// Lifecycle hooks configured under `[hooks]`, grouped by event.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HooksConfig {
+    /// Run once after the session has been configured.
+    #[serde(default)]
+    pub session_start: Vec<HookConfig>,
+
+    /// Run before a tool call is dispatched. May allow, deny or modify the call.
+    #[serde(default)]
+    pub pre_tool_call: Vec<HookConfig>,
+
+    /// Run after a tool call produced its output.
+    #[serde(default)]
+    pub post_tool_call: Vec<HookConfig>,
+
+    /// Run before a patch is applied. May allow or deny the patch.
+    #[serde(default)]
+    pub pre_patch_apply: Vec<HookConfig>,
+
+    /// Run before the user is asked for approval. May approve or deny on the
+    /// user's behalf.
+    #[serde(default)]
+    pub approval_requested: Vec<HookConfig>,
+
+    /// Run after the agent finished processing a user submission.
+    #[serde(default)]
+    pub turn_complete: Vec<HookConfig>,
+}
+
+/// A command-backed function tool declared under `[tools.custom.<name>]`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CommandToolConfig {
+    /// Description shown to the model.
+    #[serde(default)]
+    pub description: String,
+
+    /// JSON Schema for the tool arguments. Must describe an object; defaults
+    /// to an object without properties.
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+
+    /// Program to run, broken into argv tokens. `{name}` placeholders are
+    /// replaced with the argument of the same name declared in `parameters`.
+    pub command: Vec<String>,
+
+    /// Maximum time to wait for the command to exit.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -414,6 +575,183 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_slash_tmp: bool,
 }
 
+/// Read restrictions configured under `[sandbox_read]`. They apply to the
+/// `read-only` and `workspace-write` sandbox modes.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SandboxRead {
+    /// When non-empty, reads are limited to these folders, the working
+    /// directory, the writable roots and the platform's system directories.
+    #[serde(default)]
+    pub readable_roots: Vec<PathBuf>,
+
+    /// Paths that must never be readable, e.g. `~/.ssh`.
+    #[serde(default)]
+    pub deny: Vec<PathBuf>,
+}
+
+/// Settings for the local proxy configured under `[network_proxy]`. Sandboxed
+/// commands without network access can reach `allowed_domains` through it;
+/// other hosts need approval.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct NetworkProxyConfig {
+    /// Hosts that need no approval. `example.com` matches only that host,
+    /// `*.example.com` matches its subdomains.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+}
+
+/// Limits applied to each command the agent runs. Unset fields are not
+/// limited.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct ResourceLimits {
+    /// Memory the command and its children may use, in MiB.
+    pub memory_mb: Option<u64>,
+
+    /// CPU time each process may use, in seconds.
+    pub cpu_time_secs: Option<u64>,
+
+    /// Number of CPUs the command may keep busy at once. Only enforced when
+    /// the command runs in its own cgroup.
+    pub cpus: Option<f64>,
+
+    /// Number of processes the command may run at once.
+    pub max_processes: Option<u64>,
+
+    /// Size of any single file the command writes, in MiB.
+    pub max_file_size_mb: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Retention and compression of recorded sessions, configured under
+/// `[sessions]`. Unset limits are not enforced.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SessionsConfig {
+    /// Delete sessions that have not been updated for this many days.
+    pub max_age_days: Option<u64>,
+
+    /// Delete the oldest sessions once all sessions together take up more
+    /// than this many MiB.
+    pub max_total_mb: Option<u64>,
+
+    /// Never delete the most recent sessions, whatever the other limits say.
+    #[serde(default)]
+    pub keep_recent: usize,
+
+    /// Compress sessions with zstd once they are closed.
+    #[serde(default)]
+    pub compress: bool,
+
+    /// Never delete starred sessions. Defaults to `true`.
+    #[serde(default = "default_true")]
+    pub keep_starred: bool,
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: None,
+            max_total_mb: None,
+            keep_recent: 0,
+            compress: false,
+            keep_starred: true,
+        }
+    }
+}
+
+impl SessionsConfig {
+    /// Whether the background sweep has anything to do.
+    pub fn is_enabled(&self) -> bool {
+        self.compress || self.max_age_days.is_some() || self.max_total_mb.is_some()
+    }
+}
+
+/// How conversation history is compacted, configured under `[compaction]`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CompactionConfig {
+    /// Strategy used for automatic compaction and for `/compact` when no
+    /// strategy is picked.
+    #[serde(default)]
+    pub strategy: CompactionStrategy,
+
+    /// Number of most recent user turns the local strategies keep verbatim.
+    #[serde(default = "default_keep_recent_turns")]
+    pub keep_recent_turns: usize,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            strategy: CompactionStrategy::default(),
+            keep_recent_turns: default_keep_recent_turns(),
+        }
+    }
+}
+
+const fn default_keep_recent_turns() -> usize {
+    3
+}
+
+/// Prices used by `codex usage` to estimate spend, configured under `[usage]`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UsageConfig {
+    /// Prices keyed by model slug. A key also matches any model it is a
+    /// prefix of, e.g. `gpt-5` prices `gpt-5-codex` unless that has its own
+    /// entry.
+    #[serde(default)]
+    pub prices: HashMap<String, ModelPrice>,
+}
+
+impl UsageConfig {
+    /// Price for `model`: an exact match, else the longest matching prefix.
+    pub fn price_for(&self, model: &str) -> Option<&ModelPrice> {
+        self.prices.get(model).or_else(|| {
+            self.prices
+                .iter()
+                .filter(|(key, _)| model.starts_with(key.as_str()))
+                .max_by_key(|(key, _)| key.len())
+                .map(|(_, price)| price)
+        })
+    }
+}
+
+/// Price of a model in USD per million tokens.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+
+    /// Price of input tokens served from the prompt cache. Defaults to
+    /// `input`.
+    pub cached_input: Option<f64>,
+
+    pub output: f64,
+}
+
+/// Whether a model cassette captures live model traffic or serves it back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelCassetteMode {
+    Record,
+    Replay,
+}
+
+/// Records model requests and responses to a file, or replays them from one
+/// without contacting the provider. Only set through [`ConfigOverrides`] by
+/// `codex exec --record`/`--replay`, never from a config file, so a checked-in
+/// config cannot swap the model for canned responses.
+///
+/// [`ConfigOverrides`]: crate::config::ConfigOverrides
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelCassette {
+    pub mode: ModelCassetteMode,
+
+    /// Cassette file; must be absolute.
+    pub path: PathBuf,
+}
+
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
     fn from(sandbox_workspace_write: SandboxWorkspaceWrite) -> Self {
         Self {
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use crate::client_common::tools::ToolSpec;
+use crate::context_manager::history::estimate_reasoning_length;
+use crate::mcp_connection_manager::ToolInfo;
+use crate::skills::render::SKILLS_SECTION_HEADING;
+use crate::skills::render::SKILLS_SECTION_INTRO;
+use crate::truncate::approx_token_count;
+use crate::user_instructions::UserInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ContextBreakdownEvent;
+use codex_protocol::protocol::McpServerContextUsage;
+
+/// Splits the estimated size of a prompt into the categories reported by
+/// `/context`. Items are measured the same way as
+/// [`super::ContextManager::estimate_token_count`], so the totals agree apart
+/// from the tool specs, which that estimate leaves out.
+pub(crate) fn estimate_context_breakdown(
+    base_instructions: &str,
+    items: &[ResponseItem],
+    tools: &[ToolSpec],
+    mcp_tools: &HashMap<String, ToolInfo>,
+) -> ContextBreakdownEvent {
+    let mut breakdown = ContextBreakdownEvent {
+        base_instructions: tokens(base_instructions),
+        ..Default::default()
+    };
+
+    for item in items {
+        add_item(&mut breakdown, item);
+    }
+
+    let mut servers: BTreeMap<&str, McpServerContextUsage> = BTreeMap::new();
+    for spec in tools {
+        let spec_tokens = tokens(&serde_json::to_string(spec).unwrap_or_default());
+        match mcp_tools.get(spec.name()) {
+            Some(info) => {
+                let usage = servers.entry(info.server_name.as_str()).or_insert_with(|| {
+                    McpServerContextUsage {
+                        server: info.server_name.clone(),
+                        tool_count: 0,
+                        tokens: 0,
+                    }
+                });
+                usage.tool_count += 1;
+                usage.tokens = usage.tokens.saturating_add(spec_tokens);
+            }
+            None => breakdown.builtin_tools = breakdown.builtin_tools.saturating_add(spec_tokens),
+        }
+    }
+    breakdown.mcp_servers = servers.into_values().collect();
+
+    breakdown.total_tokens = [
+        breakdown.base_instructions,
+        breakdown.project_docs,
+        breakdown.skills,
+        breakdown.builtin_tools,
+        breakdown.messages,
+        breakdown.reasoning,
+        breakdown.tool_calls,
+        breakdown.tool_outputs,
+        breakdown.images,
+    ]
+    .into_iter()
+    .chain(breakdown.mcp_servers.iter().map(|usage| usage.tokens))
+    .fold(0i64, i64::saturating_add);
+    breakdown
+}
+
+fn add_item(breakdown: &mut ContextBreakdownEvent, item: &ResponseItem) {
+    let item_tokens = match item {
+        ResponseItem::GhostSnapshot { .. } => return,
+        ResponseItem::Reasoning {
+            encrypted_content: Some(content),
+            ..
+        }
+        | ResponseItem::CompactionSummary {
+            encrypted_content: content,
+        } => i64::try_from(estimate_reasoning_length(content.len())).unwrap_or(i64::MAX),
+        item => tokens(&serde_json::to_string(item).unwrap_or_default()),
+    };
+
+    let add = |category: &mut i64, value: i64| *category = category.saturating_add(value);
+    match item {
+        ResponseItem::Message { role, .. } if role == "developer" => {
+            add(&mut breakdown.base_instructions, item_tokens);
+        }
+        ResponseItem::Message { content, .. }
+            if UserInstructions::is_user_instructions(content) =>
+        {
+            let skills = content_text(content)
+                .and_then(|text| text.find(&skills_section_start()).map(|idx| &text[idx..]))
+                .map(tokens)
+                .unwrap_or(0)
+                .min(item_tokens);
+            add(&mut breakdown.skills, skills);
+            add(&mut breakdown.project_docs, item_tokens - skills);
+        }
+        ResponseItem::Message { content, .. } => {
+            let images = content
+                .iter()
+                .map(|content| match content {
+                    ContentItem::InputImage { image_url } => tokens(image_url),
+                    ContentItem::InputText { .. } | ContentItem::OutputText { .. } => 0,
+                })
+                .fold(0i64, i64::saturating_add)
+                .min(item_tokens);
+            add(&mut breakdown.images, images);
+            add(&mut breakdown.messages, item_tokens - images);
+        }
+        ResponseItem::FunctionCallOutput { output, .. } => {
+            let images = output
+                .content_items
+                .iter()
+                .flatten()
+                .map(|content| match content {
+                    FunctionCallOutputContentItem::InputImage { image_url } => tokens(image_url),
+                    FunctionCallOutputContentItem::InputText { .. } => 0,
+                })
+                .fold(0i64, i64::saturating_add)
+                .min(item_tokens);
+            add(&mut breakdown.images, images);
+            add(&mut breakdown.tool_outputs, item_tokens - images);
+        }
+        ResponseItem::CustomToolCallOutput { .. } => add(&mut breakdown.tool_outputs, item_tokens),
+        ResponseItem::FunctionCall { .. }
+        | ResponseItem::CustomToolCall { .. }
+        | ResponseItem::LocalShellCall { .. }
+        | ResponseItem::WebSearchCall { .. } => add(&mut breakdown.tool_calls, item_tokens),
+        ResponseItem::Reasoning { .. } => add(&mut breakdown.reasoning, item_tokens),
+        ResponseItem::CompactionSummary { .. } | ResponseItem::Other => {
+            add(&mut breakdown.messages, item_tokens);
+        }
+        ResponseItem::GhostSnapshot { .. } => {}
+    }
+}
+
+fn skills_section_start() -> String {
+    format!("{SKILLS_SECTION_HEADING}\n{SKILLS_SECTION_INTRO}")
+}
+
+fn content_text(content: &[ContentItem]) -> Option<&str> {
+    content.iter().find_map(|content| match content {
+        ContentItem::InputText { text } => Some(text.as_str()),
+        ContentItem::InputImage { .. } | ContentItem::OutputText { .. } => None,
+    })
+}
+
+fn tokens(text: &str) -> i64 {
+    i64::try_from(approx_token_count(text)).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_common::tools::ResponsesApiTool;
+    use crate::tools::spec::JsonSchema;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    fn function_tool(name: &str) -> ToolSpec {
+        ToolSpec::Function(ResponsesApiTool {
+            name: name.to_string(),
+            description: "does things".to_string(),
+            strict: false,
+            parameters: JsonSchema::Object {
+                properties: BTreeMap::new(),
+                required: None,
+                additional_properties: None,
+            },
+        })
+    }
+
+    fn mcp_tool(server: &str, tool: &str) -> ToolInfo {
+        ToolInfo {
+            server_name: server.to_string(),
+            tool_name: tool.to_string(),
+            tool: mcp_types::Tool {
+                annotations: None,
+                description: None,
+                input_schema: mcp_types::ToolInputSchema {
+                    properties: None,
+                    required: None,
+                    r#type: "object".to_string(),
+                },
+                name: tool.to_string(),
+                output_schema: None,
+                title: None,
+            },
+        }
+    }
+
+    fn user_message(content: Vec<ContentItem>) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content,
+        }
+    }
+
+    #[test]
+    fn attributes_items_and_tools_to_categories() {
+        let project_doc = "Run `just fmt` before committing.";
+        let skills_section = format!("{}\n- lint: run clippy", skills_section_start());
+        let instructions = user_message(vec![ContentItem::InputText {
+            text: format!(
+                "# AGENTS.md instructions for /repo\n\n<INSTRUCTIONS>\n{project_doc}\n\n{skills_section}\n</INSTRUCTIONS>"
+            ),
+        }]);
+        let image_url = format!("data:image/png;base64,{}", "A".repeat(4_000));
+        let prompt = user_message(vec![
+            ContentItem::InputText {
+                text: "what is in this screenshot?".to_string(),
+            },
+            ContentItem::InputImage {
+                image_url: image_url.clone(),
+            },
+        ]);
+        let call = ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: r#"{"command":["cargo","test"]}"#.to_string(),
+            call_id: "call-1".to_string(),
+        };
+        let output = ResponseItem::FunctionCallOutput {
+            call_id: "call-1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "test log ".repeat(1_000),
+                ..Default::default()
+            },
+        };
+        let items = vec![
+            instructions.clone(),
+            prompt.clone(),
+            call.clone(),
+            output.clone(),
+        ];
+        let tools = vec![
+            function_tool("shell"),
+            function_tool("mcp__github__search"),
+            function_tool("mcp__github__open_pr"),
+        ];
+        let mcp_tools = HashMap::from([
+            (
+                "mcp__github__search".to_string(),
+                mcp_tool("github", "search"),
+            ),
+            (
+                "mcp__github__open_pr".to_string(),
+                mcp_tool("github", "open_pr"),
+            ),
+        ]);
+
+        let breakdown = estimate_context_breakdown("You are Codex.", &items, &tools, &mcp_tools);
+
+        let item_tokens = |item: &ResponseItem| tokens(&serde_json::to_string(item).unwrap());
+        let spec_tokens = |spec: &ToolSpec| tokens(&serde_json::to_string(spec).unwrap());
+        let skills = tokens(&format!("{skills_section}\n</INSTRUCTIONS>"));
+        let images = tokens(&image_url);
+        let expected = ContextBreakdownEvent {
+            model_context_window: None,
+            total_tokens: tokens("You are Codex.")
+                + items.iter().map(item_tokens).sum::<i64>()
+                + tools.iter().map(spec_tokens).sum::<i64>(),
+            base_instructions: tokens("You are Codex."),
+            project_docs: item_tokens(&instructions) - skills,
+            skills,
+            builtin_tools: spec_tokens(&tools[0]),
+            mcp_servers: vec![McpServerContextUsage {
+                server: "github".to_string(),
+                tool_count: 2,
+                tokens: spec_tokens(&tools[1]) + spec_tokens(&tools[2]),
+            }],
+            messages: item_tokens(&prompt) - images,
+            reasoning: 0,
+            tool_calls: item_tokens(&call),
+            tool_outputs: item_tokens(&output),
+            images,
+        };
+        assert_eq!(breakdown, expected);
+    }
+}
@@ -283,7 +283,7 @@ fn is_api_message(message: &ResponseItem) -> bool {
     }
 }
 
-fn estimate_reasoning_length(encoded_len: usize) -> usize {
+pub(super) fn estimate_reasoning_length(encoded_len: usize) -> usize {
     encoded_len
         .saturating_mul(3)
         .checked_div(4)
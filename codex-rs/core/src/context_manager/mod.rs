@@ -1,4 +1,6 @@
+mod breakdown;
 mod history;
 mod normalize;
 
+pub(crate) use breakdown::estimate_context_breakdown;
 pub(crate) use history::ContextManager;
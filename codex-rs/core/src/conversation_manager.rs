@@ -12,6 +12,8 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::SessionConfiguredEvent;
 use crate::rollout::RolloutRecorder;
+use codex_git::GhostCommit;
+use codex_git::restore_ghost_commit;
 use codex_protocol::ConversationId;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ResponseItem;
@@ -32,6 +34,21 @@ pub struct NewConversation {
     pub session_configured: SessionConfiguredEvent,
 }
 
+/// Where [`ConversationManager::fork_conversation_at`] cuts the source
+/// conversation. A turn is a user message and everything that follows it up to
+/// the next user message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForkPoint {
+    /// Keep every turn.
+    Latest,
+    /// Keep the first `n` turns and drop the rest.
+    AfterTurns(usize),
+    /// Keep every turn up to and including the one containing the response
+    /// item (message, reasoning, tool call or tool output) with this id or call
+    /// id.
+    AfterItem(String),
+}
+
 /// [`ConversationManager`] is responsible for creating conversations and
 /// maintaining them in memory.
 pub struct ConversationManager {
@@ -212,6 +229,69 @@ impl ConversationManager {
         self.finalize_spawn(codex, conversation_id).await
     }
 
+    /// Fork the conversation recorded at `path` at `point`, starting a new
+    /// conversation with a fresh id.
+    ///
+    /// With `restore_snapshot`, the working tree the session ran in (the `cwd`
+    /// recorded in its rollout, not `config.cwd`) is first restored to the
+    /// ghost snapshot taken when the first dropped turn started, so files
+    /// match the forked history. Nothing is restored when the fork
+    /// keeps every turn; it is an error when the dropped turn has no snapshot.
+    pub async fn fork_conversation_at(
+        &self,
+        point: &ForkPoint,
+        config: Config,
+        path: PathBuf,
+        restore_snapshot: bool,
+    ) -> CodexResult<NewConversation> {
+        let items = RolloutRecorder::get_rollout_history(&path)
+            .await?
+            .get_rollout_items();
+        let ForkCut {
+            cut_idx,
+            ghost_commit,
+        } = resolve_fork_point(&items, point)?;
+
+        if restore_snapshot && cut_idx < items.len() {
+            let Some(ghost_commit) = ghost_commit else {
+                return Err(CodexErr::InvalidRequest(
+                    "no ghost snapshot was recorded for the turn after the fork point".to_string(),
+                ));
+            };
+            let Some(repo_path) = recorded_cwd(&items) else {
+                return Err(CodexErr::InvalidRequest(
+                    "the session did not record its working directory".to_string(),
+                ));
+            };
+            tokio::task::spawn_blocking(move || restore_ghost_commit(&repo_path, &ghost_commit))
+                .await?
+                .map_err(|err| {
+                    CodexErr::Io(std::io::Error::other(format!(
+                        "failed to restore ghost snapshot: {err}"
+                    )))
+                })?;
+        }
+
+        let history = if cut_idx == 0 {
+            InitialHistory::New
+        } else {
+            InitialHistory::Forked(items.into_iter().take(cut_idx).collect())
+        };
+        let CodexSpawnOk {
+            codex,
+            conversation_id,
+        } = Codex::spawn(
+            config,
+            self.auth_manager.clone(),
+            self.models_manager.clone(),
+            history,
+            self.session_source.clone(),
+        )
+        .await?;
+
+        self.finalize_spawn(codex, conversation_id).await
+    }
+
     pub async fn list_models(&self) -> Vec<ModelPreset> {
         self.models_manager.available_models.read().await.clone()
     }
@@ -226,19 +306,7 @@ impl ConversationManager {
 fn truncate_before_nth_user_message(history: InitialHistory, n: usize) -> InitialHistory {
     // Work directly on rollout items, and cut the vector at the nth user message input.
     let items: Vec<RolloutItem> = history.get_rollout_items();
-
-    // Find indices of user message inputs in rollout order.
-    let mut user_positions: Vec<usize> = Vec::new();
-    for (idx, item) in items.iter().enumerate() {
-        if let RolloutItem::ResponseItem(item @ ResponseItem::Message { .. }) = item
-            && matches!(
-                crate::event_mapping::parse_turn_item(item),
-                Some(TurnItem::UserMessage(_))
-            )
-        {
-            user_positions.push(idx);
-        }
-    }
+    let user_positions = user_message_positions(&items);
 
     // If fewer than or equal to n user messages exist, treat as empty (out of range).
     if user_positions.len() <= n {
@@ -256,6 +324,108 @@ fn truncate_before_nth_user_message(history: InitialHistory, n: usize) -> Initia
     }
 }
 
+/// Indices of user message inputs in rollout order.
+fn user_message_positions(items: &[RolloutItem]) -> Vec<usize> {
+    let mut user_positions: Vec<usize> = Vec::new();
+    for (idx, item) in items.iter().enumerate() {
+        if let RolloutItem::ResponseItem(item @ ResponseItem::Message { .. }) = item
+            && matches!(
+                crate::event_mapping::parse_turn_item(item),
+                Some(TurnItem::UserMessage(_))
+            )
+        {
+            user_positions.push(idx);
+        }
+    }
+    user_positions
+}
+
+#[derive(Debug, PartialEq)]
+struct ForkCut {
+    /// Number of rollout items the fork keeps.
+    cut_idx: usize,
+    /// Snapshot taken when the first dropped turn started, if any.
+    ghost_commit: Option<GhostCommit>,
+}
+
+/// The working directory recorded in the session's metadata.
+fn recorded_cwd(items: &[RolloutItem]) -> Option<PathBuf> {
+    items.iter().find_map(|item| match item {
+        RolloutItem::SessionMeta(meta_line) => Some(meta_line.meta.cwd.clone()),
+        _ => None,
+    })
+}
+
+fn resolve_fork_point(items: &[RolloutItem], point: &ForkPoint) -> CodexResult<ForkCut> {
+    let user_positions = user_message_positions(items);
+    let turns = match point {
+        ForkPoint::Latest => user_positions.len(),
+        ForkPoint::AfterTurns(turns) => {
+            if *turns > user_positions.len() {
+                return Err(CodexErr::InvalidRequest(format!(
+                    "cannot fork after turn {turns}: the session has {} turns",
+                    user_positions.len()
+                )));
+            }
+            *turns
+        }
+        ForkPoint::AfterItem(item_id) => {
+            let Some(item_idx) = items.iter().position(|item| match item {
+                RolloutItem::ResponseItem(item) => response_item_has_id(item, item_id),
+                _ => false,
+            }) else {
+                return Err(CodexErr::InvalidRequest(format!(
+                    "no item with id {item_id} in the session"
+                )));
+            };
+            user_positions.partition_point(|&pos| pos <= item_idx)
+        }
+    };
+
+    let Some(&cut_idx) = user_positions.get(turns) else {
+        return Ok(ForkCut {
+            cut_idx: items.len(),
+            ghost_commit: None,
+        });
+    };
+    let next_turn = user_positions
+        .get(turns + 1)
+        .copied()
+        .unwrap_or(items.len());
+    let ghost_commit = items[cut_idx..next_turn]
+        .iter()
+        .find_map(|item| match item {
+            RolloutItem::ResponseItem(ResponseItem::GhostSnapshot { ghost_commit }) => {
+                Some(ghost_commit.clone())
+            }
+            _ => None,
+        });
+    Ok(ForkCut {
+        cut_idx,
+        ghost_commit,
+    })
+}
+
+fn response_item_has_id(item: &ResponseItem, wanted: &str) -> bool {
+    let (id, call_id) = match item {
+        ResponseItem::Message { id, .. } | ResponseItem::WebSearchCall { id, .. } => {
+            (id.as_deref(), None)
+        }
+        ResponseItem::Reasoning { id, .. } => (Some(id.as_str()), None),
+        ResponseItem::LocalShellCall { id, call_id, .. } => (id.as_deref(), call_id.as_deref()),
+        ResponseItem::FunctionCall { id, call_id, .. }
+        | ResponseItem::CustomToolCall { id, call_id, .. } => {
+            (id.as_deref(), Some(call_id.as_str()))
+        }
+        ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => (None, Some(call_id.as_str())),
+        ResponseItem::GhostSnapshot { .. }
+        | ResponseItem::CompactionSummary { .. }
+        | ResponseItem::Other => (None, None),
+    };
+    id == Some(wanted) || call_id == Some(wanted)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -337,6 +507,69 @@ mod tests {
         assert_matches!(truncated2, InitialHistory::New);
     }
 
+    #[test]
+    fn resolves_fork_points_by_turn_and_item_id() {
+        let snapshot = |id: &str| ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+        };
+        let call = ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: "call-1".to_string(),
+        };
+        let items: Vec<RolloutItem> = vec![
+            user_msg("u1"),
+            snapshot("g1"),
+            call,
+            assistant_msg("a1"),
+            user_msg("u2"),
+            snapshot("g2"),
+            assistant_msg("a2"),
+        ]
+        .into_iter()
+        .map(RolloutItem::ResponseItem)
+        .collect();
+
+        let cut = resolve_fork_point(&items, &ForkPoint::AfterTurns(1)).expect("turn");
+        assert_eq!(cut.cut_idx, 4);
+        assert_eq!(cut.ghost_commit.as_ref().map(GhostCommit::id), Some("g2"));
+
+        let by_item =
+            resolve_fork_point(&items, &ForkPoint::AfterItem("call-1".to_string())).expect("item");
+        assert_eq!(by_item, cut);
+
+        let all = resolve_fork_point(&items, &ForkPoint::AfterTurns(2)).expect("all turns");
+        assert_eq!(
+            all,
+            ForkCut {
+                cut_idx: items.len(),
+                ghost_commit: None,
+            }
+        );
+
+        assert!(resolve_fork_point(&items, &ForkPoint::AfterTurns(3)).is_err());
+        assert!(resolve_fork_point(&items, &ForkPoint::AfterItem("missing".to_string())).is_err());
+    }
+
+    #[test]
+    fn snapshots_are_restored_into_the_recorded_working_directory() {
+        use codex_protocol::protocol::SessionMeta;
+        use codex_protocol::protocol::SessionMetaLine;
+
+        let meta = RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                cwd: PathBuf::from("/work/repo"),
+                ..Default::default()
+            },
+            git: None,
+        });
+        let items = vec![meta, RolloutItem::ResponseItem(user_msg("u1"))];
+
+        assert_eq!(recorded_cwd(&items), Some(PathBuf::from("/work/repo")));
+        assert_eq!(recorded_cwd(&items[1..]), None);
+    }
+
     #[test]
     fn ignores_session_prefix_messages_when_truncating() {
         let (session, turn_context) = make_session_and_context();
@@ -1,4 +1,5 @@
 use crate::exec::ExecToolCallOutput;
+use crate::exec::ResourceLimitKind;
 use crate::token_data::KnownPlan;
 use crate::token_data::PlanType;
 use crate::truncate::TruncationPolicy;
@@ -51,6 +52,13 @@ pub enum SandboxErr {
     #[error("command was killed by a signal")]
     Signal(i32),
 
+    /// Command ran into one of the configured resource limits
+    #[error("command exceeded its {limit} limit")]
+    ResourceLimit {
+        limit: ResourceLimitKind,
+        output: Box<ExecToolCallOutput>,
+    },
+
     /// Error from linux landlock
     #[error("Landlock was not able to fully enforce all sandbox rules")]
     LandlockRestrict,
@@ -515,6 +523,9 @@ pub fn get_error_message_ui(e: &CodexErr) -> String {
                 output.duration.as_millis()
             )
         }
+        CodexErr::Sandbox(SandboxErr::ResourceLimit { limit, .. }) => {
+            format!("error: command exceeded its {limit} limit")
+        }
         _ => e.to_string(),
     };
 
@@ -595,6 +606,7 @@ mod tests {
             aggregated_output: StreamOutput::new("aggregate detail".to_string()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -611,6 +623,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -627,6 +640,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(8),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -670,6 +684,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(5),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -2,6 +2,7 @@
 use std::os::unix::process::ExitStatusExt;
 
 use std::collections::HashMap;
+use std::fmt;
 use std::io;
 use std::path::Path;
 use std::path::PathBuf;
@@ -10,12 +11,14 @@ use std::time::Duration;
 use std::time::Instant;
 
 use async_channel::Sender;
+use serde::Serialize;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
 use tokio::io::BufReader;
 use tokio::process::Child;
 use tokio_util::sync::CancellationToken;
 
+use crate::config::types::ResourceLimits;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
@@ -25,6 +28,7 @@ use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
 use crate::protocol::ExecOutputStream;
 use crate::protocol::SandboxPolicy;
+use crate::resource_limits::CommandLimits;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
@@ -174,6 +178,8 @@ pub async fn process_exec_tool_call(
             sandbox_type,
             sandbox_cwd,
             codex_linux_sandbox_exe.as_ref(),
+            None,
+            ResourceLimits::default(),
         )
         .map_err(CodexErr::from)?;
 
@@ -195,6 +201,7 @@ pub(crate) async fn execute_exec_env(
         with_escalated_permissions,
         justification,
         arg0,
+        resource_limits,
     } = env;
 
     let params = ExecParams {
@@ -208,7 +215,14 @@ pub(crate) async fn execute_exec_env(
     };
 
     let start = Instant::now();
-    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream).await;
+    let raw_output_result = exec(
+        params,
+        sandbox,
+        sandbox_policy,
+        resource_limits,
+        stdout_stream,
+    )
+    .await;
     let duration = start.elapsed();
     finalize_exec_result(raw_output_result, sandbox, duration)
 }
@@ -294,6 +308,7 @@ async fn exec_windows_sandbox(
         stderr,
         aggregated_output,
         timed_out: capture.timed_out,
+        resource_limit_exceeded: None,
     })
 }
 
@@ -306,19 +321,23 @@ fn finalize_exec_result(
         Ok(raw_output) => {
             #[allow(unused_mut)]
             let mut timed_out = raw_output.timed_out;
+            let resource_limit_exceeded = raw_output.resource_limit_exceeded;
+            #[allow(unused_mut)]
+            let mut exit_code = raw_output.exit_status.code().unwrap_or(-1);
 
             #[cfg(target_family = "unix")]
             {
                 if let Some(signal) = raw_output.exit_status.signal() {
                     if signal == TIMEOUT_CODE {
                         timed_out = true;
+                    } else if resource_limit_exceeded.is_some() {
+                        exit_code = EXIT_CODE_SIGNAL_BASE + signal;
                     } else {
                         return Err(CodexErr::Sandbox(SandboxErr::Signal(signal)));
                     }
                 }
             }
 
-            let mut exit_code = raw_output.exit_status.code().unwrap_or(-1);
             if timed_out {
                 exit_code = EXEC_TIMEOUT_EXIT_CODE;
             }
@@ -333,6 +352,7 @@ fn finalize_exec_result(
                 aggregated_output,
                 duration,
                 timed_out,
+                resource_limit_exceeded,
             };
 
             if timed_out {
@@ -341,6 +361,15 @@ fn finalize_exec_result(
                 }));
             }
 
+            if let Some(limit) = resource_limit_exceeded
+                && exit_code != 0
+            {
+                return Err(CodexErr::Sandbox(SandboxErr::ResourceLimit {
+                    limit,
+                    output: Box::new(exec_output),
+                }));
+            }
+
             if is_likely_sandbox_denied(sandbox_type, &exec_output) {
                 return Err(CodexErr::Sandbox(SandboxErr::Denied {
                     output: Box::new(exec_output),
@@ -450,6 +479,7 @@ struct RawExecToolCallOutput {
     pub stderr: StreamOutput<Vec<u8>>,
     pub aggregated_output: StreamOutput<Vec<u8>>,
     pub timed_out: bool,
+    pub resource_limit_exceeded: Option<ResourceLimitKind>,
 }
 
 impl StreamOutput<String> {
@@ -483,6 +513,30 @@ pub struct ExecToolCallOutput {
     pub aggregated_output: StreamOutput<String>,
     pub duration: Duration,
     pub timed_out: bool,
+    /// Set when the command ran into one of the configured resource limits.
+    pub resource_limit_exceeded: Option<ResourceLimitKind>,
+}
+
+/// A `[resource_limits]` limit that a command ran into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceLimitKind {
+    Memory,
+    CpuTime,
+    Processes,
+    FileSize,
+}
+
+impl fmt::Display for ResourceLimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ResourceLimitKind::Memory => "memory",
+            ResourceLimitKind::CpuTime => "CPU time",
+            ResourceLimitKind::Processes => "process count",
+            ResourceLimitKind::FileSize => "file size",
+        };
+        f.write_str(name)
+    }
 }
 
 impl Default for ExecToolCallOutput {
@@ -494,6 +548,7 @@ impl Default for ExecToolCallOutput {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::ZERO,
             timed_out: false,
+            resource_limit_exceeded: None,
         }
     }
 }
@@ -503,6 +558,7 @@ async fn exec(
     params: ExecParams,
     sandbox: SandboxType,
     sandbox_policy: &SandboxPolicy,
+    resource_limits: ResourceLimits,
     stdout_stream: Option<StdoutStream>,
 ) -> Result<RawExecToolCallOutput> {
     #[cfg(target_os = "windows")]
@@ -527,6 +583,7 @@ async fn exec(
         ))
     })?;
     let arg0_ref = arg0.as_deref();
+    let command_limits = CommandLimits::new(resource_limits);
     let child = spawn_child_async(
         PathBuf::from(program),
         args.into(),
@@ -535,9 +592,14 @@ async fn exec(
         sandbox_policy,
         StdioPolicy::RedirectForShellTool,
         env,
+        command_limits.as_ref(),
     )
     .await?;
-    consume_truncated_output(child, expiration, stdout_stream).await
+    let mut raw_output = consume_truncated_output(child, expiration, stdout_stream).await?;
+    raw_output.resource_limit_exceeded = command_limits
+        .as_ref()
+        .and_then(|limits| limits.exceeded(raw_output.exit_status));
+    Ok(raw_output)
 }
 
 /// Consumes the output of a child process, truncating it so it is suitable for
@@ -658,6 +720,7 @@ async fn consume_truncated_output(
         stderr,
         aggregated_output,
         timed_out,
+        resource_limit_exceeded: None,
     })
 }
 
@@ -779,6 +842,7 @@ mod tests {
             aggregated_output: StreamOutput::new(aggregated.to_string()),
             duration: Duration::from_millis(1),
             timed_out: false,
+            resource_limit_exceeded: None,
         }
     }
 
@@ -862,7 +926,14 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::new_read_only_policy(),
+            ResourceLimits::default(),
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
@@ -927,6 +998,92 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(unix)]
+    async fn exec_with_limits(
+        script: &str,
+        resource_limits: ResourceLimits,
+    ) -> Result<ExecToolCallOutput> {
+        let env = ExecEnv {
+            command: vec!["/bin/sh".to_string(), "-c".to_string(), script.to_string()],
+            cwd: std::env::current_dir()?,
+            env: std::env::vars().collect(),
+            expiration: 10_000.into(),
+            sandbox: SandboxType::None,
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+            resource_limits,
+        };
+        execute_exec_env(env, &SandboxPolicy::DangerFullAccess, None).await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reports_exceeded_file_size_limit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let target = dir.path().join("big");
+        let result = exec_with_limits(
+            &format!("exec head -c 2097152 /dev/zero > '{}'", target.display()),
+            ResourceLimits {
+                max_file_size_mb: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        let (limit, output) = match result {
+            Err(CodexErr::Sandbox(SandboxErr::ResourceLimit { limit, output })) => (limit, output),
+            other => panic!("expected resource limit error, got {other:?}"),
+        };
+        assert_eq!(limit, ResourceLimitKind::FileSize);
+        assert_eq!(
+            output.resource_limit_exceeded,
+            Some(ResourceLimitKind::FileSize)
+        );
+        assert_eq!(std::fs::metadata(&target)?.len(), 1024 * 1024);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn reports_exceeded_cpu_time_limit() -> Result<()> {
+        let result = exec_with_limits(
+            "while :; do :; done",
+            ResourceLimits {
+                cpu_time_secs: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        let output = match result {
+            Err(CodexErr::Sandbox(SandboxErr::ResourceLimit { output, .. })) => output,
+            other => panic!("expected resource limit error, got {other:?}"),
+        };
+        assert_eq!(
+            output.resource_limit_exceeded,
+            Some(ResourceLimitKind::CpuTime)
+        );
+        assert!(!output.timed_out);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn limits_do_not_affect_commands_within_them() -> Result<()> {
+        let output = exec_with_limits(
+            "echo hello",
+            ResourceLimits {
+                cpu_time_secs: Some(5),
+                max_file_size_mb: Some(1),
+                ..Default::default()
+            },
+        )
+        .await?;
+        assert_eq!(output.exit_code, 0);
+        assert_eq!(output.resource_limit_exceeded, None);
+        assert_eq!(output.stdout.text, "hello\n");
+        Ok(())
+    }
+
     #[cfg(unix)]
     fn long_running_command() -> Vec<String> {
         vec![
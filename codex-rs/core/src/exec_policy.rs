@@ -1,20 +1,28 @@
+use std::collections::HashMap;
+use std::fmt;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
 use crate::command_safety::is_dangerous_command::requires_initial_appoval;
+use crate::command_safety::is_safe_command::is_known_safe_command;
 use codex_execpolicy::AmendError;
+use codex_execpolicy::ConsultedRule;
 use codex_execpolicy::Decision;
 use codex_execpolicy::Error as ExecPolicyRuleError;
 use codex_execpolicy::Evaluation;
+use codex_execpolicy::MatchContext;
 use codex_execpolicy::Policy;
 use codex_execpolicy::PolicyParser;
 use codex_execpolicy::RuleMatch;
+use codex_execpolicy::SandboxMode;
 use codex_execpolicy::blocking_append_allow_prefix_rule;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::SandboxPolicy;
+use serde::Serialize;
 use thiserror::Error;
 use tokio::fs;
 use tokio::sync::RwLock;
@@ -73,7 +81,7 @@ pub enum ExecPolicyUpdateError {
     FeatureDisabled,
 }
 
-pub(crate) async fn load_exec_policy_for_features(
+pub async fn load_exec_policy_for_features(
     features: &Features,
     codex_home: &Path,
 ) -> Result<Policy, ExecPolicyError> {
@@ -116,7 +124,8 @@ pub async fn load_exec_policy(codex_home: &Path) -> Result<Policy, ExecPolicyErr
     Ok(policy)
 }
 
-pub(crate) fn default_policy_path(codex_home: &Path) -> PathBuf {
+/// The file Codex appends rules to when the user approves an execpolicy amendment.
+pub fn default_policy_path(codex_home: &Path) -> PathBuf {
     codex_home.join(POLICY_DIR_NAME).join(DEFAULT_POLICY_FILE)
 }
 
@@ -197,24 +206,48 @@ fn derive_prompt_reason(evaluation: &Evaluation) -> Option<String> {
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn create_exec_approval_requirement_for_command(
     exec_policy: &Arc<RwLock<Policy>>,
     features: &Features,
     command: &[String],
+    cwd: &Path,
+    env: &HashMap<String, String>,
     approval_policy: AskForApproval,
     sandbox_policy: &SandboxPolicy,
     sandbox_permissions: SandboxPermissions,
 ) -> ExecApprovalRequirement {
-    let commands = parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+    let policy = exec_policy.read().await;
+    exec_approval_requirement(
+        &policy,
+        features,
+        command,
+        cwd,
+        env,
+        approval_policy,
+        sandbox_policy,
+        sandbox_permissions,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn exec_approval_requirement(
+    policy: &Policy,
+    features: &Features,
+    command: &[String],
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    approval_policy: AskForApproval,
+    sandbox_policy: &SandboxPolicy,
+    sandbox_permissions: SandboxPermissions,
+) -> ExecApprovalRequirement {
+    let commands = commands_to_evaluate(command);
     let heuristics_fallback = |cmd: &[String]| {
-        if requires_initial_appoval(approval_policy, sandbox_policy, cmd, sandbox_permissions) {
-            Decision::Prompt
-        } else {
-            Decision::Allow
-        }
+        heuristics_decision(approval_policy, sandbox_policy, cmd, sandbox_permissions)
     };
-    let policy = exec_policy.read().await;
-    let evaluation = policy.check_multiple(commands.iter(), &heuristics_fallback);
+    let context = match_context(cwd, env, sandbox_policy);
+    let evaluation =
+        policy.check_multiple_with_context(commands.iter(), &context, &heuristics_fallback);
     let has_policy_allow = evaluation.matched_rules.iter().any(|rule_match| {
         !matches!(rule_match, RuleMatch::HeuristicsRuleMatch { .. })
             && rule_match.decision() == Decision::Allow
@@ -246,6 +279,249 @@ pub(crate) async fn create_exec_approval_requirement_for_command(
     }
 }
 
+/// Splits `bash -lc` scripts into their plain commands; anything else is evaluated as is.
+fn commands_to_evaluate(command: &[String]) -> Vec<Vec<String>> {
+    parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()])
+}
+
+fn heuristics_decision(
+    approval_policy: AskForApproval,
+    sandbox_policy: &SandboxPolicy,
+    command: &[String],
+    sandbox_permissions: SandboxPermissions,
+) -> Decision {
+    if requires_initial_appoval(
+        approval_policy,
+        sandbox_policy,
+        command,
+        sandbox_permissions,
+    ) {
+        Decision::Prompt
+    } else {
+        Decision::Allow
+    }
+}
+
+fn match_context(
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    sandbox_policy: &SandboxPolicy,
+) -> MatchContext {
+    MatchContext {
+        cwd: Some(cwd.to_path_buf()),
+        workspace_roots: sandbox_policy
+            .get_writable_roots_with_cwd(cwd)
+            .into_iter()
+            .map(|writable_root| writable_root.root)
+            .collect(),
+        env: Some(env.clone()),
+        sandbox_mode: Some(match sandbox_policy {
+            SandboxPolicy::ReadOnly { .. } => SandboxMode::ReadOnly,
+            SandboxPolicy::WorkspaceWrite { .. } => SandboxMode::WorkspaceWrite,
+            SandboxPolicy::DangerFullAccess => SandboxMode::DangerFullAccess,
+        }),
+    }
+}
+
+/// Step-by-step account of how a command's approval requirement was decided, for
+/// `codex execpolicy explain` and the approval overlay.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandExplanation {
+    pub command: Vec<String>,
+    /// True when `command` was a `bash -lc` script whose commands were evaluated separately.
+    pub split_shell_script: bool,
+    pub inner_commands: Vec<InnerCommandExplanation>,
+    /// Strictest decision across `inner_commands`.
+    pub decision: Decision,
+    /// What Codex does with the command under the current approval policy.
+    pub outcome: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InnerCommandExplanation {
+    pub command: Vec<String>,
+    /// Every rule defined for the command's program, matching or not.
+    pub rules: Vec<ConsultedRule>,
+    /// The `command_safety` fallback, used when no rule matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heuristics: Option<HeuristicsExplanation>,
+    pub decision: Decision,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeuristicsExplanation {
+    pub known_safe: bool,
+    pub might_be_dangerous: bool,
+    pub decision: Decision,
+}
+
+/// Explains how [`Policy`] and the `command_safety` heuristics decide whether `command` needs
+/// approval, mirroring the checks Codex runs before executing it.
+#[allow(clippy::too_many_arguments)]
+pub fn explain_command(
+    policy: &Policy,
+    features: &Features,
+    command: &[String],
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    approval_policy: AskForApproval,
+    sandbox_policy: &SandboxPolicy,
+    sandbox_permissions: SandboxPermissions,
+) -> CommandExplanation {
+    let commands = commands_to_evaluate(command);
+    let split_shell_script = commands.as_slice() != [command.to_vec()];
+    let context = match_context(cwd, env, sandbox_policy);
+    let inner_commands: Vec<InnerCommandExplanation> = commands
+        .into_iter()
+        .map(|cmd| {
+            let rules = policy.consulted_rules(&cmd, &context);
+            let rule_decision = rules
+                .iter()
+                .filter_map(|rule| rule.rule_match.as_ref().map(RuleMatch::decision))
+                .max();
+            let heuristics = rule_decision.is_none().then(|| HeuristicsExplanation {
+                known_safe: is_known_safe_command(&cmd),
+                might_be_dangerous: command_might_be_dangerous(&cmd),
+                decision: heuristics_decision(
+                    approval_policy,
+                    sandbox_policy,
+                    &cmd,
+                    sandbox_permissions,
+                ),
+            });
+            let decision = rule_decision
+                .or(heuristics.as_ref().map(|heuristics| heuristics.decision))
+                .unwrap_or(Decision::Allow);
+            InnerCommandExplanation {
+                command: cmd,
+                rules,
+                heuristics,
+                decision,
+            }
+        })
+        .collect();
+    let decision = inner_commands
+        .iter()
+        .map(|inner| inner.decision)
+        .max()
+        .unwrap_or(Decision::Allow);
+    let outcome = match exec_approval_requirement(
+        policy,
+        features,
+        command,
+        cwd,
+        env,
+        approval_policy,
+        sandbox_policy,
+        sandbox_permissions,
+    ) {
+        ExecApprovalRequirement::Skip {
+            bypass_sandbox: true,
+        } => "runs without approval, outside the sandbox".to_string(),
+        ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+        } => "runs without approval, inside the sandbox".to_string(),
+        ExecApprovalRequirement::NeedsApproval {
+            reason: Some(reason),
+            ..
+        } => format!("asks for approval: {reason}"),
+        ExecApprovalRequirement::NeedsApproval { reason: None, .. } => {
+            "asks for approval: command_safety heuristics flagged this command".to_string()
+        }
+        ExecApprovalRequirement::Forbidden { reason } => format!("rejected: {reason}"),
+    };
+
+    CommandExplanation {
+        command: command.to_vec(),
+        split_shell_script,
+        inner_commands,
+        decision,
+        outcome,
+    }
+}
+
+impl fmt::Display for CommandExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Command: {}", join_command(&self.command))?;
+        if self.split_shell_script {
+            writeln!(
+                f,
+                "Shell script split into {} command(s):",
+                self.inner_commands.len()
+            )?;
+        }
+        for inner in &self.inner_commands {
+            writeln!(f, "  {}", join_command(&inner.command))?;
+            if inner.rules.is_empty() {
+                writeln!(
+                    f,
+                    "    no rules for `{}`",
+                    inner.command.first().map_or("", String::as_str)
+                )?;
+            }
+            for rule in &inner.rules {
+                let source = rule.source.as_ref().map_or_else(
+                    || "rule added this session".to_string(),
+                    ToString::to_string,
+                );
+                match &rule.rule_match {
+                    Some(RuleMatch::PrefixRuleMatch {
+                        matched_prefix,
+                        decision,
+                    }) => writeln!(
+                        f,
+                        "    {source}: matched `{}` -> {}",
+                        join_command(matched_prefix),
+                        decision_name(*decision)
+                    )?,
+                    Some(RuleMatch::HeuristicsRuleMatch { decision, .. }) => {
+                        writeln!(f, "    {source}: matched -> {}", decision_name(*decision))?
+                    }
+                    None => writeln!(f, "    {source}: no match")?,
+                }
+            }
+            if let Some(heuristics) = &inner.heuristics {
+                writeln!(
+                    f,
+                    "    heuristics: {}, {} -> {}",
+                    if heuristics.known_safe {
+                        "known safe"
+                    } else {
+                        "not known safe"
+                    },
+                    if heuristics.might_be_dangerous {
+                        "might be dangerous"
+                    } else {
+                        "not flagged as dangerous"
+                    },
+                    decision_name(heuristics.decision)
+                )?;
+            }
+        }
+        write!(
+            f,
+            "Decision: {} ({})",
+            decision_name(self.decision),
+            self.outcome
+        )
+    }
+}
+
+fn join_command(command: &[String]) -> String {
+    shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "))
+}
+
+fn decision_name(decision: Decision) -> &'static str {
+    match decision {
+        Decision::Allow => "allow",
+        Decision::Prompt => "prompt",
+        Decision::Forbidden => "forbidden",
+    }
+}
+
 async fn collect_policy_files(dir: &Path) -> Result<Vec<PathBuf>, ExecPolicyError> {
     let mut read_dir = match fs::read_dir(dir).await {
         Ok(read_dir) => read_dir,
@@ -297,6 +573,7 @@ mod tests {
     use super::*;
     use crate::features::Feature;
     use crate::features::Features;
+    use codex_execpolicy::RuleSource;
     use codex_protocol::protocol::AskForApproval;
     use codex_protocol::protocol::SandboxPolicy;
     use pretty_assertions::assert_eq;
@@ -413,6 +690,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &policy,
             &Features::with_defaults(),
             &forbidden_script,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::OnRequest,
             &SandboxPolicy::DangerFullAccess,
             SandboxPermissions::UseDefault,
@@ -427,6 +706,70 @@ prefix_rule(pattern=["rm"], decision="forbidden")
         );
     }
 
+    #[tokio::test]
+    async fn path_constraints_use_the_command_cwd_and_writable_roots() {
+        let policy_src = r#"
+prefix_rule(pattern=["rm"], paths_within=["workspace"])
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.codexpolicy", policy_src)
+            .expect("parse policy");
+        let policy = Arc::new(RwLock::new(parser.build()));
+        let cwd = tempdir().expect("create cwd");
+        let shared = tempdir().expect("create writable root");
+        let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![shared.path().to_path_buf()],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            readable_roots: vec![],
+            deny_read: vec![],
+        };
+        let requirement_for = |target: String| {
+            let policy = Arc::clone(&policy);
+            let cwd = cwd.path().to_path_buf();
+            let sandbox_policy = sandbox_policy.clone();
+            async move {
+                create_exec_approval_requirement_for_command(
+                    &policy,
+                    &Features::with_defaults(),
+                    &["rm".to_string(), "-rf".to_string(), target],
+                    &cwd,
+                    &HashMap::new(),
+                    AskForApproval::OnRequest,
+                    &sandbox_policy,
+                    SandboxPermissions::UseDefault,
+                )
+                .await
+            }
+        };
+
+        assert_eq!(
+            requirement_for("build".to_string()).await,
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: true
+            }
+        );
+        assert_eq!(
+            requirement_for(shared.path().join("cache").display().to_string()).await,
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: true
+            }
+        );
+        assert_eq!(
+            requirement_for("/".to_string()).await,
+            ExecApprovalRequirement::NeedsApproval {
+                reason: None,
+                proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
+                    "rm".to_string(),
+                    "-rf".to_string(),
+                    "/".to_string(),
+                ])),
+            }
+        );
+    }
+
     #[tokio::test]
     async fn exec_approval_requirement_prefers_execpolicy_match() {
         let policy_src = r#"prefix_rule(pattern=["rm"], decision="prompt")"#;
@@ -441,6 +784,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &policy,
             &Features::with_defaults(),
             &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::OnRequest,
             &SandboxPolicy::DangerFullAccess,
             SandboxPermissions::UseDefault,
@@ -470,6 +815,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &policy,
             &Features::with_defaults(),
             &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::Never,
             &SandboxPolicy::DangerFullAccess,
             SandboxPermissions::UseDefault,
@@ -493,8 +840,10 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &empty_policy,
             &Features::with_defaults(),
             &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -527,6 +876,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 &policy,
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
+                &HashMap::new(),
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -598,8 +949,10 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &empty_policy,
             &Features::with_defaults(),
             &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -624,8 +977,10 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &Arc::new(RwLock::new(Policy::empty())),
             &features,
             &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -653,6 +1008,8 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &policy,
             &Features::with_defaults(),
             &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::OnRequest,
             &SandboxPolicy::DangerFullAccess,
             SandboxPermissions::UseDefault,
@@ -679,8 +1036,10 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &Arc::new(RwLock::new(Policy::empty())),
             &Features::with_defaults(),
             &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -717,8 +1076,10 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 &policy,
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
+                &HashMap::new(),
                 AskForApproval::UnlessTrusted,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await,
@@ -730,4 +1091,164 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             }
         );
     }
+
+    #[tokio::test]
+    async fn conditions_use_the_command_env_and_sandbox_mode() {
+        let policy_src = r#"
+prefix_rule(pattern=["kubectl"], decision="prompt", env={"KUBECONFIG": "*prod*"})
+prefix_rule(pattern=["terraform", "apply"], decision="forbidden", sandbox=["danger-full-access"])
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.codexpolicy", policy_src)
+            .expect("parse policy");
+        let policy = Arc::new(RwLock::new(parser.build()));
+        let requirement_for =
+            |command: &[&str], kubeconfig: &str, sandbox_policy: SandboxPolicy| {
+                let policy = Arc::clone(&policy);
+                let command: Vec<String> = command.iter().map(ToString::to_string).collect();
+                let env = HashMap::from([("KUBECONFIG".to_string(), kubeconfig.to_string())]);
+                async move {
+                    create_exec_approval_requirement_for_command(
+                        &policy,
+                        &Features::with_defaults(),
+                        &command,
+                        Path::new("/workspace"),
+                        &env,
+                        AskForApproval::OnRequest,
+                        &sandbox_policy,
+                        SandboxPermissions::UseDefault,
+                    )
+                    .await
+                }
+            };
+
+        assert_eq!(
+            requirement_for(
+                &["kubectl", "get", "pods"],
+                "/home/me/.kube/prod",
+                SandboxPolicy::new_read_only_policy()
+            )
+            .await,
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some(PROMPT_REASON.to_string()),
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        assert_eq!(
+            requirement_for(
+                &["kubectl", "get", "pods"],
+                "/home/me/.kube/dev",
+                SandboxPolicy::new_read_only_policy()
+            )
+            .await,
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: false
+            }
+        );
+        assert_eq!(
+            requirement_for(&["terraform", "apply"], "", SandboxPolicy::DangerFullAccess).await,
+            ExecApprovalRequirement::Forbidden {
+                reason: FORBIDDEN_REASON.to_string(),
+            }
+        );
+        assert_eq!(
+            requirement_for(
+                &["terraform", "apply"],
+                "",
+                SandboxPolicy::new_read_only_policy()
+            )
+            .await,
+            ExecApprovalRequirement::Skip {
+                bypass_sandbox: false
+            }
+        );
+    }
+
+    #[test]
+    fn explain_command_reports_rules_and_heuristics_per_inner_command() {
+        let policy_src = r#"
+prefix_rule(pattern=["npm", "run"], decision="allow")
+prefix_rule(pattern=["npm", "test"], decision="prompt")
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("team.codexpolicy", policy_src)
+            .expect("parse policy");
+        let policy = parser.build();
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "npm test && ls".to_string(),
+        ];
+
+        let explanation = explain_command(
+            &policy,
+            &Features::with_defaults(),
+            &command,
+            Path::new("/workspace"),
+            &HashMap::new(),
+            AskForApproval::OnRequest,
+            &SandboxPolicy::new_workspace_write_policy(),
+            SandboxPermissions::UseDefault,
+        );
+
+        assert_eq!(
+            explanation,
+            CommandExplanation {
+                command,
+                split_shell_script: true,
+                inner_commands: vec![
+                    InnerCommandExplanation {
+                        command: vec!["npm".to_string(), "test".to_string()],
+                        rules: vec![
+                            ConsultedRule {
+                                source: Some(RuleSource {
+                                    file: "team.codexpolicy".to_string(),
+                                    line: 2,
+                                }),
+                                rule_match: None,
+                            },
+                            ConsultedRule {
+                                source: Some(RuleSource {
+                                    file: "team.codexpolicy".to_string(),
+                                    line: 3,
+                                }),
+                                rule_match: Some(RuleMatch::PrefixRuleMatch {
+                                    matched_prefix: vec!["npm".to_string(), "test".to_string()],
+                                    decision: Decision::Prompt,
+                                }),
+                            },
+                        ],
+                        heuristics: None,
+                        decision: Decision::Prompt,
+                    },
+                    InnerCommandExplanation {
+                        command: vec!["ls".to_string()],
+                        rules: vec![],
+                        heuristics: Some(HeuristicsExplanation {
+                            known_safe: true,
+                            might_be_dangerous: false,
+                            decision: Decision::Allow,
+                        }),
+                        decision: Decision::Allow,
+                    },
+                ],
+                decision: Decision::Prompt,
+                outcome: format!("asks for approval: {PROMPT_REASON}"),
+            }
+        );
+        assert_eq!(
+            explanation.to_string(),
+            "Command: bash -lc 'npm test && ls'
+Shell script split into 2 command(s):
+  npm test
+    team.codexpolicy:2: no match
+    team.codexpolicy:3: matched `npm test` -> prompt
+  ls
+    no rules for `ls`
+    heuristics: known safe, not flagged as dangerous -> allow
+Decision: prompt (asks for approval: execpolicy requires approval for this command)"
+        );
+    }
 }
@@ -58,6 +58,12 @@ pub enum Feature {
     ParallelToolCalls,
     /// Experimental skills injection (CLI flag-driven).
     Skills,
+    /// Expose the `spawn_agent` tool for delegating tasks to sub-agents.
+    SpawnAgent,
+    /// Expose the `remember` tool for pinning notes that survive compaction.
+    RememberTool,
+    /// Load project docs from the subdirectories the agent works in.
+    NestedProjectDocs,
 }
 
 impl Feature {
@@ -345,4 +351,22 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::SpawnAgent,
+        key: "spawn_agent",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::RememberTool,
+        key: "remember_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::NestedProjectDocs,
+        key: "nested_project_docs",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
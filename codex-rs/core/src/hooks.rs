@@ -0,0 +1,476 @@
+//! Lifecycle hooks: user-configured commands that run on session, turn, tool
+//! and approval events.
+//!
+//! Every hook receives a JSON-serialized [`HookEvent`] on stdin. Hooks for the
+//! blocking events (`pre_tool_call`, `pre_patch_apply` and
+//! `approval_requested`) may print a JSON [`HookResponse`] on stdout:
+//!
+//! ```json
+//! {"decision": "deny", "reason": "generated files are read-only"}
+//! ```
+//!
+//! A non-zero exit status without a JSON response denies the action, using
+//! stderr as the reason. Hooks that fail to start or time out are logged and
+//! ignored so a broken hook never wedges the session. Output of the
+//! informational events (`session_start`, `post_tool_call`, `turn_complete`)
+//! is ignored, and `session_start` and `turn_complete` hooks run in the
+//! background without delaying the session. Only `pre_tool_call` hooks can
+//! `modify`; that decision is logged and ignored for every other event.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config::types::HookConfig;
+use crate::config::types::HooksConfig;
+use crate::protocol::FileChange;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Payload written to a hook's stdin.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub(crate) enum HookEvent {
+    #[serde(rename_all = "kebab-case")]
+    SessionStart {
+        thread_id: String,
+        cwd: String,
+        model: String,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PreToolCall {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        call_id: String,
+        tool_name: String,
+        arguments: serde_json::Value,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PostToolCall {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        call_id: String,
+        tool_name: String,
+        success: bool,
+        output: String,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PrePatchApply {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        call_id: String,
+        changes: HashMap<PathBuf, FileChange>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    ApprovalRequested {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        call_id: String,
+        tool_name: String,
+        command: Option<Vec<String>>,
+        reason: Option<String>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    TurnComplete {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        input_messages: Vec<String>,
+        last_assistant_message: Option<String>,
+    },
+}
+
+impl HookEvent {
+    /// Config key of the event, as used under `[hooks]`.
+    fn kind(&self) -> &'static str {
+        match self {
+            HookEvent::SessionStart { .. } => "session_start",
+            HookEvent::PreToolCall { .. } => "pre_tool_call",
+            HookEvent::PostToolCall { .. } => "post_tool_call",
+            HookEvent::PrePatchApply { .. } => "pre_patch_apply",
+            HookEvent::ApprovalRequested { .. } => "approval_requested",
+            HookEvent::TurnComplete { .. } => "turn_complete",
+        }
+    }
+
+    fn tool_name(&self) -> Option<&str> {
+        match self {
+            HookEvent::PreToolCall { tool_name, .. }
+            | HookEvent::PostToolCall { tool_name, .. }
+            | HookEvent::ApprovalRequested { tool_name, .. } => Some(tool_name),
+            HookEvent::PrePatchApply { .. } => Some("apply_patch"),
+            HookEvent::SessionStart { .. } | HookEvent::TurnComplete { .. } => None,
+        }
+    }
+}
+
+/// Optional JSON document a hook may print on stdout.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub(crate) struct HookResponse {
+    #[serde(default)]
+    pub decision: Option<HookDecision>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Replacement tool arguments; only honoured for `pre_tool_call`.
+    #[serde(default)]
+    pub arguments: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HookDecision {
+    Allow,
+    Deny,
+    Modify,
+}
+
+/// Combined result of every hook configured for an event.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum HookOutcome {
+    /// No hook expressed an opinion.
+    Continue,
+    /// At least one hook explicitly allowed the action and none denied it.
+    Allow,
+    /// A hook denied the action.
+    Deny { reason: String },
+    /// A hook rewrote the tool arguments.
+    Modify { arguments: serde_json::Value },
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Hooks {
+    config: HooksConfig,
+}
+
+impl Hooks {
+    pub(crate) fn new(config: HooksConfig) -> Self {
+        Self { config }
+    }
+
+    pub(crate) fn has_pre_tool_call(&self, tool_name: &str) -> bool {
+        self.config
+            .pre_tool_call
+            .iter()
+            .any(|hook| hook.matches_tool(tool_name))
+    }
+
+    pub(crate) fn has_post_tool_call(&self, tool_name: &str) -> bool {
+        self.config
+            .post_tool_call
+            .iter()
+            .any(|hook| hook.matches_tool(tool_name))
+    }
+
+    /// Runs the hooks configured for `event` in order and folds their
+    /// responses. The first denial wins; a `modify` response rewrites the
+    /// arguments seen by subsequent hooks.
+    pub(crate) async fn run(&self, mut event: HookEvent) -> HookOutcome {
+        let hooks = self.hooks_for(&event);
+        let blocking = matches!(
+            event,
+            HookEvent::PreToolCall { .. }
+                | HookEvent::PrePatchApply { .. }
+                | HookEvent::ApprovalRequested { .. }
+        );
+
+        let mut outcome = HookOutcome::Continue;
+        for hook in hooks {
+            if let Some(tool_name) = event.tool_name()
+                && !hook.matches_tool(tool_name)
+            {
+                continue;
+            }
+            let Some(response) = run_hook(hook, &event).await else {
+                continue;
+            };
+            if response.decision == Some(HookDecision::Modify)
+                && !matches!(event, HookEvent::PreToolCall { .. })
+            {
+                warn!(
+                    "ignoring `modify` from {} hook {:?}: only pre_tool_call hooks can modify",
+                    event.kind(),
+                    hook.command
+                );
+                continue;
+            }
+            if !blocking {
+                continue;
+            }
+            match response.decision {
+                Some(HookDecision::Deny) => {
+                    let reason = response
+                        .reason
+                        .unwrap_or_else(|| "denied by hook".to_string());
+                    return HookOutcome::Deny { reason };
+                }
+                Some(HookDecision::Modify) => {
+                    if let (HookEvent::PreToolCall { arguments, .. }, Some(new_arguments)) =
+                        (&mut event, response.arguments)
+                    {
+                        *arguments = new_arguments.clone();
+                        outcome = HookOutcome::Modify {
+                            arguments: new_arguments,
+                        };
+                    } else {
+                        warn!(
+                            "ignoring `modify` from hook {:?}: no replacement `arguments`",
+                            hook.command
+                        );
+                    }
+                }
+                Some(HookDecision::Allow) => {
+                    if outcome == HookOutcome::Continue {
+                        outcome = HookOutcome::Allow;
+                    }
+                }
+                None => {}
+            }
+        }
+        outcome
+    }
+
+    fn hooks_for(&self, event: &HookEvent) -> &[HookConfig] {
+        match event {
+            HookEvent::SessionStart { .. } => &self.config.session_start,
+            HookEvent::PreToolCall { .. } => &self.config.pre_tool_call,
+            HookEvent::PostToolCall { .. } => &self.config.post_tool_call,
+            HookEvent::PrePatchApply { .. } => &self.config.pre_patch_apply,
+            HookEvent::ApprovalRequested { .. } => &self.config.approval_requested,
+            HookEvent::TurnComplete { .. } => &self.config.turn_complete,
+        }
+    }
+}
+
+/// Runs a single hook and returns its response, or `None` if the hook could
+/// not be run to completion.
+async fn run_hook(hook: &HookConfig, event: &HookEvent) -> Option<HookResponse> {
+    let (program, args) = hook.command.split_first()?;
+    let Ok(payload) = serde_json::to_vec(event) else {
+        warn!("failed to serialise hook payload");
+        return None;
+    };
+
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            warn!("failed to spawn hook '{program}': {e}");
+            return None;
+        }
+    };
+
+    // Write the payload concurrently with waiting, so a hook that never reads
+    // a payload larger than the pipe buffer still runs into the timeout.
+    let stdin = child.stdin.take();
+    let writer = tokio::spawn(async move {
+        if let Some(mut stdin) = stdin {
+            // A hook is free to ignore its input, so a broken pipe is not an error.
+            let _ = stdin.write_all(&payload).await;
+        }
+    });
+
+    let timeout = hook
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_HOOK_TIMEOUT);
+    // On timeout the child is dropped along with the wait and killed.
+    let result = tokio::time::timeout(timeout, child.wait_with_output()).await;
+    writer.abort();
+    let output = match result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            warn!("failed to wait for hook '{program}': {e}");
+            return None;
+        }
+        Err(_) => {
+            warn!("hook '{program}' timed out after {}ms", timeout.as_millis());
+            return None;
+        }
+    };
+
+    parse_hook_output(output.status.success(), &output.stdout, &output.stderr)
+}
+
+fn parse_hook_output(success: bool, stdout: &[u8], stderr: &[u8]) -> Option<HookResponse> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let stdout = stdout.trim();
+    if !stdout.is_empty()
+        && let Ok(response) = serde_json::from_str::<HookResponse>(stdout)
+    {
+        return Some(response);
+    }
+
+    if success {
+        return Some(HookResponse::default());
+    }
+
+    let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+    Some(HookResponse {
+        decision: Some(HookDecision::Deny),
+        reason: Some(if stderr.is_empty() {
+            "hook exited with a non-zero status".to_string()
+        } else {
+            stderr
+        }),
+        arguments: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn hook(script: &str) -> HookConfig {
+        HookConfig {
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            tools: None,
+            timeout_ms: None,
+        }
+    }
+
+    fn pre_tool_call(tool_name: &str) -> HookEvent {
+        HookEvent::PreToolCall {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            cwd: "/tmp".to_string(),
+            call_id: "call-1".to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: json!({"command": ["ls"]}),
+        }
+    }
+
+    #[test]
+    fn hook_event_serializes_with_kebab_case_keys() {
+        let serialized = serde_json::to_value(pre_tool_call("shell")).unwrap();
+        assert_eq!(
+            serialized,
+            json!({
+                "type": "pre-tool-call",
+                "thread-id": "thread",
+                "turn-id": "turn",
+                "cwd": "/tmp",
+                "call-id": "call-1",
+                "tool-name": "shell",
+                "arguments": {"command": ["ls"]},
+            })
+        );
+    }
+
+    #[test]
+    fn parse_hook_output_prefers_json_response() {
+        let response =
+            parse_hook_output(false, br#"{"decision":"allow"}"#, b"ignored").expect("response");
+        assert_eq!(response.decision, Some(HookDecision::Allow));
+    }
+
+    #[test]
+    fn parse_hook_output_denies_on_failure_without_json() {
+        let response = parse_hook_output(false, b"", b"nope\n").expect("response");
+        assert_eq!(
+            response,
+            HookResponse {
+                decision: Some(HookDecision::Deny),
+                reason: Some("nope".to_string()),
+                arguments: None,
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn deny_short_circuits_remaining_hooks() {
+        let hooks = Hooks::new(HooksConfig {
+            pre_tool_call: vec![
+                hook(r#"echo '{"decision":"deny","reason":"blocked"}'"#),
+                hook(r#"echo '{"decision":"allow"}'"#),
+            ],
+            ..Default::default()
+        });
+        assert_eq!(
+            hooks.run(pre_tool_call("shell")).await,
+            HookOutcome::Deny {
+                reason: "blocked".to_string()
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn modify_is_ignored_outside_pre_tool_call() {
+        let hooks = Hooks::new(HooksConfig {
+            approval_requested: vec![hook(
+                r#"echo '{"decision":"modify","arguments":{"command":["pwd"]}}'"#,
+            )],
+            ..Default::default()
+        });
+        let event = HookEvent::ApprovalRequested {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            cwd: "/tmp".to_string(),
+            call_id: "call-1".to_string(),
+            tool_name: "shell".to_string(),
+            command: Some(vec!["ls".to_string()]),
+            reason: None,
+        };
+        assert_eq!(hooks.run(event).await, HookOutcome::Continue);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn modify_rewrites_arguments_and_respects_tool_filter() {
+        let mut filtered = hook(r#"echo '{"decision":"deny"}'"#);
+        filtered.tools = Some(vec!["apply_patch".to_string()]);
+        let hooks = Hooks::new(HooksConfig {
+            pre_tool_call: vec![
+                filtered,
+                hook(r#"echo '{"decision":"modify","arguments":{"command":["pwd"]}}'"#),
+            ],
+            ..Default::default()
+        });
+        assert_eq!(
+            hooks.run(pre_tool_call("shell")).await,
+            HookOutcome::Modify {
+                arguments: json!({"command": ["pwd"]})
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn timeout_applies_to_hooks_that_never_read_their_input() {
+        let mut stuck = hook("sleep 30");
+        stuck.timeout_ms = Some(200);
+        let event = HookEvent::PreToolCall {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            cwd: "/tmp".to_string(),
+            call_id: "call-1".to_string(),
+            tool_name: "shell".to_string(),
+            arguments: json!({"output": "x".repeat(1 << 20)}),
+        };
+
+        let response = tokio::time::timeout(Duration::from_secs(10), run_hook(&stuck, &event))
+            .await
+            .expect("hook timeout must fire while the payload is still being written");
+        assert_eq!(response, None);
+    }
+}
@@ -9,10 +9,12 @@ pub mod api_bridge;
 mod apply_patch;
 pub mod auth;
 pub mod bash;
+mod budget;
 mod client;
 mod client_common;
 pub mod codex;
 mod codex_conversation;
+mod compact_local;
 mod compact_remote;
 pub use codex_conversation::CodexConversation;
 mod codex_delegate;
@@ -30,6 +32,7 @@ pub mod features;
 mod flags;
 pub mod git_info;
 pub mod landlock;
+mod lsp;
 pub mod mcp;
 mod mcp_connection_manager;
 pub mod openai_models;
@@ -38,13 +41,19 @@ pub use mcp_connection_manager::MCP_SANDBOX_STATE_NOTIFICATION;
 pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
 mod message_history;
+mod model_cassette;
 mod model_provider_info;
+mod nested_project_docs;
+mod network_proxy;
 pub mod parse_command;
+mod pinned_context;
 pub mod powershell;
+mod resource_limits;
 pub mod sandboxing;
 mod stream_events_utils;
 mod text_encoding;
 pub mod token_data;
+mod tool_call_text;
 mod truncate;
 mod unified_exec;
 mod user_instructions;
@@ -62,6 +71,7 @@ pub mod review_format;
 pub mod review_prompts;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
+pub use conversation_manager::ForkPoint;
 pub use conversation_manager::NewConversation;
 // Re-export common auth types for workspace consumers
 pub use auth::AuthManager;
@@ -83,13 +93,20 @@ pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::approvals;
+pub use rollout::export;
 pub use rollout::find_conversation_path_by_id_str;
+pub use rollout::labels;
 pub use rollout::list::ConversationItem;
 pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
+pub use rollout::retention;
+pub use rollout::search;
+pub use rollout::usage;
 mod function_tool;
+mod hooks;
 mod state;
 mod tasks;
 mod user_notification;
@@ -99,8 +116,14 @@ pub mod util;
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
 pub use command_safety::is_dangerous_command;
 pub use command_safety::is_safe_command;
+pub use exec_policy::CommandExplanation;
 pub use exec_policy::ExecPolicyError;
+pub use exec_policy::HeuristicsExplanation;
+pub use exec_policy::InnerCommandExplanation;
+pub use exec_policy::default_policy_path;
+pub use exec_policy::explain_command;
 pub use exec_policy::load_exec_policy;
+pub use exec_policy::load_exec_policy_for_features;
 pub use safety::get_platform_sandbox;
 pub use safety::set_windows_sandbox_enabled;
 // Re-export the protocol types from the standalone `codex-protocol` crate so existing
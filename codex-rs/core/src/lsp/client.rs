@@ -0,0 +1,628 @@
+//! Minimal JSON-RPC client for a language server speaking LSP over stdio.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::anyhow;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::process::Child;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::sync::Notify;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio::time::timeout;
+use tracing::warn;
+use url::Url;
+
+use crate::config::types::LspServerConfig;
+
+const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+type Writer = Arc<Mutex<Box<dyn AsyncWrite + Send + Unpin>>>;
+type PendingRequests = Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>;
+
+/// Diagnostics published by the server, keyed by document URI. Every publish
+/// bumps the document's generation so callers can wait for fresh results.
+#[derive(Default)]
+struct DiagnosticsStore {
+    documents: Mutex<HashMap<String, (u64, Vec<Value>)>>,
+    updated: Notify,
+}
+
+impl DiagnosticsStore {
+    async fn publish(&self, uri: String, diagnostics: Vec<Value>) {
+        let mut documents = self.documents.lock().await;
+        let entry = documents.entry(uri).or_default();
+        entry.0 += 1;
+        entry.1 = diagnostics;
+        drop(documents);
+        self.updated.notify_waiters();
+    }
+
+    async fn get(&self, uri: &str) -> Option<(u64, Vec<Value>)> {
+        self.documents.lock().await.get(uri).cloned()
+    }
+}
+
+struct OpenDocument {
+    version: i64,
+    text: String,
+}
+
+/// A document as last sent to the server.
+pub(crate) struct SyncedDocument {
+    pub(crate) uri: String,
+    pub(crate) text: String,
+    /// Whether the server saw new contents as part of this sync.
+    pub(crate) changed: bool,
+    diagnostics_generation: u64,
+}
+
+pub(crate) struct LspClient {
+    writer: Writer,
+    next_id: AtomicI64,
+    pending: PendingRequests,
+    diagnostics: Arc<DiagnosticsStore>,
+    documents: Mutex<HashMap<String, OpenDocument>>,
+    alive: Arc<AtomicBool>,
+    request_timeout: Duration,
+    reader_task: JoinHandle<()>,
+    _child: Option<Child>,
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        self.reader_task.abort();
+    }
+}
+
+impl LspClient {
+    /// Spawns the configured server rooted at `root` and performs the
+    /// `initialize` handshake.
+    pub(crate) async fn start(config: &LspServerConfig, root: &Path) -> anyhow::Result<Self> {
+        let mut command = Command::new(&config.command);
+        command
+            .args(&config.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        if let Some(env) = &config.env {
+            command.envs(env);
+        }
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("failed to start language server `{}`", config.command))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("language server stdin unavailable"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("language server stdout unavailable"))?;
+
+        let mut client = Self::connect(
+            stdout,
+            stdin,
+            config
+                .request_timeout_sec
+                .unwrap_or(DEFAULT_REQUEST_TIMEOUT),
+        );
+        client._child = Some(child);
+        client
+            .initialize(
+                root,
+                config.initialization_options.clone(),
+                config
+                    .startup_timeout_sec
+                    .unwrap_or(DEFAULT_STARTUP_TIMEOUT),
+            )
+            .await?;
+        Ok(client)
+    }
+
+    /// Wraps an already established transport. The caller is responsible for
+    /// sending `initialize`.
+    pub(crate) fn connect<R, W>(reader: R, writer: W, request_timeout: Duration) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let writer: Writer = Arc::new(Mutex::new(Box::new(writer)));
+        let pending = PendingRequests::default();
+        let diagnostics = Arc::new(DiagnosticsStore::default());
+        let alive = Arc::new(AtomicBool::new(true));
+        let reader_task = tokio::spawn(read_loop(
+            BufReader::new(reader),
+            Arc::clone(&writer),
+            Arc::clone(&pending),
+            Arc::clone(&diagnostics),
+            Arc::clone(&alive),
+        ));
+        Self {
+            writer,
+            next_id: AtomicI64::new(1),
+            pending,
+            diagnostics,
+            documents: Mutex::new(HashMap::new()),
+            alive,
+            request_timeout,
+            reader_task,
+            _child: None,
+        }
+    }
+
+    pub(crate) fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Acquire)
+    }
+
+    pub(crate) async fn initialize(
+        &self,
+        root: &Path,
+        initialization_options: Option<Value>,
+        startup_timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let root_uri = path_to_uri(root)?;
+        let params = json!({
+            "processId": std::process::id(),
+            "rootUri": root_uri,
+            "workspaceFolders": [{ "uri": root_uri, "name": root.display().to_string() }],
+            "initializationOptions": initialization_options,
+            "capabilities": {
+                "textDocument": {
+                    "synchronization": { "didSave": true },
+                    "definition": { "linkSupport": true },
+                    "references": {},
+                    "hover": { "contentFormat": ["plaintext", "markdown"] },
+                    "documentSymbol": { "hierarchicalDocumentSymbolSupport": true },
+                    "publishDiagnostics": { "versionSupport": true },
+                },
+                "workspace": { "workspaceFolders": true, "configuration": true },
+            },
+        });
+        self.request_with_timeout("initialize", params, startup_timeout)
+            .await?;
+        self.notify("initialized", json!({})).await
+    }
+
+    pub(crate) async fn request(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        self.request_with_timeout(method, params, self.request_timeout)
+            .await
+    }
+
+    async fn request_with_timeout(
+        &self,
+        method: &str,
+        params: Value,
+        request_timeout: Duration,
+    ) -> anyhow::Result<Value> {
+        if !self.is_alive() {
+            return Err(anyhow!("language server exited"));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(err) = write_message(&self.writer, &message).await {
+            self.pending.lock().await.remove(&id);
+            return Err(err.into());
+        }
+
+        match timeout(request_timeout, rx).await {
+            Ok(Ok(Ok(result))) => Ok(result),
+            Ok(Ok(Err(message))) => Err(anyhow!("{method} failed: {message}")),
+            Ok(Err(_)) => Err(anyhow!("language server exited during {method}")),
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                Err(anyhow!(
+                    "{method} timed out after {}s",
+                    request_timeout.as_secs_f32()
+                ))
+            }
+        }
+    }
+
+    pub(crate) async fn notify(&self, method: &str, params: Value) -> anyhow::Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&self.writer, &message).await?;
+        Ok(())
+    }
+
+    /// Sends the current on-disk contents of `path` to the server, opening the
+    /// document on first use.
+    pub(crate) async fn sync_document(
+        &self,
+        path: &Path,
+        language_id: &str,
+    ) -> anyhow::Result<SyncedDocument> {
+        let text = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("unable to read `{}`", path.display()))?;
+        let uri = path_to_uri(path)?;
+        let diagnostics_generation = self
+            .diagnostics
+            .get(&uri)
+            .await
+            .map(|(generation, _)| generation)
+            .unwrap_or_default();
+
+        let mut documents = self.documents.lock().await;
+        let changed = match documents.get_mut(&uri) {
+            Some(document) if document.text == text => false,
+            Some(document) => {
+                document.version += 1;
+                document.text = text.clone();
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": document.version },
+                        "contentChanges": [{ "text": text }],
+                    }),
+                )
+                .await?;
+                true
+            }
+            None => {
+                documents.insert(
+                    uri.clone(),
+                    OpenDocument {
+                        version: 1,
+                        text: text.clone(),
+                    },
+                );
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id,
+                            "version": 1,
+                            "text": text,
+                        },
+                    }),
+                )
+                .await?;
+                true
+            }
+        };
+        if changed {
+            // Servers such as rust-analyzer only run their full checks on save.
+            self.notify(
+                "textDocument/didSave",
+                json!({ "textDocument": { "uri": uri } }),
+            )
+            .await?;
+        }
+
+        Ok(SyncedDocument {
+            uri,
+            text,
+            changed,
+            diagnostics_generation,
+        })
+    }
+
+    /// Returns the diagnostics for a synced document. When the server has not
+    /// published diagnostics for the latest contents yet, waits up to `wait`
+    /// for it to do so.
+    pub(crate) async fn diagnostics(
+        &self,
+        document: &SyncedDocument,
+        wait: Duration,
+    ) -> Option<Vec<Value>> {
+        let deadline = Instant::now() + wait;
+        loop {
+            let updated = self.diagnostics.updated.notified();
+            match self.diagnostics.get(&document.uri).await {
+                Some((generation, diagnostics))
+                    if !document.changed || generation > document.diagnostics_generation =>
+                {
+                    return Some(diagnostics);
+                }
+                _ => {}
+            }
+            if tokio::time::timeout_at(deadline, updated).await.is_err() {
+                return self
+                    .diagnostics
+                    .get(&document.uri)
+                    .await
+                    .map(|(_, diagnostics)| diagnostics);
+            }
+        }
+    }
+
+    /// Politely asks the server to exit. The process is killed when the client
+    /// is dropped regardless.
+    pub(crate) async fn shutdown(&self) {
+        if !self.is_alive() {
+            return;
+        }
+        if let Err(err) = self
+            .request_with_timeout("shutdown", Value::Null, Duration::from_secs(2))
+            .await
+        {
+            warn!("language server did not shut down cleanly: {err:#}");
+            return;
+        }
+        let _ = self.notify("exit", Value::Null).await;
+    }
+}
+
+fn path_to_uri(path: &Path) -> anyhow::Result<String> {
+    Url::from_file_path(path)
+        .map(String::from)
+        .map_err(|()| anyhow!("`{}` is not an absolute path", path.display()))
+}
+
+async fn read_loop<R: AsyncBufRead + Unpin>(
+    mut reader: R,
+    writer: Writer,
+    pending: PendingRequests,
+    diagnostics: Arc<DiagnosticsStore>,
+    alive: Arc<AtomicBool>,
+) {
+    loop {
+        let message = match read_message(&mut reader).await {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(err) => {
+                warn!("failed to read from language server: {err}");
+                break;
+            }
+        };
+
+        let method = message.get("method").and_then(Value::as_str);
+        let id = message.get("id");
+        match (method, id) {
+            (Some(method), Some(id)) => {
+                let reply = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": server_request_result(method, message.get("params")),
+                });
+                if let Err(err) = write_message(&writer, &reply).await {
+                    warn!("failed to reply to language server request {method}: {err}");
+                }
+            }
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let params = message.get("params");
+                if let Some(uri) = params
+                    .and_then(|params| params.get("uri"))
+                    .and_then(Value::as_str)
+                {
+                    let published = params
+                        .and_then(|params| params.get("diagnostics"))
+                        .and_then(Value::as_array)
+                        .cloned()
+                        .unwrap_or_default();
+                    diagnostics.publish(uri.to_string(), published).await;
+                }
+            }
+            (Some(_), None) => {}
+            (None, Some(id)) => {
+                let Some(id) = id.as_i64() else {
+                    continue;
+                };
+                let sender = pending.lock().await.remove(&id);
+                if let Some(sender) = sender {
+                    let result = match message.get("error") {
+                        Some(error) => Err(error
+                            .get("message")
+                            .and_then(Value::as_str)
+                            .map(str::to_string)
+                            .unwrap_or_else(|| error.to_string())),
+                        None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                    };
+                    let _ = sender.send(result);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    alive.store(false, Ordering::Release);
+    // Dropping the senders fails every in-flight request.
+    pending.lock().await.clear();
+}
+
+/// Answers requests the server sends to the client. We advertise almost no
+/// client capabilities, so empty results are acceptable for all of them.
+fn server_request_result(method: &str, params: Option<&Value>) -> Value {
+    match method {
+        "workspace/configuration" => {
+            let items = params
+                .and_then(|params| params.get("items"))
+                .and_then(Value::as_array)
+                .map(Vec::len)
+                .unwrap_or_default();
+            Value::Array(vec![Value::Null; items])
+        }
+        _ => Value::Null,
+    }
+}
+
+async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = Some(value.trim().parse::<usize>().map_err(|err| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string())
+            })?);
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+async fn write_message(writer: &Writer, message: &Value) -> std::io::Result<()> {
+    let body = serde_json::to_vec(message)?;
+    let mut writer = writer.lock().await;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(&body).await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::io::DuplexStream;
+    use tokio::io::ReadHalf;
+    use tokio::io::WriteHalf;
+
+    struct FakeServer {
+        reader: BufReader<ReadHalf<DuplexStream>>,
+        writer: Writer,
+    }
+
+    impl FakeServer {
+        async fn next(&mut self) -> Value {
+            read_message(&mut self.reader)
+                .await
+                .expect("read message")
+                .expect("message")
+        }
+
+        async fn send(&self, message: Value) {
+            write_message(&self.writer, &message)
+                .await
+                .expect("write message");
+        }
+    }
+
+    fn connect() -> (LspClient, FakeServer) {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let (client_read, client_write) = tokio::io::split(client_io);
+        let (server_read, server_write): (ReadHalf<DuplexStream>, WriteHalf<DuplexStream>) =
+            tokio::io::split(server_io);
+        let client = LspClient::connect(client_read, client_write, Duration::from_secs(5));
+        let server = FakeServer {
+            reader: BufReader::new(server_read),
+            writer: Arc::new(Mutex::new(Box::new(server_write))),
+        };
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn requests_are_matched_to_responses_and_server_requests_answered() {
+        let (client, mut server) = connect();
+
+        let request = tokio::spawn(async move {
+            let result = client
+                .request("textDocument/hover", json!({ "position": 1 }))
+                .await;
+            (client, result)
+        });
+
+        let hover = server.next().await;
+        assert_eq!(hover["method"], json!("textDocument/hover"));
+
+        // The server asks for configuration before answering.
+        server
+            .send(json!({
+                "jsonrpc": "2.0",
+                "id": "cfg-1",
+                "method": "workspace/configuration",
+                "params": { "items": [{ "section": "a" }, { "section": "b" }] },
+            }))
+            .await;
+        let reply = server.next().await;
+        assert_eq!(
+            reply,
+            json!({ "jsonrpc": "2.0", "id": "cfg-1", "result": [null, null] })
+        );
+
+        server
+            .send(json!({ "jsonrpc": "2.0", "id": hover["id"], "result": { "contents": "docs" } }))
+            .await;
+        let (_client, result) = request.await.expect("join");
+        assert_eq!(result.expect("hover result"), json!({ "contents": "docs" }));
+    }
+
+    #[tokio::test]
+    async fn sync_document_opens_then_changes_and_waits_for_diagnostics() {
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, "fn main() {}\n").expect("write");
+        let (client, mut server) = connect();
+
+        let opened = client.sync_document(&path, "rust").await.expect("sync");
+        assert!(opened.changed);
+        let did_open = server.next().await;
+        assert_eq!(did_open["method"], json!("textDocument/didOpen"));
+        assert_eq!(
+            did_open["params"]["textDocument"]["languageId"],
+            json!("rust")
+        );
+        assert_eq!(server.next().await["method"], json!("textDocument/didSave"));
+
+        let unchanged = client.sync_document(&path, "rust").await.expect("sync");
+        assert!(!unchanged.changed);
+
+        std::fs::write(&path, "fn main() { x }\n").expect("write");
+        let changed = client.sync_document(&path, "rust").await.expect("sync");
+        assert!(changed.changed);
+        let did_change = server.next().await;
+        assert_eq!(did_change["method"], json!("textDocument/didChange"));
+        assert_eq!(did_change["params"]["textDocument"]["version"], json!(2));
+
+        server
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": {
+                    "uri": changed.uri,
+                    "diagnostics": [{ "message": "cannot find value `x`" }],
+                },
+            }))
+            .await;
+        let diagnostics = client
+            .diagnostics(&changed, Duration::from_secs(5))
+            .await
+            .expect("diagnostics");
+        assert_eq!(
+            diagnostics,
+            vec![json!({ "message": "cannot find value `x`" })]
+        );
+    }
+}
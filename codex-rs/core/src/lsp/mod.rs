@@ -0,0 +1,120 @@
+//! Language servers backing the `lsp` tool. Servers are declared under
+//! `[lsp_servers.<name>]`, started lazily on first use and kept alive for the
+//! rest of the session.
+//!
+//! Servers run as ordinary child processes outside the session sandbox, like
+//! MCP servers. Codex only sends them documents the sandbox policy lets the
+//! agent read, but a server may read other files on its own.
+
+mod client;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tokio::sync::OnceCell;
+
+pub(crate) use client::LspClient;
+pub(crate) use client::SyncedDocument;
+
+use crate::config::types::LspServerConfig;
+
+/// A running server together with the `languageId` used for its documents.
+pub(crate) struct LspServer {
+    pub(crate) client: Arc<LspClient>,
+    pub(crate) language_id: String,
+}
+
+/// A server slot. The map lock is only held to look the slot up, so a server
+/// that is still starting does not block requests to other servers.
+type ClientSlot = Arc<OnceCell<Arc<LspClient>>>;
+
+pub(crate) struct LspManager {
+    servers: BTreeMap<String, LspServerConfig>,
+    clients: Mutex<HashMap<(String, PathBuf), ClientSlot>>,
+}
+
+impl LspManager {
+    pub(crate) fn new(servers: BTreeMap<String, LspServerConfig>) -> Self {
+        Self {
+            servers,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the server responsible for `path`, starting it rooted at
+    /// `root` if it is not running yet.
+    pub(crate) async fn server_for_path(
+        &self,
+        path: &Path,
+        root: &Path,
+    ) -> anyhow::Result<LspServer> {
+        let (name, config) = self.config_for_path(path).ok_or_else(|| {
+            anyhow::anyhow!(
+                "no language server is configured for `{}`; configured servers: {}",
+                path.display(),
+                self.describe_servers()
+            )
+        })?;
+
+        let key = (name.to_string(), root.to_path_buf());
+        let slot = {
+            let mut clients = self.clients.lock().await;
+            let slot = clients.entry(key).or_default();
+            if slot.get().is_some_and(|client| !client.is_alive()) {
+                *slot = ClientSlot::default();
+            }
+            Arc::clone(slot)
+        };
+        // Concurrent callers for the same server wait on the same start; a
+        // failed start leaves the slot empty so the next call retries.
+        let client = slot
+            .get_or_try_init(|| async { LspClient::start(config, root).await.map(Arc::new) })
+            .await
+            .map(Arc::clone)?;
+
+        Ok(LspServer {
+            client,
+            language_id: config
+                .language_id
+                .clone()
+                .unwrap_or_else(|| name.to_string()),
+        })
+    }
+
+    pub(crate) async fn shutdown(&self) {
+        let slots: Vec<_> = self.clients.lock().await.drain().collect();
+        for (_, slot) in slots {
+            if let Some(client) = slot.get() {
+                client.shutdown().await;
+            }
+        }
+    }
+
+    fn config_for_path(&self, path: &Path) -> Option<(&str, &LspServerConfig)> {
+        let extension = path.extension()?.to_str()?;
+        self.servers
+            .iter()
+            .find(|(_, config)| {
+                config
+                    .file_extensions
+                    .iter()
+                    .any(|candidate| candidate.trim_start_matches('.') == extension)
+            })
+            .map(|(name, config)| (name.as_str(), config))
+    }
+
+    fn describe_servers(&self) -> String {
+        if self.servers.is_empty() {
+            return "none".to_string();
+        }
+        self.servers
+            .iter()
+            .map(|(name, config)| format!("{name} ({})", config.file_extensions.join(", ")))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
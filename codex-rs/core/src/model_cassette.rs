@@ -0,0 +1,47 @@
+//! Process-wide cassettes set by `codex exec --record`/`--replay`.
+//!
+//! Every model client in the process (the main session, sub-agents, review
+//! turns, compaction) shares one [`Cassette`] per file, so a recording holds
+//! the whole run in request order and a replay hands each recorded response
+//! out exactly once.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use codex_api::Cassette;
+
+use crate::config::Config;
+use crate::config::types::ModelCassetteMode;
+
+static CASSETTES: LazyLock<Mutex<HashMap<PathBuf, Arc<Cassette>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the cassette configured for `config`, opening it on first use.
+pub(crate) fn cassette_for(config: &Config) -> io::Result<Option<Arc<Cassette>>> {
+    let Some(settings) = &config.model_cassette else {
+        return Ok(None);
+    };
+    let mut cassettes = CASSETTES
+        .lock()
+        .map_err(|_| io::Error::other("cassette registry lock poisoned"))?;
+    if let Some(cassette) = cassettes.get(&settings.path) {
+        return Ok(Some(Arc::clone(cassette)));
+    }
+    let cassette = match settings.mode {
+        ModelCassetteMode::Record => Cassette::record(settings.path.clone()),
+        ModelCassetteMode::Replay => Cassette::replay(settings.path.clone()),
+    }
+    .map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("failed to open cassette {}: {err}", settings.path.display()),
+        )
+    })?;
+    let cassette = Arc::new(cassette);
+    cassettes.insert(settings.path.clone(), Arc::clone(&cassette));
+    Ok(Some(cassette))
+}
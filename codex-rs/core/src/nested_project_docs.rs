@@ -0,0 +1,458 @@
+//! Project docs for the subdirectories the agent works in.
+//!
+//! At startup only the docs between the repository root and the session `cwd`
+//! are loaded (see [`crate::project_doc`]). In a monorepo the packages below
+//! `cwd` often carry their own `AGENTS.md`, so after each sampling request
+//! that ran tools we collect the paths those calls read or edited and add the
+//! docs that apply to them to the conversation before the next request. A doc
+//! is added at most once while it is part of the history, and the added docs
+//! share the `project_doc_max_bytes` budget with the startup docs. Docs the
+//! sandbox policy does not let the agent read are skipped.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::maybe_parse_apply_patch;
+use codex_apply_patch::parse_patch;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::SandboxPolicy;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::parse_command::parse_command;
+use crate::project_doc::discover_nested_project_doc_paths;
+use crate::project_doc::discover_project_doc_paths;
+use crate::project_doc::read_project_doc;
+use crate::user_instructions::UserInstructions;
+
+/// Returns the instructions messages for the project docs that apply to the
+/// directories touched by `turn_items` and are not part of `history` yet.
+pub(crate) async fn nested_project_doc_items(
+    config: &Config,
+    cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+    history: &[ResponseItem],
+    turn_items: &[ResponseItem],
+) -> Vec<ResponseItem> {
+    if config.project_doc_max_bytes == 0 {
+        return Vec::new();
+    }
+    let dirs = touched_dirs(turn_items, cwd);
+    if dirs.is_empty() {
+        return Vec::new();
+    }
+
+    // Docs on the way to `cwd` are part of the startup instructions.
+    let is_startup_dir = |dir: &Path| cwd.starts_with(dir) || config.cwd.starts_with(dir);
+    let mut loaded: HashSet<PathBuf> = HashSet::new();
+    let mut used: u64 = 0;
+    for item in history {
+        let ResponseItem::Message { content, .. } = item else {
+            continue;
+        };
+        let Some(instructions) = UserInstructions::from_message(content) else {
+            continue;
+        };
+        let dir = PathBuf::from(instructions.directory);
+        if !is_startup_dir(&dir) {
+            used = used.saturating_add(instructions.text.len() as u64);
+        }
+        loaded.insert(dir);
+    }
+    let startup: u64 = discover_project_doc_paths(config)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    let mut remaining = (config.project_doc_max_bytes as u64)
+        .saturating_sub(startup)
+        .saturating_sub(used);
+
+    let mut items = Vec::new();
+    for dir in dirs {
+        let docs = match discover_nested_project_doc_paths(config, &dir) {
+            Ok(docs) => docs,
+            Err(err) => {
+                warn!("failed to find project docs for `{}`: {err}", dir.display());
+                continue;
+            }
+        };
+        for doc in docs {
+            if remaining == 0 {
+                return items;
+            }
+            let Some(doc_dir) = doc.parent() else {
+                continue;
+            };
+            if !sandbox_policy.is_path_readable(&doc, cwd) || !loaded.insert(doc_dir.to_path_buf())
+            {
+                continue;
+            }
+            match read_project_doc(&doc, remaining).await {
+                Ok(Some(text)) if !text.trim().is_empty() => {
+                    remaining = remaining.saturating_sub(text.len() as u64);
+                    items.push(
+                        UserInstructions {
+                            directory: doc_dir.to_string_lossy().into_owned(),
+                            text,
+                        }
+                        .into(),
+                    );
+                }
+                Ok(_) => {}
+                Err(err) => warn!("failed to read project doc `{}`: {err}", doc.display()),
+            }
+        }
+    }
+    items
+}
+
+/// Directories read or edited by the tool calls in `items`, in the order they
+/// were first touched.
+fn touched_dirs(items: &[ResponseItem], cwd: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for item in items {
+        match item {
+            ResponseItem::FunctionCall { arguments, .. } => {
+                if let Ok(args) = serde_json::from_str::<Value>(arguments) {
+                    function_call_dirs(&args, cwd, &mut dirs);
+                }
+            }
+            ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+                patch_dirs(input, cwd, &mut dirs);
+            }
+            ResponseItem::LocalShellCall {
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => {
+                let workdir = match &exec.working_directory {
+                    Some(workdir) => cwd.join(workdir),
+                    None => cwd.to_path_buf(),
+                };
+                command_dirs(&exec.command, &workdir, &mut dirs);
+            }
+            _ => {}
+        }
+    }
+
+    let mut seen = HashSet::new();
+    dirs.retain(|dir| seen.insert(dir.clone()));
+    dirs
+}
+
+/// The directory of `path`, which may name either a file or a directory.
+fn dir_of(path: PathBuf) -> Option<PathBuf> {
+    if path.is_dir() {
+        Some(path)
+    } else {
+        path.parent().map(Path::to_path_buf)
+    }
+}
+
+/// Collects directories from the arguments of the built-in tools: `workdir` and
+/// `command`/`cmd` of the shell tools, `input` of `apply_patch`, and the path
+/// arguments of `read_file`, `list_dir` and `grep_files`.
+fn function_call_dirs(args: &Value, cwd: &Path, dirs: &mut Vec<PathBuf>) {
+    let str_arg = |key: &str| args.get(key).and_then(Value::as_str);
+    let workdir = match str_arg("workdir") {
+        Some(workdir) => cwd.join(workdir),
+        None => cwd.to_path_buf(),
+    };
+
+    if let Some(dir) =
+        str_arg("file_path").and_then(|path| cwd.join(path).parent().map(Path::to_path_buf))
+    {
+        dirs.push(dir);
+    }
+    if let Some(dir) = str_arg("dir_path") {
+        dirs.push(cwd.join(dir));
+    }
+    if let Some(dir) = str_arg("path").and_then(|path| dir_of(cwd.join(path))) {
+        dirs.push(dir);
+    }
+    if let Some(patch) = str_arg("input") {
+        patch_dirs(patch, &workdir, dirs);
+    }
+
+    let command = match args.get("command").or_else(|| args.get("cmd")) {
+        Some(Value::Array(argv)) => argv
+            .iter()
+            .map(|arg| arg.as_str().map(str::to_string))
+            .collect::<Option<Vec<String>>>(),
+        Some(Value::String(script)) => {
+            Some(vec!["bash".to_string(), "-lc".to_string(), script.clone()])
+        }
+        _ => None,
+    };
+    if let Some(command) = command {
+        command_dirs(&command, &workdir, dirs);
+    }
+}
+
+fn command_dirs(command: &[String], workdir: &Path, dirs: &mut Vec<PathBuf>) {
+    dirs.push(workdir.to_path_buf());
+    if let MaybeApplyPatch::Body(args) = maybe_parse_apply_patch(command) {
+        let workdir = match &args.workdir {
+            Some(dir) => workdir.join(dir),
+            None => workdir.to_path_buf(),
+        };
+        hunk_dirs(&args.hunks, &workdir, dirs);
+        return;
+    }
+    for parsed in parse_command(command) {
+        match parsed {
+            ParsedCommand::Read { path, .. } => {
+                dirs.extend(workdir.join(path).parent().map(Path::to_path_buf))
+            }
+            ParsedCommand::ListFiles {
+                path: Some(path), ..
+            }
+            | ParsedCommand::Search {
+                path: Some(path), ..
+            } => dirs.extend(dir_of(workdir.join(path))),
+            ParsedCommand::ListFiles { .. }
+            | ParsedCommand::Search { .. }
+            | ParsedCommand::Unknown { .. } => {}
+        }
+    }
+}
+
+fn patch_dirs(patch: &str, workdir: &Path, dirs: &mut Vec<PathBuf>) {
+    if let Ok(args) = parse_patch(patch) {
+        hunk_dirs(&args.hunks, workdir, dirs);
+    }
+}
+
+fn hunk_dirs(hunks: &[Hunk], workdir: &Path, dirs: &mut Vec<PathBuf>) {
+    for hunk in hunks {
+        let mut paths = vec![hunk.resolve_path(workdir)];
+        if let Hunk::UpdateFile {
+            move_path: Some(move_path),
+            ..
+        } = hunk
+        {
+            paths.push(workdir.join(move_path));
+        }
+        dirs.extend(
+            paths
+                .iter()
+                .filter_map(|path| path.parent().map(Path::to_path_buf)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn make_config(cwd: &Path, limit: usize) -> Config {
+        let codex_home = TempDir::new().unwrap();
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("defaults for test should always succeed");
+        config.cwd = cwd.to_path_buf();
+        config.project_doc_max_bytes = limit;
+        config
+    }
+
+    fn function_call(name: &str, arguments: Value) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: "call-1".to_string(),
+        }
+    }
+
+    fn instructions(item: &ResponseItem) -> UserInstructions {
+        let ResponseItem::Message { content, .. } = item else {
+            panic!("expected a message, got {item:?}");
+        };
+        UserInstructions::from_message(content).expect("user instructions")
+    }
+
+    /// Creates a repository with docs at the root, in `packages` and in
+    /// `packages/web`, and returns it with its canonical path.
+    fn make_repo() -> (TempDir, PathBuf) {
+        let repo = TempDir::new().unwrap();
+        let root = dunce::canonicalize(repo.path()).unwrap();
+        fs::write(root.join(".git"), "gitdir: /path/to/actual/git/dir\n").unwrap();
+        fs::write(root.join("AGENTS.md"), "root doc").unwrap();
+        fs::create_dir_all(root.join("packages/web/src")).unwrap();
+        fs::create_dir_all(root.join("packages/api/src")).unwrap();
+        fs::write(root.join("packages/AGENTS.md"), "packages doc").unwrap();
+        fs::write(root.join("packages/web/AGENTS.md"), "web doc").unwrap();
+        (repo, root)
+    }
+
+    #[test]
+    fn touched_dirs_covers_reads_edits_and_workdirs() {
+        let cwd = Path::new("/repo");
+        let items = vec![
+            function_call(
+                "shell",
+                serde_json::json!({
+                    "command": ["bash", "-lc", "cat src/main.rs"],
+                    "workdir": "crates/cli",
+                }),
+            ),
+            function_call(
+                "read_file",
+                serde_json::json!({ "file_path": "/repo/docs/guide.md" }),
+            ),
+            ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-2".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch\n*** Add File: web/src/app.ts\n+export {};\n*** End Patch"
+                    .to_string(),
+            },
+        ];
+
+        assert_eq!(
+            touched_dirs(&items, cwd),
+            vec![
+                PathBuf::from("/repo/crates/cli"),
+                PathBuf::from("/repo/crates/cli/src"),
+                PathBuf::from("/repo/docs"),
+                PathBuf::from("/repo/web/src"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn loads_docs_below_cwd_once() {
+        let (_repo, root) = make_repo();
+        let config = make_config(&root, 32 * 1024);
+        let turn = vec![function_call(
+            "read_file",
+            serde_json::json!({ "file_path": root.join("packages/web/src/index.ts") }),
+        )];
+
+        let items =
+            nested_project_doc_items(&config, &root, &config.sandbox_policy, &[], &turn).await;
+
+        let loaded: Vec<UserInstructions> = items.iter().map(instructions).collect();
+        assert_eq!(
+            loaded,
+            vec![
+                UserInstructions {
+                    directory: root.join("packages").to_string_lossy().into_owned(),
+                    text: "packages doc".to_string(),
+                },
+                UserInstructions {
+                    directory: root.join("packages/web").to_string_lossy().into_owned(),
+                    text: "web doc".to_string(),
+                },
+            ]
+        );
+
+        // Docs that are already in the history are not added again, but
+        // siblings still are.
+        let turn = vec![function_call(
+            "shell",
+            serde_json::json!({ "command": ["ls"], "workdir": "packages/api/src" }),
+        )];
+        assert_eq!(
+            nested_project_doc_items(&config, &root, &config.sandbox_policy, &items, &turn).await,
+            Vec::new()
+        );
+        fs::write(root.join("packages/api/AGENTS.md"), "api doc").unwrap();
+        let api: Vec<UserInstructions> =
+            nested_project_doc_items(&config, &root, &config.sandbox_policy, &items, &turn)
+                .await
+                .iter()
+                .map(instructions)
+                .collect();
+        assert_eq!(
+            api,
+            vec![UserInstructions {
+                directory: root.join("packages/api").to_string_lossy().into_owned(),
+                text: "api doc".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn nested_docs_share_the_project_doc_budget() {
+        let (_repo, root) = make_repo();
+        // "root doc" is loaded at startup and leaves room for 10 more bytes.
+        let config = make_config(&root, "root doc".len() + 10);
+        let turn = vec![function_call(
+            "read_file",
+            serde_json::json!({ "file_path": root.join("packages/web/src/index.ts") }),
+        )];
+
+        let loaded: Vec<String> =
+            nested_project_doc_items(&config, &root, &config.sandbox_policy, &[], &turn)
+                .await
+                .iter()
+                .map(|item| instructions(item).text)
+                .collect();
+
+        assert_eq!(loaded, vec!["packages d".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn skips_docs_denied_by_the_sandbox() {
+        let (_repo, root) = make_repo();
+        let mut config = make_config(&root, 32 * 1024);
+        config
+            .sandbox_policy
+            .restrict_reads(&[], &[root.join("packages/web")]);
+        let turn = vec![function_call(
+            "read_file",
+            serde_json::json!({ "file_path": root.join("packages/web/src/index.ts") }),
+        )];
+
+        let loaded: Vec<String> =
+            nested_project_doc_items(&config, &root, &config.sandbox_policy, &[], &turn)
+                .await
+                .iter()
+                .map(|item| instructions(item).text)
+                .collect();
+
+        assert_eq!(loaded, vec!["packages doc".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn ignores_paths_outside_the_repository() {
+        let (_repo, root) = make_repo();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("AGENTS.md"), "outside doc").unwrap();
+        let config = make_config(&root, 32 * 1024);
+        let turn = vec![function_call(
+            "list_dir",
+            serde_json::json!({ "dir_path": outside.path() }),
+        )];
+        let history = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "hello".to_string(),
+            }],
+        }];
+
+        assert_eq!(
+            nested_project_doc_items(&config, &root, &config.sandbox_policy, &history, &turn).await,
+            Vec::new()
+        );
+    }
+}
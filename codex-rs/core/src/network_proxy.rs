@@ -0,0 +1,601 @@
+//! Local HTTP proxy configured under `[network_proxy]`.
+//!
+//! Sandboxed commands that have no network access of their own may still open
+//! TCP connections to this proxy (see `codex-linux-sandbox` and the seatbelt
+//! policy) and find it through the usual `HTTP_PROXY`/`HTTPS_PROXY`
+//! variables. The proxy serves `CONNECT` tunnels and absolute-form `http://`
+//! requests, logs every request, and asks the session for approval before
+//! connecting to a host that is not allowlisted. A plain `http://` connection
+//! carries a single request, so a keep-alive follow-up for another host cannot
+//! reach the first one's upstream; clients open a new connection instead.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio_util::task::AbortOnDropHandle;
+use tracing::info;
+use tracing::warn;
+
+use crate::config::types::NetworkProxyConfig;
+use crate::protocol::ReviewDecision;
+
+/// Request heads larger than this are rejected.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+/// Sent to the session when a command tries to reach a host that is not
+/// allowlisted. Dropping `reply` denies the request.
+pub(crate) struct HostApprovalRequest {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) reply: oneshot::Sender<ReviewDecision>,
+}
+
+pub(crate) struct NetworkProxy {
+    addr: SocketAddr,
+    _accept_loop: AbortOnDropHandle<()>,
+}
+
+impl NetworkProxy {
+    /// Starts the proxy on an ephemeral localhost port. Approval requests for
+    /// unknown hosts are delivered on the returned receiver.
+    pub(crate) async fn start(
+        config: &NetworkProxyConfig,
+    ) -> io::Result<(Self, mpsc::Receiver<HostApprovalRequest>)> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let addr = listener.local_addr()?;
+        let (approvals, approval_rx) = mpsc::channel(16);
+        let state = Arc::new(ProxyState {
+            allowed_domains: config
+                .allowed_domains
+                .iter()
+                .map(|domain| normalize_host(domain))
+                .collect(),
+            decisions: Mutex::new(HashMap::new()),
+            prompts: Mutex::new(HashMap::new()),
+            approvals,
+        });
+        let accept_loop = tokio::spawn(accept_connections(listener, state));
+        info!("network proxy listening on {addr}");
+        Ok((
+            Self {
+                addr,
+                _accept_loop: AbortOnDropHandle::new(accept_loop),
+            },
+            approval_rx,
+        ))
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+struct ProxyState {
+    allowed_domains: Vec<String>,
+    /// Hosts approved or denied for the rest of the session.
+    decisions: Mutex<HashMap<String, bool>>,
+    /// One lock per host, held while that host's prompt is pending so prompts
+    /// for the same host do not pile up while other hosts go ahead.
+    prompts: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    approvals: mpsc::Sender<HostApprovalRequest>,
+}
+
+impl ProxyState {
+    async fn authorize(&self, host: &str, port: u16) -> bool {
+        if self
+            .allowed_domains
+            .iter()
+            .any(|pattern| host_matches(pattern, host))
+        {
+            return true;
+        }
+
+        let prompt = Arc::clone(
+            self.prompts
+                .lock()
+                .await
+                .entry(host.to_string())
+                .or_default(),
+        );
+        let _pending = prompt.lock().await;
+        if let Some(allowed) = self.decisions.lock().await.get(host) {
+            return *allowed;
+        }
+
+        let (reply, reply_rx) = oneshot::channel();
+        let request = HostApprovalRequest {
+            host: host.to_string(),
+            port,
+            reply,
+        };
+        if self.approvals.send(request).await.is_err() {
+            return false;
+        }
+        let (allowed, remember) = match reply_rx.await.unwrap_or_default() {
+            ReviewDecision::ApprovedForSession => (true, true),
+            ReviewDecision::Approved | ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
+                (true, false)
+            }
+            ReviewDecision::Denied => (false, true),
+            ReviewDecision::Abort => (false, false),
+        };
+        if remember {
+            self.decisions
+                .lock()
+                .await
+                .insert(host.to_string(), allowed);
+        }
+        allowed
+    }
+}
+
+async fn accept_connections(listener: TcpListener, state: Arc<ProxyState>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let state = Arc::clone(&state);
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, &state).await {
+                        warn!("network proxy connection failed: {err}");
+                    }
+                });
+            }
+            Err(err) => warn!("network proxy failed to accept a connection: {err}"),
+        }
+    }
+}
+
+async fn handle_connection(mut client: TcpStream, state: &ProxyState) -> io::Result<()> {
+    let (head, body_prefix) = read_request_head(&mut client).await?;
+    let request = match ProxyRequest::parse(&head) {
+        Ok(request) => request,
+        Err(reason) => {
+            info!("network proxy rejected malformed request: {reason}");
+            return respond(&mut client, "400 Bad Request", reason).await;
+        }
+    };
+
+    let allowed = state.authorize(&request.host, request.port).await;
+    info!(
+        "network proxy {} {}:{} {}",
+        request.method,
+        request.host,
+        request.port,
+        if allowed { "allowed" } else { "denied" }
+    );
+    if !allowed {
+        let message = format!(
+            "codex network proxy: {} is not in network_proxy.allowed_domains",
+            request.host
+        );
+        return respond(&mut client, "403 Forbidden", &message).await;
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            let message = format!("codex network proxy: failed to connect: {err}");
+            return respond(&mut client, "502 Bad Gateway", &message).await;
+        }
+    };
+
+    let Some(forward_head) = request.forward_head else {
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        upstream.write_all(&body_prefix).await?;
+        tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+        return Ok(());
+    };
+
+    upstream.write_all(forward_head.as_bytes()).await?;
+    upstream.write_all(&body_prefix).await?;
+    // The forwarded request asks the upstream to close after its response;
+    // the client connection is closed along with it.
+    let (mut client_read, mut client_write) = client.split();
+    let (mut upstream_read, mut upstream_write) = upstream.split();
+    let response = async {
+        tokio::io::copy(&mut upstream_read, &mut client_write).await?;
+        client_write.shutdown().await
+    };
+    let request_body = async {
+        let _ = tokio::io::copy(&mut client_read, &mut upstream_write).await;
+        std::future::pending::<io::Result<()>>().await
+    };
+    tokio::select! {
+        result = response => result,
+        result = request_body => result,
+    }
+}
+
+/// Reads up to the end of the request head. Returns the head and any bytes
+/// that were read past it.
+async fn read_request_head(client: &mut TcpStream) -> io::Result<(String, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = find_subsequence(&buf, b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            let head = String::from_utf8(buf)
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "request is not UTF-8"))?;
+            return Ok((head, rest));
+        }
+        if buf.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head is too large",
+            ));
+        }
+        let read = client.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before the request head was complete",
+            ));
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+}
+
+async fn respond(client: &mut TcpStream, status: &str, message: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{message}",
+        message.len()
+    );
+    client.write_all(response.as_bytes()).await?;
+    client.shutdown().await
+}
+
+#[derive(Debug, PartialEq)]
+struct ProxyRequest {
+    method: String,
+    host: String,
+    port: u16,
+    /// Request head to send upstream; `None` for `CONNECT` tunnels.
+    forward_head: Option<String>,
+}
+
+impl ProxyRequest {
+    fn parse(head: &str) -> Result<Self, &'static str> {
+        let mut lines = head.split("\r\n");
+        let request_line = lines.next().unwrap_or_default();
+        let mut parts = request_line.split(' ');
+        let (Some(method), Some(target), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err("invalid request line");
+        };
+
+        if method.eq_ignore_ascii_case("CONNECT") {
+            let (host, port) = split_host_port(target, None).ok_or("invalid CONNECT target")?;
+            return Ok(Self {
+                method: method.to_string(),
+                host,
+                port,
+                forward_head: None,
+            });
+        }
+
+        let Some(rest) = target.strip_prefix("http://") else {
+            return Err("only CONNECT and absolute http:// requests are supported");
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let (host, port) = split_host_port(authority, Some(80)).ok_or("invalid request host")?;
+
+        // Forward in origin form, without the headers meant for the proxy, and
+        // limited to this one request.
+        let mut forward_head = format!("{method} {path} {version}\r\n");
+        for line in lines.filter(|line| !line.is_empty()) {
+            let name = line
+                .split(':')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            if name.starts_with("proxy-") || name == "connection" || name == "keep-alive" {
+                continue;
+            }
+            forward_head.push_str(line);
+            forward_head.push_str("\r\n");
+        }
+        forward_head.push_str("Connection: close\r\n\r\n");
+
+        Ok(Self {
+            method: method.to_string(),
+            host,
+            port,
+            forward_head: Some(forward_head),
+        })
+    }
+}
+
+/// Splits `host:port`, accepting bracketed IPv6 literals. Without a port,
+/// `default_port` is used if given.
+fn split_host_port(authority: &str, default_port: Option<u16>) -> Option<(String, u16)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port?,
+    };
+    let host = normalize_host(host);
+    if host.is_empty() {
+        return None;
+    }
+    Some((host, port))
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// `example.com` matches only that host; `*.example.com` matches any of its
+/// subdomains but not `example.com` itself.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+        None => pattern == host,
+    }
+}
+
+fn find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matches_exact_hosts_and_wildcard_subdomains() {
+        assert!(host_matches("crates.io", "crates.io"));
+        assert!(!host_matches("crates.io", "static.crates.io"));
+        assert!(host_matches("*.crates.io", "static.crates.io"));
+        assert!(!host_matches("*.crates.io", "crates.io"));
+        assert!(!host_matches("*.crates.io", "evilcrates.io"));
+        assert_eq!(
+            split_host_port("[::1]:8080", None),
+            Some(("::1".to_string(), 8080))
+        );
+        assert_eq!(
+            split_host_port("PyPI.org.", Some(80)),
+            Some(("pypi.org".to_string(), 80))
+        );
+        assert_eq!(split_host_port("pypi.org", None), None);
+    }
+
+    #[test]
+    fn rewrites_absolute_form_requests() {
+        let request = ProxyRequest::parse(
+            "GET http://example.com:8080/simple/?q=1 HTTP/1.1\r\nHost: example.com:8080\r\nProxy-Connection: keep-alive\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .expect("valid request");
+        assert_eq!(
+            request,
+            ProxyRequest {
+                method: "GET".to_string(),
+                host: "example.com".to_string(),
+                port: 8080,
+                forward_head: Some(
+                    "GET /simple/?q=1 HTTP/1.1\r\nHost: example.com:8080\r\nConnection: close\r\n\r\n"
+                        .to_string()
+                ),
+            }
+        );
+        assert_eq!(
+            ProxyRequest::parse("GET https://example.com/ HTTP/1.1\r\n\r\n"),
+            Err("only CONNECT and absolute http:// requests are supported")
+        );
+    }
+
+    /// Answers each request with a plain-text response echoing its request
+    /// line, and keeps the connection open unless asked to close it.
+    async fn start_upstream() -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .expect("bind upstream");
+        let addr = listener.local_addr().expect("upstream addr");
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    while let Ok((head, _)) = read_request_head(&mut stream).await {
+                        let request_line = head.lines().next().unwrap_or_default().to_string();
+                        if head.to_ascii_lowercase().contains("\r\nconnection: close") {
+                            let _ = respond(&mut stream, "200 OK", &request_line).await;
+                            break;
+                        }
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{request_line}",
+                            request_line.len()
+                        );
+                        if stream.write_all(response.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    async fn send(proxy: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(proxy).await.expect("connect to proxy");
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .expect("write request");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("read response");
+        response
+    }
+
+    #[tokio::test]
+    async fn forwards_allowlisted_requests_and_tunnels() {
+        let upstream = start_upstream().await;
+        let (proxy, _approvals) = NetworkProxy::start(&NetworkProxyConfig {
+            allowed_domains: vec!["localhost".to_string()],
+        })
+        .await
+        .expect("start proxy");
+
+        let response = send(
+            proxy.addr(),
+            &format!(
+                "GET http://localhost:{}/index.html HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                upstream.port()
+            ),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("GET /index.html HTTP/1.1"), "{response}");
+
+        let response = send(
+            proxy.addr(),
+            &format!(
+                "CONNECT localhost:{port} HTTP/1.1\r\n\r\nGET /tunneled HTTP/1.1\r\nConnection: close\r\n\r\n",
+                port = upstream.port()
+            ),
+        )
+        .await;
+        assert!(
+            response.starts_with("HTTP/1.1 200 Connection Established\r\n\r\nHTTP/1.1 200 OK"),
+            "{response}"
+        );
+        assert!(response.ends_with("GET /tunneled HTTP/1.1"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn plain_http_connections_carry_a_single_request() {
+        let upstream = start_upstream().await;
+        let (proxy, _approvals) = NetworkProxy::start(&NetworkProxyConfig {
+            allowed_domains: vec!["localhost".to_string()],
+        })
+        .await
+        .expect("start proxy");
+
+        // The client asks for keep-alive, but the proxy closes the connection
+        // after the first response so a follow-up cannot reuse the upstream.
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            send(
+                proxy.addr(),
+                &format!(
+                    "GET http://localhost:{}/first HTTP/1.1\r\nHost: localhost\r\nConnection: keep-alive\r\n\r\n",
+                    upstream.port()
+                ),
+            ),
+        )
+        .await
+        .expect("proxy closes the connection after one response");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("GET /first HTTP/1.1"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn prompts_for_different_hosts_do_not_wait_for_each_other() {
+        let upstream = start_upstream().await;
+        let (proxy, mut approvals) = NetworkProxy::start(&NetworkProxyConfig::default())
+            .await
+            .expect("start proxy");
+        let addr = proxy.addr();
+        let port = upstream.port();
+
+        let first = tokio::spawn(async move {
+            send(
+                addr,
+                &format!("GET http://127.0.0.1:{port}/ HTTP/1.1\r\n\r\n"),
+            )
+            .await
+        });
+        let first_approval = approvals.recv().await.expect("first approval request");
+        // While the first prompt is still open, another host gets its own.
+        let second = tokio::spawn(async move {
+            send(
+                addr,
+                &format!("GET http://localhost:{port}/ HTTP/1.1\r\n\r\n"),
+            )
+            .await
+        });
+        let second_approval = approvals.recv().await.expect("second approval request");
+        assert_eq!(
+            (first_approval.host.as_str(), second_approval.host.as_str()),
+            ("127.0.0.1", "localhost")
+        );
+
+        let _ = second_approval.reply.send(ReviewDecision::Approved);
+        let response = second.await.expect("second request");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        let _ = first_approval.reply.send(ReviewDecision::Denied);
+        let response = first.await.expect("first request");
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn asks_before_connecting_to_unknown_hosts() {
+        let upstream = start_upstream().await;
+        let (proxy, mut approvals) = NetworkProxy::start(&NetworkProxyConfig::default())
+            .await
+            .expect("start proxy");
+        let request = format!("GET http://127.0.0.1:{}/ HTTP/1.1\r\n\r\n", upstream.port());
+
+        let (response, approval) = tokio::join!(send(proxy.addr(), &request), async {
+            let approval = approvals.recv().await.expect("approval request");
+            let target = (approval.host.clone(), approval.port);
+            let _ = approval.reply.send(ReviewDecision::Denied);
+            target
+        });
+        assert_eq!(approval, ("127.0.0.1".to_string(), upstream.port()));
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"), "{response}");
+
+        // Denials are remembered for the session.
+        let response = send(proxy.addr(), &request).await;
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"), "{response}");
+        assert!(approvals.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn remembers_hosts_approved_for_the_session() {
+        let upstream = start_upstream().await;
+        let (proxy, mut approvals) = NetworkProxy::start(&NetworkProxyConfig::default())
+            .await
+            .expect("start proxy");
+        let request = format!("GET http://127.0.0.1:{}/ HTTP/1.1\r\n\r\n", upstream.port());
+
+        let (response, ()) = tokio::join!(send(proxy.addr(), &request), async {
+            let approval = approvals.recv().await.expect("approval request");
+            let _ = approval.reply.send(ReviewDecision::ApprovedForSession);
+        });
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+
+        let response = send(proxy.addr(), &request).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(approvals.try_recv().is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! Notes pinned to a session so they survive compaction.
+//!
+//! Compaction replaces the conversation with a summary, which can drop or
+//! paraphrase facts established early on. Pinned notes are kept in session
+//! state and appended verbatim, in a single `<pinned_context>` message, to
+//! the history that replaces the compacted one.
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::PinnedNote;
+
+pub(crate) const PINNED_CONTEXT_OPEN_TAG: &str = "<pinned_context>";
+const PINNED_CONTEXT_CLOSE_TAG: &str = "</pinned_context>";
+
+/// Builds the message that carries `notes` into the conversation, or `None`
+/// when nothing is pinned.
+pub(crate) fn pinned_context_item(notes: &[PinnedNote]) -> Option<ResponseItem> {
+    if notes.is_empty() {
+        return None;
+    }
+    let mut text = format!(
+        "{PINNED_CONTEXT_OPEN_TAG}\nThese notes were pinned earlier in this session and still apply:\n"
+    );
+    for note in notes {
+        text.push_str(&format!("\n<note>\n{}\n</note>\n", note.text));
+    }
+    text.push_str(PINNED_CONTEXT_CLOSE_TAG);
+    Some(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText { text }],
+    })
+}
+
+/// Appends the pinned notes to `history`, which has just replaced the
+/// conversation during compaction. Earlier pinned-context messages that
+/// survived compaction are dropped first, and ghost snapshots stay at the end.
+pub(crate) fn append_pinned_context(history: &mut Vec<ResponseItem>, notes: &[PinnedNote]) {
+    history.retain(|item| !is_pinned_context_item(item));
+    let Some(item) = pinned_context_item(notes) else {
+        return;
+    };
+    let index = history
+        .iter()
+        .position(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
+        .unwrap_or(history.len());
+    history.insert(index, item);
+}
+
+fn is_pinned_context_item(item: &ResponseItem) -> bool {
+    let ResponseItem::Message { role, content, .. } = item else {
+        return false;
+    };
+    role == "user"
+        && matches!(
+            content.as_slice(),
+            [ContentItem::InputText { text }] if text.starts_with(PINNED_CONTEXT_OPEN_TAG)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn pinned_notes_replace_stale_copy_before_ghost_snapshots() {
+        let summary = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "summary".to_string(),
+            }],
+        };
+        let ghost = ResponseItem::GhostSnapshot {
+            ghost_commit: codex_git::GhostCommit::new("abc".to_string(), None, vec![], vec![]),
+        };
+        let stale = pinned_context_item(&[PinnedNote {
+            id: 1,
+            text: "stale".to_string(),
+        }])
+        .expect("pinned context item");
+        let mut history = vec![summary.clone(), stale, ghost.clone()];
+        let notes = vec![
+            PinnedNote {
+                id: 1,
+                text: "Never edit files under migrations/.".to_string(),
+            },
+            PinnedNote {
+                id: 2,
+                text: "Target Rust 1.80:\n  no let chains".to_string(),
+            },
+        ];
+
+        append_pinned_context(&mut history, &notes);
+
+        let expected = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "<pinned_context>\nThese notes were pinned earlier in this session and still apply:\n\n<note>\nNever edit files under migrations/.\n</note>\n\n<note>\nTarget Rust 1.80:\n  no let chains\n</note>\n</pinned_context>".to_string(),
+            }],
+        };
+        assert_eq!(history, vec![summary, expected, ghost]);
+    }
+
+    #[test]
+    fn nothing_is_appended_without_pins() {
+        let mut history = Vec::new();
+        append_pinned_context(&mut history, &[]);
+        assert_eq!(history, Vec::new());
+    }
+}
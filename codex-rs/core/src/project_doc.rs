@@ -18,6 +18,7 @@ use crate::features::Feature;
 use crate::skills::load_skills;
 use crate::skills::render_skills_section;
 use dunce::canonicalize as normalize_path;
+use std::path::Path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
 use tracing::error;
@@ -104,29 +105,12 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
             break;
         }
 
-        let file = match tokio::fs::File::open(&p).await {
-            Ok(f) => f,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-            Err(e) => return Err(e),
+        let Some(text) = read_project_doc(&p, remaining).await? else {
+            continue;
         };
-
-        let size = file.metadata().await?.len();
-        let mut reader = tokio::io::BufReader::new(file).take(remaining);
-        let mut data: Vec<u8> = Vec::new();
-        reader.read_to_end(&mut data).await?;
-
-        if size > remaining {
-            tracing::warn!(
-                "Project doc `{}` exceeds remaining budget ({} bytes) - truncating.",
-                p.display(),
-                remaining,
-            );
-        }
-
-        let text = String::from_utf8_lossy(&data).to_string();
         if !text.trim().is_empty() {
+            remaining = remaining.saturating_sub(text.len() as u64);
             parts.push(text);
-            remaining = remaining.saturating_sub(data.len() as u64);
         }
     }
 
@@ -137,6 +121,34 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
     }
 }
 
+/// Reads at most `remaining` bytes of the project doc at `path`. Returns
+/// `Ok(None)` when the file does not exist.
+pub(crate) async fn read_project_doc(
+    path: &Path,
+    remaining: u64,
+) -> std::io::Result<Option<String>> {
+    let file = match tokio::fs::File::open(path).await {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let size = file.metadata().await?.len();
+    let mut reader = tokio::io::BufReader::new(file).take(remaining);
+    let mut data: Vec<u8> = Vec::new();
+    reader.read_to_end(&mut data).await?;
+
+    if size > remaining {
+        tracing::warn!(
+            "Project doc `{}` exceeds remaining budget ({} bytes) - truncating.",
+            path.display(),
+            remaining,
+        );
+    }
+
+    Ok(Some(String::from_utf8_lossy(&data).to_string()))
+}
+
 /// Discover the list of AGENTS.md files using the same search rules as
 /// `read_project_docs`, but return the file paths instead of concatenated
 /// contents. The list is ordered from repository root to the current working
@@ -148,40 +160,13 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
         dir = canon;
     }
 
-    // Build chain from cwd upwards and detect git root.
-    let mut chain: Vec<PathBuf> = vec![dir.clone()];
-    let mut git_root: Option<PathBuf> = None;
-    let mut cursor = dir;
-    while let Some(parent) = cursor.parent() {
-        let git_marker = cursor.join(".git");
-        let git_exists = match std::fs::metadata(&git_marker) {
-            Ok(_) => true,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
-            Err(e) => return Err(e),
-        };
-
-        if git_exists {
-            git_root = Some(cursor.clone());
-            break;
-        }
-
-        chain.push(parent.to_path_buf());
-        cursor = parent.to_path_buf();
-    }
-
-    let search_dirs: Vec<PathBuf> = if let Some(root) = git_root {
-        let mut dirs: Vec<PathBuf> = Vec::new();
-        let mut saw_root = false;
-        for p in chain.iter().rev() {
-            if !saw_root {
-                if p == &root {
-                    saw_root = true;
-                } else {
-                    continue;
-                }
-            }
-            dirs.push(p.clone());
-        }
+    let search_dirs: Vec<PathBuf> = if let Some(root) = find_git_root(&dir)? {
+        let mut dirs: Vec<PathBuf> = dir
+            .ancestors()
+            .take_while(|p| p.starts_with(&root))
+            .map(Path::to_path_buf)
+            .collect();
+        dirs.reverse();
         dirs
     } else {
         vec![config.cwd.clone()]
@@ -190,26 +175,79 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
     let mut found: Vec<PathBuf> = Vec::new();
     let candidate_filenames = candidate_filenames(config);
     for d in search_dirs {
-        for name in &candidate_filenames {
-            let candidate = d.join(name);
-            match std::fs::symlink_metadata(&candidate) {
-                Ok(md) => {
-                    let ft = md.file_type();
-                    // Allow regular files and symlinks; opening will later fail for dangling links.
-                    if ft.is_file() || ft.is_symlink() {
-                        found.push(candidate);
-                        break;
-                    }
-                }
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-                Err(e) => return Err(e),
-            }
+        if let Some(doc) = find_project_doc(&d, &candidate_filenames)? {
+            found.push(doc);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Discover the project docs that apply to `dir` but were not picked up by
+/// [`discover_project_doc_paths`]: one file per directory from the repository
+/// root down to `dir` (inclusive), skipping the directories on the way to the
+/// current working directory. Without a Git root only directories below the
+/// current working directory are considered. Paths outside of those bounds
+/// yield an empty list.
+pub(crate) fn discover_nested_project_doc_paths(
+    config: &Config,
+    dir: &Path,
+) -> std::io::Result<Vec<PathBuf>> {
+    let cwd = normalize_path(&config.cwd).unwrap_or_else(|_| config.cwd.clone());
+    let root = find_git_root(&cwd)?.unwrap_or_else(|| cwd.clone());
+    let dir = normalize_path(dir).unwrap_or_else(|_| dir.to_path_buf());
+
+    let mut search_dirs: Vec<&Path> = dir
+        .ancestors()
+        .take_while(|p| p.starts_with(&root))
+        .filter(|p| !cwd.starts_with(p))
+        .collect();
+    search_dirs.reverse();
+
+    let mut found: Vec<PathBuf> = Vec::new();
+    let candidate_filenames = candidate_filenames(config);
+    for d in search_dirs {
+        if let Some(doc) = find_project_doc(d, &candidate_filenames)? {
+            found.push(doc);
         }
     }
 
     Ok(found)
 }
 
+/// Walks upwards from `dir` until a `.git` directory or file is found.
+fn find_git_root(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let mut cursor = dir;
+    while let Some(parent) = cursor.parent() {
+        match std::fs::metadata(cursor.join(".git")) {
+            Ok(_) => return Ok(Some(cursor.to_path_buf())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        cursor = parent;
+    }
+    Ok(None)
+}
+
+/// Returns the first of `candidate_filenames` present in `dir`.
+fn find_project_doc(dir: &Path, candidate_filenames: &[&str]) -> std::io::Result<Option<PathBuf>> {
+    for name in candidate_filenames {
+        let candidate = dir.join(name);
+        match std::fs::symlink_metadata(&candidate) {
+            Ok(md) => {
+                let ft = md.file_type();
+                // Allow regular files and symlinks; opening will later fail for dangling links.
+                if ft.is_file() || ft.is_symlink() {
+                    return Ok(Some(candidate));
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
 fn candidate_filenames<'a>(config: &'a Config) -> Vec<&'a str> {
     let mut names: Vec<&'a str> =
         Vec::with_capacity(2 + config.project_doc_fallback_filenames.len());
@@ -0,0 +1,535 @@
+//! Enforcement of the `[resource_limits]` config for commands the agent runs.
+//!
+//! On Linux each command gets its own cgroup v2 below Codex's own cgroup when
+//! that cgroup was delegated to Codex; memory, process and CPU limits then
+//! cover the command's whole process tree. Everything else, and everything on
+//! other platforms, is applied with rlimits in the child right before exec.
+
+use std::process::ExitStatus;
+
+use crate::config::types::ResourceLimits;
+use crate::exec::ResourceLimitKind;
+
+#[cfg(unix)]
+const MIB: u64 = 1024 * 1024;
+
+/// Limits prepared for a single command. Dropping it removes the command's
+/// cgroup, if one was created.
+pub(crate) struct CommandLimits {
+    limits: ResourceLimits,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<cgroup::CommandCgroup>,
+}
+
+impl CommandLimits {
+    /// Returns `None` when no limit is configured.
+    pub(crate) fn new(limits: ResourceLimits) -> Option<Self> {
+        if limits.is_empty() {
+            return None;
+        }
+
+        #[cfg(target_os = "linux")]
+        let cgroup = cgroup::CommandCgroup::create(&limits).unwrap_or_else(|err| {
+            tracing::debug!("running command without a cgroup, using rlimits only: {err}");
+            None
+        });
+
+        Some(Self {
+            limits,
+            #[cfg(target_os = "linux")]
+            cgroup,
+        })
+    }
+
+    /// Returns a `pre_exec` hook that moves the child into the command's
+    /// cgroup and applies the rlimits.
+    #[cfg(unix)]
+    pub(crate) fn pre_exec_hook(
+        &self,
+    ) -> impl FnMut() -> std::io::Result<()> + Send + Sync + 'static {
+        let rlimits = self.rlimits();
+        #[cfg(target_os = "linux")]
+        let cgroup_procs = self.cgroup.as_ref().map(|cgroup| cgroup.procs.clone());
+        move || {
+            #[cfg(target_os = "linux")]
+            if let Some(procs) = &cgroup_procs {
+                cgroup::join(procs)?;
+            }
+            for rlimit in &rlimits {
+                rlimit.apply()?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Works out which limit, if any, stopped the command.
+    pub(crate) fn exceeded(&self, exit_status: ExitStatus) -> Option<ResourceLimitKind> {
+        #[cfg(target_os = "linux")]
+        if let Some(kind) = self
+            .cgroup
+            .as_ref()
+            .and_then(cgroup::CommandCgroup::exceeded)
+        {
+            return Some(kind);
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+
+            // An exit code of 128 + signal is not trusted: the command may
+            // simply have exited with it.
+            match exit_status.signal() {
+                Some(libc::SIGXCPU) if self.limits.cpu_time_secs.is_some() => {
+                    return Some(ResourceLimitKind::CpuTime);
+                }
+                Some(libc::SIGXFSZ) if self.limits.max_file_size_mb.is_some() => {
+                    return Some(ResourceLimitKind::FileSize);
+                }
+                _ => {}
+            }
+        }
+
+        #[cfg(not(unix))]
+        let _ = exit_status;
+
+        None
+    }
+
+    #[cfg(unix)]
+    fn rlimits(&self) -> Vec<Rlimit> {
+        #[cfg(target_os = "linux")]
+        let (cgroup_memory, cgroup_pids) = self
+            .cgroup
+            .as_ref()
+            .map_or((false, false), |cgroup| (cgroup.memory, cgroup.pids));
+        #[cfg(not(target_os = "linux"))]
+        let (cgroup_memory, cgroup_pids) = (false, false);
+
+        let mut rlimits = Vec::new();
+        if let Some(secs) = self.limits.cpu_time_secs {
+            // SIGXCPU at the soft limit, SIGKILL a second later if ignored.
+            rlimits.push(Rlimit::new(libc::RLIMIT_CPU, secs, secs.saturating_add(1)));
+        }
+        if let Some(mb) = self.limits.max_file_size_mb {
+            let bytes = mb.saturating_mul(MIB);
+            rlimits.push(Rlimit::new(libc::RLIMIT_FSIZE, bytes, bytes));
+        }
+        if let Some(mb) = self.limits.memory_mb
+            && !cgroup_memory
+        {
+            let bytes = mb.saturating_mul(MIB);
+            rlimits.push(Rlimit::new(libc::RLIMIT_AS, bytes, bytes));
+        }
+        if let Some(processes) = self.limits.max_processes
+            && !cgroup_pids
+        {
+            rlimits.push(Rlimit::new(libc::RLIMIT_NPROC, processes, processes));
+        }
+        rlimits
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type RlimitResource = libc::c_int;
+
+#[cfg(unix)]
+struct Rlimit {
+    resource: RlimitResource,
+    soft: libc::rlim_t,
+    hard: libc::rlim_t,
+}
+
+#[cfg(unix)]
+impl Rlimit {
+    fn new(resource: RlimitResource, soft: u64, hard: u64) -> Self {
+        Self {
+            resource,
+            soft: soft as libc::rlim_t,
+            hard: hard as libc::rlim_t,
+        }
+    }
+
+    /// Lowers the limit for the calling process. Never tries to raise the
+    /// current hard limit, which would need privileges.
+    fn apply(&self) -> std::io::Result<()> {
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        if unsafe { libc::getrlimit(self.resource, &mut current) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let limit = libc::rlimit {
+            rlim_cur: self.soft.min(current.rlim_max),
+            rlim_max: self.hard.min(current.rlim_max),
+        };
+        if unsafe { libc::setrlimit(self.resource, &limit) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod cgroup {
+    use std::ffi::CStr;
+    use std::ffi::CString;
+    use std::fs;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::sync::OnceLock;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+
+    use super::MIB;
+    use crate::config::types::ResourceLimits;
+    use crate::exec::ResourceLimitKind;
+
+    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+    const CPU_PERIOD_US: u64 = 100_000;
+    const CONTROLLERS: [&str; 3] = ["memory", "pids", "cpu"];
+    /// Leaf below Codex's own cgroup that Codex and its other children move
+    /// into, so the own cgroup can hand controllers to command cgroups.
+    const SUPERVISOR_LEAF: &str = "codex";
+    /// How far up the process tree to look when checking whether a process
+    /// was started by Codex.
+    const MAX_ANCESTRY_DEPTH: usize = 64;
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+    /// The delegated subtree command cgroups are created in, set up on first
+    /// use.
+    static SUBTREE: OnceLock<Result<Subtree, String>> = OnceLock::new();
+
+    /// Cgroups that still held background processes when their command
+    /// finished. They are removed once those processes are gone.
+    static LINGERING: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+    struct Subtree {
+        path: PathBuf,
+        controllers: Vec<&'static str>,
+    }
+
+    pub(super) struct CommandCgroup {
+        path: PathBuf,
+        pub(super) procs: CString,
+        pub(super) memory: bool,
+        pub(super) pids: bool,
+    }
+
+    impl CommandCgroup {
+        /// Returns `Ok(None)` when none of the limits needs a cgroup.
+        pub(super) fn create(limits: &ResourceLimits) -> io::Result<Option<Self>> {
+            let wanted: Vec<&str> = [
+                (limits.memory_mb.is_some(), "memory"),
+                (limits.max_processes.is_some(), "pids"),
+                (limits.cpus.is_some(), "cpu"),
+            ]
+            .into_iter()
+            .filter_map(|(wanted, controller)| wanted.then_some(controller))
+            .collect();
+            if wanted.is_empty() {
+                return Ok(None);
+            }
+
+            remove_lingering();
+            let subtree = SUBTREE
+                .get_or_init(|| set_up_subtree().map_err(|err| err.to_string()))
+                .as_ref()
+                .map_err(|err| io::Error::other(err.clone()))?;
+            let enabled: Vec<&str> = wanted
+                .iter()
+                .copied()
+                .filter(|controller| subtree.controllers.contains(controller))
+                .collect();
+            if enabled.is_empty() {
+                return Err(io::Error::other(format!(
+                    "{} does not delegate any of {wanted:?}",
+                    subtree.path.display()
+                )));
+            }
+
+            let path = subtree.path.join(format!(
+                "codex-exec-{}",
+                NEXT_ID.fetch_add(1, Ordering::Relaxed)
+            ));
+            let procs = CString::new(path.join("cgroup.procs").as_os_str().as_bytes())
+                .map_err(io::Error::other)?;
+            fs::create_dir(&path)?;
+            let mut cgroup = Self {
+                path,
+                procs,
+                memory: false,
+                pids: false,
+            };
+
+            if let Some(mb) = limits.memory_mb
+                && enabled.contains(&"memory")
+            {
+                cgroup.write("memory.max", &mb.saturating_mul(MIB).to_string())?;
+                // Otherwise the command is pushed into swap instead of stopped.
+                let _ = cgroup.write("memory.swap.max", "0");
+                cgroup.memory = true;
+            }
+            if let Some(processes) = limits.max_processes
+                && enabled.contains(&"pids")
+            {
+                cgroup.write("pids.max", &processes.to_string())?;
+                cgroup.pids = true;
+            }
+            if let Some(cpus) = limits.cpus
+                && enabled.contains(&"cpu")
+            {
+                let quota = ((cpus * CPU_PERIOD_US as f64) as u64).max(1_000);
+                cgroup.write("cpu.max", &format!("{quota} {CPU_PERIOD_US}"))?;
+            }
+            Ok(Some(cgroup))
+        }
+
+        pub(super) fn exceeded(&self) -> Option<ResourceLimitKind> {
+            if self.memory && self.event_count("memory.events", "oom_kill") > 0 {
+                return Some(ResourceLimitKind::Memory);
+            }
+            if self.pids && self.event_count("pids.events", "max") > 0 {
+                return Some(ResourceLimitKind::Processes);
+            }
+            None
+        }
+
+        fn write(&self, file: &str, value: &str) -> io::Result<()> {
+            fs::write(self.path.join(file), value)
+        }
+
+        fn event_count(&self, file: &str, event: &str) -> u64 {
+            fs::read_to_string(self.path.join(file))
+                .ok()
+                .and_then(|events| parse_event_count(&events, event))
+                .unwrap_or(0)
+        }
+    }
+
+    impl Drop for CommandCgroup {
+        fn drop(&mut self) {
+            // Background processes started by the command keep running, so
+            // the cgroup can only go away once they exit.
+            if fs::remove_dir(&self.path).is_err()
+                && let Ok(mut lingering) = LINGERING.lock()
+            {
+                lingering.push(self.path.clone());
+            }
+        }
+    }
+
+    /// Moves the calling process into the cgroup. Runs between fork and exec,
+    /// so it sticks to raw syscalls.
+    pub(super) fn join(procs: &CStr) -> io::Result<()> {
+        unsafe {
+            let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+            let err = io::Error::last_os_error();
+            libc::close(fd);
+            if written != 1 {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_lingering() {
+        if let Ok(mut lingering) = LINGERING.lock() {
+            lingering.retain(|path| {
+                fs::remove_dir(path).is_err_and(|err| err.kind() != io::ErrorKind::NotFound)
+            });
+        }
+    }
+
+    /// Command cgroups only go below Codex's own cgroup, and only when that
+    /// cgroup was delegated to Codex, as with `systemd-run --user --scope -p
+    /// Delegate=yes`. cgroup v2 does not allow handing controllers to the
+    /// children of a cgroup that has processes, so Codex and the processes it
+    /// started move into a leaf first. A cgroup shared with processes Codex
+    /// did not start, such as the terminal's shell, is left alone, and so is
+    /// everything above it.
+    fn set_up_subtree() -> io::Result<Subtree> {
+        let root = Path::new(CGROUP_ROOT);
+        if !root.join("cgroup.controllers").exists() {
+            return Err(io::Error::other("cgroup v2 is not mounted"));
+        }
+        let own = own_cgroup(root, &fs::read_to_string("/proc/self/cgroup")?)?;
+
+        let pid = std::process::id();
+        let members: Vec<u32> = fs::read_to_string(own.join("cgroup.procs"))?
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+        if let Some(other) = members
+            .iter()
+            .find(|&&member| member != pid && !is_descendant(member, pid))
+        {
+            return Err(io::Error::other(format!(
+                "{} is shared with process {other} that Codex did not start",
+                own.display()
+            )));
+        }
+
+        let leaf = own.join(SUPERVISOR_LEAF);
+        if let Err(err) = fs::create_dir(&leaf)
+            && err.kind() != io::ErrorKind::AlreadyExists
+        {
+            return Err(err);
+        }
+        let leaf_procs = leaf.join("cgroup.procs");
+        fs::write(&leaf_procs, pid.to_string())?;
+        for member in members.into_iter().filter(|&member| member != pid) {
+            // The process may have exited in the meantime.
+            let _ = fs::write(&leaf_procs, member.to_string());
+        }
+
+        let controllers = enable_controllers(&own)?;
+        Ok(Subtree {
+            path: own,
+            controllers,
+        })
+    }
+
+    /// The cgroup v2 path listed in `/proc/self/cgroup`. The root cgroup is
+    /// never delegated, so it is rejected.
+    fn own_cgroup(root: &Path, membership: &str) -> io::Result<PathBuf> {
+        let own = membership
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .ok_or_else(|| io::Error::other("not running in a cgroup v2 hierarchy"))?
+            .trim_start_matches('/');
+        if own.is_empty() {
+            return Err(io::Error::other("running in the root cgroup"));
+        }
+        Ok(root.join(own))
+    }
+
+    fn is_descendant(pid: u32, ancestor: u32) -> bool {
+        let mut pid = pid;
+        for _ in 0..MAX_ANCESTRY_DEPTH {
+            let Some(parent) = fs::read_to_string(format!("/proc/{pid}/stat"))
+                .ok()
+                .and_then(|stat| parent_pid(&stat))
+            else {
+                return false;
+            };
+            if parent == ancestor {
+                return true;
+            }
+            if parent <= 1 {
+                return false;
+            }
+            pid = parent;
+        }
+        false
+    }
+
+    /// The parent pid from `/proc/<pid>/stat`. The command name comes before
+    /// it in parentheses and may itself contain spaces or parentheses.
+    fn parent_pid(stat: &str) -> Option<u32> {
+        let (_, rest) = stat.rsplit_once(')')?;
+        rest.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    fn parse_event_count(events: &str, event: &str) -> Option<u64> {
+        events.lines().find_map(|line| {
+            let (name, count) = line.split_once(' ')?;
+            (name == event).then(|| count.trim().parse().ok())?
+        })
+    }
+
+    /// Enables the controllers the limits can use for the children of `own`
+    /// where possible and returns the ones that ended up enabled.
+    fn enable_controllers(own: &Path) -> io::Result<Vec<&'static str>> {
+        let subtree_control = own.join("cgroup.subtree_control");
+        let enabled = fs::read_to_string(&subtree_control)?;
+        for controller in CONTROLLERS {
+            if !enabled.split_whitespace().any(|c| c == controller) {
+                let _ = fs::write(&subtree_control, format!("+{controller}"));
+            }
+        }
+        let enabled = fs::read_to_string(&subtree_control)?;
+        Ok(CONTROLLERS
+            .into_iter()
+            .filter(|controller| enabled.split_whitespace().any(|c| c == *controller))
+            .collect())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn own_cgroup_is_read_from_the_unified_hierarchy_line() {
+            let root = Path::new("/sys/fs/cgroup");
+            let membership = "12:pids:/user.slice\n0::/user.slice/user-1000.slice/run-r1.scope\n";
+            assert_eq!(
+                own_cgroup(root, membership).unwrap(),
+                PathBuf::from("/sys/fs/cgroup/user.slice/user-1000.slice/run-r1.scope")
+            );
+            assert!(own_cgroup(root, "0::/\n").is_err());
+            assert!(own_cgroup(root, "12:pids:/user.slice\n").is_err());
+        }
+
+        #[test]
+        fn parent_pid_skips_command_names_with_spaces_and_parentheses() {
+            assert_eq!(parent_pid("4242 (bash) S 4100 4242 4242 0"), Some(4100));
+            assert_eq!(parent_pid("4243 (my (odd) cmd) R 4242 4243"), Some(4242));
+            assert_eq!(parent_pid("garbage"), None);
+        }
+
+        #[test]
+        fn event_counts_are_parsed_by_name() {
+            let events = "low 0\nhigh 0\nmax 3\noom 1\noom_kill 1\n";
+            assert_eq!(parse_event_count(events, "max"), Some(3));
+            assert_eq!(parse_event_count(events, "oom_kill"), Some(1));
+            assert_eq!(parse_event_count(events, "oom_group_kill"), None);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::os::unix::process::ExitStatusExt;
+
+    #[test]
+    fn exceeded_limits_are_read_from_the_terminating_signal() {
+        let limits = CommandLimits {
+            limits: ResourceLimits {
+                cpu_time_secs: Some(1),
+                max_file_size_mb: Some(1),
+                ..Default::default()
+            },
+            #[cfg(target_os = "linux")]
+            cgroup: None,
+        };
+
+        assert_eq!(
+            limits.exceeded(ExitStatus::from_raw(libc::SIGXCPU)),
+            Some(ResourceLimitKind::CpuTime)
+        );
+        assert_eq!(
+            limits.exceeded(ExitStatus::from_raw(libc::SIGXFSZ)),
+            Some(ResourceLimitKind::FileSize)
+        );
+        // Exiting with 128 + SIGXCPU is an ordinary exit code.
+        assert_eq!(
+            limits.exceeded(ExitStatus::from_raw((128 + libc::SIGXCPU) << 8)),
+            None
+        );
+    }
+}
@@ -0,0 +1,447 @@
+//! Execpolicy rule suggestions mined from recorded approval decisions.
+//!
+//! Every answer to an exec approval request is recorded in the rollout as an
+//! `ExecApprovalDecision` item. Each approved or denied command, or each
+//! command of a `bash -lc` script, is reduced to its stem: the program and
+//! the plain words that follow it, up to the first flag, path or value. A
+//! command is then grouped under the shortest prefix of its stem that no
+//! command with the opposite decision shares, so `cargo nextest run -p core`
+//! and `cargo nextest run -p tui` both land under `cargo nextest`. Approved
+//! groups become `allow` suggestions and denied groups `prompt` suggestions.
+//! An `allow` suggestion always has at least `MIN_PREFIX_TOKENS` tokens, so a
+//! bare program such as `rm` is never allowed outright.
+//! A forked rollout repeats its parent's decisions; they are counted once,
+//! in both sessions.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_execpolicy::Decision;
+use codex_execpolicy::MatchContext;
+use codex_execpolicy::Policy;
+use codex_protocol::protocol::ExecApprovalDecisionItem;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use serde::Serialize;
+use shlex::try_join;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compression;
+use super::list::collect_rollout_paths;
+use crate::bash::parse_shell_lc_plain_commands;
+
+/// Suggested prefixes are at least this long. Shorter stems only become
+/// `prompt` suggestions.
+const MIN_PREFIX_TOKENS: usize = 2;
+/// Older sessions recorded network approvals as commands starting with this.
+const NETWORK_ACCESS_COMMAND: &str = "network-access";
+/// Stems stop after this many tokens.
+const MAX_PREFIX_TOKENS: usize = 4;
+/// Distinct commands kept as examples for each suggestion.
+const MAX_EXAMPLES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrefixRuleSuggestion {
+    pub prefix: Vec<String>,
+    pub decision: Decision,
+    /// Number of decisions this rule would have made for the user.
+    pub count: usize,
+    /// Number of sessions those decisions were made in.
+    pub sessions: usize,
+    /// A few of the commands behind the suggestion, shell-quoted.
+    pub examples: Vec<String>,
+}
+
+/// Reads the approval decisions recorded under `codex_home` and clusters
+/// them into prefix rules. Commands that already match a rule of `policy` are
+/// left out, as are suggestions backed by fewer than `min_count` decisions.
+pub async fn suggest_prefix_rules(
+    codex_home: &Path,
+    policy: &Policy,
+    min_count: usize,
+) -> io::Result<Vec<PrefixRuleSuggestion>> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let mut sessions = Vec::new();
+    for path in collect_rollout_paths(&root).await? {
+        match compression::read_to_string(&path).await {
+            Ok(text) => sessions.push((path, parse_decisions(&text))),
+            Err(err) => warn!("failed to read rollout {}: {err}", path.display()),
+        }
+    }
+    Ok(cluster_decisions(&sessions, policy, min_count))
+}
+
+/// One approved or denied command, after splitting shell scripts.
+struct DecidedCommand<'a> {
+    sessions: &'a [&'a Path],
+    command: Vec<String>,
+    stem: Vec<String>,
+    approved: bool,
+}
+
+#[derive(Default)]
+struct SuggestionBuilder {
+    count: usize,
+    sessions: HashSet<PathBuf>,
+    examples: BTreeSet<String>,
+}
+
+fn cluster_decisions(
+    sessions: &[(PathBuf, Vec<ExecApprovalDecisionItem>)],
+    policy: &Policy,
+    min_count: usize,
+) -> Vec<PrefixRuleSuggestion> {
+    // Decisions in the order they were first seen, with every session that
+    // recorded them.
+    let mut decisions: Vec<(&ExecApprovalDecisionItem, Vec<&Path>)> = Vec::new();
+    let mut index_by_call: HashMap<(&String, &Vec<String>), usize> = HashMap::new();
+    for (path, items) in sessions {
+        for item in items {
+            match index_by_call.get(&(&item.call_id, &item.command)) {
+                Some(&index) => decisions[index].1.push(path.as_path()),
+                None => {
+                    index_by_call.insert((&item.call_id, &item.command), decisions.len());
+                    decisions.push((item, vec![path.as_path()]));
+                }
+            }
+        }
+    }
+
+    let mut commands = Vec::new();
+    for (item, recorded_in) in &decisions {
+        let approved = match item.decision {
+            ReviewDecision::Approved
+            | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+            | ReviewDecision::ApprovedForSession => true,
+            ReviewDecision::Denied | ReviewDecision::Abort => false,
+        };
+        let context = MatchContext::new(item.cwd.clone());
+        let inner = parse_shell_lc_plain_commands(&item.command)
+            .unwrap_or_else(|| vec![item.command.clone()]);
+        for command in inner {
+            if command.is_empty() || !policy.matching_rules(&command, &context).is_empty() {
+                continue;
+            }
+            commands.push(DecidedCommand {
+                sessions: recorded_in,
+                stem: stem(&command),
+                command,
+                approved,
+            });
+        }
+    }
+
+    // How many approved and denied stems pass through each prefix.
+    let mut counts: HashMap<&[String], (usize, usize)> = HashMap::new();
+    for command in &commands {
+        for len in 1..=command.stem.len() {
+            let entry = counts.entry(&command.stem[..len]).or_default();
+            if command.approved {
+                entry.0 += 1;
+            } else {
+                entry.1 += 1;
+            }
+        }
+    }
+
+    let mut builders: BTreeMap<(&[String], bool), SuggestionBuilder> = BTreeMap::new();
+    for command in &commands {
+        if command.approved && command.stem.len() < MIN_PREFIX_TOKENS {
+            continue;
+        }
+        let min_len = MIN_PREFIX_TOKENS.min(command.stem.len());
+        let prefix = (min_len..=command.stem.len())
+            .map(|len| &command.stem[..len])
+            .find(|prefix| {
+                let (approved, denied) = counts.get(prefix).copied().unwrap_or_default();
+                if command.approved {
+                    denied == 0
+                } else {
+                    approved == 0
+                }
+            });
+        // Commands whose whole stem was both approved and denied say nothing
+        // about a rule.
+        let Some(prefix) = prefix else {
+            continue;
+        };
+        let builder = builders.entry((prefix, command.approved)).or_default();
+        builder.count += 1;
+        builder
+            .sessions
+            .extend(command.sessions.iter().map(|session| session.to_path_buf()));
+        builder.examples.insert(
+            try_join(command.command.iter().map(String::as_str))
+                .unwrap_or_else(|_| command.command.join(" ")),
+        );
+    }
+
+    let mut suggestions: Vec<PrefixRuleSuggestion> = builders
+        .into_iter()
+        .filter(|(_, builder)| builder.count >= min_count)
+        .map(|((prefix, approved), builder)| PrefixRuleSuggestion {
+            prefix: prefix.to_vec(),
+            decision: if approved {
+                Decision::Allow
+            } else {
+                Decision::Prompt
+            },
+            count: builder.count,
+            sessions: builder.sessions.len(),
+            examples: builder.examples.into_iter().take(MAX_EXAMPLES).collect(),
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.prefix.cmp(&b.prefix)));
+    suggestions
+}
+
+/// The program followed by the plain words after it, so flags, paths and
+/// values such as crate names are left for the rule to match anything.
+fn stem(command: &[String]) -> Vec<String> {
+    let words = command
+        .iter()
+        .skip(1)
+        .take_while(|token| is_plain_word(token));
+    command
+        .iter()
+        .take(1)
+        .chain(words)
+        .take(MAX_PREFIX_TOKENS)
+        .cloned()
+        .collect()
+}
+
+fn is_plain_word(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_alphabetic())
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':')
+}
+
+fn parse_decisions(text: &str) -> Vec<ExecApprovalDecisionItem> {
+    text.lines()
+        // Skip the bulk of the rollout without parsing it.
+        .filter(|line| line.contains("\"exec_approval_decision\""))
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line.trim()).ok())
+        .filter_map(|line| match line.item {
+            RolloutItem::ExecApprovalDecision(item) => Some(item),
+            _ => None,
+        })
+        .filter(|item| item.command.first().map(String::as_str) != Some(NETWORK_ACCESS_COMMAND))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_execpolicy::PolicyParser;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn decision(call_id: &str, command: &[&str], decision: ReviewDecision) -> String {
+        let line = RolloutLine {
+            timestamp: "2025-01-06T09:00:00.000Z".to_string(),
+            item: RolloutItem::ExecApprovalDecision(ExecApprovalDecisionItem {
+                call_id: call_id.to_string(),
+                command: command.iter().map(ToString::to_string).collect(),
+                cwd: PathBuf::from("/repo"),
+                decision,
+            }),
+        };
+        serde_json::to_string(&line).expect("serialize rollout line") + "\n"
+    }
+
+    const FIRST_ID: &str = "5973b6c0-94b8-487b-a530-2aeb6098ae0e";
+    const FORK_ID: &str = "0199a213-81c0-7800-8aa1-bbab2a035a53";
+
+    fn write_rollout(codex_home: &Path, id: &str, lines: &[String]) {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/01/06");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        std::fs::write(
+            dir.join(format!("rollout-2025-01-06T09-00-00-{id}.jsonl")),
+            lines.concat(),
+        )
+        .expect("write rollout");
+    }
+
+    fn suggestion(
+        prefix: &[&str],
+        decision: Decision,
+        count: usize,
+        sessions: usize,
+        examples: &[&str],
+    ) -> PrefixRuleSuggestion {
+        PrefixRuleSuggestion {
+            prefix: prefix.iter().map(ToString::to_string).collect(),
+            decision,
+            count,
+            sessions,
+            examples: examples.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn clusters_decisions_across_sessions_into_prefix_rules() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let first = [
+            decision(
+                "call-1",
+                &["cargo", "nextest", "run", "-p", "codex-core"],
+                ReviewDecision::Approved,
+            ),
+            decision(
+                "call-2",
+                &[
+                    "bash",
+                    "-lc",
+                    "cargo nextest run -p codex-tui && git status",
+                ],
+                ReviewDecision::ApprovedForSession,
+            ),
+            decision(
+                "call-3",
+                &["git", "push", "origin", "main"],
+                ReviewDecision::Approved,
+            ),
+            decision(
+                "call-4",
+                &["git", "push", "--force"],
+                ReviewDecision::Denied,
+            ),
+            decision("call-5", &["rm", "-rf", "/"], ReviewDecision::Abort),
+        ];
+        write_rollout(codex_home.path(), FIRST_ID, &first);
+        // A fork repeats the first session's decisions before adding its own.
+        let mut fork = first.to_vec();
+        fork.extend([
+            decision(
+                "call-6",
+                &["cargo", "nextest", "run", "--workspace"],
+                ReviewDecision::Approved,
+            ),
+            decision(
+                "call-7",
+                &["git", "push", "origin", "main"],
+                ReviewDecision::Approved,
+            ),
+            decision("call-8", &["rm", "-rf", "build"], ReviewDecision::Denied),
+        ]);
+        write_rollout(codex_home.path(), FORK_ID, &fork);
+
+        let policy = PolicyParser::new().build();
+        let suggestions = suggest_prefix_rules(codex_home.path(), &policy, 2)
+            .await
+            .expect("suggest");
+
+        assert_eq!(
+            suggestions,
+            vec![
+                suggestion(
+                    &["cargo", "nextest"],
+                    Decision::Allow,
+                    3,
+                    2,
+                    &[
+                        "cargo nextest run --workspace",
+                        "cargo nextest run -p codex-core",
+                        "cargo nextest run -p codex-tui",
+                    ],
+                ),
+                suggestion(
+                    &["git", "push", "origin"],
+                    Decision::Allow,
+                    2,
+                    2,
+                    &["git push origin main"],
+                ),
+                suggestion(
+                    &["rm"],
+                    Decision::Prompt,
+                    2,
+                    2,
+                    &["rm -rf /", "rm -rf build"],
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn commands_matched_by_the_policy_are_not_suggested() {
+        let mut parser = PolicyParser::new();
+        parser
+            .parse(
+                "test.codexpolicy",
+                r#"prefix_rule(pattern = ["cargo", "nextest"], decision = "allow")"#,
+            )
+            .expect("parse policy");
+        let policy = parser.build();
+        let sessions = vec![(
+            PathBuf::from("rollout.jsonl"),
+            parse_decisions(&decision(
+                "call-1",
+                &["cargo", "nextest", "run"],
+                ReviewDecision::Approved,
+            )),
+        )];
+
+        assert_eq!(cluster_decisions(&sessions, &policy, 1), Vec::new());
+    }
+
+    #[test]
+    fn single_token_approvals_are_not_suggested_as_allow_rules() {
+        let policy = PolicyParser::new().build();
+        let lines = [
+            decision("call-1", &["make"], ReviewDecision::Approved),
+            decision("call-2", &["make"], ReviewDecision::Approved),
+            decision("call-3", &["rm", "-rf", "/tmp/x"], ReviewDecision::Approved),
+            decision("call-4", &["rm", "-rf", "/tmp/y"], ReviewDecision::Approved),
+            decision("call-5", &["shutdown", "-h", "now"], ReviewDecision::Denied),
+            decision("call-6", &["shutdown", "-r", "now"], ReviewDecision::Denied),
+        ];
+        let sessions = vec![(
+            PathBuf::from("rollout.jsonl"),
+            parse_decisions(&lines.concat()),
+        )];
+
+        assert_eq!(
+            cluster_decisions(&sessions, &policy, 2),
+            vec![suggestion(
+                &["shutdown"],
+                Decision::Prompt,
+                2,
+                1,
+                &["shutdown -h now", "shutdown -r now"],
+            )]
+        );
+    }
+
+    #[test]
+    fn network_approvals_are_not_mined() {
+        let policy = PolicyParser::new().build();
+        let lines = [
+            decision(
+                "network-1",
+                &["network-access", "example.com:443"],
+                ReviewDecision::Approved,
+            ),
+            decision(
+                "network-2",
+                &["network-access", "example.com:443"],
+                ReviewDecision::ApprovedForSession,
+            ),
+        ];
+        let sessions = vec![(
+            PathBuf::from("rollout.jsonl"),
+            parse_decisions(&lines.concat()),
+        )];
+
+        assert_eq!(cluster_decisions(&sessions, &policy, 1), Vec::new());
+    }
+}
@@ -0,0 +1,180 @@
+//! zstd compression of closed rollout files.
+//!
+//! A compressed rollout keeps its original name with a `.zst` suffix
+//! (`rollout-…-<uuid>.jsonl.zst`) and its original modification time, so
+//! listing order and "last updated" times do not change. The recorder never
+//! appends to a compressed file: resuming one decompresses it back to `.jsonl`
+//! first, marking it as just modified so a retention sweep leaves it alone
+//! until the recorder has it open. The only writes to a `.zst` file are label
+//! edits from [`super::labels::append_session_labels`], which add a separate
+//! zstd frame and keep the modification time.
+
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use tempfile::NamedTempFile;
+
+pub(crate) const COMPRESSED_SUFFIX: &str = ".zst";
+
+/// zstd's default level; a good trade-off for JSONL, which compresses well.
+const COMPRESSION_LEVEL: i32 = 3;
+
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "zst")
+}
+
+/// Path of the compressed counterpart of the rollout at `path`.
+pub(crate) fn compressed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(COMPRESSED_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Path of the plain counterpart of the compressed rollout at `path`.
+pub(crate) fn decompressed_path(path: &Path) -> PathBuf {
+    path.with_extension("")
+}
+
+/// Reads `path` line by line, decompressing if needed. This does blocking
+/// I/O.
+pub(crate) fn read_lines(path: &Path) -> io::Result<io::Lines<Box<dyn BufRead + Send>>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn BufRead + Send> = if is_compressed(path) {
+        Box::new(BufReader::new(zstd::Decoder::new(file)?))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    Ok(reader.lines())
+}
+
+/// Reads the whole rollout at `path` as text, decompressing if needed.
+pub(crate) async fn read_to_string(path: &Path) -> io::Result<String> {
+    if !is_compressed(path) {
+        return tokio::fs::read_to_string(path).await;
+    }
+    let bytes = tokio::fs::read(path).await?;
+    let text = tokio::task::spawn_blocking(move || zstd::decode_all(bytes.as_slice()))
+        .await
+        .map_err(io::Error::other)??;
+    String::from_utf8(text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Replaces the rollout at `path` with a compressed copy and returns the new
+/// path.
+pub(crate) async fn compress(path: &Path) -> io::Result<PathBuf> {
+    let source = path.to_path_buf();
+    let target = compressed_path(path);
+    tokio::task::spawn_blocking(move || {
+        transcode(&source, &target, |input, output| {
+            zstd::stream::copy_encode(input, output, COMPRESSION_LEVEL)
+        })?;
+        Ok(target)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Replaces the compressed rollout at `path` with a plain copy and returns the
+/// new path. The copy's modification time is now.
+pub(crate) async fn decompress(path: &Path) -> io::Result<PathBuf> {
+    let source = path.to_path_buf();
+    let target = decompressed_path(path);
+    tokio::task::spawn_blocking(move || {
+        transcode(&source, &target, |input, output| {
+            zstd::stream::copy_decode(input, output)
+        })?;
+        File::options()
+            .write(true)
+            .open(&target)?
+            .set_modified(SystemTime::now())?;
+        Ok(target)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Whether `file` is still the one at `path`, rather than one that compression
+/// or decompression has since replaced.
+pub(crate) fn is_still_at(file: &File, path: &Path) -> bool {
+    let (Ok(opened), Ok(current)) = (file.metadata(), std::fs::metadata(path)) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        opened.dev() == current.dev() && opened.ino() == current.ino()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (opened, current);
+        true
+    }
+}
+
+/// Writes `convert(source)` to `target` atomically, carries over the
+/// modification time and removes `source`.
+fn transcode(
+    source: &Path,
+    target: &Path,
+    convert: impl FnOnce(&File, &mut File) -> io::Result<()>,
+) -> io::Result<()> {
+    let input = File::open(source)?;
+    let modified = input.metadata()?.modified()?;
+    let dir = target
+        .parent()
+        .ok_or_else(|| io::Error::other(format!("{} has no parent", target.display())))?;
+    let mut tmp = NamedTempFile::new_in(dir)?;
+    convert(&input, tmp.as_file_mut())?;
+    tmp.as_file().sync_all()?;
+    tmp.as_file().set_modified(modified)?;
+    tmp.persist(target).map_err(|err| err.error)?;
+    std::fs::remove_file(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn compress_round_trips_and_keeps_mtime() {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join("rollout-2025-01-01T00-00-00-id.jsonl");
+        let text = "{\"a\":1}\n{\"b\":2}\n".repeat(100);
+        std::fs::write(&path, &text).expect("write");
+        let mtime = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|f| f.set_modified(mtime))
+            .expect("set mtime");
+
+        let compressed = compress(&path).await.expect("compress");
+        assert_eq!(compressed, compressed_path(&path));
+        assert!(!path.exists());
+        assert!(is_compressed(&compressed));
+        assert_eq!(
+            std::fs::metadata(&compressed)
+                .and_then(|m| m.modified())
+                .ok(),
+            Some(mtime)
+        );
+        assert_eq!(read_to_string(&compressed).await.expect("read"), text);
+        let first = read_lines(&compressed)
+            .expect("open")
+            .next()
+            .and_then(Result::ok);
+        assert_eq!(first.as_deref(), Some("{\"a\":1}"));
+
+        let restored = decompress(&compressed).await.expect("decompress");
+        assert_eq!(restored, path);
+        assert!(!compressed.exists());
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), text);
+    }
+}
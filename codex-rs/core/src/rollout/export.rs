@@ -0,0 +1,897 @@
+//! Rendering of rollout files into shareable transcripts.
+//!
+//! A rollout is first reduced to a [`Transcript`]: the conversation as a
+//! reader would follow it, with tool calls paired with their outputs and
+//! internal context (environment context, instructions) left out. The
+//! transcript is then rendered as Markdown, HTML or JSON.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::items::AgentMessageContent;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::plan_tool::PlanItemArg;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::user_input::UserInput;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::RolloutRecorder;
+use super::labels::SessionLabels;
+use crate::event_mapping::parse_turn_item;
+use crate::parse_command::shlex_join;
+use crate::tool_call_text::command_from_arguments;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl ExportFormat {
+    /// File extension conventionally used for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Include the model's reasoning summaries.
+    pub include_reasoning: bool,
+    /// Include the output of tool calls (commands, patches, MCP tools).
+    pub include_tool_output: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            include_reasoning: true,
+            include_tool_output: true,
+        }
+    }
+}
+
+/// A session reduced to what a reader of the conversation cares about.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Transcript {
+    pub session_id: Option<String>,
+    /// Title set by the user, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub started_at: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub model: Option<String>,
+    pub cli_version: Option<String>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    UserMessage {
+        text: String,
+    },
+    AgentMessage {
+        text: String,
+    },
+    Reasoning {
+        text: String,
+    },
+    ToolCall {
+        call_id: String,
+        name: String,
+        input: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    Patch {
+        call_id: String,
+        patch: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    PlanUpdate {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        explanation: Option<String>,
+        plan: Vec<PlanItemArg>,
+    },
+    WebSearch {
+        query: String,
+    },
+    Compacted,
+}
+
+impl TranscriptEntry {
+    fn is_user(&self) -> bool {
+        matches!(self, TranscriptEntry::UserMessage { .. })
+    }
+}
+
+/// Reads the rollout at `path` and renders it in `format`.
+pub async fn export_rollout(
+    path: &Path,
+    format: ExportFormat,
+    options: ExportOptions,
+) -> std::io::Result<String> {
+    let history = RolloutRecorder::get_rollout_history(path).await?;
+    let transcript = Transcript::from_rollout_items(&history.get_rollout_items(), options);
+    transcript.render(format)
+}
+
+impl Transcript {
+    pub fn from_rollout_items(items: &[RolloutItem], options: ExportOptions) -> Self {
+        let mut transcript = Transcript::default();
+        // Index of the entry each pending tool call was recorded at, so its
+        // output can be attached when it shows up.
+        let mut calls: HashMap<String, usize> = HashMap::new();
+        let mut labels = SessionLabels::default();
+
+        for item in items {
+            match item {
+                RolloutItem::SessionMeta(meta_line) => {
+                    if transcript.session_id.is_none() {
+                        let meta = &meta_line.meta;
+                        transcript.session_id = Some(meta.id.to_string());
+                        transcript.started_at = Some(meta.timestamp.clone());
+                        transcript.cwd = Some(meta.cwd.clone());
+                        transcript.cli_version = Some(meta.cli_version.clone());
+                    }
+                }
+                RolloutItem::TurnContext(turn_context) => {
+                    if transcript.model.is_none() {
+                        transcript.model = Some(turn_context.model.clone());
+                    }
+                }
+                RolloutItem::Compacted(_) => transcript.entries.push(TranscriptEntry::Compacted),
+                RolloutItem::ResponseItem(item) => {
+                    transcript.push_response_item(item, options, &mut calls);
+                }
+                RolloutItem::EventMsg(_) | RolloutItem::ExecApprovalDecision(_) => {}
+                RolloutItem::SessionLabels(item) => labels.apply(item),
+            }
+        }
+
+        transcript.title = labels.title;
+        transcript.tags = labels.tags;
+        transcript
+    }
+
+    pub fn render(&self, format: ExportFormat) -> std::io::Result<String> {
+        match format {
+            ExportFormat::Markdown => Ok(self.to_markdown()),
+            ExportFormat::Html => Ok(self.to_html()),
+            ExportFormat::Json => serde_json::to_string_pretty(self)
+                .map(|mut json| {
+                    json.push('\n');
+                    json
+                })
+                .map_err(std::io::Error::other),
+        }
+    }
+
+    fn push_response_item(
+        &mut self,
+        item: &ResponseItem,
+        options: ExportOptions,
+        calls: &mut HashMap<String, usize>,
+    ) {
+        let entry = match item {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => tool_call_entry(call_id, name, arguments),
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                if name == "apply_patch" {
+                    TranscriptEntry::Patch {
+                        call_id: call_id.clone(),
+                        patch: input.clone(),
+                        output: None,
+                    }
+                } else {
+                    TranscriptEntry::ToolCall {
+                        call_id: call_id.clone(),
+                        name: name.clone(),
+                        input: input.clone(),
+                        output: None,
+                    }
+                }
+            }
+            ResponseItem::LocalShellCall {
+                id,
+                call_id,
+                action: LocalShellAction::Exec(action),
+                ..
+            } => TranscriptEntry::ToolCall {
+                call_id: call_id.clone().or_else(|| id.clone()).unwrap_or_default(),
+                name: "local_shell".to_string(),
+                input: shlex_join(&action.command),
+                output: None,
+            },
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                self.attach_output(calls, call_id, tool_output_text(&output.content), options);
+                return;
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                self.attach_output(calls, call_id, tool_output_text(output), options);
+                return;
+            }
+            item => match parse_turn_item(item) {
+                Some(TurnItem::UserMessage(message)) => TranscriptEntry::UserMessage {
+                    text: user_message_text(&message.content),
+                },
+                Some(TurnItem::AgentMessage(message)) => TranscriptEntry::AgentMessage {
+                    text: message
+                        .content
+                        .iter()
+                        .map(|AgentMessageContent::Text { text }| text.as_str())
+                        .collect(),
+                },
+                Some(TurnItem::Reasoning(reasoning)) => {
+                    if !options.include_reasoning || reasoning.summary_text.is_empty() {
+                        return;
+                    }
+                    TranscriptEntry::Reasoning {
+                        text: reasoning.summary_text.join("\n\n"),
+                    }
+                }
+                Some(TurnItem::WebSearch(search)) => TranscriptEntry::WebSearch {
+                    query: search.query,
+                },
+                None => return,
+            },
+        };
+
+        if let TranscriptEntry::ToolCall { call_id, .. } | TranscriptEntry::Patch { call_id, .. } =
+            &entry
+        {
+            calls.insert(call_id.clone(), self.entries.len());
+        }
+        self.entries.push(entry);
+    }
+
+    fn attach_output(
+        &mut self,
+        calls: &HashMap<String, usize>,
+        call_id: &str,
+        text: String,
+        options: ExportOptions,
+    ) {
+        if !options.include_tool_output {
+            return;
+        }
+        match calls
+            .get(call_id)
+            .and_then(|index| self.entries.get_mut(*index))
+        {
+            Some(TranscriptEntry::ToolCall { output, .. })
+            | Some(TranscriptEntry::Patch { output, .. }) => *output = Some(text),
+            _ => {}
+        }
+    }
+
+    fn title(&self) -> String {
+        if let Some(title) = &self.title {
+            return title.clone();
+        }
+        match &self.session_id {
+            Some(id) => format!("Codex session {id}"),
+            None => "Codex session".to_string(),
+        }
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = Vec::new();
+        if self.title.is_some()
+            && let Some(session_id) = &self.session_id
+        {
+            details.push(("Session", session_id.clone()));
+        }
+        if !self.tags.is_empty() {
+            details.push(("Tags", self.tags.join(", ")));
+        }
+        if let Some(started_at) = &self.started_at {
+            details.push(("Started", started_at.clone()));
+        }
+        if let Some(cwd) = &self.cwd {
+            details.push(("Directory", cwd.display().to_string()));
+        }
+        if let Some(model) = &self.model {
+            details.push(("Model", model.clone()));
+        }
+        if let Some(cli_version) = &self.cli_version {
+            details.push(("Codex version", cli_version.clone()));
+        }
+        details
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title());
+        for (label, value) in self.details() {
+            let _ = writeln!(out, "- **{label}:** {value}");
+        }
+
+        let mut last_was_user = None;
+        for entry in &self.entries {
+            if last_was_user != Some(entry.is_user())
+                && !matches!(entry, TranscriptEntry::Compacted)
+            {
+                let speaker = if entry.is_user() { "User" } else { "Codex" };
+                let _ = write!(out, "\n## {speaker}\n");
+                last_was_user = Some(entry.is_user());
+            }
+            out.push('\n');
+            match entry {
+                TranscriptEntry::UserMessage { text } | TranscriptEntry::AgentMessage { text } => {
+                    let _ = writeln!(out, "{}", text.trim_end());
+                }
+                TranscriptEntry::Reasoning { text } => {
+                    out.push_str("**Reasoning**\n\n");
+                    for line in text.trim_end().lines() {
+                        let _ = writeln!(out, "> {line}");
+                    }
+                }
+                TranscriptEntry::ToolCall {
+                    name,
+                    input,
+                    output,
+                    ..
+                } => {
+                    let _ = writeln!(out, "**Tool call:** `{name}`\n");
+                    out.push_str(&fenced(input, "sh"));
+                    if let Some(output) = output {
+                        out.push_str("\nOutput:\n\n");
+                        out.push_str(&fenced(output, ""));
+                    }
+                }
+                TranscriptEntry::Patch { patch, output, .. } => {
+                    out.push_str("**Patch**\n\n");
+                    out.push_str(&fenced(patch, "diff"));
+                    if let Some(output) = output {
+                        out.push_str("\nOutput:\n\n");
+                        out.push_str(&fenced(output, ""));
+                    }
+                }
+                TranscriptEntry::PlanUpdate { explanation, plan } => {
+                    out.push_str("**Plan update**\n\n");
+                    if let Some(explanation) = explanation {
+                        let _ = writeln!(out, "{}\n", explanation.trim_end());
+                    }
+                    for item in plan {
+                        let (mark, note) = match item.status {
+                            StepStatus::Completed => ("x", ""),
+                            StepStatus::InProgress => (" ", " _(in progress)_"),
+                            StepStatus::Pending => (" ", ""),
+                        };
+                        let _ = writeln!(out, "- [{mark}] {}{note}", item.step);
+                    }
+                }
+                TranscriptEntry::WebSearch { query } => {
+                    let _ = writeln!(out, "**Web search:** {query}");
+                }
+                TranscriptEntry::Compacted => {
+                    out.push_str("---\n\n_Earlier conversation was compacted._\n");
+                }
+            }
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let title = escape_html(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        let details = self.details();
+        if !details.is_empty() {
+            out.push_str("<dl class=\"details\">\n");
+            for (label, value) in details {
+                let _ = writeln!(out, "<dt>{label}</dt><dd>{}</dd>", escape_html(&value));
+            }
+            out.push_str("</dl>\n");
+        }
+
+        for entry in &self.entries {
+            match entry {
+                TranscriptEntry::UserMessage { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<section class=\"user\"><h2>User</h2><div class=\"text\">{}</div></section>",
+                        escape_html(text.trim_end())
+                    );
+                }
+                TranscriptEntry::AgentMessage { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<section class=\"agent\"><h2>Codex</h2><div class=\"text\">{}</div></section>",
+                        escape_html(text.trim_end())
+                    );
+                }
+                TranscriptEntry::Reasoning { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<details class=\"reasoning\"><summary>Reasoning</summary><div class=\"text\">{}</div></details>",
+                        escape_html(text.trim_end())
+                    );
+                }
+                TranscriptEntry::ToolCall {
+                    name,
+                    input,
+                    output,
+                    ..
+                } => {
+                    let _ = write!(
+                        out,
+                        "<div class=\"tool\"><div class=\"label\">Tool call: <code>{}</code></div><pre>{}</pre>",
+                        escape_html(name),
+                        escape_html(input.trim_end())
+                    );
+                    push_html_output(&mut out, output.as_deref());
+                    out.push_str("</div>\n");
+                }
+                TranscriptEntry::Patch { patch, output, .. } => {
+                    out.push_str(
+                        "<div class=\"tool\"><div class=\"label\">Patch</div><pre class=\"diff\">",
+                    );
+                    for line in patch.lines() {
+                        let class = match line.chars().next() {
+                            Some('+') if !line.starts_with("+++") => Some("add"),
+                            Some('-') if !line.starts_with("---") => Some("del"),
+                            _ => None,
+                        };
+                        match class {
+                            Some(class) => {
+                                let _ = writeln!(
+                                    out,
+                                    "<span class=\"{class}\">{}</span>",
+                                    escape_html(line)
+                                );
+                            }
+                            None => {
+                                let _ = writeln!(out, "{}", escape_html(line));
+                            }
+                        }
+                    }
+                    out.push_str("</pre>");
+                    push_html_output(&mut out, output.as_deref());
+                    out.push_str("</div>\n");
+                }
+                TranscriptEntry::PlanUpdate { explanation, plan } => {
+                    out.push_str("<div class=\"plan\"><div class=\"label\">Plan update</div>");
+                    if let Some(explanation) = explanation {
+                        let _ = write!(out, "<p>{}</p>", escape_html(explanation.trim_end()));
+                    }
+                    out.push_str("<ul>");
+                    for item in plan {
+                        let mark = match item.status {
+                            StepStatus::Completed => "&#x2611;",
+                            StepStatus::InProgress => "&#x25B6;",
+                            StepStatus::Pending => "&#x2610;",
+                        };
+                        let _ = write!(out, "<li>{mark} {}</li>", escape_html(&item.step));
+                    }
+                    out.push_str("</ul></div>\n");
+                }
+                TranscriptEntry::WebSearch { query } => {
+                    let _ = writeln!(
+                        out,
+                        "<div class=\"tool\"><div class=\"label\">Web search: {}</div></div>",
+                        escape_html(query)
+                    );
+                }
+                TranscriptEntry::Compacted => {
+                    out.push_str("<hr><p class=\"note\">Earlier conversation was compacted.</p>\n");
+                }
+            }
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: -apple-system, BlinkMacSystemFont, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; color: #1f2328; }
+h2 { font-size: 1rem; margin: 0 0 0.25rem; }
+section, .tool, .plan, details { margin: 1rem 0; }
+section.user { border-left: 3px solid #0969da; padding-left: 0.75rem; }
+.text { white-space: pre-wrap; }
+.label { font-weight: 600; font-size: 0.9rem; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; white-space: pre-wrap; }
+.diff .add { color: #1a7f37; }
+.diff .del { color: #cf222e; }
+.details dt { font-weight: 600; float: left; clear: left; margin-right: 0.5rem; }
+.details dd { margin: 0; }
+.note { color: #59636e; font-style: italic; }
+ul { list-style: none; padding-left: 0; }
+";
+
+fn push_html_output(out: &mut String, output: Option<&str>) {
+    if let Some(output) = output {
+        let _ = write!(
+            out,
+            "<details><summary>Output</summary><pre>{}</pre></details>",
+            escape_html(output.trim_end())
+        );
+    }
+}
+
+fn tool_call_entry(call_id: &str, name: &str, arguments: &str) -> TranscriptEntry {
+    let args: Option<Value> = serde_json::from_str(arguments).ok();
+    match name {
+        "update_plan" => {
+            if let Ok(UpdatePlanArgs { explanation, plan }) = serde_json::from_str(arguments) {
+                return TranscriptEntry::PlanUpdate { explanation, plan };
+            }
+        }
+        "apply_patch" => {
+            if let Some(patch) = args.as_ref().and_then(|args| args.get("input")?.as_str()) {
+                return TranscriptEntry::Patch {
+                    call_id: call_id.to_string(),
+                    patch: patch.to_string(),
+                    output: None,
+                };
+            }
+        }
+        _ => {}
+    }
+
+    let input = args
+        .as_ref()
+        .and_then(command_from_arguments)
+        .or_else(|| {
+            args.as_ref()
+                .and_then(|args| serde_json::to_string_pretty(args).ok())
+        })
+        .unwrap_or_else(|| arguments.to_string());
+    TranscriptEntry::ToolCall {
+        call_id: call_id.to_string(),
+        name: name.to_string(),
+        input,
+        output: None,
+    }
+}
+
+/// Shell tools report `{"output": ..., "metadata": ...}`; show just the
+/// output in that case.
+fn tool_output_text(content: &str) -> String {
+    serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|value| value.get("output")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| content.to_string())
+}
+
+fn user_message_text(content: &[UserInput]) -> String {
+    content
+        .iter()
+        .filter_map(|input| match input {
+            UserInput::Text { text } => Some(text.clone()),
+            UserInput::Image { .. } => Some("[image]".to_string()),
+            UserInput::LocalImage { path } => Some(format!("[image: {}]", path.display())),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Wraps `text` in a code fence longer than any backtick run inside it.
+fn fenced(text: &str, lang: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        if c == '`' {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{fence}{lang}\n{}\n{fence}\n", text.trim_end())
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use codex_protocol::config_types::ReasoningSummary;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::models::ReasoningItemReasoningSummary;
+    use codex_protocol::protocol::AskForApproval;
+    use codex_protocol::protocol::SandboxPolicy;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::TurnContextItem;
+    use pretty_assertions::assert_eq;
+
+    const SESSION_ID: &str = "5973b6c0-94b8-487b-a530-2aeb6098ae0e";
+
+    fn message(role: &str, content: ContentItem) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+        })
+    }
+
+    fn sample_items() -> Vec<RolloutItem> {
+        vec![
+            RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: ConversationId::from_string(SESSION_ID).expect("valid id"),
+                    timestamp: "2025-01-02T03:04:05Z".to_string(),
+                    cwd: PathBuf::from("/repo"),
+                    cli_version: "0.0.0".to_string(),
+                    ..Default::default()
+                },
+                git: None,
+            }),
+            RolloutItem::TurnContext(TurnContextItem {
+                cwd: PathBuf::from("/repo"),
+                approval_policy: AskForApproval::OnRequest,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                model: "gpt-5".to_string(),
+                effort: None,
+                summary: ReasoningSummary::Auto,
+            }),
+            message(
+                "user",
+                ContentItem::InputText {
+                    text: "<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>"
+                        .to_string(),
+                },
+            ),
+            message(
+                "user",
+                ContentItem::InputText {
+                    text: "Fix the <b> tag".to_string(),
+                },
+            ),
+            RolloutItem::ResponseItem(ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "Looking at the template".to_string(),
+                }],
+                content: None,
+                encrypted_content: None,
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["rg","-n","<b>"]}"#.to_string(),
+                call_id: "call-1".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: r#"{"output":"index.html:3:<b>","metadata":{"exit_code":0}}"#
+                        .to_string(),
+                    ..Default::default()
+                },
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "update_plan".to_string(),
+                arguments: r#"{"plan":[{"step":"Find tag","status":"completed"},{"step":"Fix tag","status":"in_progress"}]}"#.to_string(),
+                call_id: "call-2".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-3".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch\n*** Update File: index.html\n-<b>\n+</b>\n*** End Patch"
+                    .to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCallOutput {
+                call_id: "call-3".to_string(),
+                output: "Success. Updated the following files:\nM index.html".to_string(),
+            }),
+            message(
+                "assistant",
+                ContentItem::OutputText {
+                    text: "Fixed the closing tag.".to_string(),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn builds_transcript_entries() {
+        let transcript = Transcript::from_rollout_items(&sample_items(), ExportOptions::default());
+
+        assert_eq!(transcript.model.as_deref(), Some("gpt-5"));
+        assert_eq!(transcript.cwd, Some(PathBuf::from("/repo")));
+        assert_eq!(
+            transcript.entries,
+            vec![
+                TranscriptEntry::UserMessage {
+                    text: "Fix the <b> tag".to_string(),
+                },
+                TranscriptEntry::Reasoning {
+                    text: "Looking at the template".to_string(),
+                },
+                TranscriptEntry::ToolCall {
+                    call_id: "call-1".to_string(),
+                    name: "shell".to_string(),
+                    input: "rg -n '<b>'".to_string(),
+                    output: Some("index.html:3:<b>".to_string()),
+                },
+                TranscriptEntry::PlanUpdate {
+                    explanation: None,
+                    plan: vec![
+                        PlanItemArg {
+                            step: "Find tag".to_string(),
+                            status: StepStatus::Completed,
+                        },
+                        PlanItemArg {
+                            step: "Fix tag".to_string(),
+                            status: StepStatus::InProgress,
+                        },
+                    ],
+                },
+                TranscriptEntry::Patch {
+                    call_id: "call-3".to_string(),
+                    patch:
+                        "*** Begin Patch\n*** Update File: index.html\n-<b>\n+</b>\n*** End Patch"
+                            .to_string(),
+                    output: Some("Success. Updated the following files:\nM index.html".to_string()),
+                },
+                TranscriptEntry::AgentMessage {
+                    text: "Fixed the closing tag.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn omits_reasoning_and_tool_output_when_asked() {
+        let transcript = Transcript::from_rollout_items(
+            &sample_items(),
+            ExportOptions {
+                include_reasoning: false,
+                include_tool_output: false,
+            },
+        );
+
+        assert!(
+            !transcript
+                .entries
+                .iter()
+                .any(|entry| matches!(entry, TranscriptEntry::Reasoning { .. }))
+        );
+        assert!(transcript.entries.iter().all(|entry| match entry {
+            TranscriptEntry::ToolCall { output, .. } | TranscriptEntry::Patch { output, .. } =>
+                output.is_none(),
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let transcript = Transcript::from_rollout_items(&sample_items(), ExportOptions::default());
+        let markdown = transcript.render(ExportFormat::Markdown).expect("render");
+
+        let expected = format!(
+            "# Codex session {SESSION_ID}
+
+- **Started:** 2025-01-02T03:04:05Z
+- **Directory:** /repo
+- **Model:** gpt-5
+- **Codex version:** 0.0.0
+
+## User
+
+Fix the <b> tag
+
+## Codex
+
+**Reasoning**
+
+> Looking at the template
+
+**Tool call:** `shell`
+
+```sh
+rg -n '<b>'
+```
+
+Output:
+
+```
+index.html:3:<b>
+```
+
+**Plan update**
+
+- [x] Find tag
+- [ ] Fix tag _(in progress)_
+
+**Patch**
+
+```diff
+*** Begin Patch
+*** Update File: index.html
+-<b>
++</b>
+*** End Patch
+```
+
+Output:
+
+```
+Success. Updated the following files:
+M index.html
+```
+
+Fixed the closing tag.
+"
+        );
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn escapes_html() {
+        let transcript = Transcript::from_rollout_items(&sample_items(), ExportOptions::default());
+        let html = transcript.render(ExportFormat::Html).expect("render");
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<div class=\"text\">Fix the &lt;b&gt; tag</div>"));
+        assert!(html.contains("<span class=\"del\">-&lt;b&gt;</span>"));
+        assert!(!html.contains("Fix the <b> tag"));
+    }
+
+    #[test]
+    fn renders_json_entries() {
+        let transcript = Transcript::from_rollout_items(&sample_items(), ExportOptions::default());
+        let json: Value =
+            serde_json::from_str(&transcript.render(ExportFormat::Json).expect("render"))
+                .expect("valid json");
+
+        assert_eq!(json["model"], "gpt-5");
+        assert_eq!(json["entries"][0]["type"], "user_message");
+        assert_eq!(json["entries"][2]["type"], "tool_call");
+        assert_eq!(json["entries"][3]["plan"][1]["status"], "in_progress");
+    }
+
+    #[test]
+    fn fences_content_containing_backticks() {
+        assert_eq!(fenced("a ``` b", ""), "````\na ``` b\n````\n");
+        assert_eq!(fenced("plain", "sh"), "```sh\nplain\n```\n");
+    }
+}
@@ -0,0 +1,283 @@
+//! User-set titles, tags and stars for recorded sessions.
+//!
+//! Labels live in the rollout itself as [`RolloutItem::SessionLabels`] items,
+//! and the labels of a session are all of its items folded in order. Each
+//! item only carries the fields it changes, and tags are normally edited by
+//! adding or removing individual tags, so a running session and
+//! `codex sessions tag` can both append edits without losing each other's
+//! tags. The last title or starred flag written wins, and an edit that sets
+//! `tags` outright replaces whatever tags were recorded before it.
+
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionLabelsItem;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+
+use super::compression;
+
+/// Tag used to recognise labels lines before parsing them.
+const LABELS_LINE_MARKER: &str = "\"session_labels\"";
+
+/// The current title, tags and starred flag of a session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionLabels {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub starred: bool,
+}
+
+impl SessionLabels {
+    /// Folds one recorded edit into these labels.
+    pub fn apply(&mut self, item: &SessionLabelsItem) {
+        if let Some(title) = &item.title {
+            let title = title.trim();
+            self.title = (!title.is_empty()).then(|| title.to_string());
+        }
+        if let Some(tags) = &item.tags {
+            self.tags = normalize_tags(tags.iter().cloned());
+        }
+        if let Some(added) = &item.add_tags {
+            self.tags = normalize_tags(self.tags.iter().chain(added).cloned());
+        }
+        if let Some(removed) = &item.remove_tags {
+            self.tags
+                .retain(|tag| !removed.iter().any(|r| r.trim().eq_ignore_ascii_case(tag)));
+        }
+        if let Some(starred) = item.starred {
+            self.starred = starred;
+        }
+    }
+
+    /// Whether the session carries `tag`, ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim();
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Trims tags and drops empty and duplicate (case-insensitive) ones, keeping
+/// the first spelling of each.
+pub fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() || normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            continue;
+        }
+        normalized.push(tag.to_string());
+    }
+    normalized
+}
+
+/// Reads the labels of the rollout at `path`.
+pub async fn read_session_labels(path: &Path) -> io::Result<SessionLabels> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut labels = SessionLabels::default();
+        for line in compression::read_lines(&path)? {
+            let line = line?;
+            if let Some(item) = parse_labels_line(&line) {
+                labels.apply(&item);
+            }
+        }
+        Ok(labels)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Appends `item` to the rollout at `path` and returns the resulting labels.
+///
+/// This is meant for sessions that are not running in this process; a live
+/// session records edits through `Op::UpdateSessionLabels` instead. Appends
+/// are single writes, so they interleave safely with a recorder writing to
+/// the same file from another process. Compressed rollouts get the line as a
+/// new zstd frame, which decoders read as a continuation of the file.
+///
+/// Labelling is not activity, so the file keeps its modification time: a
+/// labelled session neither moves up in "last updated" order nor looks in use
+/// to retention.
+pub async fn append_session_labels(
+    path: &Path,
+    item: SessionLabelsItem,
+) -> io::Result<SessionLabels> {
+    let mut labels = read_session_labels(path).await?;
+    labels.apply(&item);
+
+    let timestamp_format: &[FormatItem] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z");
+    let timestamp = OffsetDateTime::now_utc()
+        .format(timestamp_format)
+        .map_err(|e| io::Error::other(format!("failed to format timestamp: {e}")))?;
+    let line = RolloutLine {
+        timestamp,
+        item: RolloutItem::SessionLabels(item),
+    };
+    let mut bytes = serde_json::to_vec(&line).map_err(io::Error::other)?;
+    bytes.push(b'\n');
+
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        if compression::is_compressed(&path) {
+            bytes = zstd::encode_all(bytes.as_slice(), 0)?;
+        } else if !ends_with_newline(&path)? {
+            // Don't glue the edit onto a line cut short by an interrupted write.
+            bytes.insert(0, b'\n');
+        }
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path)?;
+        let modified = file.metadata()?.modified()?;
+        file.write_all(&bytes)?;
+        file.flush()?;
+        file.set_modified(modified)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    Ok(labels)
+}
+
+fn ends_with_newline(path: &Path) -> io::Result<bool> {
+    let mut file = std::fs::File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+    file.seek(SeekFrom::End(-1))?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last)?;
+    Ok(last[0] == b'\n')
+}
+
+fn parse_labels_line(line: &str) -> Option<SessionLabelsItem> {
+    if !line.contains(LABELS_LINE_MARKER) {
+        return None;
+    }
+    match serde_json::from_str::<RolloutLine>(line).ok()?.item {
+        RolloutItem::SessionLabels(item) => Some(item),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn apply_keeps_fields_an_edit_leaves_unset() {
+        let mut labels = SessionLabels::default();
+        labels.apply(&SessionLabelsItem {
+            title: Some("  release prep ".to_string()),
+            tags: Some(vec![
+                "release".into(),
+                " Release".into(),
+                "".into(),
+                "db".into(),
+            ]),
+            starred: None,
+            add_tags: None,
+            remove_tags: None,
+        });
+        labels.apply(&SessionLabelsItem {
+            starred: Some(true),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            labels,
+            SessionLabels {
+                title: Some("release prep".to_string()),
+                tags: vec!["release".to_string(), "db".to_string()],
+                starred: true,
+            }
+        );
+        assert!(labels.has_tag("RELEASE"));
+
+        labels.apply(&SessionLabelsItem {
+            title: Some(String::new()),
+            ..Default::default()
+        });
+        assert_eq!(labels.title, None);
+    }
+
+    #[tokio::test]
+    async fn append_round_trips_for_plain_and_compressed_rollouts() {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join("rollout-2025-01-01T00-00-00-id.jsonl");
+        std::fs::write(&path, "{\"timestamp\":\"t\",\"type\":\"unknown\"}\n").expect("write");
+
+        let labels = append_session_labels(
+            &path,
+            SessionLabelsItem {
+                title: Some("first".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("append");
+        assert_eq!(labels.title.as_deref(), Some("first"));
+
+        let compressed = compression::compress(&path).await.expect("compress");
+        append_session_labels(
+            &compressed,
+            SessionLabelsItem {
+                tags: Some(vec!["db".to_string()]),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("append compressed");
+
+        assert_eq!(
+            read_session_labels(&compressed).await.expect("read"),
+            SessionLabels {
+                title: Some("first".to_string()),
+                tags: vec!["db".to_string()],
+                starred: false,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn tag_additions_and_removals_from_different_editors_are_all_kept() {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join("rollout-2025-01-01T00-00-00-id.jsonl");
+        std::fs::write(&path, "{\"timestamp\":\"t\",\"type\":\"unknown\"}\n").expect("write");
+
+        // Each editor records only its own change, whatever it last read.
+        for edit in [
+            SessionLabelsItem {
+                add_tags: Some(vec!["db".to_string(), "flaky".to_string()]),
+                ..Default::default()
+            },
+            SessionLabelsItem {
+                add_tags: Some(vec!["release".to_string()]),
+                ..Default::default()
+            },
+            SessionLabelsItem {
+                remove_tags: Some(vec![" FLAKY".to_string()]),
+                ..Default::default()
+            },
+        ] {
+            append_session_labels(&path, edit).await.expect("append");
+        }
+
+        assert_eq!(
+            read_session_labels(&path).await.expect("read").tags,
+            vec!["db".to_string(), "release".to_string()]
+        );
+    }
+}
@@ -14,6 +14,9 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use super::SESSIONS_SUBDIR;
+use super::compression;
+use super::labels::SessionLabels;
+use super::labels::read_session_labels;
 use crate::protocol::EventMsg;
 use codex_file_search as file_search;
 use codex_protocol::protocol::RolloutItem;
@@ -44,6 +47,8 @@ pub struct ConversationItem {
     pub created_at: Option<String>,
     /// RFC3339 timestamp string for the most recent update (from file mtime).
     pub updated_at: Option<String>,
+    /// Title, tags and starred flag set by the user.
+    pub labels: SessionLabels,
 }
 
 #[derive(Default)]
@@ -175,10 +180,6 @@ async fn traverse_directories_for_paths(
                     break 'outer;
                 }
                 let mut day_files = collect_files(day_path, |name_str, path| {
-                    if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
-                        return None;
-                    }
-
                     parse_timestamp_uuid_from_filename(name_str)
                         .map(|(ts, id)| (ts, id, name_str.to_string(), path.to_path_buf()))
                 })
@@ -232,11 +233,13 @@ async fn traverse_directories_for_paths(
                                 .unwrap_or(None)
                                 .or_else(|| created_at.clone());
                         }
+                        let labels = read_session_labels(&path).await.unwrap_or_default();
                         items.push(ConversationItem {
                             path,
                             head,
                             created_at,
                             updated_at,
+                            labels,
                         });
                     }
                 }
@@ -262,6 +265,30 @@ async fn traverse_directories_for_paths(
     })
 }
 
+/// Collects the paths of all rollout files under `root` (the sessions
+/// directory), newest first. Returns an empty list if `root` does not exist.
+pub(crate) async fn collect_rollout_paths(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    if !root.exists() {
+        return Ok(paths);
+    }
+    for (_year, year_path) in collect_dirs_desc(root, |s| s.parse::<u16>().ok()).await? {
+        for (_month, month_path) in collect_dirs_desc(&year_path, |s| s.parse::<u8>().ok()).await? {
+            for (_day, day_path) in collect_dirs_desc(&month_path, |s| s.parse::<u8>().ok()).await?
+            {
+                let mut day_files = collect_files(&day_path, |name_str, path| {
+                    parse_timestamp_uuid_from_filename(name_str)
+                        .map(|(ts, id)| (ts, id, path.to_path_buf()))
+                })
+                .await?;
+                day_files.sort_by_key(|(ts, sid, _path)| (Reverse(*ts), Reverse(*sid)));
+                paths.extend(day_files.into_iter().map(|(_, _, path)| path));
+            }
+        }
+    }
+    Ok(paths)
+}
+
 /// Pagination cursor token format: "<file_ts>|<uuid>" where `file_ts` matches the
 /// filename timestamp portion (YYYY-MM-DDThh-mm-ss) used in rollout filenames.
 /// The cursor orders files by timestamp desc, then UUID desc.
@@ -334,7 +361,11 @@ where
 }
 
 fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
-    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
+    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl, optionally compressed
+    // with a trailing `.zst`.
+    let name = name
+        .strip_suffix(compression::COMPRESSED_SUFFIX)
+        .unwrap_or(name);
     let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
 
     // Scan from the right for a '-' such that the suffix parses as a UUID.
@@ -379,6 +410,21 @@ impl<'a> ProviderMatcher<'a> {
 async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTailSummary> {
     use tokio::io::AsyncBufReadExt;
 
+    if compression::is_compressed(path) {
+        let path = path.to_path_buf();
+        return tokio::task::spawn_blocking(move || {
+            let mut summary = HeadTailSummary::default();
+            for line in compression::read_lines(&path)? {
+                if summary.head.len() >= head_limit || summary.ingest_line(&line?) {
+                    break;
+                }
+            }
+            Ok(summary)
+        })
+        .await
+        .map_err(io::Error::other)?;
+    }
+
     let file = tokio::fs::File::open(path).await?;
     let reader = tokio::io::BufReader::new(file);
     let mut lines = reader.lines();
@@ -387,55 +433,67 @@ async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTai
     while summary.head.len() < head_limit {
         let line_opt = lines.next_line().await?;
         let Some(line) = line_opt else { break };
+        if summary.ingest_line(&line) {
+            break;
+        }
+    }
+
+    Ok(summary)
+}
+
+impl HeadTailSummary {
+    /// Folds one rollout line into the summary. Returns true once both the
+    /// session meta and a user message have been seen.
+    fn ingest_line(&mut self, line: &str) -> bool {
         let trimmed = line.trim();
         if trimmed.is_empty() {
-            continue;
+            return false;
         }
 
         let parsed: Result<RolloutLine, _> = serde_json::from_str(trimmed);
-        let Ok(rollout_line) = parsed else { continue };
+        let Ok(rollout_line) = parsed else {
+            return false;
+        };
 
         match rollout_line.item {
             RolloutItem::SessionMeta(session_meta_line) => {
-                summary.source = Some(session_meta_line.meta.source.clone());
-                summary.model_provider = session_meta_line.meta.model_provider.clone();
-                summary.created_at = summary
+                self.source = Some(session_meta_line.meta.source.clone());
+                self.model_provider = session_meta_line.meta.model_provider.clone();
+                self.created_at = self
                     .created_at
                     .clone()
                     .or_else(|| Some(rollout_line.timestamp.clone()));
                 if let Ok(val) = serde_json::to_value(session_meta_line) {
-                    summary.head.push(val);
-                    summary.saw_session_meta = true;
+                    self.head.push(val);
+                    self.saw_session_meta = true;
                 }
             }
             RolloutItem::ResponseItem(item) => {
-                summary.created_at = summary
+                self.created_at = self
                     .created_at
                     .clone()
                     .or_else(|| Some(rollout_line.timestamp.clone()));
                 if let Ok(val) = serde_json::to_value(item) {
-                    summary.head.push(val);
+                    self.head.push(val);
                 }
             }
             RolloutItem::TurnContext(_) => {
                 // Not included in `head`; skip.
             }
-            RolloutItem::Compacted(_) => {
+            RolloutItem::Compacted(_)
+            | RolloutItem::SessionLabels(_)
+            | RolloutItem::ExecApprovalDecision(_) => {
                 // Not included in `head`; skip.
             }
             RolloutItem::EventMsg(ev) => {
                 if matches!(ev, EventMsg::UserMessage(_)) {
-                    summary.saw_user_event = true;
+                    self.saw_user_event = true;
                 }
             }
         }
 
-        if summary.saw_session_meta && summary.saw_user_event {
-            break;
-        }
+        self.saw_session_meta && self.saw_user_event
     }
-
-    Ok(summary)
 }
 
 /// Read up to `HEAD_RECORD_LIMIT` records from the start of the rollout file at `path`.
@@ -7,10 +7,17 @@ pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
+pub mod approvals;
+pub(crate) mod compression;
 pub(crate) mod error;
+pub mod export;
+pub mod labels;
 pub mod list;
 pub(crate) mod policy;
 pub mod recorder;
+pub mod retention;
+pub mod search;
+pub mod usage;
 
 pub use codex_protocol::protocol::SessionMeta;
 pub(crate) use error::map_session_init_error;
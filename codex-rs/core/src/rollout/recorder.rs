@@ -19,6 +19,7 @@ use tracing::info;
 use tracing::warn;
 
 use super::SESSIONS_SUBDIR;
+use super::compression;
 use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
@@ -151,14 +152,10 @@ impl RolloutRecorder {
                     }),
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
+            RolloutRecorderParams::Resume { path } => {
+                let (file, path) = open_for_resume(&path).await?;
+                (tokio::fs::File::from_std(file), path, None)
+            }
         };
 
         // Clone the cwd for the spawned task to collect git info asynchronously
@@ -209,7 +206,7 @@ impl RolloutRecorder {
 
     pub async fn get_rollout_history(path: &Path) -> std::io::Result<InitialHistory> {
         info!("Resuming rollout from {path:?}");
-        let text = tokio::fs::read_to_string(path).await?;
+        let text = compression::read_to_string(path).await?;
         if text.trim().is_empty() {
             return Err(IoError::other("empty session file"));
         }
@@ -251,6 +248,12 @@ impl RolloutRecorder {
                     RolloutItem::EventMsg(_ev) => {
                         items.push(RolloutItem::EventMsg(_ev));
                     }
+                    RolloutItem::SessionLabels(item) => {
+                        items.push(RolloutItem::SessionLabels(item));
+                    }
+                    RolloutItem::ExecApprovalDecision(item) => {
+                        items.push(RolloutItem::ExecApprovalDecision(item));
+                    }
                 },
                 Err(e) => {
                     warn!("failed to parse rollout line: {v:?}, error: {e}");
@@ -308,6 +311,59 @@ struct LogFileInfo {
     timestamp: OffsetDateTime,
 }
 
+/// Takes a shared lock on the rollout for as long as the recorder keeps it
+/// open, so retention sweeps in this or another process leave it alone.
+/// Windows locks are mandatory and would block our own appends, so there the
+/// sweep relies on modification times alone.
+fn mark_in_use(file: &File, path: &Path) {
+    #[cfg(unix)]
+    if let Err(err) = file.try_lock_shared() {
+        warn!("failed to lock rollout {}: {err}", path.display());
+    }
+    #[cfg(not(unix))]
+    let _ = (file, path);
+}
+
+/// Opens the rollout at `path` for appending and marks it in use, first
+/// waiting for a retention sweep that is compressing or removing it. Compressed
+/// rollouts are never appended to, so one that is (or meanwhile became)
+/// compressed is decompressed while it is locked.
+pub(super) async fn open_for_resume(path: &Path) -> std::io::Result<(File, PathBuf)> {
+    let plain = if compression::is_compressed(path) {
+        compression::decompressed_path(path)
+    } else {
+        path.to_path_buf()
+    };
+    let compressed = compression::compressed_path(&plain);
+    loop {
+        if plain.exists() {
+            let file = std::fs::OpenOptions::new().append(true).open(&plain)?;
+            let file = wait_until_in_use(file).await?;
+            if compression::is_still_at(&file, &plain) {
+                return Ok((file, plain));
+            }
+        } else {
+            let file = wait_until_in_use(File::open(&compressed)?).await?;
+            if compression::is_still_at(&file, &compressed) {
+                compression::decompress(&compressed).await?;
+            }
+        }
+    }
+}
+
+/// Like [`mark_in_use`], but waits for a retention sweep holding the
+/// rollout's exclusive lock to release it.
+async fn wait_until_in_use(file: File) -> std::io::Result<File> {
+    #[cfg(unix)]
+    {
+        tokio::task::spawn_blocking(move || file.lock_shared().map(|()| file))
+            .await
+            .map_err(IoError::other)?
+    }
+    #[cfg(not(unix))]
+    Ok(file)
+}
+
 fn create_log_file(
     config: &Config,
     conversation_id: ConversationId,
@@ -337,6 +393,7 @@ fn create_log_file(
         .append(true)
         .create(true)
         .open(&path)?;
+    mark_in_use(&file, &path);
 
     Ok(LogFileInfo {
         file,
@@ -0,0 +1,465 @@
+//! Retention of recorded sessions, configured under `[sessions]`.
+//!
+//! A sweep walks the rollouts under `~/.codex/sessions` newest first. Rollouts
+//! that are in use (locked by a running recorder or modified within the last
+//! hour) are never touched. Of the rest, those idle for longer than
+//! `max_age_days` are deleted, the survivors are compressed when `compress` is
+//! set, and once the running total exceeds `max_total_mb` every older rollout
+//! is deleted. The `keep_recent` newest rollouts are never deleted, and
+//! neither are starred ones unless `keep_starred` is turned off.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tracing::info;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compression;
+use super::labels::read_session_labels;
+use super::list::collect_rollout_paths;
+use crate::config::Config;
+use crate::config::types::SessionsConfig;
+
+/// Rollouts modified more recently than this are assumed to belong to a
+/// running session.
+const IN_USE_GRACE: Duration = Duration::from_secs(60 * 60);
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+static SWEEP_STARTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneReason {
+    MaxAge,
+    MaxTotalSize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrunedSession {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub reason: PruneReason,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct PruneReport {
+    pub removed: Vec<PrunedSession>,
+    /// Paths of the newly compressed rollouts.
+    pub compressed: Vec<PathBuf>,
+    /// Total size of the rollouts that remain.
+    pub remaining_bytes: u64,
+}
+
+/// Applies `policy` to the sessions under `codex_home`. With `dry_run`, the
+/// report describes what would happen without changing anything.
+pub async fn prune_sessions(
+    codex_home: &Path,
+    policy: &SessionsConfig,
+    dry_run: bool,
+) -> io::Result<PruneReport> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let max_age = policy
+        .max_age_days
+        .map(|days| Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)));
+    let max_total = policy
+        .max_total_mb
+        .map(|mb| mb.saturating_mul(BYTES_PER_MB));
+    let now = SystemTime::now();
+
+    let mut report = PruneReport::default();
+    let mut over_budget = false;
+    for (index, path) in collect_rollout_paths(&root).await?.into_iter().enumerate() {
+        let Ok(metadata) = tokio::fs::metadata(&path).await else {
+            continue;
+        };
+        let mut bytes = metadata.len();
+        let idle = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        // Held until this rollout has been compressed or removed, so a recorder
+        // resuming it waits for that to finish.
+        let lock = if idle < IN_USE_GRACE {
+            None
+        } else {
+            lock_idle(&path).await
+        };
+        let in_use = lock.is_none();
+        let mut deletable = !in_use && index >= policy.keep_recent;
+        if deletable && policy.keep_starred {
+            deletable = !read_session_labels(&path)
+                .await
+                .is_ok_and(|labels| labels.starred);
+        }
+
+        if deletable && max_age.is_some_and(|max_age| idle > max_age) {
+            remove(
+                &root,
+                path,
+                bytes,
+                PruneReason::MaxAge,
+                dry_run,
+                &mut report,
+            )
+            .await;
+            continue;
+        }
+
+        let path = if policy.compress && !in_use && !compression::is_compressed(&path) {
+            if dry_run {
+                report.compressed.push(compression::compressed_path(&path));
+                path
+            } else {
+                match compression::compress(&path).await {
+                    Ok(compressed) => {
+                        if let Ok(metadata) = tokio::fs::metadata(&compressed).await {
+                            bytes = metadata.len();
+                        }
+                        report.compressed.push(compressed.clone());
+                        compressed
+                    }
+                    Err(err) => {
+                        warn!("failed to compress rollout {}: {err}", path.display());
+                        path
+                    }
+                }
+            }
+        } else {
+            path
+        };
+
+        if let Some(max_total) = max_total {
+            over_budget |= report.remaining_bytes.saturating_add(bytes) > max_total;
+        }
+        if deletable && over_budget {
+            remove(
+                &root,
+                path,
+                bytes,
+                PruneReason::MaxTotalSize,
+                dry_run,
+                &mut report,
+            )
+            .await;
+            continue;
+        }
+        report.remaining_bytes += bytes;
+    }
+    Ok(report)
+}
+
+/// Runs one sweep in the background the first time a session starts in this
+/// process, if `[sessions]` asks for any retention or compression.
+pub(crate) fn spawn_sweep(config: &Config) {
+    if !config.sessions.is_enabled() || SWEEP_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let codex_home = config.codex_home.clone();
+    let policy = config.sessions;
+    tokio::spawn(async move {
+        match prune_sessions(&codex_home, &policy, false).await {
+            Ok(report) => info!(
+                "session sweep removed {} and compressed {} rollouts",
+                report.removed.len(),
+                report.compressed.len()
+            ),
+            Err(err) => warn!("session sweep failed: {err}"),
+        }
+    });
+}
+
+async fn remove(
+    root: &Path,
+    path: PathBuf,
+    bytes: u64,
+    reason: PruneReason,
+    dry_run: bool,
+    report: &mut PruneReport,
+) {
+    if !dry_run {
+        if let Err(err) = tokio::fs::remove_file(&path).await {
+            warn!("failed to remove rollout {}: {err}", path.display());
+            report.remaining_bytes += bytes;
+            return;
+        }
+        remove_empty_parents(root, &path).await;
+    }
+    report.removed.push(PrunedSession {
+        path,
+        bytes,
+        reason,
+    });
+}
+
+/// Removes the day, month and year directories left empty below `root`.
+async fn remove_empty_parents(root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) {
+            break;
+        }
+        if tokio::fs::remove_dir(current).await.is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+/// Takes an exclusive lock on the rollout at `path`, held for as long as the
+/// returned file stays open. Returns `None` when a running recorder holds the
+/// lock taken in [`super::recorder::RolloutRecorder::new`], or when the
+/// rollout was replaced or removed in the meantime.
+async fn lock_idle(path: &Path) -> Option<std::fs::File> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&path).ok()?;
+        #[cfg(unix)]
+        file.try_lock().ok()?;
+        compression::is_still_at(&file, &path).then_some(file)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::SessionLabelsItem;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(SECONDS_PER_DAY);
+
+    /// Writes a rollout for day `day` of January 2025, last modified `age` ago.
+    fn write_rollout(codex_home: &Path, day: u8, bytes: usize, age: Duration) -> PathBuf {
+        let dir = codex_home
+            .join(SESSIONS_SUBDIR)
+            .join("2025")
+            .join("01")
+            .join(format!("{day:02}"));
+        std::fs::create_dir_all(&dir).expect("create day dir");
+        let path = dir.join(format!(
+            "rollout-2025-01-{day:02}T00-00-00-00000000-0000-0000-0000-0000000000{day:02}.jsonl"
+        ));
+        std::fs::write(&path, "x".repeat(bytes)).expect("write rollout");
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now() - age))
+            .expect("set mtime");
+        path
+    }
+
+    #[tokio::test]
+    async fn prunes_by_age_but_keeps_recent_and_in_use() {
+        let home = TempDir::new().expect("tempdir");
+        let active = write_rollout(home.path(), 4, 10, Duration::ZERO);
+        let kept = write_rollout(home.path(), 3, 10, 40 * DAY);
+        let expired = write_rollout(home.path(), 2, 10, 40 * DAY);
+        let fresh = write_rollout(home.path(), 1, 10, 2 * DAY);
+        let policy = SessionsConfig {
+            max_age_days: Some(30),
+            keep_recent: 2,
+            ..Default::default()
+        };
+
+        let report = prune_sessions(home.path(), &policy, false)
+            .await
+            .expect("prune");
+
+        assert_eq!(
+            report.removed,
+            vec![PrunedSession {
+                path: expired.clone(),
+                bytes: 10,
+                reason: PruneReason::MaxAge,
+            }]
+        );
+        assert_eq!(report.remaining_bytes, 30);
+        assert!(active.exists() && kept.exists() && fresh.exists());
+        assert!(!expired.exists());
+        assert!(!expired.parent().expect("day dir").exists());
+    }
+
+    #[tokio::test]
+    async fn prunes_oldest_once_over_total_size() {
+        let home = TempDir::new().expect("tempdir");
+        let newest = write_rollout(home.path(), 3, 600 * 1024, 2 * DAY);
+        let middle = write_rollout(home.path(), 2, 600 * 1024, 3 * DAY);
+        let oldest = write_rollout(home.path(), 1, 10, 4 * DAY);
+        let policy = SessionsConfig {
+            max_total_mb: Some(1),
+            ..Default::default()
+        };
+
+        let dry_run = prune_sessions(home.path(), &policy, true)
+            .await
+            .expect("dry run");
+        assert_eq!(
+            dry_run
+                .removed
+                .iter()
+                .map(|pruned| (pruned.path.clone(), pruned.reason))
+                .collect::<Vec<_>>(),
+            vec![
+                (middle.clone(), PruneReason::MaxTotalSize),
+                (oldest.clone(), PruneReason::MaxTotalSize),
+            ]
+        );
+        assert!(middle.exists() && oldest.exists());
+
+        let report = prune_sessions(home.path(), &policy, false)
+            .await
+            .expect("prune");
+        assert_eq!(report, dry_run);
+        assert!(newest.exists());
+        assert!(!middle.exists() && !oldest.exists());
+    }
+
+    #[tokio::test]
+    async fn keeps_starred_rollouts_unless_disabled() {
+        let home = TempDir::new().expect("tempdir");
+        let starred = write_rollout(home.path(), 2, 10, 40 * DAY);
+        let plain = write_rollout(home.path(), 1, 10, 40 * DAY);
+        crate::rollout::labels::append_session_labels(
+            &starred,
+            SessionLabelsItem {
+                starred: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("star");
+        let mut policy = SessionsConfig {
+            max_age_days: Some(30),
+            ..Default::default()
+        };
+
+        let report = prune_sessions(home.path(), &policy, true)
+            .await
+            .expect("dry run");
+        assert_eq!(
+            report
+                .removed
+                .into_iter()
+                .map(|pruned| pruned.path)
+                .collect::<Vec<_>>(),
+            vec![plain.clone()]
+        );
+
+        policy.keep_starred = false;
+        let report = prune_sessions(home.path(), &policy, true)
+            .await
+            .expect("dry run");
+        assert_eq!(report.removed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn compresses_closed_rollouts_only() {
+        let home = TempDir::new().expect("tempdir");
+        let active = write_rollout(home.path(), 2, 4096, Duration::ZERO);
+        let closed = write_rollout(home.path(), 1, 4096, 2 * DAY);
+        let policy = SessionsConfig {
+            compress: true,
+            ..Default::default()
+        };
+
+        let report = prune_sessions(home.path(), &policy, false)
+            .await
+            .expect("prune");
+
+        let compressed = compression::compressed_path(&closed);
+        assert_eq!(report.compressed, vec![compressed.clone()]);
+        assert!(active.exists());
+        assert!(!closed.exists());
+        assert_eq!(
+            compression::read_to_string(&compressed)
+                .await
+                .expect("read compressed"),
+            "x".repeat(4096)
+        );
+        assert!(report.remaining_bytes < 2 * 4096);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn resuming_waits_for_a_sweep_compressing_the_rollout() {
+        let home = TempDir::new().expect("tempdir");
+        let rollout = write_rollout(home.path(), 1, 4096, 2 * DAY);
+        let sweep_lock = lock_idle(&rollout).await.expect("idle rollout");
+
+        let resume = tokio::spawn({
+            let rollout = rollout.clone();
+            async move { super::super::recorder::open_for_resume(&rollout).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!resume.is_finished());
+
+        let compressed = compression::compress(&rollout).await.expect("compress");
+        drop(sweep_lock);
+        let (file, path) = resume.await.expect("join").expect("resume");
+
+        assert_eq!(path, rollout);
+        assert!(!compressed.exists());
+        assert!(compression::is_still_at(&file, &rollout));
+        assert_eq!(
+            std::fs::read_to_string(&rollout).expect("read"),
+            "x".repeat(4096)
+        );
+        assert!(lock_idle(&rollout).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn labelling_a_compressed_rollout_keeps_it_expiring() {
+        let home = TempDir::new().expect("tempdir");
+        let rollout = write_rollout(home.path(), 1, 10, 40 * DAY);
+        let compressed = compression::compress(&rollout).await.expect("compress");
+        let mtime = |path: &Path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .expect("mtime")
+        };
+        let before = mtime(&compressed);
+
+        crate::rollout::labels::append_session_labels(
+            &compressed,
+            SessionLabelsItem {
+                tags: Some(vec!["db".to_string()]),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("tag");
+
+        assert_eq!(mtime(&compressed), before);
+        let report = prune_sessions(
+            home.path(),
+            &SessionsConfig {
+                max_age_days: Some(30),
+                ..Default::default()
+            },
+            true,
+        )
+        .await
+        .expect("dry run");
+        assert_eq!(
+            report
+                .removed
+                .into_iter()
+                .map(|pruned| (pruned.path, pruned.reason))
+                .collect::<Vec<_>>(),
+            vec![(compressed, PruneReason::MaxAge)]
+        );
+    }
+}
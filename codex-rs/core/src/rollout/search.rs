@@ -0,0 +1,819 @@
+//! Full-text search across recorded sessions.
+//!
+//! Searching is backed by a local index stored under
+//! `~/.codex/session_search_index/`, one file per rollout. Each file keeps the
+//! rollout's searchable text (user messages, assistant messages, commands run
+//! and files touched by patches) along with how many bytes of it have been
+//! indexed. Rollouts are append-only, so refreshing the index only parses the
+//! lines written since the previous search and rewrites the entries of the
+//! rollouts that grew; rollouts that shrank are re-indexed from scratch and
+//! entries of rollouts that disappeared are removed. Compressed rollouts are
+//! closed and are indexed in one pass.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionSource;
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compression;
+use super::export::ExportOptions;
+use super::export::Transcript;
+use super::export::TranscriptEntry;
+use super::labels::SessionLabels;
+use super::list::collect_rollout_paths;
+use crate::tool_call_text::SHELL_TOOL_NAMES;
+use crate::tool_call_text::files_in_patch;
+
+/// Directory holding the search index, relative to `codex_home`.
+pub const SEARCH_INDEX_DIR: &str = "session_search_index";
+
+/// Single-file index written by earlier versions; removed when found.
+const LEGACY_SEARCH_INDEX_FILE: &str = "session_search_index.json";
+
+/// Bump when the indexed fields change so stale indexes are rebuilt.
+const INDEX_VERSION: u32 = 3;
+
+/// Maximum number of matching snippets returned per session.
+const MAX_MATCHES_PER_HIT: usize = 3;
+
+/// Characters of context kept on either side of a match in a snippet.
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// Which part of a session a match was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    UserMessage,
+    AgentMessage,
+    Command,
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchMatch {
+    pub field: SearchField,
+    /// Excerpt of the matching text around the first matched term.
+    pub snippet: String,
+}
+
+/// A session matching a search query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSearchHit {
+    pub id: ConversationId,
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    /// RFC3339 timestamp of when the session was created.
+    pub created_at: String,
+    pub cwd: PathBuf,
+    pub source: SessionSource,
+    pub model_provider: Option<String>,
+    /// Title, tags and starred flag set by the user.
+    pub labels: SessionLabels,
+    /// The first user message of the session.
+    pub preview: String,
+    /// Relevance score; higher is better.
+    pub score: usize,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Searches all recorded sessions under `codex_home` for `query` and returns
+/// up to `limit` hits, best match first.
+///
+/// A session matches when every term of the query occurs somewhere in it
+/// (case-insensitive). Double quotes group words into a single phrase term.
+pub async fn search_sessions(
+    codex_home: &Path,
+    query: &str,
+    limit: usize,
+) -> io::Result<Vec<SessionSearchHit>> {
+    SessionSearcher::new(codex_home.to_path_buf())
+        .search(query, limit)
+        .await
+}
+
+/// Runs repeated searches against one in-memory copy of the index.
+///
+/// The index is loaded from disk on the first search and only refreshed
+/// with new rollout data afterwards, so callers that search as the user
+/// types should keep a single searcher rather than call [`search_sessions`]
+/// for every query.
+pub struct SessionSearcher {
+    codex_home: PathBuf,
+    index: Option<SessionIndex>,
+}
+
+impl SessionSearcher {
+    pub fn new(codex_home: PathBuf) -> Self {
+        Self {
+            codex_home,
+            index: None,
+        }
+    }
+
+    /// Same as [`search_sessions`], reusing the index loaded by earlier calls.
+    pub async fn search(&mut self, query: &str, limit: usize) -> io::Result<Vec<SessionSearchHit>> {
+        let terms = parse_query(query);
+        let index = match self.index.as_mut() {
+            Some(index) => index,
+            None => self
+                .index
+                .insert(SessionIndex::load(&self.codex_home).await),
+        };
+        let changed = index
+            .refresh(&self.codex_home.join(SESSIONS_SUBDIR))
+            .await?;
+        if !changed.is_empty() {
+            let index_dir = self.codex_home.join(SEARCH_INDEX_DIR);
+            if let Err(err) = index.save(&index_dir, &changed).await {
+                warn!(
+                    "failed to write session search index {}: {err}",
+                    index_dir.display()
+                );
+            }
+        }
+        Ok(index.search(&terms, limit))
+    }
+}
+
+#[derive(Debug, Default)]
+struct SessionIndex {
+    /// Indexed sessions keyed by rollout path.
+    sessions: BTreeMap<PathBuf, IndexedSession>,
+}
+
+/// Contents of one rollout's file in [`SEARCH_INDEX_DIR`].
+#[derive(Serialize, Deserialize)]
+struct IndexEntry<S> {
+    version: u32,
+    path: PathBuf,
+    #[serde(flatten)]
+    session: S,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexedSession {
+    /// Number of bytes of the rollout file that have been indexed. For a
+    /// compressed rollout this is its compressed size once indexed.
+    indexed_len: u64,
+    meta: Option<IndexedMeta>,
+    #[serde(default)]
+    labels: SessionLabels,
+    documents: Vec<IndexedDocument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedMeta {
+    id: ConversationId,
+    created_at: String,
+    cwd: PathBuf,
+    source: SessionSource,
+    model_provider: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedDocument {
+    field: SearchField,
+    text: String,
+}
+
+impl SessionIndex {
+    async fn load(codex_home: &Path) -> Self {
+        let codex_home = codex_home.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let _ = std::fs::remove_file(codex_home.join(LEGACY_SEARCH_INDEX_FILE));
+            let mut index = SessionIndex::default();
+            let Ok(entries) = std::fs::read_dir(codex_home.join(SEARCH_INDEX_DIR)) else {
+                return index;
+            };
+            for entry in entries.flatten() {
+                let entry_path = entry.path();
+                let parsed = std::fs::read(&entry_path).ok().and_then(|bytes| {
+                    serde_json::from_slice::<IndexEntry<IndexedSession>>(&bytes).ok()
+                });
+                match parsed {
+                    Some(parsed) if parsed.version == INDEX_VERSION => {
+                        index.sessions.insert(parsed.path, parsed.session);
+                    }
+                    // Unreadable or stale; the rollout is indexed again.
+                    _ => {
+                        let _ = std::fs::remove_file(&entry_path);
+                    }
+                }
+            }
+            index
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// Writes the entries of the `changed` rollouts to `index_dir`, removing
+    /// those of rollouts no longer indexed.
+    async fn save(&self, index_dir: &Path, changed: &[PathBuf]) -> io::Result<()> {
+        let mut writes = Vec::with_capacity(changed.len());
+        for path in changed {
+            let contents = match self.sessions.get(path) {
+                Some(session) => Some(
+                    serde_json::to_vec(&IndexEntry {
+                        version: INDEX_VERSION,
+                        path: path.clone(),
+                        session,
+                    })
+                    .map_err(io::Error::other)?,
+                ),
+                None => None,
+            };
+            writes.push((index_dir.join(entry_file_name(path)), contents));
+        }
+        let index_dir = index_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&index_dir)?;
+            for (entry_path, contents) in writes {
+                match contents {
+                    Some(contents) => {
+                        let tmp = NamedTempFile::new_in(&index_dir)?;
+                        std::fs::write(tmp.path(), contents)?;
+                        tmp.persist(&entry_path).map_err(|err| err.error)?;
+                    }
+                    None => match std::fs::remove_file(&entry_path) {
+                        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                        _ => {}
+                    },
+                }
+            }
+            Ok(())
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+
+    /// Brings the index up to date with the rollout files under
+    /// `sessions_root`. Returns the rollouts whose entries changed or were
+    /// dropped.
+    async fn refresh(&mut self, sessions_root: &Path) -> io::Result<Vec<PathBuf>> {
+        let paths = collect_rollout_paths(sessions_root).await?;
+        let live: HashSet<&PathBuf> = paths.iter().collect();
+        let mut changed: Vec<PathBuf> = self
+            .sessions
+            .keys()
+            .filter(|path| !live.contains(path))
+            .cloned()
+            .collect();
+        for path in &changed {
+            self.sessions.remove(path);
+        }
+
+        for path in &paths {
+            let Ok(metadata) = tokio::fs::metadata(path).await else {
+                continue;
+            };
+            let len = metadata.len();
+            let session = self.sessions.entry(path.clone()).or_default();
+            if len == session.indexed_len {
+                continue;
+            }
+            let mut advanced = false;
+            if len < session.indexed_len {
+                // Rewritten rather than appended to; start over.
+                *session = IndexedSession::default();
+                advanced = true;
+            }
+            match session.index_appended(path).await {
+                Ok(appended) => advanced |= appended,
+                Err(err) => warn!("failed to index rollout {}: {err}", path.display()),
+            }
+            if advanced {
+                changed.push(path.clone());
+            }
+        }
+        Ok(changed)
+    }
+
+    fn search(&self, terms: &[String], limit: usize) -> Vec<SessionSearchHit> {
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let mut hits: Vec<SessionSearchHit> = self
+            .sessions
+            .iter()
+            .filter_map(|(path, session)| session.search(path, terms))
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.created_at.cmp(&a.created_at))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+impl IndexedSession {
+    /// Indexes the complete lines appended to `path` since the last refresh.
+    /// Returns whether any new lines were indexed.
+    async fn index_appended(&mut self, path: &Path) -> io::Result<bool> {
+        if compression::is_compressed(path) {
+            let text = compression::read_to_string(path).await?;
+            *self = IndexedSession::default();
+            self.add_items(&parse_rollout_items(&text));
+            self.indexed_len = tokio::fs::metadata(path).await?.len();
+            return Ok(true);
+        }
+
+        let mut file = tokio::fs::File::open(path).await?;
+        file.seek(io::SeekFrom::Start(self.indexed_len)).await?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended).await?;
+
+        // A line still being written is picked up by the next refresh.
+        let Some(end) = appended.iter().rposition(|b| *b == b'\n') else {
+            return Ok(false);
+        };
+        let complete = &appended[..=end];
+        self.add_items(&parse_rollout_items(&String::from_utf8_lossy(complete)));
+        self.indexed_len += complete.len() as u64;
+        Ok(true)
+    }
+
+    fn add_items(&mut self, items: &[RolloutItem]) {
+        if self.meta.is_none()
+            && let Some((line, created_at)) = items.iter().find_map(|item| match item {
+                RolloutItem::SessionMeta(line) => Some((line, line.meta.timestamp.clone())),
+                _ => None,
+            })
+        {
+            self.meta = Some(IndexedMeta {
+                id: line.meta.id,
+                created_at,
+                cwd: line.meta.cwd.clone(),
+                source: line.meta.source.clone(),
+                model_provider: line.meta.model_provider.clone(),
+            });
+        }
+        for item in items {
+            if let RolloutItem::SessionLabels(edit) = item {
+                self.labels.apply(edit);
+            }
+        }
+
+        let transcript = Transcript::from_rollout_items(
+            items,
+            ExportOptions {
+                include_reasoning: false,
+                include_tool_output: false,
+            },
+        );
+        for entry in transcript.entries {
+            match entry {
+                TranscriptEntry::UserMessage { text } => {
+                    self.push_document(SearchField::UserMessage, text);
+                }
+                TranscriptEntry::AgentMessage { text } => {
+                    self.push_document(SearchField::AgentMessage, text);
+                }
+                TranscriptEntry::ToolCall { name, input, .. }
+                    if SHELL_TOOL_NAMES.contains(&name.as_str()) =>
+                {
+                    self.push_document(SearchField::Command, input);
+                }
+                TranscriptEntry::Patch { patch, .. } => {
+                    for file in files_in_patch(&patch) {
+                        self.push_document(SearchField::File, file);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn push_document(&mut self, field: SearchField, text: String) {
+        if text.trim().is_empty() {
+            return;
+        }
+        self.documents.push(IndexedDocument { field, text });
+    }
+
+    fn search(&self, path: &Path, terms: &[String]) -> Option<SessionSearchHit> {
+        let meta = self.meta.as_ref()?;
+        let lowered: Vec<String> = self
+            .documents
+            .iter()
+            .map(|doc| doc.text.to_lowercase())
+            .collect();
+
+        let mut found = vec![false; terms.len()];
+        let mut score = 0;
+        // (terms matched, document index) for every document matching any term.
+        let mut matching_docs: Vec<(usize, usize)> = Vec::new();
+        for (doc_idx, text) in lowered.iter().enumerate() {
+            let mut matched = 0;
+            for (term_idx, term) in terms.iter().enumerate() {
+                if text.contains(term.as_str()) {
+                    found[term_idx] = true;
+                    matched += 1;
+                }
+            }
+            if matched > 0 {
+                // Documents containing every term count extra.
+                score += if matched == terms.len() {
+                    2 * matched
+                } else {
+                    matched
+                };
+                matching_docs.push((matched, doc_idx));
+            }
+        }
+        if !found.iter().all(|found| *found) {
+            return None;
+        }
+
+        // Best documents first; earlier documents win ties.
+        matching_docs.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        let matches = matching_docs
+            .into_iter()
+            .take(MAX_MATCHES_PER_HIT)
+            .map(|(_, doc_idx)| {
+                let doc = &self.documents[doc_idx];
+                SearchMatch {
+                    field: doc.field,
+                    snippet: snippet(&doc.text, &lowered[doc_idx], terms),
+                }
+            })
+            .collect();
+        let preview = self
+            .documents
+            .iter()
+            .find(|doc| doc.field == SearchField::UserMessage)
+            .map(|doc| doc.text.clone())
+            .unwrap_or_default();
+
+        Some(SessionSearchHit {
+            id: meta.id,
+            path: path.to_path_buf(),
+            created_at: meta.created_at.clone(),
+            cwd: meta.cwd.clone(),
+            source: meta.source.clone(),
+            model_provider: meta.model_provider.clone(),
+            labels: self.labels.clone(),
+            preview,
+            score,
+            matches,
+        })
+    }
+}
+
+/// Name of the index entry for the rollout at `path`. Rollout file names are
+/// unique across the date directories they are stored in.
+fn entry_file_name(path: &Path) -> OsString {
+    let mut name = path.file_name().unwrap_or(path.as_os_str()).to_owned();
+    name.push(".json");
+    name
+}
+
+/// Splits a query into lowercase terms, keeping double-quoted phrases whole.
+fn parse_query(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = part.trim();
+            if !phrase.is_empty() {
+                terms.push(phrase.to_lowercase());
+            }
+        } else {
+            terms.extend(part.split_whitespace().map(str::to_lowercase));
+        }
+    }
+    terms
+}
+
+/// Returns a single-line excerpt of `text` around the first term found in
+/// `lowered` (the lowercased `text`).
+fn snippet(text: &str, lowered: &str, terms: &[String]) -> String {
+    let Some(byte_idx) = terms.iter().filter_map(|term| lowered.find(term)).min() else {
+        return String::new();
+    };
+    // Lowercasing can change byte lengths, so locate the match by character
+    // position, which it preserves for all but a handful of characters.
+    let match_char = lowered[..byte_idx].chars().count();
+    let chars: Vec<char> = text.chars().collect();
+    let start = match_char.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (match_char + 2 * SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let start = start.min(end);
+
+    let excerpt: String = chars[start..end].iter().collect();
+    let mut snippet = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+fn parse_rollout_items(text: &str) -> Vec<RolloutItem> {
+    text.lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line.trim()).ok())
+        .map(|line| line.item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const SESSION_ID: &str = "5973b6c0-94b8-487b-a530-2aeb6098ae0e";
+
+    fn rollout_line(item_type: &str, payload: serde_json::Value) -> String {
+        json!({
+            "timestamp": "2025-01-01T00:00:00.000Z",
+            "type": item_type,
+            "payload": payload,
+        })
+        .to_string()
+            + "\n"
+    }
+
+    fn meta_line(id: &str) -> String {
+        rollout_line(
+            "session_meta",
+            json!({
+                "id": id,
+                "timestamp": "2025-01-01T00:00:00.000Z",
+                "cwd": "/repo",
+                "originator": "codex",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+            }),
+        )
+    }
+
+    fn message_line(role: &str, text: &str) -> String {
+        let content_type = if role == "user" {
+            "input_text"
+        } else {
+            "output_text"
+        };
+        rollout_line(
+            "response_item",
+            json!({
+                "type": "message",
+                "role": role,
+                "content": [{"type": content_type, "text": text}],
+            }),
+        )
+    }
+
+    fn write_rollout(codex_home: &Path, id: &str, lines: &[String]) -> PathBuf {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/01/01");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        let path = dir.join(format!("rollout-2025-01-01T00-00-00-{id}.jsonl"));
+        std::fs::write(&path, lines.concat()).expect("write rollout");
+        path
+    }
+
+    #[tokio::test]
+    async fn finds_sessions_by_messages_commands_and_files() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let path = write_rollout(
+            codex_home.path(),
+            SESSION_ID,
+            &[
+                meta_line(SESSION_ID),
+                message_line("user", "Fix the flaky migration test"),
+                rollout_line(
+                    "response_item",
+                    json!({
+                        "type": "function_call",
+                        "name": "shell",
+                        "arguments": "{\"command\":[\"cargo\",\"test\",\"-p\",\"db-migrate\"]}",
+                        "call_id": "call-1",
+                    }),
+                ),
+                rollout_line(
+                    "response_item",
+                    json!({
+                        "type": "custom_tool_call",
+                        "name": "apply_patch",
+                        "input": "*** Begin Patch\n*** Update File: migrations/0042_users.sql\n@@\n-a\n+b\n*** End Patch",
+                        "call_id": "call-2",
+                    }),
+                ),
+                message_line("assistant", "The migration now waits for the lock."),
+            ],
+        );
+
+        let hits = search_sessions(codex_home.path(), "Migration \"0042_users\"", 10)
+            .await
+            .expect("search");
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.path, path);
+        assert_eq!(hit.preview, "Fix the flaky migration test");
+        assert_eq!(
+            hit.matches
+                .iter()
+                .map(|m| (m.field, m.snippet.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (SearchField::File, "migrations/0042_users.sql"),
+                (SearchField::UserMessage, "Fix the flaky migration test"),
+                (
+                    SearchField::AgentMessage,
+                    "The migration now waits for the lock."
+                ),
+            ]
+        );
+
+        let hits = search_sessions(codex_home.path(), "db-migrate", 10)
+            .await
+            .expect("search");
+        assert_eq!(
+            hits[0].matches,
+            vec![SearchMatch {
+                field: SearchField::Command,
+                snippet: "cargo test -p db-migrate".to_string(),
+            }]
+        );
+
+        let hits = search_sessions(codex_home.path(), "migration postgres", 10)
+            .await
+            .expect("search");
+        assert!(hits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn indexes_appended_lines_incrementally() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let path = write_rollout(
+            codex_home.path(),
+            SESSION_ID,
+            &[meta_line(SESSION_ID), message_line("user", "first request")],
+        );
+        assert!(
+            search_sessions(codex_home.path(), "second", 10)
+                .await
+                .expect("search")
+                .is_empty()
+        );
+
+        let mut contents = std::fs::read_to_string(&path).expect("read rollout");
+        contents.push_str(&message_line("user", "second request"));
+        // A partially written line must not be indexed yet.
+        contents.push_str("{\"timestamp\":");
+        std::fs::write(&path, &contents).expect("append rollout");
+
+        let hits = search_sessions(codex_home.path(), "second", 10)
+            .await
+            .expect("search");
+        assert_eq!(hits.len(), 1);
+
+        let index = SessionIndex::load(codex_home.path()).await;
+        let session = &index.sessions[&path];
+        assert_eq!(
+            session.indexed_len,
+            (contents.len() - "{\"timestamp\":".len()) as u64
+        );
+        assert_eq!(session.documents.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn searcher_keeps_its_index_up_to_date_between_searches() {
+        let codex_home = TempDir::new().expect("tempdir");
+        write_rollout(
+            codex_home.path(),
+            SESSION_ID,
+            &[meta_line(SESSION_ID), message_line("user", "first request")],
+        );
+        let mut searcher = SessionSearcher::new(codex_home.path().to_path_buf());
+        assert_eq!(
+            searcher.search("request", 10).await.expect("search").len(),
+            1
+        );
+
+        let other_id = "11111111-2222-3333-4444-555555555555";
+        write_rollout(
+            codex_home.path(),
+            other_id,
+            &[meta_line(other_id), message_line("user", "second request")],
+        );
+        let hits = searcher.search("request", 10).await.expect("search");
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rewrites_only_the_entries_of_rollouts_that_changed() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let active = write_rollout(
+            codex_home.path(),
+            SESSION_ID,
+            &[meta_line(SESSION_ID), message_line("user", "first request")],
+        );
+        let other_id = "11111111-2222-3333-4444-555555555555";
+        let idle = write_rollout(
+            codex_home.path(),
+            other_id,
+            &[meta_line(other_id), message_line("user", "other request")],
+        );
+        std::fs::write(codex_home.path().join(LEGACY_SEARCH_INDEX_FILE), "{}")
+            .expect("write legacy index");
+        let mut searcher = SessionSearcher::new(codex_home.path().to_path_buf());
+        assert_eq!(
+            searcher.search("request", 10).await.expect("search").len(),
+            2
+        );
+        assert!(!codex_home.path().join(LEGACY_SEARCH_INDEX_FILE).exists());
+
+        let index_dir = codex_home.path().join(SEARCH_INDEX_DIR);
+        let idle_entry = index_dir.join(entry_file_name(&idle));
+        let written_at = std::time::SystemTime::UNIX_EPOCH;
+        std::fs::File::options()
+            .write(true)
+            .open(&idle_entry)
+            .and_then(|file| file.set_modified(written_at))
+            .expect("set mtime");
+
+        let mut contents = std::fs::read_to_string(&active).expect("read rollout");
+        contents.push_str(&message_line("user", "second request"));
+        std::fs::write(&active, contents).expect("append rollout");
+        assert_eq!(
+            searcher.search("second", 10).await.expect("search").len(),
+            1
+        );
+
+        assert_eq!(
+            std::fs::metadata(&idle_entry)
+                .and_then(|metadata| metadata.modified())
+                .expect("idle entry mtime"),
+            written_at
+        );
+        let reloaded = SessionIndex::load(codex_home.path()).await;
+        assert_eq!(reloaded.sessions[&active].documents.len(), 2);
+        assert_eq!(reloaded.sessions[&idle].documents.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn drops_deleted_sessions_from_the_index() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let path = write_rollout(
+            codex_home.path(),
+            SESSION_ID,
+            &[meta_line(SESSION_ID), message_line("user", "hello world")],
+        );
+        assert_eq!(
+            search_sessions(codex_home.path(), "hello", 10)
+                .await
+                .expect("search")
+                .len(),
+            1
+        );
+
+        std::fs::remove_file(&path).expect("remove rollout");
+        assert!(
+            search_sessions(codex_home.path(), "hello", 10)
+                .await
+                .expect("search")
+                .is_empty()
+        );
+        assert!(
+            SessionIndex::load(codex_home.path())
+                .await
+                .sessions
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parses_quoted_phrases() {
+        assert_eq!(
+            parse_query(r#"Flaky "DB migration"  test"#),
+            vec!["flaky", "db migration", "test"]
+        );
+    }
+
+    #[test]
+    fn snippet_trims_long_text_around_match() {
+        let text = format!("{} needle {}", "a ".repeat(100), "b ".repeat(100));
+        let snippet = snippet(&text, &text.to_lowercase(), &["needle".to_string()]);
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+    }
+}
@@ -13,6 +13,7 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+use crate::rollout::RolloutRecorder;
 use crate::rollout::list::ConversationItem;
 use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
@@ -234,18 +235,21 @@ async fn test_list_conversations_latest_first() {
                 head: head_3,
                 created_at: Some("2025-01-03T12-00-00".into()),
                 updated_at: updated_times.first().cloned().flatten(),
+                labels: Default::default(),
             },
             ConversationItem {
                 path: p2,
                 head: head_2,
                 created_at: Some("2025-01-02T12-00-00".into()),
                 updated_at: updated_times.get(1).cloned().flatten(),
+                labels: Default::default(),
             },
             ConversationItem {
                 path: p3,
                 head: head_1,
                 created_at: Some("2025-01-01T12-00-00".into()),
                 updated_at: updated_times.get(2).cloned().flatten(),
+                labels: Default::default(),
             },
         ],
         next_cursor: None,
@@ -364,12 +368,14 @@ async fn test_pagination_cursor() {
                 head: head_5,
                 created_at: Some("2025-03-05T09-00-00".into()),
                 updated_at: updated_page1.first().cloned().flatten(),
+                labels: Default::default(),
             },
             ConversationItem {
                 path: p4,
                 head: head_4,
                 created_at: Some("2025-03-04T09-00-00".into()),
                 updated_at: updated_page1.get(1).cloned().flatten(),
+                labels: Default::default(),
             },
         ],
         next_cursor: Some(expected_cursor1.clone()),
@@ -431,12 +437,14 @@ async fn test_pagination_cursor() {
                 head: head_3,
                 created_at: Some("2025-03-03T09-00-00".into()),
                 updated_at: updated_page2.first().cloned().flatten(),
+                labels: Default::default(),
             },
             ConversationItem {
                 path: p2,
                 head: head_2,
                 created_at: Some("2025-03-02T09-00-00".into()),
                 updated_at: updated_page2.get(1).cloned().flatten(),
+                labels: Default::default(),
             },
         ],
         next_cursor: Some(expected_cursor2.clone()),
@@ -479,6 +487,7 @@ async fn test_pagination_cursor() {
             head: head_1,
             created_at: Some("2025-03-01T09-00-00".into()),
             updated_at: updated_page3.first().cloned().flatten(),
+            labels: Default::default(),
         }],
         next_cursor: None,
         num_scanned_files: 5, // scanned 05, 04 (anchor), 03, 02 (anchor), 01
@@ -534,6 +543,7 @@ async fn test_get_conversation_contents() {
             head: expected_head,
             created_at: Some(ts.into()),
             updated_at: page.items[0].updated_at.clone(),
+            labels: Default::default(),
         }],
         next_cursor: None,
         num_scanned_files: 1,
@@ -708,12 +718,14 @@ async fn test_stable_ordering_same_second_pagination() {
                 head: head(u3),
                 created_at: Some(ts.to_string()),
                 updated_at: updated_page1.first().cloned().flatten(),
+                labels: Default::default(),
             },
             ConversationItem {
                 path: p2,
                 head: head(u2),
                 created_at: Some(ts.to_string()),
                 updated_at: updated_page1.get(1).cloned().flatten(),
+                labels: Default::default(),
             },
         ],
         next_cursor: Some(expected_cursor1.clone()),
@@ -746,6 +758,7 @@ async fn test_stable_ordering_same_second_pagination() {
             head: head(u1),
             created_at: Some(ts.to_string()),
             updated_at: updated_page2.first().cloned().flatten(),
+            labels: Default::default(),
         }],
         next_cursor: None,
         num_scanned_files: 3, // scanned u3, u2 (anchor), u1
@@ -916,3 +929,37 @@ async fn test_model_provider_filter_selects_only_matching_sessions() -> Result<(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_compressed_rollouts_are_listed_and_readable() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let uuid = Uuid::from_u128(7);
+    let ts = "2025-05-01T09-00-00";
+    write_session_file(home, ts, uuid, 1, Some(SessionSource::Cli)).unwrap();
+    let path = home
+        .join("sessions")
+        .join("2025")
+        .join("05")
+        .join("01")
+        .join(format!("rollout-{ts}-{uuid}.jsonl"));
+    let compressed = crate::rollout::compression::compress(&path).await.unwrap();
+
+    let page = get_conversations(home, 10, None, NO_SOURCE_FILTER, None, TEST_PROVIDER)
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].path, compressed);
+    assert_eq!(page.items[0].created_at.as_deref(), Some(ts));
+    assert!(!page.items[0].head.is_empty());
+
+    let history = RolloutRecorder::get_rollout_history(&compressed)
+        .await
+        .unwrap();
+    assert_eq!(
+        history.get_rollout_items().len(),
+        2,
+        "session meta and user message should round-trip"
+    );
+}
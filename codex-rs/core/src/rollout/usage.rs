@@ -0,0 +1,514 @@
+//! Token usage and estimated cost aggregated across recorded sessions.
+//!
+//! Every `TokenCount` event in a rollout carries the session's cumulative
+//! usage, so a session's usage is the sum of the increases between
+//! consecutive events. The running total starts over when a session is
+//! resumed, which shows up as a decrease and is counted in full. A forked
+//! rollout starts with a copy of its parent's history; the copied events are
+//! skipped so that usage is only counted once. Each increase is attributed to
+//! the model of the latest turn context and to the UTC day of the event.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::Utc;
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::TokenUsage;
+use serde::Serialize;
+use tracing::warn;
+
+use super::SESSIONS_SUBDIR;
+use super::compression;
+use super::list::collect_rollout_paths;
+use crate::config::types::UsageConfig;
+
+/// Model reported for usage recorded before any turn context.
+const UNKNOWN_MODEL: &str = "unknown";
+
+const TOKENS_PER_MILLION: f64 = 1_000_000.0;
+
+/// How usage rows are grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroup {
+    Day,
+    /// ISO 8601 week, e.g. `2025-W02`.
+    Week,
+    Model,
+    /// Working directory of the session.
+    Project,
+    Session,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsageQuery {
+    pub group_by: UsageGroup,
+    /// Only count usage on or after this UTC day.
+    pub since: Option<NaiveDate>,
+    /// Only count usage on or before this UTC day.
+    pub until: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageRow {
+    pub key: String,
+    /// Number of sessions that contributed to this row.
+    pub sessions: usize,
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_output_tokens: i64,
+    pub total_tokens: i64,
+    /// Estimated cost in USD of the usage whose model has a configured price.
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageReport {
+    pub group_by: UsageGroup,
+    pub rows: Vec<UsageRow>,
+    pub total: UsageRow,
+    /// Models with usage but no configured price; their usage is not
+    /// included in `cost_usd`.
+    pub unpriced_models: Vec<String>,
+}
+
+/// Token usage of one rollout, as read from disk.
+#[derive(Debug)]
+struct SessionUsage {
+    id: Option<ConversationId>,
+    cwd: PathBuf,
+    /// The session this one was forked from, when the rollout starts with a
+    /// copy of another session's history.
+    forked_from: Option<ConversationId>,
+    counts: Vec<TokenCount>,
+}
+
+#[derive(Debug)]
+struct TokenCount {
+    timestamp: DateTime<Utc>,
+    model: Option<String>,
+    total: TokenUsage,
+}
+
+#[derive(Default)]
+struct RowBuilder {
+    sessions: HashSet<PathBuf>,
+    row: UsageRow,
+}
+
+/// Aggregates the token usage recorded under `codex_home` according to
+/// `query`, pricing it with `prices`.
+pub async fn summarize_usage(
+    codex_home: &Path,
+    prices: &UsageConfig,
+    query: &UsageQuery,
+) -> io::Result<UsageReport> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let mut sessions = Vec::new();
+    for path in collect_rollout_paths(&root).await? {
+        match compression::read_to_string(&path).await {
+            Ok(text) => sessions.push((path, parse_session_usage(&text))),
+            Err(err) => warn!("failed to read rollout {}: {err}", path.display()),
+        }
+    }
+    let by_id: HashMap<ConversationId, &SessionUsage> = sessions
+        .iter()
+        .filter_map(|(_, usage)| usage.id.map(|id| (id, usage)))
+        .collect();
+
+    let mut rows: BTreeMap<String, RowBuilder> = BTreeMap::new();
+    let mut total = RowBuilder::default();
+    let mut unpriced_models = HashSet::new();
+    for (path, usage) in &sessions {
+        let parent = usage.forked_from.and_then(|id| by_id.get(&id).copied());
+        let mut previous = TokenUsage::default();
+        for count in own_counts(usage, parent) {
+            let delta = if count.total.total_tokens < previous.total_tokens {
+                count.total.clone()
+            } else {
+                subtract(&count.total, &previous)
+            };
+            previous = count.total.clone();
+
+            let day = count.timestamp.date_naive();
+            if delta.is_zero()
+                || query.since.is_some_and(|since| day < since)
+                || query.until.is_some_and(|until| day > until)
+            {
+                continue;
+            }
+
+            let model = count.model.as_deref().unwrap_or(UNKNOWN_MODEL);
+            let cost = match prices.price_for(model) {
+                Some(price) => {
+                    let cached = delta.cached_input_tokens as f64;
+                    let uncached = (delta.input_tokens - delta.cached_input_tokens) as f64;
+                    (uncached * price.input
+                        + cached * price.cached_input.unwrap_or(price.input)
+                        + delta.output_tokens as f64 * price.output)
+                        / TOKENS_PER_MILLION
+                }
+                None => {
+                    unpriced_models.insert(model.to_string());
+                    0.0
+                }
+            };
+
+            let key = match query.group_by {
+                UsageGroup::Day => day.format("%Y-%m-%d").to_string(),
+                UsageGroup::Week => {
+                    let week = day.iso_week();
+                    format!("{}-W{:02}", week.year(), week.week())
+                }
+                UsageGroup::Model => model.to_string(),
+                UsageGroup::Project => usage.cwd.display().to_string(),
+                UsageGroup::Session => usage
+                    .id
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| path.display().to_string()),
+            };
+            let builder = rows.entry(key).or_default();
+            builder.add(path, &delta, cost);
+            total.add(path, &delta, cost);
+        }
+    }
+
+    let mut rows: Vec<UsageRow> = rows
+        .into_iter()
+        .map(|(key, builder)| UsageRow {
+            key,
+            ..builder.finish()
+        })
+        .collect();
+    if !matches!(query.group_by, UsageGroup::Day | UsageGroup::Week) {
+        rows.sort_by(|a, b| {
+            b.total_tokens
+                .cmp(&a.total_tokens)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+    }
+    let mut unpriced_models: Vec<String> = unpriced_models.into_iter().collect();
+    unpriced_models.sort();
+    Ok(UsageReport {
+        group_by: query.group_by,
+        rows,
+        total: UsageRow {
+            key: "total".to_string(),
+            ..total.finish()
+        },
+        unpriced_models,
+    })
+}
+
+impl RowBuilder {
+    fn add(&mut self, path: &Path, delta: &TokenUsage, cost: f64) {
+        self.sessions.insert(path.to_path_buf());
+        self.row.input_tokens += delta.input_tokens;
+        self.row.cached_input_tokens += delta.cached_input_tokens;
+        self.row.output_tokens += delta.output_tokens;
+        self.row.reasoning_output_tokens += delta.reasoning_output_tokens;
+        self.row.total_tokens += delta.total_tokens;
+        self.row.cost_usd += cost;
+    }
+
+    fn finish(self) -> UsageRow {
+        UsageRow {
+            sessions: self.sessions.len(),
+            ..self.row
+        }
+    }
+}
+
+/// The token counts recorded by the session itself, leaving out those copied
+/// from its parent when it was forked.
+fn own_counts<'a>(usage: &'a SessionUsage, parent: Option<&SessionUsage>) -> &'a [TokenCount] {
+    if usage.forked_from.is_none() {
+        return &usage.counts;
+    }
+    let copied = match parent {
+        // The copy is a prefix of the parent's rollout.
+        Some(parent) => usage
+            .counts
+            .iter()
+            .zip(&parent.counts)
+            .take_while(|(own, theirs)| own.total == theirs.total)
+            .count(),
+        // Without the parent, the copy ends where the running total starts
+        // over for the forked session.
+        None => usage
+            .counts
+            .windows(2)
+            .position(|pair| pair[1].total.total_tokens < pair[0].total.total_tokens)
+            .map_or(usage.counts.len(), |index| index + 1),
+    };
+    &usage.counts[copied..]
+}
+
+fn parse_session_usage(text: &str) -> SessionUsage {
+    let mut usage = SessionUsage {
+        id: None,
+        cwd: PathBuf::new(),
+        forked_from: None,
+        counts: Vec::new(),
+    };
+    let mut model = None;
+    for line in text.lines() {
+        // Skip the bulk of the rollout without parsing it.
+        if !(line.contains("\"token_count\"")
+            || line.contains("\"session_meta\"")
+            || line.contains("\"turn_context\""))
+        {
+            continue;
+        }
+        let Ok(line) = serde_json::from_str::<RolloutLine>(line.trim()) else {
+            continue;
+        };
+        match line.item {
+            RolloutItem::SessionMeta(meta_line) => match usage.id {
+                None => {
+                    usage.id = Some(meta_line.meta.id);
+                    usage.cwd = meta_line.meta.cwd;
+                }
+                Some(id) if id != meta_line.meta.id && usage.forked_from.is_none() => {
+                    usage.forked_from = Some(meta_line.meta.id);
+                }
+                Some(_) => {}
+            },
+            RolloutItem::TurnContext(context) => model = Some(context.model),
+            RolloutItem::EventMsg(EventMsg::TokenCount(event)) => {
+                let Some(info) = event.info else {
+                    continue;
+                };
+                let Ok(timestamp) = DateTime::parse_from_rfc3339(&line.timestamp) else {
+                    continue;
+                };
+                usage.counts.push(TokenCount {
+                    timestamp: timestamp.with_timezone(&Utc),
+                    model: model.clone(),
+                    total: info.total_token_usage,
+                });
+            }
+            _ => {}
+        }
+    }
+    usage
+}
+
+fn subtract(total: &TokenUsage, previous: &TokenUsage) -> TokenUsage {
+    TokenUsage {
+        input_tokens: total.input_tokens - previous.input_tokens,
+        cached_input_tokens: total.cached_input_tokens - previous.cached_input_tokens,
+        output_tokens: total.output_tokens - previous.output_tokens,
+        reasoning_output_tokens: total.reasoning_output_tokens - previous.reasoning_output_tokens,
+        total_tokens: total.total_tokens - previous.total_tokens,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::ModelPrice;
+    use codex_protocol::config_types::ReasoningSummary;
+    use codex_protocol::protocol::AskForApproval;
+    use codex_protocol::protocol::SandboxPolicy;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::TokenCountEvent;
+    use codex_protocol::protocol::TokenUsageInfo;
+    use codex_protocol::protocol::TurnContextItem;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const PARENT_ID: &str = "5973b6c0-94b8-487b-a530-2aeb6098ae0e";
+    const FORK_ID: &str = "0199a213-81c0-7800-8aa1-bbab2a035a53";
+
+    fn line(timestamp: &str, item: RolloutItem) -> String {
+        let line = RolloutLine {
+            timestamp: timestamp.to_string(),
+            item,
+        };
+        serde_json::to_string(&line).expect("serialize rollout line") + "\n"
+    }
+
+    fn meta(id: &str, cwd: &str) -> String {
+        line(
+            "2025-01-06T09:00:00.000Z",
+            RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: ConversationId::from_string(id).expect("valid id"),
+                    cwd: PathBuf::from(cwd),
+                    ..SessionMeta::default()
+                },
+                git: None,
+            }),
+        )
+    }
+
+    fn turn_context(model: &str) -> String {
+        line(
+            "2025-01-06T09:00:00.000Z",
+            RolloutItem::TurnContext(TurnContextItem {
+                cwd: PathBuf::from("/repo"),
+                approval_policy: AskForApproval::Never,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                model: model.to_string(),
+                effort: None,
+                summary: ReasoningSummary::Auto,
+            }),
+        )
+    }
+
+    /// A token count whose cumulative total has `input` input tokens, half
+    /// of them cached, and `output` output tokens.
+    fn token_count(timestamp: &str, input: i64, output: i64) -> String {
+        let total = TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: input / 2,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        };
+        line(
+            timestamp,
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: Some(TokenUsageInfo {
+                    total_token_usage: total.clone(),
+                    last_token_usage: total,
+                    model_context_window: None,
+                }),
+                rate_limits: None,
+            })),
+        )
+    }
+
+    fn write_rollout(codex_home: &Path, id: &str, lines: &[String]) {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/01/06");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        let path = dir.join(format!("rollout-2025-01-06T09-00-00-{id}.jsonl"));
+        std::fs::write(path, lines.concat()).expect("write rollout");
+    }
+
+    fn prices() -> UsageConfig {
+        UsageConfig {
+            prices: HashMap::from([(
+                "gpt-5".to_string(),
+                ModelPrice {
+                    input: 2.0,
+                    cached_input: Some(1.0),
+                    output: 10.0,
+                },
+            )]),
+        }
+    }
+
+    #[tokio::test]
+    async fn counts_increases_across_resumes_and_groups_by_day() {
+        let codex_home = TempDir::new().expect("tempdir");
+        write_rollout(
+            codex_home.path(),
+            PARENT_ID,
+            &[
+                meta(PARENT_ID, "/repo"),
+                turn_context("gpt-5-codex"),
+                token_count("2025-01-06T09:01:00.000Z", 1_000, 100),
+                // Rate limit updates repeat the same total.
+                token_count("2025-01-06T09:01:01.000Z", 1_000, 100),
+                token_count("2025-01-06T09:02:00.000Z", 3_000, 300),
+                // Resumed the next day: the running total starts over.
+                turn_context("o3"),
+                token_count("2025-01-07T10:00:00.000Z", 2_000, 200),
+            ],
+        );
+
+        let query = UsageQuery {
+            group_by: UsageGroup::Day,
+            since: None,
+            until: None,
+        };
+        let report = summarize_usage(codex_home.path(), &prices(), &query)
+            .await
+            .expect("summarize usage");
+
+        let day = |key: &str, input: i64, output: i64, cost_usd: f64| UsageRow {
+            key: key.to_string(),
+            sessions: 1,
+            input_tokens: input,
+            cached_input_tokens: input / 2,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+            cost_usd,
+        };
+        // 1,500 uncached at $2, 1,500 cached at $1 and 300 output at $10 per
+        // million tokens; `o3` has no price.
+        assert_eq!(
+            report,
+            UsageReport {
+                group_by: UsageGroup::Day,
+                rows: vec![
+                    day("2025-01-06", 3_000, 300, 0.0075),
+                    day("2025-01-07", 2_000, 200, 0.0),
+                ],
+                total: day("total", 5_000, 500, 0.0075),
+                unpriced_models: vec!["o3".to_string()],
+            }
+        );
+
+        let query = UsageQuery {
+            group_by: UsageGroup::Model,
+            since: NaiveDate::from_ymd_opt(2025, 1, 7),
+            until: None,
+        };
+        let report = summarize_usage(codex_home.path(), &prices(), &query)
+            .await
+            .expect("summarize usage");
+        assert_eq!(report.rows, vec![day("o3", 2_000, 200, 0.0)]);
+    }
+
+    #[tokio::test]
+    async fn skips_token_counts_copied_into_forks() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let parent = [
+            meta(PARENT_ID, "/repo"),
+            turn_context("gpt-5"),
+            token_count("2025-01-06T09:01:00.000Z", 1_000, 100),
+        ];
+        write_rollout(codex_home.path(), PARENT_ID, &parent);
+        let mut fork = vec![meta(FORK_ID, "/repo")];
+        fork.extend(parent.iter().cloned());
+        fork.push(token_count("2025-01-06T09:05:00.000Z", 1_200, 50));
+        write_rollout(codex_home.path(), FORK_ID, &fork);
+
+        let query = UsageQuery {
+            group_by: UsageGroup::Session,
+            since: None,
+            until: None,
+        };
+        let report = summarize_usage(codex_home.path(), &prices(), &query)
+            .await
+            .expect("summarize usage");
+
+        let keys_and_totals: Vec<(String, i64)> = report
+            .rows
+            .iter()
+            .map(|row| (row.key.clone(), row.total_tokens))
+            .collect();
+        assert_eq!(
+            keys_and_totals,
+            vec![(FORK_ID.to_string(), 1_250), (PARENT_ID.to_string(), 1_100),]
+        );
+        assert_eq!(report.total.sessions, 2);
+        assert_eq!(report.total.total_tokens, 2_350);
+    }
+}
@@ -122,7 +122,7 @@ fn is_write_patch_constrained_to_writable_paths(
 ) -> bool {
     // Early‑exit if there are no declared writable roots.
     let writable_roots = match sandbox_policy {
-        SandboxPolicy::ReadOnly => {
+        SandboxPolicy::ReadOnly { .. } => {
             return false;
         }
         SandboxPolicy::DangerFullAccess => {
@@ -211,6 +211,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: Vec::new(),
+            deny_read: Vec::new(),
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -232,6 +234,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: Vec::new(),
+            deny_read: Vec::new(),
         };
         assert!(is_write_patch_constrained_to_writable_paths(
             &add_outside,
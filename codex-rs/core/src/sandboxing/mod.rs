@@ -8,6 +8,7 @@ ready‑to‑spawn environment.
 
 pub mod assessment;
 
+use crate::config::types::ResourceLimits;
 use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
@@ -24,6 +25,7 @@ use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use crate::tools::sandboxing::SandboxablePreference;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -70,6 +72,7 @@ pub struct ExecEnv {
     pub with_escalated_permissions: Option<bool>,
     pub justification: Option<String>,
     pub arg0: Option<String>,
+    pub resource_limits: ResourceLimits,
 }
 
 pub enum SandboxPreference {
@@ -114,6 +117,11 @@ impl SandboxManager {
         }
     }
 
+    /// `network_proxy` is the address of the session's network proxy, if
+    /// any. Sandboxed commands without network access are pointed at it and
+    /// allowed to connect to it. `resource_limits` are carried over to the
+    /// returned `ExecEnv` and applied when the command is spawned.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn transform(
         &self,
         mut spec: CommandSpec,
@@ -121,13 +129,25 @@ impl SandboxManager {
         sandbox: SandboxType,
         sandbox_policy_cwd: &Path,
         codex_linux_sandbox_exe: Option<&PathBuf>,
+        network_proxy: Option<SocketAddr>,
+        resource_limits: ResourceLimits,
     ) -> Result<ExecEnv, SandboxTransformError> {
         let mut env = spec.env;
+        let mut network_proxy_port = None;
         if !policy.has_full_network_access() {
             env.insert(
                 CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR.to_string(),
                 "1".to_string(),
             );
+            if let Some(addr) = network_proxy
+                && matches!(
+                    sandbox,
+                    SandboxType::LinuxSeccomp | SandboxType::MacosSeatbelt
+                )
+            {
+                network_proxy_port = Some(addr.port());
+                set_network_proxy_env(&mut env, addr);
+            }
         }
 
         let mut command = Vec::with_capacity(1 + spec.args.len());
@@ -140,8 +160,12 @@ impl SandboxManager {
             SandboxType::MacosSeatbelt => {
                 let mut seatbelt_env = HashMap::new();
                 seatbelt_env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
-                let mut args =
-                    create_seatbelt_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_seatbelt_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    network_proxy_port,
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(MACOS_PATH_TO_SEATBELT_EXECUTABLE.to_string());
                 full_command.append(&mut args);
@@ -152,8 +176,12 @@ impl SandboxManager {
             SandboxType::LinuxSeccomp => {
                 let exe = codex_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
-                let mut args =
-                    create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_linux_sandbox_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    network_proxy_port,
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
                 full_command.append(&mut args);
@@ -184,6 +212,7 @@ impl SandboxManager {
             with_escalated_permissions: spec.with_escalated_permissions,
             justification: spec.justification,
             arg0: arg0_override,
+            resource_limits,
         })
     }
 
@@ -192,6 +221,24 @@ impl SandboxManager {
     }
 }
 
+/// Points the usual proxy variables at the network proxy, and drops the
+/// `NO_PROXY` exemptions since direct connections would be blocked anyway.
+fn set_network_proxy_env(env: &mut HashMap<String, String>, addr: SocketAddr) {
+    let proxy_url = format!("http://{addr}");
+    for key in [
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+        "http_proxy",
+        "https_proxy",
+        "all_proxy",
+    ] {
+        env.insert(key.to_string(), proxy_url.clone());
+    }
+    env.remove("NO_PROXY");
+    env.remove("no_proxy");
+}
+
 pub async fn execute_env(
     env: ExecEnv,
     policy: &SandboxPolicy,
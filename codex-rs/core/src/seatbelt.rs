@@ -20,6 +20,21 @@ const MACOS_SEATBELT_NETWORK_POLICY: &str = include_str!("seatbelt_network_polic
 /// already has root access.
 pub(crate) const MACOS_PATH_TO_SEATBELT_EXECUTABLE: &str = "/usr/bin/sandbox-exec";
 
+/// System locations that stay readable when `readable_roots` restricts reads,
+/// so that shells and common tools can still start.
+const MACOS_SYSTEM_READ_ROOTS: &[&str] = &[
+    "/bin",
+    "/sbin",
+    "/usr",
+    "/System",
+    "/Library",
+    "/Applications",
+    "/opt",
+    "/private/etc",
+    "/private/var/db",
+    "/dev",
+];
+
 pub async fn spawn_command_under_seatbelt(
     command: Vec<String>,
     command_cwd: PathBuf,
@@ -28,7 +43,7 @@ pub async fn spawn_command_under_seatbelt(
     stdio_policy: StdioPolicy,
     mut env: HashMap<String, String>,
 ) -> std::io::Result<Child> {
-    let args = create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args = create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd, None);
     let arg0 = None;
     env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
     spawn_child_async(
@@ -39,14 +54,18 @@ pub async fn spawn_command_under_seatbelt(
         sandbox_policy,
         stdio_policy,
         env,
+        None,
     )
     .await
 }
 
+/// `network_proxy_port`, when set, allows connections to the codex network
+/// proxy on localhost even though the policy has no network access.
 pub(crate) fn create_seatbelt_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    network_proxy_port: Option<u16>,
 ) -> Vec<String> {
     let (file_write_policy, file_write_dir_params) = {
         if sandbox_policy.has_full_disk_write_access() {
@@ -98,24 +117,77 @@ pub(crate) fn create_seatbelt_command_args(
         }
     };
 
-    let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
-    } else {
-        ""
+    let (file_read_policy, file_read_dir_params) = match sandbox_policy
+        .get_readable_roots_with_cwd(sandbox_policy_cwd)
+    {
+        None => (
+            "; allow read-only file operations\n(allow file-read*)".to_string(),
+            Vec::new(),
+        ),
+        Some(readable_roots) => {
+            let mut readable_folder_policies: Vec<String> = Vec::new();
+            let mut file_read_params = Vec::new();
+            let system_roots = MACOS_SYSTEM_READ_ROOTS.iter().map(PathBuf::from);
+            for (index, root) in readable_roots.into_iter().chain(system_roots).enumerate() {
+                let canonical_root = root.canonicalize().unwrap_or(root);
+                let root_param = format!("READABLE_ROOT_{index}");
+                readable_folder_policies.push(format!("(subpath (param \"{root_param}\"))"));
+                file_read_params.push((root_param, canonical_root));
+            }
+            // Path lookups need metadata for every ancestor directory, even
+            // when the contents of that directory are not readable.
+            let file_read_policy = format!(
+                "; allow reads only under the readable roots\n(allow file-read-metadata)\n(allow file-read*\n{}\n)",
+                readable_folder_policies.join(" ")
+            );
+            (file_read_policy, file_read_params)
+        }
+    };
+
+    // Deny rules must come after the allow rules: seatbelt applies the last
+    // matching rule.
+    let (file_deny_policy, file_deny_dir_params) = {
+        let mut denied_policies: Vec<String> = Vec::new();
+        let mut file_deny_params = Vec::new();
+        for (index, path) in sandbox_policy.get_deny_read_paths().iter().enumerate() {
+            let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+            let deny_param = format!("DENY_READ_{index}");
+            denied_policies.push(format!("(subpath (param \"{deny_param}\"))"));
+            file_deny_params.push((deny_param, canonical_path));
+        }
+        if denied_policies.is_empty() {
+            ("".to_string(), Vec::new())
+        } else {
+            let file_deny_policy = format!(
+                "\n(deny file-read* file-write*\n{}\n)",
+                denied_policies.join(" ")
+            );
+            (file_deny_policy, file_deny_params)
+        }
     };
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
     let network_policy = if sandbox_policy.has_full_network_access() {
-        MACOS_SEATBELT_NETWORK_POLICY
+        MACOS_SEATBELT_NETWORK_POLICY.to_string()
+    } else if let Some(port) = network_proxy_port {
+        format!(
+            "; allow connections to the codex network proxy only\n(allow network-outbound (remote ip \"localhost:{port}\"))\n"
+        )
     } else {
-        ""
+        String::new()
     };
 
     let full_policy = format!(
-        "{MACOS_SEATBELT_BASE_POLICY}\n{file_read_policy}\n{file_write_policy}\n{network_policy}"
+        "{MACOS_SEATBELT_BASE_POLICY}\n{file_read_policy}\n{file_write_policy}\n{network_policy}{file_deny_policy}"
     );
 
-    let dir_params = [file_write_dir_params, macos_dir_params()].concat();
+    let dir_params = [
+        file_read_dir_params,
+        file_write_dir_params,
+        file_deny_dir_params,
+        macos_dir_params(),
+    ]
+    .concat();
 
     let mut seatbelt_args: Vec<String> = vec!["-p".to_string(), full_policy];
     let definition_args = dir_params
@@ -186,12 +258,15 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: Vec::new(),
+            deny_read: Vec::new(),
         };
 
         let args = create_seatbelt_command_args(
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             &cwd,
+            None,
         );
 
         // Build the expected policy text using a raw string for readability.
@@ -262,12 +337,15 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            readable_roots: Vec::new(),
+            deny_read: Vec::new(),
         };
 
         let args = create_seatbelt_command_args(
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             root_with_git.as_path(),
+            None,
         );
 
         let tmpdir_env_var = std::env::var("TMPDIR")
@@ -336,6 +414,71 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn create_seatbelt_args_with_read_restrictions() {
+        let tmp = TempDir::new().expect("tempdir");
+        let PopulatedTmp {
+            root_with_git,
+            root_without_git,
+            root_with_git_canon,
+            root_without_git_canon,
+            ..
+        } = populate_tmpdir(tmp.path());
+        let secret = root_without_git.join("secret");
+        fs::write(&secret, "hunter2").expect("write secret");
+
+        let mut policy = SandboxPolicy::new_read_only_policy();
+        policy.restrict_reads(std::slice::from_ref(&root_without_git), &[secret]);
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            root_with_git.as_path(),
+            None,
+        );
+
+        let full_policy = &args[1];
+        assert!(!full_policy.contains("(allow file-read*)"));
+        assert!(full_policy.contains("(allow file-read-metadata)"));
+        assert!(
+            full_policy
+                .ends_with("(deny file-read* file-write*\n(subpath (param \"DENY_READ_0\"))\n)")
+        );
+        for expected in [
+            format!(
+                "-DREADABLE_ROOT_0={}",
+                root_without_git_canon.to_string_lossy()
+            ),
+            format!(
+                "-DREADABLE_ROOT_1={}",
+                root_with_git_canon.to_string_lossy()
+            ),
+            format!(
+                "-DDENY_READ_0={}",
+                root_without_git_canon.join("secret").to_string_lossy()
+            ),
+        ] {
+            assert!(args.contains(&expected), "missing {expected} in {args:?}");
+        }
+    }
+
+    #[test]
+    fn create_seatbelt_args_with_network_proxy() {
+        let tmp = TempDir::new().expect("tempdir");
+        let policy = SandboxPolicy::new_read_only_policy();
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            tmp.path(),
+            Some(3128),
+        );
+
+        let full_policy = &args[1];
+        assert!(full_policy.contains("(allow network-outbound (remote ip \"localhost:3128\"))"));
+        assert!(!full_policy.contains("(allow network-outbound)"));
+    }
+
     struct PopulatedTmp {
         root_with_git: PathBuf,
         root_without_git: PathBuf,
@@ -6,6 +6,7 @@ use tokio::process::Command;
 use tracing::trace;
 
 use crate::protocol::SandboxPolicy;
+use crate::resource_limits::CommandLimits;
 
 /// Experimental environment variable that will be set to some non-empty value
 /// if both of the following are true:
@@ -35,6 +36,9 @@ pub enum StdioPolicy {
 /// For now, we take `SandboxPolicy` as a parameter to spawn_child() because
 /// we need to determine whether to set the
 /// `CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR` environment variable.
+///
+/// `command_limits`, when set, are applied to the child right before it execs.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn spawn_child_async(
     program: PathBuf,
     args: Vec<String>,
@@ -43,6 +47,7 @@ pub(crate) async fn spawn_child_async(
     sandbox_policy: &SandboxPolicy,
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
+    #[cfg_attr(not(unix), allow(unused_variables))] command_limits: Option<&CommandLimits>,
 ) -> std::io::Result<Child> {
     trace!(
         "spawn_child_async: {program:?} {args:?} {arg0:?} {cwd:?} {sandbox_policy:?} {stdio_policy:?} {env:?}"
@@ -95,6 +100,13 @@ pub(crate) async fn spawn_child_async(
         });
     }
 
+    #[cfg(unix)]
+    if let Some(command_limits) = command_limits {
+        unsafe {
+            cmd.pre_exec(command_limits.pre_exec_hook());
+        }
+    }
+
     match stdio_policy {
         StdioPolicy::RedirectForShellTool => {
             // Do not create a file descriptor for stdin because otherwise some
@@ -2,7 +2,11 @@ use std::sync::Arc;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
+use crate::budget::SharedBudget;
+use crate::hooks::Hooks;
+use crate::lsp::LspManager;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::network_proxy::NetworkProxy;
 use crate::openai_models::models_manager::ModelsManager;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
@@ -17,6 +21,10 @@ pub(crate) struct SessionServices {
     pub(crate) mcp_startup_cancellation_token: CancellationToken,
     pub(crate) unified_exec_manager: UnifiedExecSessionManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: Hooks,
+    pub(crate) lsp_manager: LspManager,
+    pub(crate) budget: SharedBudget,
+    pub(crate) network_proxy: Option<NetworkProxy>,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: crate::shell::Shell,
     pub(crate) show_raw_agent_reasoning: bool,
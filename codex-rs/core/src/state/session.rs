@@ -1,6 +1,7 @@
 //! Session-wide mutable state.
 
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::PinnedNote;
 
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
@@ -14,6 +15,7 @@ pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    pub(crate) pinned_notes: Vec<PinnedNote>,
 }
 
 impl SessionState {
@@ -24,6 +26,7 @@ impl SessionState {
             session_configuration,
             history,
             latest_rate_limits: None,
+            pinned_notes: Vec::new(),
         }
     }
 
@@ -81,6 +84,27 @@ impl SessionState {
     pub(crate) fn get_total_token_usage(&self) -> i64 {
         self.history.get_total_token_usage()
     }
+
+    // Pinned context helpers
+    pub(crate) fn pin_note(&mut self, text: String) -> PinnedNote {
+        let id = self
+            .pinned_notes
+            .iter()
+            .map(|note| note.id)
+            .max()
+            .unwrap_or_default()
+            + 1;
+        let note = PinnedNote { id, text };
+        self.pinned_notes.push(note.clone());
+        note
+    }
+
+    /// Returns whether a note with `id` was pinned.
+    pub(crate) fn unpin_note(&mut self, id: u32) -> bool {
+        let before = self.pinned_notes.len();
+        self.pinned_notes.retain(|note| note.id != id);
+        self.pinned_notes.len() != before
+    }
 }
 
 // Sometimes new snapshots don't include credits or plan information.
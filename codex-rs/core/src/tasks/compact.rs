@@ -5,11 +5,21 @@ use super::SessionTaskContext;
 use crate::codex::TurnContext;
 use crate::state::TaskKind;
 use async_trait::async_trait;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::user_input::UserInput;
 use tokio_util::sync::CancellationToken;
 
 #[derive(Clone, Copy, Default)]
-pub(crate) struct CompactTask;
+pub(crate) struct CompactTask {
+    /// Overrides the configured strategy for this compaction.
+    strategy: Option<CompactionStrategy>,
+}
+
+impl CompactTask {
+    pub(crate) fn new(strategy: Option<CompactionStrategy>) -> Self {
+        Self { strategy }
+    }
+}
 
 #[async_trait]
 impl SessionTask for CompactTask {
@@ -25,10 +35,18 @@ impl SessionTask for CompactTask {
         _cancellation_token: CancellationToken,
     ) -> Option<String> {
         let session = session.clone_session();
-        if crate::compact::should_use_remote_compact_task(&session) {
-            crate::compact_remote::run_remote_compact_task(session, ctx).await
-        } else {
-            crate::compact::run_compact_task(session, ctx, input).await
+        let strategy = self
+            .strategy
+            .unwrap_or(ctx.client.config().compaction.strategy);
+        match strategy {
+            CompactionStrategy::Summarize => {
+                if crate::compact::should_use_remote_compact_task(&session) {
+                    crate::compact_remote::run_remote_compact_task(session, ctx).await
+                } else {
+                    crate::compact::run_compact_task(session, ctx, input).await
+                }
+            }
+            strategy => crate::compact_local::run_local_compact_task(session, ctx, strategy).await,
         }
 
         None
@@ -173,6 +173,7 @@ impl Session {
             *active = None;
         }
         drop(active);
+        self.budget().end_turn().await;
         let event = EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message });
         self.send_event(turn_context.as_ref(), event).await;
     }
@@ -215,6 +216,7 @@ impl Session {
         }
 
         task.handle.abort();
+        self.budget().end_turn().await;
 
         let session_ctx = Arc::new(SessionTaskContext::new(Arc::clone(self)));
         session_task
@@ -11,6 +11,7 @@ use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExitedReviewModeEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::SubAgentSource;
 use tokio_util::sync::CancellationToken;
 
 use crate::codex::Session;
@@ -78,7 +79,9 @@ async fn start_review_conversation(
 ) -> Option<async_channel::Receiver<Event>> {
     let config = ctx.client.config();
     let mut sub_agent_config = config.as_ref().clone();
-    sub_agent_config.sandbox_policy = SandboxPolicy::new_read_only_policy();
+    let mut sandbox_policy = SandboxPolicy::new_read_only_policy();
+    sandbox_policy.inherit_read_restrictions(&config.sandbox_policy);
+    sub_agent_config.sandbox_policy = sandbox_policy;
     // Run with only reviewer rubric — drop outer user_instructions
     sub_agent_config.user_instructions = None;
     // Avoid loading project docs; reviewer only needs findings
@@ -94,6 +97,7 @@ async fn start_review_conversation(
     sub_agent_config.base_instructions = Some(crate::REVIEW_PROMPT.to_string());
     (run_codex_conversation_one_shot(
         sub_agent_config,
+        SubAgentSource::Review,
         session.auth_manager(),
         session.models_manager(),
         input,
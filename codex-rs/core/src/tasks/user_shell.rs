@@ -10,6 +10,7 @@ use tracing::error;
 use uuid::Uuid;
 
 use crate::codex::TurnContext;
+use crate::config::types::ResourceLimits;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
@@ -103,6 +104,7 @@ impl SessionTask for UserShellCommandTask {
             with_escalated_permissions: None,
             justification: None,
             arg0: None,
+            resource_limits: ResourceLimits::default(),
         };
 
         let stdout_stream = Some(StdoutStream {
@@ -126,6 +128,7 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(aborted_message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    resource_limit_exceeded: None,
                 };
                 let output_items = [user_shell_command_record_item(
                     &raw_command,
@@ -202,6 +205,7 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    resource_limit_exceeded: None,
                 };
                 session
                     .send_event(
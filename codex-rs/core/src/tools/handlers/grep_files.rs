@@ -10,6 +10,7 @@ use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 
@@ -75,6 +76,7 @@ impl ToolHandler for GrepFilesHandler {
         let search_path = turn.resolve_path(args.path.clone());
 
         verify_path_exists(&search_path).await?;
+        ensure_readable(&turn, &search_path)?;
 
         let include = args.include.as_deref().map(str::trim).and_then(|val| {
             if val.is_empty() {
@@ -84,8 +86,13 @@ impl ToolHandler for GrepFilesHandler {
             }
         });
 
-        let search_results =
+        let mut search_results =
             run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?;
+        // rg runs outside the sandbox, so drop matches under `deny_read`.
+        search_results.retain(|path| {
+            turn.sandbox_policy
+                .is_path_readable(&turn.cwd.join(path), &turn.cwd)
+        });
 
         if search_results.is_empty() {
             Ok(ToolOutput::Function {
@@ -0,0 +1,600 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use url::Url;
+
+use crate::function_tool::FunctionCallError;
+use crate::lsp::SyncedDocument;
+use crate::protocol::SandboxPolicy;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct LspHandler;
+
+const MAX_RESULTS: usize = 200;
+const DIAGNOSTICS_WAIT: Duration = Duration::from_secs(20);
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum LspOperation {
+    GotoDefinition,
+    FindReferences,
+    Hover,
+    DocumentSymbols,
+    Diagnostics,
+}
+
+#[derive(Deserialize)]
+struct LspArgs {
+    operation: LspOperation,
+    path: String,
+    #[serde(default)]
+    line: Option<usize>,
+    #[serde(default)]
+    column: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for LspHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "lsp handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: LspArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let path = turn.resolve_path(Some(args.path.clone()));
+        // The document is read in-process and handed to an unsandboxed server.
+        ensure_readable(&turn, &path)?;
+        let server = session
+            .services
+            .lsp_manager
+            .server_for_path(&path, &turn.cwd)
+            .await
+            .map_err(respond)?;
+        let document = server
+            .client
+            .sync_document(&path, &server.language_id)
+            .await
+            .map_err(respond)?;
+        let text_document = json!({ "uri": document.uri });
+
+        let mut files = SourceFiles::new(&turn.cwd, &turn.sandbox_policy);
+        let lines = match args.operation {
+            LspOperation::GotoDefinition | LspOperation::FindReferences | LspOperation::Hover => {
+                let position = lsp_position(&document, args.line, args.column)?;
+                let (method, params) = match args.operation {
+                    LspOperation::GotoDefinition => (
+                        "textDocument/definition",
+                        json!({ "textDocument": text_document, "position": position }),
+                    ),
+                    LspOperation::FindReferences => (
+                        "textDocument/references",
+                        json!({
+                            "textDocument": text_document,
+                            "position": position,
+                            "context": { "includeDeclaration": true },
+                        }),
+                    ),
+                    _ => (
+                        "textDocument/hover",
+                        json!({ "textDocument": text_document, "position": position }),
+                    ),
+                };
+                let result = server
+                    .client
+                    .request(method, params)
+                    .await
+                    .map_err(respond)?;
+                if args.operation == LspOperation::Hover {
+                    hover_text(result.get("contents").unwrap_or(&Value::Null))
+                        .lines()
+                        .map(str::to_string)
+                        .collect()
+                } else {
+                    format_locations(&result, &mut files).await
+                }
+            }
+            LspOperation::DocumentSymbols => {
+                let result = server
+                    .client
+                    .request(
+                        "textDocument/documentSymbol",
+                        json!({ "textDocument": text_document }),
+                    )
+                    .await
+                    .map_err(respond)?;
+                let mut lines = Vec::new();
+                format_symbols(&result, &document.text, 0, &mut lines);
+                lines
+            }
+            LspOperation::Diagnostics => {
+                let diagnostics = server.client.diagnostics(&document, DIAGNOSTICS_WAIT).await;
+                match diagnostics {
+                    Some(diagnostics) => {
+                        format_diagnostics(&path, &document.text, &diagnostics, &files)
+                    }
+                    None => {
+                        return Err(FunctionCallError::RespondToModel(format!(
+                            "the language server did not report diagnostics for `{}` within {}s",
+                            args.path,
+                            DIAGNOSTICS_WAIT.as_secs()
+                        )));
+                    }
+                }
+            }
+        };
+
+        let content = if lines.is_empty() {
+            match args.operation {
+                LspOperation::Diagnostics => "No diagnostics.".to_string(),
+                _ => "No results.".to_string(),
+            }
+        } else {
+            let total = lines.len();
+            let mut content = lines
+                .into_iter()
+                .take(MAX_RESULTS)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if total > MAX_RESULTS {
+                content.push_str(&format!("\n... {} more", total - MAX_RESULTS));
+            }
+            content
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn respond(err: anyhow::Error) -> FunctionCallError {
+    FunctionCallError::RespondToModel(format!("{err:#}"))
+}
+
+/// Converts a 1-indexed line and character column into an LSP position, which
+/// counts UTF-16 code units from zero.
+fn lsp_position(
+    document: &SyncedDocument,
+    line: Option<usize>,
+    column: Option<usize>,
+) -> Result<Value, FunctionCallError> {
+    let (Some(line), Some(column)) = (line, column) else {
+        return Err(FunctionCallError::RespondToModel(
+            "line and column are required for this operation".to_string(),
+        ));
+    };
+    if line == 0 || column == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "line and column are 1-indexed".to_string(),
+        ));
+    }
+    let Some(line_text) = document.text.lines().nth(line - 1) else {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "line {line} is past the end of the file"
+        )));
+    };
+    let character: usize = line_text
+        .chars()
+        .take(column - 1)
+        .map(char::len_utf16)
+        .sum();
+    Ok(json!({ "line": line - 1, "character": character }))
+}
+
+/// Converts an LSP position back into 1-indexed line and character column.
+fn display_position(text: Option<&str>, position: &Value) -> (usize, usize) {
+    let line = position.get("line").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let character = position
+        .get("character")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as usize;
+    let column = match text.and_then(|text| text.lines().nth(line)) {
+        Some(line_text) => {
+            let mut units = 0;
+            line_text
+                .chars()
+                .take_while(|ch| {
+                    units += ch.len_utf16();
+                    units <= character
+                })
+                .count()
+        }
+        None => character,
+    };
+    (line + 1, column + 1)
+}
+
+/// Source files referenced by results, read once per call to render
+/// positions and snippets. Files the sandbox policy does not let the agent
+/// read are listed without a snippet.
+struct SourceFiles<'a> {
+    cwd: &'a Path,
+    sandbox_policy: &'a SandboxPolicy,
+    contents: HashMap<PathBuf, Option<String>>,
+}
+
+impl<'a> SourceFiles<'a> {
+    fn new(cwd: &'a Path, sandbox_policy: &'a SandboxPolicy) -> Self {
+        Self {
+            cwd,
+            sandbox_policy,
+            contents: HashMap::new(),
+        }
+    }
+
+    async fn load(&mut self, path: &Path) -> Option<&str> {
+        if !self.contents.contains_key(path) {
+            let text = if self.sandbox_policy.is_path_readable(path, self.cwd) {
+                tokio::fs::read_to_string(path).await.ok()
+            } else {
+                None
+            };
+            self.contents.insert(path.to_path_buf(), text);
+        }
+        self.contents.get(path).and_then(|text| text.as_deref())
+    }
+
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(self.cwd)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+/// Formats `Location`, `Location[]` and `LocationLink[]` results as
+/// `path:line:column: source line`.
+async fn format_locations(result: &Value, files: &mut SourceFiles<'_>) -> Vec<String> {
+    let locations = match result {
+        Value::Array(locations) => locations.clone(),
+        Value::Null => Vec::new(),
+        location => vec![location.clone()],
+    };
+
+    let mut lines = Vec::new();
+    for location in &locations {
+        let uri = location
+            .get("uri")
+            .or_else(|| location.get("targetUri"))
+            .and_then(Value::as_str);
+        let start = location
+            .get("range")
+            .or_else(|| location.get("targetSelectionRange"))
+            .and_then(|range| range.get("start"));
+        let (Some(uri), Some(start)) = (uri, start) else {
+            continue;
+        };
+        let Some(path) = uri_to_path(uri) else {
+            lines.push(uri.to_string());
+            continue;
+        };
+        let display = files.display(&path);
+        let text = files.load(&path).await;
+        let (line, column) = display_position(text, start);
+        let snippet = text
+            .and_then(|text| text.lines().nth(line - 1))
+            .map(str::trim)
+            .unwrap_or_default();
+        lines.push(format!("{display}:{line}:{column}: {snippet}"));
+    }
+    lines
+}
+
+/// Flattens `MarkupContent`, `MarkedString` and arrays of either.
+fn hover_text(contents: &Value) -> String {
+    match contents {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(hover_text)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        Value::Object(object) => {
+            let value = object
+                .get("value")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            match object.get("language").and_then(Value::as_str) {
+                Some(language) => format!("```{language}\n{value}\n```"),
+                None => value.to_string(),
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+/// Formats `DocumentSymbol[]` (nested) or `SymbolInformation[]` (flat) as an
+/// indented outline.
+fn format_symbols(result: &Value, text: &str, depth: usize, lines: &mut Vec<String>) {
+    let Some(symbols) = result.as_array() else {
+        return;
+    };
+    for symbol in symbols {
+        let name = symbol
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let kind = symbol
+            .get("kind")
+            .and_then(Value::as_u64)
+            .map(symbol_kind_name)
+            .unwrap_or("Symbol");
+        let start = symbol
+            .get("selectionRange")
+            .or_else(|| {
+                symbol
+                    .get("location")
+                    .and_then(|location| location.get("range"))
+            })
+            .and_then(|range| range.get("start"))
+            .unwrap_or(&Value::Null);
+        let (line, _) = display_position(Some(text), start);
+        let indent = "  ".repeat(depth);
+        lines.push(format!("{indent}{kind} {name} (line {line})"));
+        if let Some(children) = symbol.get("children") {
+            format_symbols(children, text, depth + 1, lines);
+        }
+    }
+}
+
+fn format_diagnostics(
+    path: &Path,
+    text: &str,
+    diagnostics: &[Value],
+    files: &SourceFiles<'_>,
+) -> Vec<String> {
+    let display = files.display(path);
+    let mut diagnostics: Vec<&Value> = diagnostics.iter().collect();
+    // Errors first, then by position.
+    diagnostics.sort_by_key(|diagnostic| {
+        let severity = diagnostic
+            .get("severity")
+            .and_then(Value::as_u64)
+            .unwrap_or(1);
+        let line = diagnostic
+            .pointer("/range/start/line")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        (severity, line)
+    });
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let start = diagnostic.pointer("/range/start").unwrap_or(&Value::Null);
+            let (line, column) = display_position(Some(text), start);
+            let severity = match diagnostic.get("severity").and_then(Value::as_u64) {
+                Some(2) => "warning",
+                Some(3) => "info",
+                Some(4) => "hint",
+                _ => "error",
+            };
+            let message = diagnostic
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let source = diagnostic
+                .get("source")
+                .and_then(Value::as_str)
+                .map(|source| format!(" [{source}]"))
+                .unwrap_or_default();
+            format!("{display}:{line}:{column}: {severity}: {message}{source}")
+        })
+        .collect()
+}
+
+fn symbol_kind_name(kind: u64) -> &'static str {
+    match kind {
+        1 => "File",
+        2 => "Module",
+        3 => "Namespace",
+        4 => "Package",
+        5 => "Class",
+        6 => "Method",
+        7 => "Property",
+        8 => "Field",
+        9 => "Constructor",
+        10 => "Enum",
+        11 => "Interface",
+        12 => "Function",
+        13 => "Variable",
+        14 => "Constant",
+        15 => "String",
+        16 => "Number",
+        17 => "Boolean",
+        18 => "Array",
+        19 => "Object",
+        20 => "Key",
+        21 => "Null",
+        22 => "EnumMember",
+        23 => "Struct",
+        24 => "Event",
+        25 => "Operator",
+        26 => "TypeParameter",
+        _ => "Symbol",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn positions_round_trip_through_utf16_columns() {
+        let text = "let é = \"😀\"; x\n";
+        // `x` is the 14th character, but the emoji before it takes two UTF-16
+        // code units so the server reports it at offset 14 rather than 13.
+        let position = json!({ "line": 0, "character": 14 });
+        assert_eq!(display_position(Some(text), &position), (1, 14));
+        assert_eq!(
+            display_position(None, &json!({ "line": 4, "character": 2 })),
+            (5, 3)
+        );
+    }
+
+    #[test]
+    fn hover_text_flattens_marked_strings() {
+        let contents = json!([
+            { "language": "rust", "value": "fn parse() -> Config" },
+            "Parses the config.",
+        ]);
+        assert_eq!(
+            hover_text(&contents),
+            "```rust\nfn parse() -> Config\n```\n\nParses the config."
+        );
+        assert_eq!(
+            hover_text(&json!({ "kind": "markdown", "value": "docs" })),
+            "docs"
+        );
+    }
+
+    #[test]
+    fn document_symbols_render_as_outline() {
+        let text = "struct Config {\n    path: String,\n}\n";
+        let symbols = json!([{
+            "name": "Config",
+            "kind": 23,
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 2, "character": 1 } },
+            "selectionRange": { "start": { "line": 0, "character": 7 }, "end": { "line": 0, "character": 13 } },
+            "children": [{
+                "name": "path",
+                "kind": 8,
+                "range": { "start": { "line": 1, "character": 4 }, "end": { "line": 1, "character": 16 } },
+                "selectionRange": { "start": { "line": 1, "character": 4 }, "end": { "line": 1, "character": 8 } },
+            }],
+        }]);
+        let mut lines = Vec::new();
+        format_symbols(&symbols, text, 0, &mut lines);
+        assert_eq!(
+            lines,
+            vec!["Struct Config (line 1)", "  Field path (line 2)"]
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_paths_denied_by_the_sandbox() {
+        use crate::codex::make_session_and_context;
+        use crate::config::types::LspServerConfig;
+        use crate::lsp::LspManager;
+        use crate::tools::context::ToolInvocation;
+        use crate::turn_diff_tracker::TurnDiffTracker;
+        use std::collections::BTreeMap;
+        use std::sync::Arc;
+        use tokio::sync::Mutex;
+
+        let dir = tempfile::TempDir::new().expect("tempdir");
+        let secret = dir.path().join("secret");
+        std::fs::create_dir_all(&secret).expect("create secret dir");
+        let path = secret.join("keys.rs");
+        std::fs::write(&path, "const KEY: &str = \"hunter2\";\n").expect("write");
+
+        let (mut session, mut turn) = make_session_and_context();
+        // A server that cannot start: the request must fail before reaching it.
+        let server: LspServerConfig = serde_json::from_value(json!({
+            "command": "/nonexistent/language-server",
+            "file_extensions": ["rs"],
+        }))
+        .expect("server config");
+        session.services.lsp_manager =
+            LspManager::new(BTreeMap::from([("rust".to_string(), server)]));
+        turn.cwd = dir.path().to_path_buf();
+        let mut policy = SandboxPolicy::new_workspace_write_policy();
+        policy.restrict_reads(&[], std::slice::from_ref(&secret));
+        turn.sandbox_policy = policy;
+
+        let result = LspHandler
+            .handle(ToolInvocation {
+                session: Arc::new(session),
+                turn: Arc::new(turn),
+                tracker: Arc::new(Mutex::new(TurnDiffTracker::new())),
+                call_id: "call-1".to_string(),
+                tool_name: "lsp".to_string(),
+                payload: ToolPayload::Function {
+                    arguments: json!({
+                        "operation": "document_symbols",
+                        "path": path.display().to_string(),
+                    })
+                    .to_string(),
+                },
+            })
+            .await;
+        let Err(FunctionCallError::RespondToModel(message)) = result else {
+            panic!("expected the request to be refused");
+        };
+        assert!(
+            message.contains("not allowed by the sandbox policy"),
+            "unexpected error: {message}"
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_sorted_by_severity() {
+        let cwd = PathBuf::from("/repo");
+        let policy = SandboxPolicy::DangerFullAccess;
+        let files = SourceFiles::new(&cwd, &policy);
+        let diagnostics = vec![
+            json!({
+                "range": { "start": { "line": 0, "character": 4 } },
+                "severity": 2,
+                "message": "unused variable",
+                "source": "rustc",
+            }),
+            json!({
+                "range": { "start": { "line": 1, "character": 0 } },
+                "severity": 1,
+                "message": "mismatched types",
+            }),
+        ];
+        assert_eq!(
+            format_diagnostics(
+                Path::new("/repo/src/lib.rs"),
+                "let x = 1;\nx\n",
+                &diagnostics,
+                &files,
+            ),
+            vec![
+                "src/lib.rs:2:1: error: mismatched types",
+                "src/lib.rs:1:5: warning: unused variable [rustc]",
+            ]
+        );
+    }
+}
@@ -1,26 +1,53 @@
 pub mod apply_patch;
+mod command_tool;
 mod grep_files;
 mod list_dir;
+mod lsp;
 mod mcp;
 mod mcp_resource;
 mod plan;
 mod read_file;
+mod remember;
 mod shell;
+mod spawn_agent;
 mod test_sync;
 mod unified_exec;
 mod view_image;
 
+use std::path::Path;
+
+use crate::codex::TurnContext;
+use crate::function_tool::FunctionCallError;
+
 pub use plan::PLAN_TOOL;
 
 pub use apply_patch::ApplyPatchHandler;
+pub use command_tool::CommandToolHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
+pub use lsp::LspHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
+pub use remember::RememberHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
+pub use spawn_agent::SpawnAgentHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
+
+/// Rejects reads the sandbox policy forbids. Tools that read files in-process
+/// bypass the platform sandbox, so they enforce `readable_roots` and
+/// `deny_read` here.
+fn ensure_readable(turn: &TurnContext, path: &Path) -> Result<(), FunctionCallError> {
+    if turn.sandbox_policy.is_path_readable(path, &turn.cwd) {
+        Ok(())
+    } else {
+        Err(FunctionCallError::RespondToModel(format!(
+            "reading `{}` is not allowed by the sandbox policy",
+            path.display()
+        )))
+    }
+}
@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct RememberHandler;
+
+#[derive(Deserialize)]
+struct RememberArgs {
+    note: String,
+}
+
+#[async_trait]
+impl ToolHandler for RememberHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "remember handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: RememberArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+        })?;
+        let note = args.note.trim();
+        if note.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "remember requires a non-empty note".to_string(),
+            ));
+        }
+
+        // The tool call and its arguments already carry the note in the
+        // current context; session state keeps it across compaction.
+        let pinned = session.pin_note(&turn.sub_id, note.to_string()).await;
+
+        Ok(ToolOutput::Function {
+            content: format!("Pinned note #{}", pinned.id),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -235,6 +235,8 @@ impl ShellHandler {
             &turn.exec_policy,
             &features,
             &exec_params.command,
+            &exec_params.cwd,
+            &exec_params.env,
             turn.approval_policy,
             &turn.sandbox_policy,
             SandboxPermissions::from(exec_params.with_escalated_permissions.unwrap_or(false)),
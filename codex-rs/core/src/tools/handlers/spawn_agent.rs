@@ -0,0 +1,281 @@
+use async_trait::async_trait;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::SubAgentSource;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::codex_delegate::run_codex_conversation_one_shot;
+use crate::features::Feature;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::SubAgentActivityEvent;
+use crate::protocol::SubAgentBeginEvent;
+use crate::protocol::SubAgentEndEvent;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+const SPAWN_AGENT_SOURCE: &str = "spawn_agent";
+
+pub struct SpawnAgentHandler;
+
+#[derive(Deserialize)]
+struct SpawnAgentArgs {
+    task: String,
+    #[serde(default)]
+    instructions: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    sandbox: Option<SandboxMode>,
+    #[serde(default)]
+    tools: Option<Vec<String>>,
+}
+
+#[async_trait]
+impl ToolHandler for SpawnAgentHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            call_id,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "spawn_agent handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SpawnAgentArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+        })?;
+        if args.task.trim().is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "spawn_agent requires a non-empty task".to_string(),
+            ));
+        }
+
+        let mut config = turn.client.config().as_ref().clone();
+        config.cwd = turn.cwd.clone();
+        config.approval_policy = turn.approval_policy;
+        config.sandbox_policy = sub_agent_sandbox_policy(&turn.sandbox_policy, args.sandbox);
+        if let Some(model) = args.model {
+            config.model = model;
+        }
+        if let Some(instructions) = args.instructions {
+            config.developer_instructions = Some(match config.developer_instructions.take() {
+                Some(existing) => format!("{existing}\n\n{instructions}"),
+                None => instructions,
+            });
+        }
+        config.enabled_tools = args.tools;
+        let model = config.model.clone();
+        let task = args.task.clone();
+        // Sub-agents cannot spawn further sub-agents.
+        config.features.disable(Feature::SpawnAgent);
+
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::SubAgentBegin(SubAgentBeginEvent {
+                    call_id: call_id.clone(),
+                    task: task.clone(),
+                    model: model.clone(),
+                }),
+            )
+            .await;
+
+        // The handler future is dropped when the turn is interrupted; make
+        // sure the sub-agent goes down with it.
+        let cancellation_token = CancellationToken::new();
+        let _cancel_on_drop = cancellation_token.clone().drop_guard();
+        let io = run_codex_conversation_one_shot(
+            config,
+            SubAgentSource::Other(SPAWN_AGENT_SOURCE.to_string()),
+            session.services.auth_manager.clone(),
+            session.services.models_manager.clone(),
+            vec![UserInput::Text { text: args.task }],
+            session.clone(),
+            turn.clone(),
+            cancellation_token,
+            None,
+        )
+        .await;
+        let io = match io {
+            Ok(io) => io,
+            Err(err) => {
+                session
+                    .send_event(
+                        turn.as_ref(),
+                        EventMsg::SubAgentEnd(SubAgentEndEvent {
+                            call_id,
+                            task,
+                            model,
+                            last_agent_message: None,
+                        }),
+                    )
+                    .await;
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "failed to start sub-agent: {err}"
+                )));
+            }
+        };
+
+        let mut completed = false;
+        let mut last_agent_message = None;
+        while let Ok(event) = io.next_event().await {
+            match event.msg {
+                EventMsg::TaskComplete(task_complete) => {
+                    completed = true;
+                    last_agent_message = task_complete.last_agent_message;
+                    break;
+                }
+                EventMsg::TurnAborted(_) => break,
+                msg if is_nested_event(&msg) => {
+                    session
+                        .send_event(
+                            turn.as_ref(),
+                            EventMsg::SubAgentActivity(SubAgentActivityEvent {
+                                call_id: call_id.clone(),
+                                msg: Box::new(msg),
+                            }),
+                        )
+                        .await;
+                }
+                _ => {}
+            }
+        }
+
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::SubAgentEnd(SubAgentEndEvent {
+                    call_id,
+                    task,
+                    model,
+                    last_agent_message: last_agent_message.clone(),
+                }),
+            )
+            .await;
+
+        if !completed {
+            return Err(FunctionCallError::RespondToModel(
+                "sub-agent was interrupted before it finished".to_string(),
+            ));
+        }
+
+        Ok(ToolOutput::Function {
+            content: last_agent_message
+                .unwrap_or_else(|| "sub-agent finished without a final message".to_string()),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Resolves the sandbox for a sub-agent. A sub-agent may ask for a tighter
+/// sandbox than its parent but never a looser one.
+fn sub_agent_sandbox_policy(
+    parent: &SandboxPolicy,
+    requested: Option<SandboxMode>,
+) -> SandboxPolicy {
+    let mut policy = match (requested, parent) {
+        (Some(SandboxMode::ReadOnly), _) => SandboxPolicy::new_read_only_policy(),
+        (Some(SandboxMode::WorkspaceWrite), SandboxPolicy::DangerFullAccess) => {
+            SandboxPolicy::new_workspace_write_policy()
+        }
+        _ => parent.clone(),
+    };
+    policy.inherit_read_restrictions(parent);
+    policy
+}
+
+/// Sub-agent events surfaced to clients. Streaming deltas, token counts and
+/// the sub-agent's own turn bookkeeping stay hidden.
+fn is_nested_event(msg: &EventMsg) -> bool {
+    matches!(
+        msg,
+        EventMsg::ExecCommandBegin(_)
+            | EventMsg::ExecCommandEnd(_)
+            | EventMsg::PatchApplyBegin(_)
+            | EventMsg::PatchApplyEnd(_)
+            | EventMsg::McpToolCallBegin(_)
+            | EventMsg::McpToolCallEnd(_)
+            | EventMsg::WebSearchEnd(_)
+            | EventMsg::ViewImageToolCall(_)
+            | EventMsg::PlanUpdate(_)
+            | EventMsg::Warning(_)
+            | EventMsg::BudgetWarning(_)
+            | EventMsg::Error(_)
+            | EventMsg::StreamError(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn sub_agent_sandbox_never_loosens_parent_policy() {
+        let workspace_write = SandboxPolicy::new_workspace_write_policy();
+        let read_only = SandboxPolicy::new_read_only_policy();
+
+        assert_eq!(
+            sub_agent_sandbox_policy(&read_only, Some(SandboxMode::WorkspaceWrite)),
+            read_only
+        );
+        assert_eq!(
+            sub_agent_sandbox_policy(&workspace_write, Some(SandboxMode::DangerFullAccess)),
+            workspace_write
+        );
+        assert_eq!(
+            sub_agent_sandbox_policy(&workspace_write, Some(SandboxMode::ReadOnly)),
+            read_only
+        );
+        assert_eq!(
+            sub_agent_sandbox_policy(
+                &SandboxPolicy::DangerFullAccess,
+                Some(SandboxMode::WorkspaceWrite)
+            ),
+            workspace_write
+        );
+        assert_eq!(
+            sub_agent_sandbox_policy(&workspace_write, None),
+            workspace_write
+        );
+    }
+
+    #[test]
+    fn sub_agent_sandbox_keeps_parent_read_restrictions() {
+        let mut parent = SandboxPolicy::new_workspace_write_policy();
+        parent.restrict_reads(&[], &[PathBuf::from("/home/user/.ssh")]);
+
+        assert_eq!(
+            sub_agent_sandbox_policy(&parent, Some(SandboxMode::ReadOnly)),
+            SandboxPolicy::ReadOnly {
+                readable_roots: Vec::new(),
+                deny_read: vec![PathBuf::from("/home/user/.ssh")],
+            }
+        );
+    }
+}
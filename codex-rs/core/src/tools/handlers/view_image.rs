@@ -8,6 +8,7 @@ use crate::protocol::ViewImageToolCallEvent;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_readable;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use codex_protocol::user_input::UserInput;
@@ -48,6 +49,7 @@ impl ToolHandler for ViewImageHandler {
         })?;
 
         let abs_path = turn.resolve_path(Some(args.path));
+        ensure_readable(&turn, &abs_path)?;
 
         let metadata = fs::metadata(&abs_path).await.map_err(|error| {
             FunctionCallError::RespondToModel(format!(
@@ -10,6 +10,7 @@ pub mod sandboxing;
 pub mod spec;
 
 use crate::exec::ExecToolCallOutput;
+use crate::exec::ResourceLimitKind;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
@@ -31,6 +32,7 @@ pub fn format_exec_output_for_model_structured(
     let ExecToolCallOutput {
         exit_code,
         duration,
+        resource_limit_exceeded,
         ..
     } = exec_output;
 
@@ -38,6 +40,8 @@ pub fn format_exec_output_for_model_structured(
     struct ExecMetadata {
         exit_code: i32,
         duration_seconds: f32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resource_limit_exceeded: Option<ResourceLimitKind>,
     }
 
     #[derive(Serialize)]
@@ -56,6 +60,7 @@ pub fn format_exec_output_for_model_structured(
         metadata: ExecMetadata {
             exit_code: *exit_code,
             duration_seconds,
+            resource_limit_exceeded: *resource_limit_exceeded,
         },
     };
 
@@ -78,6 +83,9 @@ pub fn format_exec_output_for_model_freeform(
 
     sections.push(format!("Exit code: {}", exec_output.exit_code));
     sections.push(format!("Wall time: {duration_seconds} seconds"));
+    if let Some(limit) = exec_output.resource_limit_exceeded {
+        sections.push(format!("Resource limit exceeded: {limit}"));
+    }
     if total_lines != formatted_output.lines().count() {
         sections.push(format!("Total output lines: {total_lines}"));
     }
@@ -103,6 +111,8 @@ pub fn format_exec_output_str(
             "command timed out after {} milliseconds\n{content}",
             exec_output.duration.as_millis()
         )
+    } else if let Some(limit) = exec_output.resource_limit_exceeded {
+        format!("command exceeded its {limit} limit\n{content}")
     } else {
         content.to_string()
     };
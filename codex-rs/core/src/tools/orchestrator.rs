@@ -9,6 +9,8 @@ use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
 use crate::exec::ExecToolCallOutput;
+use crate::hooks::HookEvent;
+use crate::hooks::HookOutcome;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ExecApprovalRequirement;
@@ -59,7 +61,12 @@ impl ToolOrchestrator {
         });
         match requirement {
             ExecApprovalRequirement::Skip { .. } => {
-                otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
+                otel.tool_decision(
+                    otel_tn,
+                    otel_ci,
+                    &ReviewDecision::Approved,
+                    otel_cfg.clone(),
+                );
             }
             ExecApprovalRequirement::Forbidden { reason } => {
                 return Err(ToolError::Rejected(reason));
@@ -79,16 +86,33 @@ impl ToolOrchestrator {
                         .await;
                 }
 
-                let approval_ctx = ApprovalCtx {
-                    session: tool_ctx.session,
-                    turn: turn_ctx,
-                    call_id: &tool_ctx.call_id,
-                    retry_reason: reason,
-                    risk,
-                };
-                let decision = tool.start_approval_async(req, approval_ctx).await;
-
-                otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
+                let decision =
+                    match run_approval_hook(req, tool_ctx, turn_ctx, reason.clone()).await {
+                        HookOutcome::Allow => {
+                            otel.tool_decision(
+                                otel_tn,
+                                otel_ci,
+                                &ReviewDecision::Approved,
+                                otel_cfg.clone(),
+                            );
+                            ReviewDecision::Approved
+                        }
+                        HookOutcome::Deny { reason } => {
+                            return Err(ToolError::Rejected(format!("rejected by hook: {reason}")));
+                        }
+                        HookOutcome::Continue | HookOutcome::Modify { .. } => {
+                            let approval_ctx = ApprovalCtx {
+                                session: tool_ctx.session,
+                                turn: turn_ctx,
+                                call_id: &tool_ctx.call_id,
+                                retry_reason: reason,
+                                risk,
+                            };
+                            let decision = tool.start_approval_async(req, approval_ctx).await;
+                            otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
+                            decision
+                        }
+                    };
 
                 match decision {
                     ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -118,6 +142,8 @@ impl ToolOrchestrator {
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            network_proxy: tool_ctx.session.network_proxy_addr(),
+            resource_limits: turn_ctx.client.config().resource_limits,
         };
 
         match tool.run(req, &initial_attempt, tool_ctx).await {
@@ -162,16 +188,37 @@ impl ToolOrchestrator {
                     }
 
                     let reason_msg = build_denial_reason_from_output(output.as_ref());
-                    let approval_ctx = ApprovalCtx {
-                        session: tool_ctx.session,
-                        turn: turn_ctx,
-                        call_id: &tool_ctx.call_id,
-                        retry_reason: Some(reason_msg),
-                        risk,
-                    };
-
-                    let decision = tool.start_approval_async(req, approval_ctx).await;
-                    otel.tool_decision(otel_tn, otel_ci, &decision, otel_user);
+                    let decision =
+                        match run_approval_hook(req, tool_ctx, turn_ctx, Some(reason_msg.clone()))
+                            .await
+                        {
+                            HookOutcome::Allow => {
+                                otel.tool_decision(
+                                    otel_tn,
+                                    otel_ci,
+                                    &ReviewDecision::Approved,
+                                    otel_cfg,
+                                );
+                                ReviewDecision::Approved
+                            }
+                            HookOutcome::Deny { reason } => {
+                                return Err(ToolError::Rejected(format!(
+                                    "rejected by hook: {reason}"
+                                )));
+                            }
+                            HookOutcome::Continue | HookOutcome::Modify { .. } => {
+                                let approval_ctx = ApprovalCtx {
+                                    session: tool_ctx.session,
+                                    turn: turn_ctx,
+                                    call_id: &tool_ctx.call_id,
+                                    retry_reason: Some(reason_msg),
+                                    risk,
+                                };
+                                let decision = tool.start_approval_async(req, approval_ctx).await;
+                                otel.tool_decision(otel_tn, otel_ci, &decision, otel_user);
+                                decision
+                            }
+                        };
 
                     match decision {
                         ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -189,6 +236,8 @@ impl ToolOrchestrator {
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
+                    network_proxy: None,
+                    resource_limits: turn_ctx.client.config().resource_limits,
                 };
 
                 // Second attempt.
@@ -199,6 +248,26 @@ impl ToolOrchestrator {
     }
 }
 
+/// Gives `approval_requested` hooks a chance to approve or deny on the user's
+/// behalf before the approval prompt is shown.
+async fn run_approval_hook<Rq: ProvidesSandboxRetryData>(
+    req: &Rq,
+    tool_ctx: &ToolCtx<'_>,
+    turn_ctx: &crate::codex::TurnContext,
+    reason: Option<String>,
+) -> HookOutcome {
+    let event = HookEvent::ApprovalRequested {
+        thread_id: tool_ctx.session.conversation_id().to_string(),
+        turn_id: turn_ctx.sub_id.clone(),
+        cwd: turn_ctx.cwd.display().to_string(),
+        call_id: tool_ctx.call_id.clone(),
+        tool_name: tool_ctx.tool_name.clone(),
+        command: req.sandbox_retry_data().map(|data| data.command),
+        reason,
+    };
+    tool_ctx.session.hooks().run(event).await
+}
+
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
@@ -4,6 +4,8 @@ use std::time::Duration;
 
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookEvent;
+use crate::hooks::HookOutcome;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -60,13 +62,61 @@ impl ToolRegistry {
 
     pub async fn dispatch(
         &self,
-        invocation: ToolInvocation,
+        mut invocation: ToolInvocation,
     ) -> Result<ResponseInputItem, FunctionCallError> {
         let tool_name = invocation.tool_name.clone();
         let call_id_owned = invocation.call_id.clone();
         let otel = invocation.turn.client.get_otel_event_manager();
+
+        if invocation.session.hooks().has_pre_tool_call(&tool_name) {
+            let event = HookEvent::PreToolCall {
+                thread_id: invocation.session.conversation_id().to_string(),
+                turn_id: invocation.turn.sub_id.clone(),
+                cwd: invocation.turn.cwd.display().to_string(),
+                call_id: call_id_owned.clone(),
+                tool_name: tool_name.clone(),
+                arguments: invocation.payload.hook_arguments(),
+            };
+            match invocation.session.hooks().run(event).await {
+                HookOutcome::Deny { reason } => {
+                    let message = format!("tool call blocked by hook: {reason}");
+                    otel.tool_result(
+                        tool_name.as_ref(),
+                        &call_id_owned,
+                        invocation.payload.log_payload().as_ref(),
+                        Duration::ZERO,
+                        false,
+                        &message,
+                    );
+                    return Err(FunctionCallError::RespondToModel(message));
+                }
+                HookOutcome::Modify { arguments } => {
+                    invocation.payload = invocation
+                        .payload
+                        .with_hook_arguments(arguments)
+                        .map_err(|err| {
+                            FunctionCallError::RespondToModel(format!(
+                                "hook returned invalid arguments for {tool_name}: {err}"
+                            ))
+                        })?;
+                }
+                HookOutcome::Allow | HookOutcome::Continue => {}
+            }
+        }
+
         let payload_for_response = invocation.payload.clone();
         let log_payload = payload_for_response.log_payload();
+        let post_hook_ctx = invocation
+            .session
+            .hooks()
+            .has_post_tool_call(&tool_name)
+            .then(|| {
+                (
+                    Arc::clone(&invocation.session),
+                    invocation.turn.sub_id.clone(),
+                    invocation.turn.cwd.display().to_string(),
+                )
+            });
 
         let handler = match self.handler(tool_name.as_ref()) {
             Some(handler) => handler,
@@ -98,6 +148,8 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        invocation.session.budget().lock().await.record_tool_call();
+
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -130,16 +182,34 @@ impl ToolRegistry {
             )
             .await;
 
-        match result {
+        let result = match result {
             Ok(_) => {
                 let mut guard = output_cell.lock().await;
-                let output = guard.take().ok_or_else(|| {
-                    FunctionCallError::Fatal("tool produced no output".to_string())
-                })?;
-                Ok(output.into_response(&call_id_owned, &payload_for_response))
+                guard
+                    .take()
+                    .ok_or_else(|| FunctionCallError::Fatal("tool produced no output".to_string()))
             }
             Err(err) => Err(err),
+        };
+
+        if let Some((session, turn_id, cwd)) = post_hook_ctx {
+            let (success, output) = match &result {
+                Ok(output) => (output.success_for_logging(), output.hook_text()),
+                Err(err) => (false, err.to_string()),
+            };
+            let event = HookEvent::PostToolCall {
+                thread_id: session.conversation_id().to_string(),
+                turn_id,
+                cwd,
+                call_id: call_id_owned.clone(),
+                tool_name,
+                success,
+                output,
+            };
+            session.hooks().run(event).await;
         }
+
+        result.map(|output| output.into_response(&call_id_owned, &payload_for_response))
     }
 }
 
@@ -195,6 +265,10 @@ impl ToolRegistryBuilder {
         }
     }
 
+    pub fn has_handler(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register_many<I>(&mut self, names: I, handler: Arc<dyn ToolHandler>)
     // where
@@ -213,6 +287,12 @@ impl ToolRegistryBuilder {
     //     }
     // }
 
+    /// Drops every spec and handler whose tool name does not satisfy `keep`.
+    pub fn retain_tools(&mut self, keep: impl Fn(&str) -> bool) {
+        self.specs.retain(|configured| keep(configured.spec.name()));
+        self.handlers.retain(|name, _| keep(name));
+    }
+
     pub fn build(self) -> (Vec<ConfiguredToolSpec>, ToolRegistry) {
         let registry = ToolRegistry::new(self.handlers);
         (self.specs, registry)
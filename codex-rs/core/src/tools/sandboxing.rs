@@ -6,6 +6,7 @@
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::ResourceLimits;
 use crate::error::CodexErr;
 use crate::protocol::SandboxCommandAssessment;
 use crate::protocol::SandboxPolicy;
@@ -243,6 +244,8 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    pub(crate) network_proxy: Option<std::net::SocketAddr>,
+    pub(crate) resource_limits: ResourceLimits,
 }
 
 impl<'a> SandboxAttempt<'a> {
@@ -256,6 +259,8 @@ impl<'a> SandboxAttempt<'a> {
             self.sandbox,
             self.sandbox_cwd,
             self.codex_linux_sandbox_exe,
+            self.network_proxy,
+            self.resource_limits,
         )
     }
 }
@@ -1,5 +1,6 @@
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::config::types::CommandToolConfig;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::openai_models::model_family::ModelFamily;
@@ -22,7 +23,13 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_spawn_agent_tool: bool,
+    pub include_remember_tool: bool,
+    pub include_lsp_tool: bool,
     pub experimental_supported_tools: Vec<String>,
+    /// When set, only tools with these names are exposed to the model.
+    pub enabled_tools: Option<Vec<String>>,
+    pub command_tools: BTreeMap<String, CommandToolConfig>,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -39,6 +46,8 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_spawn_agent_tool = features.enabled(Feature::SpawnAgent);
+        let include_remember_tool = features.enabled(Feature::RememberTool);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -65,7 +74,12 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_spawn_agent_tool,
+            include_remember_tool,
+            include_lsp_tool: false,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            enabled_tools: None,
+            command_tools: BTreeMap::new(),
         }
     }
 }
@@ -411,6 +425,92 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
+fn create_spawn_agent_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "task".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Self-contained description of the task. The sub-agent does not see this conversation."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "instructions".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional extra instructions for the sub-agent, e.g. constraints on its final answer."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "model".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional model for the sub-agent; defaults to the current model.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "sandbox".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional sandbox for the sub-agent: `read-only` or `workspace-write`. It can never be less restrictive than the current sandbox."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "tools".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some(
+                "Optional names of the tools the sub-agent may use; defaults to the current tools."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "spawn_agent".to_string(),
+        description: "Delegate a bounded task to a sub-agent that runs in its own context and returns only its final message. Use it for self-contained investigations whose intermediate output you do not need; several sub-agents can run in parallel."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["task".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_remember_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "note".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Self-contained fact, decision or constraint to keep, written as it should be read later."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "remember".to_string(),
+        description: "Pin a note to this session. Pinned notes are re-inserted verbatim after the conversation is compacted, so use this for facts and constraints that must not be lost or paraphrased."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["note".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -523,6 +623,62 @@ fn create_grep_files_tool() -> ToolSpec {
     })
 }
 
+fn create_lsp_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "operation".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "One of `goto_definition`, `find_references`, `hover`, `document_symbols` or \
+                 `diagnostics`."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "File to query, absolute or relative to the working directory.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "line".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "1-indexed line of the symbol. Required for `goto_definition`, \
+                 `find_references` and `hover`."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "column".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "1-indexed column (in characters) of the symbol. Required for \
+                 `goto_definition`, `find_references` and `hover`."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "lsp".to_string(),
+        description: "Queries the language server for a file: jump to a symbol's definition, \
+                      list its references, show hover documentation, outline the file's symbols \
+                      or report compiler diagnostics for the file as it is on disk."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["operation".to_string(), "path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_read_file_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -853,6 +1009,30 @@ pub(crate) fn mcp_tool_to_openai_tool(
     })
 }
 
+pub(crate) fn command_tool_to_openai_tool(
+    name: String,
+    tool: &CommandToolConfig,
+) -> Result<ResponsesApiTool, serde_json::Error> {
+    let mut parameters = tool
+        .parameters
+        .clone()
+        .unwrap_or_else(|| json!({ "type": "object", "properties": {} }));
+    sanitize_json_schema(&mut parameters);
+    let parameters = serde_json::from_value::<JsonSchema>(parameters)?;
+    if !matches!(parameters, JsonSchema::Object { .. }) {
+        return Err(serde::de::Error::custom(
+            "command tool parameters must describe an object",
+        ));
+    }
+
+    Ok(ResponsesApiTool {
+        name,
+        description: tool.description.clone(),
+        strict: false,
+        parameters,
+    })
+}
+
 /// Sanitize a JSON Schema (as serde_json::Value) so it can fit our limited
 /// JsonSchema enum. This function:
 /// - Ensures every schema object has a "type". If missing, infers it from
@@ -970,14 +1150,18 @@ pub(crate) fn build_specs(
     mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
 ) -> ToolRegistryBuilder {
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::CommandToolHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
+    use crate::tools::handlers::LspHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::RememberHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SpawnAgentHandler;
     use crate::tools::handlers::TestSyncHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
@@ -1091,6 +1275,37 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.include_spawn_agent_tool {
+        builder.push_spec_with_parallel_support(create_spawn_agent_tool(), true);
+        builder.register_handler("spawn_agent", Arc::new(SpawnAgentHandler));
+    }
+
+    if config.include_remember_tool {
+        builder.push_spec(create_remember_tool());
+        builder.register_handler("remember", Arc::new(RememberHandler));
+    }
+
+    if config.include_lsp_tool {
+        builder.push_spec_with_parallel_support(create_lsp_tool(), true);
+        builder.register_handler("lsp", Arc::new(LspHandler));
+    }
+
+    for (name, tool) in &config.command_tools {
+        if builder.has_handler(name) {
+            tracing::warn!("ignoring command tool {name:?}: a built-in tool has the same name");
+            continue;
+        }
+        match command_tool_to_openai_tool(name.clone(), tool) {
+            Ok(converted_tool) => {
+                builder.push_spec(ToolSpec::Function(converted_tool));
+                builder.register_handler(name, Arc::new(CommandToolHandler::new(tool.clone())));
+            }
+            Err(e) => {
+                tracing::error!("Failed to convert {name:?} command tool to OpenAI tool: {e:?}");
+            }
+        }
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -1108,6 +1323,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    if let Some(enabled_tools) = &config.enabled_tools {
+        builder.retain_tools(|name| enabled_tools.iter().any(|tool| tool == name));
+    }
+
     builder
 }
 
@@ -1912,6 +2131,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_specs_command_tools_converted() {
+        let model_family = find_family_for_model("gpt-5-codex");
+        let features = Features::with_defaults();
+        let mut config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        config.command_tools = BTreeMap::from([
+            (
+                "lint".to_string(),
+                CommandToolConfig {
+                    description: "Run the linter".to_string(),
+                    parameters: Some(serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "target": { "type": "string", "description": "make target" }
+                        },
+                        "required": ["target"]
+                    })),
+                    command: vec!["make".to_string(), "lint".to_string()],
+                    timeout_ms: None,
+                },
+            ),
+            (
+                "update_plan".to_string(),
+                CommandToolConfig {
+                    description: "Shadows a built-in tool".to_string(),
+                    parameters: None,
+                    command: vec!["true".to_string()],
+                    timeout_ms: None,
+                },
+            ),
+        ]);
+
+        let (tools, _) = build_specs(&config, None).build();
+
+        let tool = find_tool(&tools, "lint");
+        assert_eq!(
+            tool.spec,
+            ToolSpec::Function(ResponsesApiTool {
+                name: "lint".to_string(),
+                parameters: JsonSchema::Object {
+                    properties: BTreeMap::from([(
+                        "target".to_string(),
+                        JsonSchema::String {
+                            description: Some("make target".to_string())
+                        }
+                    )]),
+                    required: Some(vec!["target".to_string()]),
+                    additional_properties: None,
+                },
+                description: "Run the linter".to_string(),
+                strict: false,
+            })
+        );
+        // Command tools never replace built-in tools.
+        assert_eq!(find_tool(&tools, "update_plan").spec, PLAN_TOOL.clone());
+    }
+
+    #[test]
+    fn test_command_tool_parameters_must_be_object() {
+        let tool = CommandToolConfig {
+            description: String::new(),
+            parameters: Some(serde_json::json!({ "type": "string" })),
+            command: vec!["true".to_string()],
+            timeout_ms: None,
+        };
+
+        assert!(command_tool_to_openai_tool("bad".to_string(), &tool).is_err());
+    }
+
     #[test]
     fn test_shell_tool() {
         let tool = super::create_shell_tool();
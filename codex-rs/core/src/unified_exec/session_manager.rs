@@ -326,6 +326,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(response.output.clone()),
             duration: response.wall_time,
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         interaction_emitter
             .emit(
@@ -467,6 +468,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let event_ctx = ToolEventCtx::new(
             entry.session_ref.as_ref(),
@@ -502,6 +504,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let event_ctx = ToolEventCtx::new(
             context.session.as_ref(),
@@ -577,6 +580,8 @@ impl UnifiedExecSessionManager {
             &context.turn.exec_policy,
             &features,
             command,
+            &cwd,
+            &env,
             context.turn.approval_policy,
             &context.turn.sandbox_policy,
             SandboxPermissions::from(with_escalated_permissions.unwrap_or(false)),
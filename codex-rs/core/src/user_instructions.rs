@@ -23,6 +23,22 @@ impl UserInstructions {
             false
         }
     }
+
+    /// Recovers the directory and instructions from a message produced by the
+    /// `From<UserInstructions>` conversion below.
+    pub(crate) fn from_message(message: &[ContentItem]) -> Option<Self> {
+        let [ContentItem::InputText { text }] = message else {
+            return None;
+        };
+        let (directory, rest) = text
+            .strip_prefix(USER_INSTRUCTIONS_PREFIX)?
+            .split_once("\n\n<INSTRUCTIONS>\n")?;
+        let text = rest.strip_suffix("\n</INSTRUCTIONS>")?;
+        Some(Self {
+            directory: directory.to_string(),
+            text: text.to_string(),
+        })
+    }
 }
 
 impl From<UserInstructions> for ResponseItem {
@@ -115,4 +131,27 @@ mod tests {
             }
         ]));
     }
+
+    #[test]
+    fn test_user_instructions_from_message() {
+        let user_instructions = UserInstructions {
+            directory: "/repo/packages/web".to_string(),
+            text: "Use pnpm.\n\nRun `pnpm lint` before committing.".to_string(),
+        };
+        let ResponseItem::Message { content, .. } = ResponseItem::from(user_instructions.clone())
+        else {
+            panic!("expected ResponseItem::Message");
+        };
+
+        assert_eq!(
+            UserInstructions::from_message(&content),
+            Some(user_instructions)
+        );
+        assert_eq!(
+            UserInstructions::from_message(&[ContentItem::InputText {
+                text: "<user_instructions>test_text</user_instructions>".to_string(),
+            }]),
+            None
+        );
+    }
 }
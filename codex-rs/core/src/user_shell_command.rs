@@ -89,6 +89,7 @@ mod tests {
             aggregated_output: StreamOutput::new("hi".to_string()),
             duration: Duration::from_secs(1),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let (_, turn_context) = make_session_and_context();
         let item = user_shell_command_record_item("echo hi", &exec_output, &turn_context);
@@ -113,6 +114,7 @@ mod tests {
             aggregated_output: StreamOutput::new("combined output wins".to_string()),
             duration: Duration::from_millis(120),
             timed_out: false,
+            resource_limit_exceeded: None,
         };
         let (_, turn_context) = make_session_and_context();
         let record = format_user_shell_command_record("false", &exec_output, &turn_context);
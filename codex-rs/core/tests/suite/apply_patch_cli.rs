@@ -570,6 +570,8 @@ async fn apply_patch_cli_rejects_path_traversal_outside_workspace(
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
     harness
         .submit_with_policy(
@@ -626,6 +628,8 @@ async fn apply_patch_cli_rejects_move_path_traversal_outside_workspace(
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
     harness
         .submit_with_policy("attempt move traversal via apply_patch", sandbox_policy)
@@ -0,0 +1,147 @@
+#![cfg(not(target_os = "windows"))]
+
+use anyhow::Result;
+use codex_core::config::types::BudgetLimits;
+use codex_core::features::Feature;
+use codex_core::protocol::BudgetLimit;
+use codex_core::protocol::BudgetScope;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::TurnAbortReason;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exhausted_turn_budget_aborts_before_next_request() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let args = json!({ "command": "echo budget" }).to_string();
+    let response_mock = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call("call-1", "shell_command", &args),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let codex = test_codex()
+        .with_config(|config| {
+            config.budget.turn = BudgetLimits {
+                max_tool_calls: Some(1),
+                ..Default::default()
+            };
+        })
+        .build(&server)
+        .await?
+        .codex;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "run a command".into(),
+            }],
+        })
+        .await?;
+
+    let warning = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::BudgetWarning(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        (warning.scope, warning.limit, warning.exceeded),
+        (BudgetScope::Turn, BudgetLimit::ToolCalls, true)
+    );
+
+    let aborted = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnAborted(_))).await;
+    let EventMsg::TurnAborted(aborted) = aborted else {
+        unreachable!("wait_for_event returned an unexpected event");
+    };
+    assert_eq!(aborted.reason, TurnAbortReason::BudgetExceeded);
+    // The tool call used up the budget, so its output is never sent back.
+    response_mock.single_request();
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sub_agent_usage_counts_against_parent_budget() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let args = json!({ "task": "summarize the repo" }).to_string();
+    let response_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("call-1", "spawn_agent", &args),
+                ev_completed_with_tokens("resp-1", 100),
+            ]),
+            // The sub-agent alone uses up the parent's session budget.
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "a small repo"),
+                ev_completed_with_tokens("resp-2", 5_000),
+            ]),
+        ],
+    )
+    .await;
+
+    let codex = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::SpawnAgent);
+            config.budget.session = BudgetLimits {
+                max_tokens: Some(1_000),
+                ..Default::default()
+            };
+        })
+        .build(&server)
+        .await?
+        .codex;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "delegate the summary".into(),
+            }],
+        })
+        .await?;
+
+    let warning = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::BudgetWarning(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        (warning.scope, warning.limit, warning.exceeded),
+        (BudgetScope::Session, BudgetLimit::Tokens, true)
+    );
+
+    let aborted = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnAborted(_))).await;
+    let EventMsg::TurnAborted(aborted) = aborted else {
+        unreachable!("wait_for_event returned an unexpected event");
+    };
+    assert_eq!(aborted.reason, TurnAbortReason::BudgetExceeded);
+    // The parent never sends the sub-agent's answer back to the model.
+    assert_eq!(response_mock.requests().len(), 2);
+
+    Ok(())
+}
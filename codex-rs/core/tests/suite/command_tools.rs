@@ -0,0 +1,135 @@
+#![cfg(not(target_os = "windows"))]
+
+use anyhow::Result;
+use codex_core::config::types::CommandToolConfig;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodexHarness;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+fn command_tool_responses(call_id: &str, tool_name: &str, arguments: Value) -> Vec<String> {
+    vec![
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(call_id, tool_name, &arguments.to_string()),
+            ev_completed("resp-1"),
+        ]),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    ]
+}
+
+fn greet_tool(command: &[&str]) -> CommandToolConfig {
+    CommandToolConfig {
+        description: "Greets someone".to_string(),
+        parameters: Some(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" }
+            },
+            "required": ["name"]
+        })),
+        command: command.iter().map(ToString::to_string).collect(),
+        timeout_ms: Some(2_000),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn command_tool_is_advertised_and_returns_stdout() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_config(|config| {
+        config
+            .command_tools
+            .insert("greet".to_string(), greet_tool(&["echo", "hello {name}"]));
+    })
+    .await?;
+
+    let call_id = "command-tool-greet";
+    mount_sse_sequence(
+        harness.server(),
+        command_tool_responses(call_id, "greet", json!({ "name": "world" })),
+    )
+    .await;
+    harness.submit("greet the world").await?;
+
+    let bodies = harness.request_bodies().await;
+    let tools = bodies[0]["tools"].as_array().expect("tools array");
+    let greet = tools
+        .iter()
+        .find(|tool| tool["name"] == json!("greet"))
+        .expect("greet tool advertised");
+    assert_eq!(greet["type"], json!("function"));
+    assert_eq!(greet["description"], json!("Greets someone"));
+
+    let output = harness.function_call_stdout(call_id).await;
+    assert_eq!(output, "hello world\n");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn command_tool_failure_reports_exit_code() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_config(|config| {
+        config.command_tools.insert(
+            "greet".to_string(),
+            greet_tool(&["sh", "-c", "echo no {name} >&2; exit 3"]),
+        );
+    })
+    .await?;
+
+    let call_id = "command-tool-fail";
+    mount_sse_sequence(
+        harness.server(),
+        command_tool_responses(call_id, "greet", json!({ "name": "thanks" })),
+    )
+    .await;
+    harness.submit("greet badly").await?;
+
+    let output = harness.function_call_stdout(call_id).await;
+    assert!(
+        output.contains("Exit code: 3"),
+        "unexpected output: {output}"
+    );
+    assert!(output.contains("no thanks"), "unexpected output: {output}");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn command_tool_does_not_splice_arguments_into_shell_scripts() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_config(|config| {
+        config.command_tools.insert(
+            "greet".to_string(),
+            greet_tool(&["sh", "-c", "echo hello {name}"]),
+        );
+    })
+    .await?;
+
+    let call_id = "command-tool-injection";
+    let payload = "x; echo injected $(echo subst) | cat";
+    mount_sse_sequence(
+        harness.server(),
+        command_tool_responses(call_id, "greet", json!({ "name": payload })),
+    )
+    .await;
+    harness.submit("greet with a payload").await?;
+
+    let output = harness.function_call_stdout(call_id).await;
+    assert_eq!(output, format!("hello {payload}\n"));
+
+    Ok(())
+}
@@ -13,6 +13,7 @@ use codex_core::protocol::Op;
 use codex_core::protocol::RolloutItem;
 use codex_core::protocol::RolloutLine;
 use codex_core::protocol::WarningEvent;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::user_input::UserInput;
 use core_test_support::load_default_config_for_test;
 use core_test_support::responses::ev_local_shell_call;
@@ -155,7 +156,7 @@ async fn summarize_context_three_requests_and_instructions() {
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     // 2) Summarize – second hit should include the summarization prompt.
-    codex.submit(Op::Compact).await.unwrap();
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
     let warning_event = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Warning(_))).await;
     let EventMsg::Warning(WarningEvent { message }) = warning_event else {
         panic!("expected warning event after compact");
@@ -336,7 +337,10 @@ async fn manual_compact_uses_custom_prompt() {
         .expect("create conversation")
         .conversation;
 
-    codex.submit(Op::Compact).await.expect("trigger compact");
+    codex
+        .submit(Op::Compact { strategy: None })
+        .await
+        .expect("trigger compact");
     let warning_event = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Warning(_))).await;
     let EventMsg::Warning(WarningEvent { message }) = warning_event else {
         panic!("expected warning event after compact");
@@ -416,7 +420,7 @@ async fn manual_compact_emits_api_and_local_token_usage_events() {
     } = conversation_manager.new_conversation(config).await.unwrap();
 
     // Trigger manual compact and collect TokenCount events for the compact turn.
-    codex.submit(Op::Compact).await.unwrap();
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
 
     // First TokenCount: from the compact API call (usage.total_tokens = 0).
     let first = wait_for_event_match(&codex, |ev| match ev {
@@ -1413,7 +1417,7 @@ async fn manual_compact_retries_after_context_window_error() {
         .unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    codex.submit(Op::Compact).await.unwrap();
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
     let EventMsg::BackgroundEvent(event) =
         wait_for_event(&codex, |ev| matches!(ev, EventMsg::BackgroundEvent(_))).await
     else {
@@ -1545,7 +1549,7 @@ async fn manual_compact_twice_preserves_latest_user_messages() {
         .unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    codex.submit(Op::Compact).await.unwrap();
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     codex
@@ -1558,7 +1562,7 @@ async fn manual_compact_twice_preserves_latest_user_messages() {
         .unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    codex.submit(Op::Compact).await.unwrap();
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     codex
@@ -1686,6 +1690,317 @@ async fn manual_compact_twice_preserves_latest_user_messages() {
     assert_eq!(final_output, expected);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pinned_context_survives_manual_compact() {
+    skip_if_no_network!();
+
+    let user_pin = "Never edit files under migrations/.";
+    let model_pin = "The release branch is release/2.4.";
+    let pinned_block = format!(
+        "<pinned_context>\nThese notes were pinned earlier in this session and still apply:\n\n<note>\n{user_pin}\n</note>\n\n<note>\n{model_pin}\n</note>\n</pinned_context>"
+    );
+
+    let server = start_mock_server().await;
+    let remember_args = json!({ "note": model_pin }).to_string();
+    let responses_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_function_call("call-remember", "remember", &remember_args),
+                ev_completed("r1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m1", FIRST_REPLY),
+                ev_completed("r2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m2", SUMMARY_TEXT),
+                ev_completed("r3"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m3", FINAL_REPLY),
+                ev_completed("r4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.features.enable(Feature::RememberTool);
+    set_test_compact_prompt(&mut config);
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::PinContext {
+            text: user_pin.to_string(),
+        })
+        .await
+        .unwrap();
+    wait_for_event(
+        &codex,
+        |ev| matches!(ev, EventMsg::PinnedContextUpdated(event) if event.notes.len() == 1),
+    )
+    .await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "remember the release branch".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    let EventMsg::PinnedContextUpdated(updated) =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::PinnedContextUpdated(_))).await
+    else {
+        unreachable!();
+    };
+    assert_eq!(
+        updated
+            .notes
+            .iter()
+            .map(|note| (note.id, note.text.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(1, user_pin), (2, model_pin)]
+    );
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex.submit(Op::Compact { strategy: None }).await.unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: POST_AUTO_USER_MSG.into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses_mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(
+        requests[0]
+            .message_input_texts("user")
+            .iter()
+            .any(|text| text.contains(user_pin)),
+        "a user pin should reach the model before any compaction"
+    );
+
+    let final_texts = requests[3].message_input_texts("user");
+    let pinned: Vec<&String> = final_texts
+        .iter()
+        .filter(|text| text.starts_with("<pinned_context>"))
+        .collect();
+    assert_eq!(pinned, vec![&pinned_block]);
+    assert_eq!(
+        final_texts.last().map(String::as_str),
+        Some(POST_AUTO_USER_MSG)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn heuristic_manual_compact_summarizes_without_calling_the_model() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let responses_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_assistant_message("m1", FIRST_REPLY),
+                ev_completed("r1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("m2", FINAL_REPLY),
+                ev_completed("r2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.compaction.keep_recent_turns = 0;
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello world".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::Compact {
+            strategy: Some(CompactionStrategy::Heuristic),
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::ContextCompacted(_))).await;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: POST_AUTO_USER_MSG.into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = responses_mock.requests();
+    assert_eq!(requests.len(), 2, "compaction must not call the model");
+
+    let final_texts = requests[1].message_input_texts("user");
+    let summaries: Vec<&String> = final_texts
+        .iter()
+        .filter(|text| text.starts_with(SUMMARY_PREFIX))
+        .collect();
+    assert_eq!(summaries.len(), 1);
+    assert!(summaries[0].contains("User requests:\n- hello world\n"));
+    assert!(summaries[0].contains(&format!("Last assistant message:\n{FIRST_REPLY}\n")));
+    assert!(!final_texts.iter().any(|text| text == "hello world"));
+    assert_eq!(
+        final_texts.last().map(String::as_str),
+        Some(POST_AUTO_USER_MSG)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn heuristic_auto_compact_elides_tool_outputs_of_a_single_huge_turn() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let call_id = "call-huge-output";
+    let args = json!({ "command": "seq 1 2000" }).to_string();
+    let responses_mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_function_call(call_id, "shell_command", &args),
+                ev_completed_with_tokens("r1", 50),
+            ]),
+            sse(vec![
+                ev_assistant_message("m2", FIRST_REPLY),
+                ev_completed_with_tokens("r2", 500),
+            ]),
+            sse(vec![
+                ev_assistant_message("m3", FINAL_REPLY),
+                ev_completed_with_tokens("r3", 50),
+            ]),
+        ],
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.model_auto_compact_token_limit = Some(200);
+    config.compaction.strategy = CompactionStrategy::Heuristic;
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "print a lot of numbers".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::ContextCompacted(_))).await;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // The only turn is kept, but its tool output is elided so compaction
+    // still makes progress.
+    let requests = responses_mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(
+        requests[1]
+            .function_call_output_text(call_id)
+            .is_some_and(|output| output.contains("2000"))
+    );
+    assert_eq!(
+        requests[2].function_call_output_text(call_id).as_deref(),
+        Some("[output elided during compaction]")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn auto_compact_stops_when_history_cannot_shrink() {
+    skip_if_no_network!();
+
+    let server = start_mock_server().await;
+    let responses_mock = mount_sse_sequence(
+        &server,
+        vec![sse(vec![
+            ev_assistant_message("m1", FIRST_REPLY),
+            ev_completed_with_tokens("r1", 500),
+        ])],
+    )
+    .await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.model_auto_compact_token_limit = Some(200);
+    config.compaction.strategy = CompactionStrategy::Heuristic;
+    let codex = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"))
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello world".into(),
+            }],
+        })
+        .await
+        .unwrap();
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    assert_eq!(responses_mock.requests().len(), 1);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn auto_compact_allows_multiple_attempts_when_interleaved_with_other_turn_events() {
     skip_if_no_network!();
@@ -73,7 +73,7 @@ async fn remote_compact_replaces_history_for_followups() -> Result<()> {
         .await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    codex.submit(Op::Compact).await?;
+    codex.submit(Op::Compact { strategy: None }).await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     codex
@@ -249,7 +249,7 @@ async fn remote_compact_persists_replacement_history_in_rollout() -> Result<()>
         .await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
-    codex.submit(Op::Compact).await?;
+    codex.submit(Op::Compact { strategy: None }).await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     codex.submit(Op::Shutdown).await?;
@@ -0,0 +1,101 @@
+#![cfg(not(target_os = "windows"))]
+
+use anyhow::Result;
+use codex_core::config::types::HookConfig;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodexHarness;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn shell_command_responses(call_id: &str, command: &str) -> Vec<String> {
+    let arguments = json!({ "command": command, "timeout_ms": 2_000 }).to_string();
+    vec![
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_function_call(call_id, "shell_command", &arguments),
+            ev_completed("resp-1"),
+        ]),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    ]
+}
+
+fn sh_hook(script: &str) -> HookConfig {
+    HookConfig {
+        command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+        tools: Some(vec!["shell_command".to_string()]),
+        timeout_ms: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pre_tool_call_hook_can_deny_tool_call() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_config(|config| {
+        config.hooks.pre_tool_call = vec![sh_hook(
+            r#"cat > /dev/null; echo '{"decision":"deny","reason":"no shell today"}'"#,
+        )];
+    })
+    .await?;
+
+    let call_id = "hook-deny";
+    let marker = harness.path("marker.txt");
+    mount_sse_sequence(
+        harness.server(),
+        shell_command_responses(call_id, &format!("touch {}", marker.display())),
+    )
+    .await;
+    harness.submit("touch the marker").await?;
+
+    let output = harness.function_call_stdout(call_id).await;
+    assert_eq!(output, "tool call blocked by hook: no shell today");
+    assert!(!marker.exists());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tool_call_hooks_receive_payload_and_can_modify_arguments() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let log_dir = tempfile::TempDir::new()?;
+    let log_path = log_dir.path().join("post.json");
+    let post_hook = sh_hook(&format!("cat > {}", log_path.display()));
+
+    let harness = TestCodexHarness::with_config(move |config| {
+        config.hooks.pre_tool_call = vec![sh_hook(
+            r#"cat > /dev/null; echo '{"decision":"modify","arguments":{"command":"echo rewritten"}}'"#,
+        )];
+        config.hooks.post_tool_call = vec![post_hook];
+    })
+    .await?;
+
+    let call_id = "hook-modify";
+    mount_sse_sequence(
+        harness.server(),
+        shell_command_responses(call_id, "echo original"),
+    )
+    .await;
+    harness.submit("echo something").await?;
+
+    let output = harness.function_call_stdout(call_id).await;
+    assert!(output.contains("rewritten"), "unexpected output: {output}");
+    assert!(!output.contains("original"), "unexpected output: {output}");
+
+    let payload: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&log_path)?)?;
+    assert_eq!(payload["type"], json!("post-tool-call"));
+    assert_eq!(payload["call-id"], json!(call_id));
+    assert_eq!(payload["tool-name"], json!("shell_command"));
+    assert_eq!(payload["success"], json!(true));
+
+    Ok(())
+}
@@ -19,9 +19,11 @@ mod apply_patch_cli;
 #[cfg(not(target_os = "windows"))]
 mod approvals;
 mod auth_refresh;
+mod budget;
 mod cli_stream;
 mod client;
 mod codex_delegate;
+mod command_tools;
 mod compact;
 mod compact_remote;
 mod compact_resume_fork;
@@ -30,6 +32,7 @@ mod exec;
 mod exec_policy;
 mod fork_conversation;
 mod grep_files;
+mod hooks;
 mod items;
 mod json_result;
 mod list_dir;
@@ -37,6 +40,7 @@ mod list_models;
 mod live_cli;
 mod model_overrides;
 mod model_tools;
+mod nested_project_docs;
 mod otel;
 mod prompt_caching;
 mod quota_exceeded;
@@ -48,6 +52,7 @@ mod rollout_list_find;
 mod seatbelt;
 mod shell_command;
 mod shell_serialization;
+mod spawn_agent;
 mod stream_error_allows_next_turn;
 mod stream_no_completed;
 mod text_encoding_fix;
@@ -0,0 +1,59 @@
+#![cfg(not(target_os = "windows"))]
+
+use codex_core::features::Feature;
+use core_test_support::responses::mount_function_call_agent_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn docs_of_touched_subdirectories_are_added_before_the_next_request() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex()
+        .with_config(|config| {
+            config.features.enable(Feature::NestedProjectDocs);
+        })
+        .build(&server)
+        .await?;
+
+    let cwd = std::fs::canonicalize(test.cwd.path())?;
+    let package = cwd.join("packages/web");
+    std::fs::create_dir_all(package.join("src"))?;
+    std::fs::write(package.join("AGENTS.md"), "Use pnpm, never npm.")?;
+    std::fs::write(package.join("src/index.ts"), "export {};\n")?;
+
+    let call_id = "read-web-index";
+    let arguments = json!({
+        "command": ["cat", "packages/web/src/index.ts"],
+        "workdir": cwd,
+    })
+    .to_string();
+    let mocks = mount_function_call_agent_response(&server, call_id, &arguments, "shell").await;
+
+    test.submit_turn("what does the web package export?")
+        .await?;
+
+    let expected = format!(
+        "# AGENTS.md instructions for {}\n\n<INSTRUCTIONS>\nUse pnpm, never npm.\n</INSTRUCTIONS>",
+        package.display()
+    );
+    let first_request = mocks.function_call.single_request();
+    assert!(
+        !first_request
+            .message_input_texts("user")
+            .contains(&expected)
+    );
+    let follow_up = mocks.completion.single_request();
+    let docs: Vec<String> = follow_up
+        .message_input_texts("user")
+        .into_iter()
+        .filter(|text| text.contains("Use pnpm"))
+        .collect();
+    assert_eq!(docs, vec![expected]);
+
+    Ok(())
+}
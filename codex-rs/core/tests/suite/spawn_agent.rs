@@ -0,0 +1,87 @@
+#![cfg(not(target_os = "windows"))]
+
+use anyhow::Result;
+use codex_core::features::Feature;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodexHarness;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+fn tool_names(body: &Value) -> Vec<String> {
+    body["tools"]
+        .as_array()
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spawn_agent_returns_sub_agent_final_message_to_parent() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_config(|config| {
+        config.features.enable(Feature::SpawnAgent);
+    })
+    .await?;
+
+    let call_id = "spawn-agent-1";
+    let task = "count the files in src";
+    mount_sse_sequence(
+        harness.server(),
+        vec![
+            // Parent turn delegates the task.
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call(
+                    call_id,
+                    "spawn_agent",
+                    &json!({ "task": task }).to_string(),
+                ),
+                ev_completed("resp-1"),
+            ]),
+            // The sub-agent answers in a single turn.
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-sub", "src has 3 files"),
+                ev_completed("resp-2"),
+            ]),
+            // Parent finishes after reading the tool output.
+            sse(vec![
+                ev_assistant_message("msg-parent", "done"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+    harness.submit("delegate the file count").await?;
+
+    let bodies = harness.request_bodies().await;
+    assert_eq!(bodies.len(), 3);
+    assert!(tool_names(&bodies[0]).contains(&"spawn_agent".to_string()));
+
+    let sub_agent_request = &bodies[1];
+    assert!(
+        sub_agent_request["input"].to_string().contains(task),
+        "sub-agent request should carry the task: {sub_agent_request}"
+    );
+    assert!(
+        !tool_names(sub_agent_request).contains(&"spawn_agent".to_string()),
+        "sub-agents must not be able to spawn further sub-agents"
+    );
+
+    let output = harness.function_call_stdout(call_id).await;
+    assert_eq!(output, "src has 3 files");
+
+    Ok(())
+}
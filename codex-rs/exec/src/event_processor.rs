@@ -2,6 +2,7 @@ use std::path::Path;
 
 use codex_core::config::Config;
 use codex_core::protocol::Event;
+use codex_core::protocol::NetworkApprovalRequestEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 
 pub(crate) enum CodexStatus {
@@ -36,6 +37,15 @@ pub(crate) fn handle_last_message(last_agent_message: Option<&str>, output_file:
     }
 }
 
+/// `codex exec` cannot ask for network approvals, so every host outside the
+/// allowlist is blocked; this says so.
+pub(crate) fn blocked_host_message(event: &NetworkApprovalRequestEvent) -> String {
+    format!(
+        "blocked {}:{} for this session; add it to network_proxy.allowed_domains to allow it",
+        event.host, event.port
+    )
+}
+
 fn write_last_message_file(contents: &str, last_message_path: Option<&Path>) {
     if let Some(path) = last_message_path
         && let Err(e) = std::fs::write(path, contents)
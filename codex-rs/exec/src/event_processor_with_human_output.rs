@@ -33,6 +33,7 @@ use std::time::Instant;
 
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::event_processor::blocked_host_message;
 use crate::event_processor::handle_last_message;
 use codex_common::create_config_summary_entries;
 use codex_protocol::plan_tool::StepStatus;
@@ -172,6 +173,17 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::BudgetWarning(ev) => {
+                if ev.exceeded {
+                    ts_msg!(self, "{} {ev}", "ERROR:".style(self.red));
+                } else {
+                    ts_msg!(
+                        self,
+                        "{} {ev}",
+                        "warning:".style(self.yellow).style(self.bold)
+                    );
+                }
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -548,6 +560,22 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     view.path.display()
                 );
             }
+            EventMsg::SubAgentBegin(begin) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "spawned agent".style(self.magenta),
+                    begin.task.style(self.dimmed)
+                );
+            }
+            EventMsg::SubAgentEnd(end) => {
+                let status = if end.last_agent_message.is_some() {
+                    "agent finished"
+                } else {
+                    "agent stopped without a result"
+                };
+                ts_msg!(self, "{}", status.style(self.magenta));
+            }
             EventMsg::TurnAborted(abort_reason) => match abort_reason.reason {
                 TurnAbortReason::Interrupted => {
                     ts_msg!(self, "task interrupted");
@@ -558,10 +586,22 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 TurnAbortReason::ReviewEnded => {
                     ts_msg!(self, "task aborted: review ended");
                 }
+                TurnAbortReason::BudgetExceeded => {
+                    ts_msg!(self, "task aborted: budget exhausted");
+                    return CodexStatus::InitiateShutdown;
+                }
             },
             EventMsg::ContextCompacted(_) => {
                 ts_msg!(self, "context compacted");
             }
+            EventMsg::NetworkApprovalRequest(ev) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "network:".style(self.yellow).style(self.bold),
+                    blocked_host_message(&ev)
+                );
+            }
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::WebSearchBegin(_)
             | EventMsg::ExecApprovalRequest(_)
@@ -570,6 +610,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ContextBreakdown(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -583,7 +624,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningContentDelta(_)
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::UndoCompleted(_)
-            | EventMsg::UndoStarted(_) => {}
+            | EventMsg::UndoStarted(_)
+            | EventMsg::SubAgentActivity(_)
+            | EventMsg::PinnedContextUpdated(_) => {}
         }
         CodexStatus::Running
     }
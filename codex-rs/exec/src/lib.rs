@@ -26,14 +26,19 @@ use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
+use codex_core::config::types::ModelCassette;
+use codex_core::config::types::ModelCassetteMode;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SessionSource;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAbortedEvent;
 use codex_protocol::approvals::ElicitationAction;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
@@ -90,6 +95,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
+        record,
+        replay,
         config_overrides,
     } = cli;
 
@@ -123,6 +130,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         sandbox_mode_cli_arg.map(Into::<SandboxMode>::into)
     };
 
+    let model_cassette = model_cassette(record, replay)?;
+
     // Parse `-c` overrides from the CLI.
     let cli_kv_overrides = match config_overrides.parse_overrides() {
         Ok(v) => v,
@@ -202,6 +211,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         tools_web_search_request: None,
         experimental_sandbox_command_assessment: None,
         additional_writable_roots: add_dir,
+        model_cassette,
     };
 
     let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
@@ -444,7 +454,23 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 })
                 .await?;
         }
-        if matches!(event.msg, EventMsg::Error(_)) {
+        if let EventMsg::NetworkApprovalRequest(ev) = &event.msg {
+            // Nobody can answer the prompt in exec mode; the event processor
+            // reports the blocked host.
+            conversation
+                .submit(Op::NetworkApproval {
+                    id: ev.call_id.clone(),
+                    decision: ReviewDecision::Denied,
+                })
+                .await?;
+        }
+        if matches!(
+            event.msg,
+            EventMsg::Error(_)
+                | EventMsg::TurnAborted(TurnAbortedEvent {
+                    reason: TurnAbortReason::BudgetExceeded,
+                })
+        ) {
             error_seen = true;
         }
         let shutdown: CodexStatus = event_processor.process_event(event);
@@ -496,6 +522,24 @@ async fn resolve_resume_path(
     }
 }
 
+/// Translates `--record`/`--replay` into a cassette override. The path is
+/// made absolute here so it is relative to where `codex exec` was invoked
+/// rather than to `--cd`.
+fn model_cassette(
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+) -> anyhow::Result<Option<ModelCassette>> {
+    let (mode, path) = match (record, replay) {
+        (Some(path), _) => (ModelCassetteMode::Record, path),
+        (None, Some(path)) => (ModelCassetteMode::Replay, path),
+        (None, None) => return Ok(None),
+    };
+    Ok(Some(ModelCassette {
+        mode,
+        path: std::path::absolute(path)?,
+    }))
+}
+
 fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
     let path = path?;
 
@@ -0,0 +1,50 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_replays_recorded_cassette_without_network() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let cassette_path = test.home_path().join("run.cassette.json");
+
+    let server = responses::start_mock_server().await;
+    let body = responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", "recorded answer"),
+        responses::ev_completed("resp1"),
+    ]);
+    responses::mount_sse_once(&server, body).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--record")
+        .arg(&cassette_path)
+        .arg("say something")
+        .assert()
+        .success();
+    assert_eq!(
+        server.received_requests().await.unwrap_or_default().len(),
+        1
+    );
+
+    let uri = server.uri();
+    drop(server);
+
+    let last_message = test.cwd_path().join("last.txt");
+    test.cmd()
+        .env("OPENAI_BASE_URL", format!("{uri}/v1"))
+        .arg("--skip-git-repo-check")
+        .arg("--replay")
+        .arg(&cassette_path)
+        .arg("-o")
+        .arg(&last_message)
+        .arg("say something else")
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_to_string(&last_message)?, "recorded answer");
+    Ok(())
+}
@@ -9,6 +9,8 @@ use std::path::PathBuf;
 use serde_json;
 use thiserror::Error;
 
+use crate::Decision;
+
 #[derive(Debug, Error)]
 pub enum AmendError {
     #[error("prefix rule requires at least one token")]
@@ -59,6 +61,15 @@ pub enum AmendError {
 pub fn blocking_append_allow_prefix_rule(
     policy_path: &Path,
     prefix: &[String],
+) -> Result<(), AmendError> {
+    blocking_append_prefix_rule(policy_path, prefix, Decision::Allow)
+}
+
+/// Like [`blocking_append_allow_prefix_rule`], but for any `decision`.
+pub fn blocking_append_prefix_rule(
+    policy_path: &Path,
+    prefix: &[String],
+    decision: Decision,
 ) -> Result<(), AmendError> {
     if prefix.is_empty() {
         return Err(AmendError::EmptyPrefix);
@@ -70,7 +81,8 @@ pub fn blocking_append_allow_prefix_rule(
         .collect::<Result<Vec<_>, _>>()
         .map_err(|source| AmendError::SerializePrefix { source })?;
     let pattern = format!("[{}]", tokens.join(", "));
-    let rule = format!(r#"prefix_rule(pattern={pattern}, decision="allow")"#);
+    let decision = decision.as_str();
+    let rule = format!(r#"prefix_rule(pattern={pattern}, decision="{decision}")"#);
 
     let dir = policy_path
         .parent()
@@ -171,6 +183,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn appends_rule_with_given_decision() {
+        let tmp = tempdir().expect("create temp dir");
+        let policy_path = tmp.path().join("policy").join("default.codexpolicy");
+
+        blocking_append_prefix_rule(
+            &policy_path,
+            &[String::from("rm"), String::from("-rf")],
+            Decision::Prompt,
+        )
+        .expect("append rule");
+
+        let contents =
+            std::fs::read_to_string(&policy_path).expect("default.codexpolicy should exist");
+        assert_eq!(
+            contents,
+            r#"prefix_rule(pattern=["rm", "-rf"], decision="prompt")
+"#
+        );
+    }
+
     #[test]
     fn appends_rule_without_duplicate_newline() {
         let tmp = tempdir().expect("create temp dir");
@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use crate::context::MatchContext;
+use crate::context::SandboxMode;
+use crate::rule::GlobPattern;
+
+/// Checks on where and how a command runs that a rule applies on top of its pattern. A command
+/// that fails them is treated as if the rule did not match. Facts missing from the
+/// [`MatchContext`] are not checked, which is how `match`/`not_match` examples are validated.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RuleConditions {
+    /// When non-empty, the working directory must match one of these patterns.
+    pub cwd: Vec<CwdPattern>,
+    /// The working directory must not match any of these patterns.
+    pub not_cwd: Vec<CwdPattern>,
+    /// Environment variables that must be set, each to a value matching its glob.
+    pub env: Vec<(String, GlobPattern)>,
+    /// When non-empty, the command must run under one of these sandbox modes.
+    pub sandbox: Vec<SandboxMode>,
+}
+
+impl RuleConditions {
+    pub fn is_empty(&self) -> bool {
+        self.cwd.is_empty()
+            && self.not_cwd.is_empty()
+            && self.env.is_empty()
+            && self.sandbox.is_empty()
+    }
+
+    pub fn hold(&self, context: &MatchContext) -> bool {
+        if let Some(cwd) = &context.cwd {
+            if !self.cwd.is_empty() && !self.cwd.iter().any(|pattern| pattern.matches(cwd)) {
+                return false;
+            }
+            if self.not_cwd.iter().any(|pattern| pattern.matches(cwd)) {
+                return false;
+            }
+        }
+        if let Some(env) = &context.env
+            && !self
+                .env
+                .iter()
+                .all(|(name, value)| env.get(name).is_some_and(|actual| value.matches(actual)))
+        {
+            return false;
+        }
+        match context.sandbox_mode {
+            Some(mode) => self.sandbox.is_empty() || self.sandbox.contains(&mode),
+            None => true,
+        }
+    }
+}
+
+/// A glob over the working directory. Patterns starting with `/` match the whole path; other
+/// patterns match the trailing path components, so `infra/sandbox/**` matches
+/// `/home/me/repo/infra/sandbox/eu`. A trailing `/**` also matches the directory itself.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CwdPattern {
+    glob: GlobPattern,
+    /// `glob` without its trailing `/**`, if it had one.
+    dir: Option<GlobPattern>,
+}
+
+impl CwdPattern {
+    pub fn new(source: &str) -> Self {
+        Self {
+            glob: GlobPattern::new(source),
+            dir: source.strip_suffix("/**").map(GlobPattern::new),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.glob.as_str()
+    }
+
+    pub fn matches(&self, cwd: &Path) -> bool {
+        let cwd = cwd.to_string_lossy();
+        let cwd = cwd.trim_end_matches('/');
+        let matches = |candidate: &str| {
+            self.glob.matches(candidate)
+                || self.dir.as_ref().is_some_and(|dir| dir.matches(candidate))
+        };
+        if self.as_str().starts_with('/') {
+            return matches(cwd);
+        }
+        matches(cwd)
+            || cwd
+                .match_indices('/')
+                .any(|(index, _)| matches(&cwd[index + 1..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn cwd_patterns_match_trailing_components_and_the_directory_itself() {
+        let relative = CwdPattern::new("infra/sandbox/**");
+        let absolute = CwdPattern::new("/srv/*/deploy");
+        let matches = |pattern: &CwdPattern, cwd: &str| pattern.matches(Path::new(cwd));
+
+        assert_eq!(
+            (
+                matches(&relative, "/home/me/repo/infra/sandbox/eu"),
+                matches(&relative, "/home/me/repo/infra/sandbox"),
+                matches(&relative, "/home/me/repo/infra/prod"),
+                matches(&relative, "/home/me/repo/infra/sandboxes"),
+            ),
+            (true, true, false, false)
+        );
+        assert_eq!(
+            (
+                matches(&absolute, "/srv/api/deploy"),
+                matches(&absolute, "/home/srv/api/deploy"),
+            ),
+            (true, false)
+        );
+    }
+
+    #[test]
+    fn conditions_check_only_the_facts_the_context_knows() {
+        let conditions = RuleConditions {
+            not_cwd: vec![CwdPattern::new("infra/sandbox/**")],
+            env: vec![("KUBECONFIG".to_string(), GlobPattern::new("*prod*"))],
+            sandbox: vec![SandboxMode::WorkspaceWrite],
+            ..Default::default()
+        };
+        let context = MatchContext {
+            cwd: Some(PathBuf::from("/repo/app")),
+            env: Some(HashMap::from([(
+                "KUBECONFIG".to_string(),
+                "/home/me/.kube/prod.yaml".to_string(),
+            )])),
+            sandbox_mode: Some(SandboxMode::WorkspaceWrite),
+            ..Default::default()
+        };
+        assert!(conditions.hold(&context));
+        assert!(conditions.hold(&MatchContext::default()));
+
+        assert!(!conditions.hold(&MatchContext {
+            cwd: Some(PathBuf::from("/repo/infra/sandbox")),
+            ..context.clone()
+        }));
+        assert!(!conditions.hold(&MatchContext {
+            env: Some(HashMap::new()),
+            ..context.clone()
+        }));
+        assert!(!conditions.hold(&MatchContext {
+            sandbox_mode: Some(SandboxMode::DangerFullAccess),
+            ..context
+        }));
+    }
+}
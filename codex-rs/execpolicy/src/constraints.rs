@@ -0,0 +1,315 @@
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::context::MatchContext;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Argument checks a rule applies on top of its pattern. A command that fails them is treated
+/// as if the rule did not match.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ArgConstraints {
+    /// Flags that must not appear anywhere in the arguments.
+    pub forbidden_flags: Vec<String>,
+    /// When non-empty, every path-like argument must resolve inside one of these roots.
+    pub paths_within: Vec<PathRoot>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathRoot {
+    /// The command's working directory and the workspace roots from [`MatchContext`].
+    Workspace,
+    /// A fixed absolute directory.
+    Dir(PathBuf),
+}
+
+impl PathRoot {
+    pub fn parse(raw: &str) -> Result<Self> {
+        if raw == "workspace" {
+            Ok(Self::Workspace)
+        } else if Path::new(raw).is_absolute() {
+            Ok(Self::Dir(normalize(Path::new(raw))))
+        } else {
+            Err(Error::InvalidConstraint(format!(
+                "paths_within entries must be \"workspace\" or an absolute path (got {raw})"
+            )))
+        }
+    }
+}
+
+impl ArgConstraints {
+    pub fn is_empty(&self) -> bool {
+        self.forbidden_flags.is_empty() && self.paths_within.is_empty()
+    }
+
+    /// Checks the arguments that follow the program name. Arguments after `--` are never
+    /// treated as flags.
+    pub fn allows(&self, args: &[String], context: &MatchContext) -> bool {
+        let (options, operands) = match args.iter().position(|arg| arg == "--") {
+            Some(end) => (&args[..end], &args[end + 1..]),
+            None => (args, &[][..]),
+        };
+        if options.iter().any(|arg| self.is_forbidden_flag(arg)) {
+            return false;
+        }
+        self.paths_within.is_empty()
+            || (options.iter().all(|arg| self.option_allowed(arg, context))
+                && operands.iter().all(|arg| self.path_allowed(arg, context)))
+    }
+
+    /// Whether `arg` sets one of the forbidden flags, either verbatim, as `--flag=value`, or as
+    /// part of a group of short flags such as `-fu`.
+    fn is_forbidden_flag(&self, arg: &str) -> bool {
+        self.forbidden_flags.iter().any(|flag| {
+            if arg == flag {
+                return true;
+            }
+            if flag.starts_with("--") {
+                return arg
+                    .strip_prefix(flag.as_str())
+                    .is_some_and(|rest| rest.starts_with('='));
+            }
+            match (flag.strip_prefix('-'), arg.strip_prefix('-')) {
+                (Some(short), Some(group)) if short.chars().count() == 1 => {
+                    !group.starts_with('-') && group.contains(short)
+                }
+                _ => false,
+            }
+        })
+    }
+
+    /// Flags are skipped apart from their values: the value of `--flag=value`, and the text
+    /// attached to a short flag such as `-o/etc/passwd` or `-C..` when it looks like a path
+    /// (contains `/` or starts with `.` or `~`). An attached bare name such as `-Cbuild` is
+    /// indistinguishable from a group of short flags and is not checked. Every other argument
+    /// is treated as a path.
+    fn option_allowed(&self, arg: &str, context: &MatchContext) -> bool {
+        let Some(flag) = arg.strip_prefix('-') else {
+            return self.path_allowed(arg, context);
+        };
+        if let Some((_, value)) = arg.split_once('=') {
+            return self.path_allowed(value, context);
+        }
+        if flag.starts_with('-') {
+            return true;
+        }
+        let mut chars = flag.chars();
+        chars.next();
+        let attached = chars.as_str();
+        let looks_like_path =
+            attached.contains('/') || attached.starts_with('.') || attached.starts_with('~');
+        !looks_like_path || self.path_allowed(attached, context)
+    }
+
+    fn path_allowed(&self, path: &str, context: &MatchContext) -> bool {
+        if path.is_empty() {
+            return true;
+        }
+        // `~` is expanded by the shell, so we cannot tell where it points.
+        if path.starts_with('~') {
+            return false;
+        }
+
+        let path = Path::new(path);
+        let resolved = match (&context.cwd, path.is_absolute()) {
+            (_, true) => resolve(path),
+            (Some(cwd), false) => resolve(&cwd.join(path)),
+            // Without a working directory a relative path stays inside the workspace unless it
+            // climbs out of it.
+            (None, false) => {
+                return self.paths_within.contains(&PathRoot::Workspace)
+                    && !normalize(path).starts_with(Component::ParentDir.as_os_str());
+            }
+        };
+
+        self.paths_within.iter().any(|root| match root {
+            PathRoot::Workspace => context
+                .cwd
+                .iter()
+                .chain(&context.workspace_roots)
+                .any(|dir| resolved.starts_with(resolve(dir))),
+            PathRoot::Dir(dir) => resolved.starts_with(resolve(dir)),
+        })
+    }
+}
+
+/// Resolves symlinks in the longest existing ancestor of `path` and appends the remaining
+/// components, so neither a link nor a `..` that follows one can point out of a root. `..` is
+/// only applied lexically to components that do not exist yet.
+fn resolve(path: &Path) -> PathBuf {
+    let components: Vec<Component> = path.components().collect();
+    for existing in (0..=components.len()).rev() {
+        let prefix: PathBuf = components[..existing].iter().collect();
+        let Ok(mut resolved) = std::fs::canonicalize(&prefix) else {
+            continue;
+        };
+        for component in &components[existing..] {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    resolved.pop();
+                }
+                component => resolved.push(component),
+            }
+        }
+        return resolved;
+    }
+    normalize(path)
+}
+
+/// Removes `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let can_pop = matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                );
+                if can_pop {
+                    normalized.pop();
+                } else if !normalized.has_root() {
+                    normalized.push(component);
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn forbidden_flags_match_exact_assigned_and_grouped_forms() {
+        let constraints = ArgConstraints {
+            forbidden_flags: args(&["--force", "-f"]),
+            ..Default::default()
+        };
+        let context = MatchContext::default();
+
+        assert!(constraints.allows(&args(&["push", "origin", "main"]), &context));
+        assert!(constraints.allows(&args(&["push", "--force-with-lease"]), &context));
+        assert!(!constraints.allows(&args(&["push", "--force"]), &context));
+        assert!(!constraints.allows(&args(&["push", "--force=true"]), &context));
+        assert!(!constraints.allows(&args(&["push", "-uf", "origin"]), &context));
+        assert!(constraints.allows(&args(&["checkout", "--", "-f"]), &context));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_arguments_must_stay_inside_the_roots() {
+        let workspace = tempfile::tempdir().expect("tempdir");
+        let cwd = workspace.path().join("project");
+        std::fs::create_dir(&cwd).expect("create project dir");
+        let constraints = ArgConstraints {
+            paths_within: vec![PathRoot::Workspace, PathRoot::parse("/srv/shared").unwrap()],
+            ..Default::default()
+        };
+        let context = MatchContext::new(cwd.clone());
+
+        let allowed = |cmd: &[&str]| constraints.allows(&args(cmd), &context);
+        assert_eq!(
+            (
+                allowed(&["-rf", "build", "./target/debug"]),
+                allowed(&["--output=dist/app.js", "src/index.ts"]),
+                allowed(&["-rf", "/srv/shared/cache"]),
+                allowed(&["-rf", cwd.join("nested/..").to_str().unwrap()]),
+            ),
+            (true, true, true, true)
+        );
+        assert_eq!(
+            (
+                allowed(&["-rf", "../"]),
+                allowed(&["-rf", "/"]),
+                allowed(&["--output=/etc/passwd"]),
+                allowed(&["-rf", "~/.ssh"]),
+                allowed(&["-rf", "/srv/shared/../other"]),
+            ),
+            (false, false, false, false, false)
+        );
+
+        let no_cwd = MatchContext::default();
+        assert!(constraints.allows(&args(&["-rf", "build"]), &no_cwd));
+        assert!(!constraints.allows(&args(&["-rf", "../build"]), &no_cwd));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn attached_short_flag_values_are_checked_as_paths() {
+        let workspace = tempfile::tempdir().expect("tempdir");
+        let constraints = ArgConstraints {
+            paths_within: vec![PathRoot::Workspace],
+            ..Default::default()
+        };
+        let context = MatchContext::new(workspace.path().to_path_buf());
+
+        let allowed = |cmd: &[&str]| constraints.allows(&args(cmd), &context);
+        assert_eq!(
+            (
+                allowed(&["-obuild/out.txt", "input"]),
+                allowed(&["-C./sub", "status"]),
+                allowed(&["-rf", "build"]),
+            ),
+            (true, true, true)
+        );
+        assert_eq!(
+            (
+                allowed(&["-o/etc/passwd", "input"]),
+                allowed(&["-C/etc", "status"]),
+                allowed(&["-C..", "status"]),
+                allowed(&["-I~/.ssh"]),
+            ),
+            (false, false, false, false)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_cannot_lead_out_of_the_workspace() {
+        let workspace = tempfile::tempdir().expect("tempdir");
+        let outside = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(outside.path().join("ssh")).expect("create outside dir");
+        std::os::unix::fs::symlink(outside.path().join("ssh"), workspace.path().join("link"))
+            .expect("create symlink");
+        let constraints = ArgConstraints {
+            paths_within: vec![PathRoot::Workspace],
+            ..Default::default()
+        };
+        let context = MatchContext::new(workspace.path().to_path_buf());
+
+        let allowed = |cmd: &[&str]| constraints.allows(&args(cmd), &context);
+        assert_eq!(
+            (
+                // `..` after the link leads to the link target's parent, not back into the
+                // workspace, even though `secret` does not exist.
+                allowed(&["link/../secret"]),
+                // A file that does not exist yet behind the link is still outside.
+                allowed(&["link/newfile"]),
+                allowed(&["missing/../newfile"]),
+            ),
+            (false, false, true)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_roots_must_be_workspace_or_absolute() {
+        assert_eq!(PathRoot::parse("workspace").unwrap(), PathRoot::Workspace);
+        assert_eq!(
+            PathRoot::parse("/srv/data/../cache").unwrap(),
+            PathRoot::Dir(PathBuf::from("/srv/cache"))
+        );
+        assert!(PathRoot::parse("relative/dir").is_err());
+    }
+}
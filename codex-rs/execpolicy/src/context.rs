@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// Where a command is about to run. Rules with path constraints resolve relative arguments
+/// against `cwd` and compare them with the workspace roots; rule conditions check `cwd`, `env`
+/// and `sandbox_mode`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MatchContext {
+    /// Directory the command runs in; counts as part of the workspace.
+    pub cwd: Option<PathBuf>,
+    /// Additional directories that count as part of the workspace, such as the writable roots
+    /// of the sandbox.
+    pub workspace_roots: Vec<PathBuf>,
+    /// Environment the command runs with. `env` conditions are not checked when unknown.
+    pub env: Option<HashMap<String, String>>,
+    /// Sandbox the command runs under. `sandbox` conditions are not checked when unknown.
+    pub sandbox_mode: Option<SandboxMode>,
+}
+
+impl MatchContext {
+    pub fn new(cwd: PathBuf) -> Self {
+        Self {
+            cwd: Some(cwd),
+            ..Default::default()
+        }
+    }
+}
+
+/// Sandbox modes a rule can be limited to, named as in `sandbox_mode` in `config.toml`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SandboxMode {
+    ReadOnly,
+    WorkspaceWrite,
+    DangerFullAccess,
+}
+
+impl SandboxMode {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw {
+            "read-only" => Ok(Self::ReadOnly),
+            "workspace-write" => Ok(Self::WorkspaceWrite),
+            "danger-full-access" => Ok(Self::DangerFullAccess),
+            other => Err(Error::InvalidConstraint(format!(
+                "sandbox must be \"read-only\", \"workspace-write\", or \"danger-full-access\" (got {other})"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for SandboxMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ReadOnly => "read-only",
+            Self::WorkspaceWrite => "workspace-write",
+            Self::DangerFullAccess => "danger-full-access",
+        })
+    }
+}
@@ -9,6 +9,8 @@ pub enum Error {
     InvalidDecision(String),
     #[error("invalid pattern element: {0}")]
     InvalidPattern(String),
+    #[error("invalid constraint: {0}")]
+    InvalidConstraint(String),
     #[error("invalid example: {0}")]
     InvalidExample(String),
     #[error(
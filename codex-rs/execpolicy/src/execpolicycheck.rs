@@ -7,9 +7,11 @@ use clap::Parser;
 use serde::Serialize;
 
 use crate::Decision;
+use crate::MatchContext;
 use crate::Policy;
 use crate::PolicyParser;
 use crate::RuleMatch;
+use crate::SandboxMode;
 
 /// Arguments for evaluating a command against one or more execpolicy files.
 #[derive(Debug, Parser, Clone)]
@@ -22,6 +24,16 @@ pub struct ExecPolicyCheckCommand {
     #[arg(long)]
     pub pretty: bool,
 
+    /// Directory the command would run in, used for `paths_within` constraints. Defaults to
+    /// the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Sandbox mode (`read-only`, `workspace-write`, or `danger-full-access`) for rules with a
+    /// `sandbox` condition. Those conditions are not checked when omitted.
+    #[arg(long, value_name = "MODE", value_parser = SandboxMode::parse)]
+    pub sandbox: Option<SandboxMode>,
+
     /// Command tokens to check against the policy.
     #[arg(
         value_name = "COMMAND",
@@ -36,7 +48,17 @@ impl ExecPolicyCheckCommand {
     /// Load the policies for this command, evaluate the command, and render JSON output.
     pub fn run(&self) -> Result<()> {
         let policy = load_policies(&self.policies)?;
-        let matched_rules = policy.matches_for_command(&self.command, None);
+        let cwd = match &self.cwd {
+            Some(cwd) => cwd.clone(),
+            None => std::env::current_dir().context("failed to read the current directory")?,
+        };
+        let context = MatchContext {
+            cwd: Some(cwd),
+            env: Some(std::env::vars().collect()),
+            sandbox_mode: self.sandbox,
+            ..Default::default()
+        };
+        let matched_rules = policy.matches_for_command(&self.command, &context, None);
 
         let json = format_matches_json(&matched_rules, self.pretty)?;
         println!("{json}");
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use clap::Parser;
+use shlex::try_join;
+
+use crate::Decision;
+use crate::MatchContext;
+use crate::Policy;
+use crate::PolicyParser;
+use crate::RuleRef;
+use crate::SandboxMode;
+use crate::parser::RuleExamples;
+
+const POLICY_EXTENSION: &str = "codexpolicy";
+
+/// Arguments for checking a directory of execpolicy files against their examples and fixtures.
+#[derive(Debug, Parser, Clone)]
+pub struct ExecPolicyTestCommand {
+    /// Directory containing the `*.codexpolicy` files to test.
+    #[arg(value_name = "DIR")]
+    pub policy_dir: PathBuf,
+
+    /// Fixture files of `command -> decision` lines to check (repeatable). The decision is
+    /// `allow`, `prompt`, `forbidden`, or `unmatched`.
+    #[arg(long = "fixtures", value_name = "PATH")]
+    pub fixtures: Vec<PathBuf>,
+
+    /// Directory fixture commands would run in, used for `paths_within` constraints and `cwd`
+    /// conditions. Defaults to the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Environment variable fixture commands run with (repeatable), for `env` conditions.
+    /// Fixtures run with an otherwise empty environment.
+    #[arg(long = "env", value_name = "NAME=VALUE", value_parser = parse_env_var)]
+    pub env: Vec<(String, String)>,
+
+    /// Sandbox mode (`read-only`, `workspace-write`, or `danger-full-access`) fixture commands
+    /// run under, for `sandbox` conditions. Those conditions are not checked when omitted.
+    #[arg(long, value_name = "MODE", value_parser = SandboxMode::parse)]
+    pub sandbox: Option<SandboxMode>,
+}
+
+impl ExecPolicyTestCommand {
+    /// Load the policies, run every embedded example and fixture, and print one line per case.
+    /// Fails when any case does not produce the expected result.
+    pub fn run(&self) -> Result<()> {
+        let (policy, examples) = load_policy_dir(&self.policy_dir)?;
+        let cwd = match &self.cwd {
+            Some(cwd) => cwd.clone(),
+            None => std::env::current_dir().context("failed to read the current directory")?,
+        };
+        let context = MatchContext {
+            cwd: Some(cwd),
+            env: Some(self.env.iter().cloned().collect()),
+            sandbox_mode: self.sandbox,
+            ..Default::default()
+        };
+
+        let mut results = check_examples(&policy, &examples);
+        for fixture_path in &self.fixtures {
+            let fixtures = load_fixtures(fixture_path)?;
+            results.extend(check_fixtures(&policy, &fixtures, &context));
+        }
+
+        for result in &results {
+            println!("{result}");
+        }
+        let failed = results.iter().filter(|result| !result.passed).count();
+        println!("{} passed, {failed} failed", results.len() - failed);
+
+        if failed > 0 {
+            bail!("{failed} policy test(s) failed");
+        }
+        Ok(())
+    }
+}
+
+/// Loads every `*.codexpolicy` file directly inside `dir`, in file name order, keeping the
+/// rule examples for [`check_examples`] instead of rejecting the policy when one fails.
+pub fn load_policy_dir(dir: &Path) -> Result<(Policy, Vec<RuleExamples>)> {
+    let mut policy_paths = Vec::new();
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("failed to read policy directory {}", dir.display()))?
+    {
+        let path = entry
+            .with_context(|| format!("failed to read policy directory {}", dir.display()))?
+            .path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some(POLICY_EXTENSION) && path.is_file()
+        {
+            policy_paths.push(path);
+        }
+    }
+    policy_paths.sort();
+
+    let mut parser = PolicyParser::new_collecting_examples();
+    for policy_path in &policy_paths {
+        let policy_file_contents = fs::read_to_string(policy_path)
+            .with_context(|| format!("failed to read policy at {}", policy_path.display()))?;
+        let policy_identifier = policy_path.to_string_lossy().to_string();
+        parser
+            .parse(&policy_identifier, &policy_file_contents)
+            .with_context(|| format!("failed to parse policy at {}", policy_path.display()))?;
+    }
+
+    Ok(parser.build_with_examples())
+}
+
+/// A `command -> decision` pair read from a fixture file. `expected` is `None` when no rule
+/// should match the command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fixture {
+    pub location: String,
+    pub command: Vec<String>,
+    pub expected: Option<Decision>,
+    /// Working directory from a `cwd=` setting, relative to the suite's working directory.
+    pub cwd: Option<PathBuf>,
+    /// Variables from `NAME=VALUE` settings, added to the suite's environment.
+    pub env: HashMap<String, String>,
+    /// Sandbox mode from a `sandbox=` setting.
+    pub sandbox: Option<SandboxMode>,
+}
+
+/// Parses a fixture file. Blank lines and lines starting with `#` are ignored; every other line
+/// is a shell-quoted command, `->`, and the expected decision. A line may start with settings
+/// in brackets for where its command runs: `[cwd=infra/sandbox sandbox=workspace-write
+/// KUBECONFIG=prod.yaml] kubectl apply -> prompt`. Settings other than `cwd` and `sandbox` are
+/// environment variables.
+pub fn parse_fixtures(identifier: &str, contents: &str) -> Result<Vec<Fixture>> {
+    let mut fixtures = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let location = format!("{identifier}:{}", index + 1);
+        let (settings, line) = match line.strip_prefix('[') {
+            Some(rest) => {
+                let Some((settings, line)) = rest.split_once(']') else {
+                    bail!("{location}: missing `]` after the fixture settings");
+                };
+                (Some(settings), line)
+            }
+            None => (None, line),
+        };
+        let Some((command, expected)) = line.rsplit_once("->") else {
+            bail!("{location}: expected `command -> decision`");
+        };
+        let command = shlex::split(command.trim())
+            .filter(|command| !command.is_empty())
+            .with_context(|| format!("{location}: invalid command `{}`", command.trim()))?;
+        let expected = match expected.trim() {
+            "unmatched" => None,
+            raw => Some(
+                Decision::parse(raw)
+                    .with_context(|| format!("{location}: invalid decision `{raw}`"))?,
+            ),
+        };
+        let mut fixture = Fixture {
+            location,
+            command,
+            expected,
+            cwd: None,
+            env: HashMap::new(),
+            sandbox: None,
+        };
+        if let Some(settings) = settings {
+            apply_fixture_settings(&mut fixture, settings)?;
+        }
+        fixtures.push(fixture);
+    }
+    Ok(fixtures)
+}
+
+fn apply_fixture_settings(fixture: &mut Fixture, settings: &str) -> Result<()> {
+    let location = &fixture.location;
+    let settings = shlex::split(settings)
+        .with_context(|| format!("{location}: invalid fixture settings `{settings}`"))?;
+    for setting in settings {
+        let (name, value) = parse_env_var(&setting)
+            .with_context(|| format!("{location}: invalid fixture setting `{setting}`"))?;
+        match name.as_str() {
+            "cwd" => fixture.cwd = Some(PathBuf::from(value)),
+            "sandbox" => {
+                fixture.sandbox = Some(
+                    SandboxMode::parse(&value)
+                        .with_context(|| format!("{location}: invalid sandbox `{value}`"))?,
+                );
+            }
+            _ => {
+                fixture.env.insert(name, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses a `NAME=VALUE` pair.
+fn parse_env_var(raw: &str) -> Result<(String, String)> {
+    match raw.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => bail!("expected NAME=VALUE (got `{raw}`)"),
+    }
+}
+
+fn load_fixtures(path: &Path) -> Result<Vec<Fixture>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read fixtures at {}", path.display()))?;
+    parse_fixtures(&path.to_string_lossy(), &contents)
+}
+
+/// The outcome of one embedded example or fixture line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyTestResult {
+    /// Where the case was declared: the rule for embedded examples, the line for fixtures.
+    pub location: String,
+    pub command: Vec<String>,
+    pub expected: String,
+    pub actual: String,
+    /// Locations of the rules that matched the command.
+    pub matched_rules: Vec<String>,
+    pub passed: bool,
+}
+
+impl fmt::Display for PolicyTestResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = try_join(self.command.iter().map(String::as_str))
+            .unwrap_or_else(|_| self.command.join(" "));
+        if self.passed {
+            write!(f, "PASS {}: {command} -> {}", self.location, self.expected)?;
+        } else {
+            write!(
+                f,
+                "FAIL {}: {command}: expected {}, got {}",
+                self.location, self.expected, self.actual
+            )?;
+        }
+        if !self.matched_rules.is_empty() {
+            write!(f, " (matched {})", self.matched_rules.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that every `match` example matches one of its rules and that no `not_match` example
+/// does, the same way the parser validates them when loading a policy.
+pub fn check_examples(policy: &Policy, examples: &[RuleExamples]) -> Vec<PolicyTestResult> {
+    let mut results = Vec::new();
+    for rule_examples in examples {
+        let location = rule_examples
+            .source
+            .as_ref()
+            .map_or_else(|| "<unknown>".to_string(), ToString::to_string);
+        let cases = rule_examples
+            .matches
+            .iter()
+            .map(|example| (example, true))
+            .chain(
+                rule_examples
+                    .not_matches
+                    .iter()
+                    .map(|example| (example, false)),
+            );
+        for (example, should_match) in cases {
+            let matched_rules: Vec<String> = rule_examples
+                .rules
+                .iter()
+                .filter(|rule| example.matched_by(rule))
+                .map(|rule| describe_rule(policy, rule))
+                .collect();
+            let did_match = !matched_rules.is_empty();
+            results.push(PolicyTestResult {
+                location: location.clone(),
+                command: example.command.clone(),
+                expected: describe_match(should_match),
+                actual: describe_match(did_match),
+                matched_rules,
+                passed: did_match == should_match,
+            });
+        }
+    }
+    results
+}
+
+/// Evaluates each fixture command against the whole policy and compares the resulting decision.
+/// Each fixture runs in `context` with its own settings applied on top.
+pub fn check_fixtures(
+    policy: &Policy,
+    fixtures: &[Fixture],
+    context: &MatchContext,
+) -> Vec<PolicyTestResult> {
+    fixtures
+        .iter()
+        .map(|fixture| {
+            let context = fixture_context(fixture, context);
+            let matches = policy.matching_rules(&fixture.command, &context);
+            let actual = matches
+                .iter()
+                .map(|(_, rule_match)| rule_match.decision())
+                .max();
+            PolicyTestResult {
+                location: fixture.location.clone(),
+                command: fixture.command.clone(),
+                expected: describe_decision(fixture.expected),
+                actual: describe_decision(actual),
+                matched_rules: matches
+                    .iter()
+                    .map(|(rule, _)| describe_rule(policy, rule))
+                    .collect(),
+                passed: actual == fixture.expected,
+            }
+        })
+        .collect()
+}
+
+fn fixture_context(fixture: &Fixture, context: &MatchContext) -> MatchContext {
+    let mut context = context.clone();
+    if let Some(cwd) = &fixture.cwd {
+        context.cwd = Some(match &context.cwd {
+            Some(base) => base.join(cwd),
+            None => cwd.clone(),
+        });
+    }
+    if !fixture.env.is_empty() {
+        context
+            .env
+            .get_or_insert_with(HashMap::new)
+            .extend(fixture.env.clone());
+    }
+    if fixture.sandbox.is_some() {
+        context.sandbox_mode = fixture.sandbox;
+    }
+    context
+}
+
+fn describe_rule(policy: &Policy, rule: &RuleRef) -> String {
+    match policy.rule_source(rule) {
+        Some(source) => source.to_string(),
+        None => format!("{rule:?}"),
+    }
+}
+
+fn describe_match(matched: bool) -> String {
+    if matched { "match" } else { "no match" }.to_string()
+}
+
+fn describe_decision(decision: Option<Decision>) -> String {
+    decision.map_or("unmatched", Decision::as_str).to_string()
+}
@@ -1,20 +1,31 @@
 pub mod amend;
+pub mod conditions;
+pub mod constraints;
+pub mod context;
 pub mod decision;
 pub mod error;
 pub mod execpolicycheck;
+pub mod execpolicytest;
 pub mod parser;
 pub mod policy;
 pub mod rule;
 
 pub use amend::AmendError;
 pub use amend::blocking_append_allow_prefix_rule;
+pub use amend::blocking_append_prefix_rule;
+pub use context::MatchContext;
+pub use context::SandboxMode;
 pub use decision::Decision;
 pub use error::Error;
 pub use error::Result;
 pub use execpolicycheck::ExecPolicyCheckCommand;
+pub use execpolicytest::ExecPolicyTestCommand;
 pub use parser::PolicyParser;
+pub use policy::ConsultedRule;
 pub use policy::Evaluation;
 pub use policy::Policy;
 pub use rule::Rule;
+pub use rule::RuleExample;
 pub use rule::RuleMatch;
 pub use rule::RuleRef;
+pub use rule::RuleSource;
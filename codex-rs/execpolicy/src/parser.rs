@@ -1,3 +1,4 @@
+use allocative::Allocative;
 use multimap::MultiMap;
 use shlex;
 use starlark::any::ProvidesStaticType;
@@ -7,21 +8,42 @@ use starlark::eval::Evaluator;
 use starlark::starlark_module;
 use starlark::syntax::AstModule;
 use starlark::syntax::Dialect;
+use starlark::values::AllocValue;
+use starlark::values::Heap;
+use starlark::values::NoSerialize;
+use starlark::values::StarlarkValue;
 use starlark::values::Value;
+use starlark::values::ValueLike;
+use starlark::values::dict::DictRef;
 use starlark::values::list::ListRef;
 use starlark::values::list::UnpackList;
 use starlark::values::none::NoneType;
+use starlark::values::starlark_value;
 use std::cell::RefCell;
 use std::cell::RefMut;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::conditions::CwdPattern;
+use crate::conditions::RuleConditions;
+use crate::constraints::ArgConstraints;
+use crate::constraints::PathRoot;
+use crate::context::MatchContext;
+use crate::context::SandboxMode;
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use crate::rule::ConstrainedPrefixRule;
+use crate::rule::GlobPattern;
 use crate::rule::PatternToken;
 use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
+use crate::rule::RegexPattern;
+use crate::rule::RuleExample;
 use crate::rule::RuleRef;
+use crate::rule::RuleSource;
 use crate::rule::validate_match_examples;
 use crate::rule::validate_not_match_examples;
 
@@ -38,7 +60,15 @@ impl Default for PolicyParser {
 impl PolicyParser {
     pub fn new() -> Self {
         Self {
-            builder: RefCell::new(PolicyBuilder::new()),
+            builder: RefCell::new(PolicyBuilder::new(false)),
+        }
+    }
+
+    /// Creates a parser that records each rule's `match` / `not_match` examples instead of
+    /// rejecting the policy when one fails, so they can be reported by [`Self::build_with_examples`].
+    pub fn new_collecting_examples() -> Self {
+        Self {
+            builder: RefCell::new(PolicyBuilder::new(true)),
         }
     }
 
@@ -64,29 +94,55 @@ impl PolicyParser {
     }
 
     pub fn build(self) -> crate::policy::Policy {
+        self.builder.into_inner().build().0
+    }
+
+    /// Builds the policy along with the examples recorded by a parser created with
+    /// [`Self::new_collecting_examples`].
+    pub fn build_with_examples(self) -> (crate::policy::Policy, Vec<RuleExamples>) {
         self.builder.into_inner().build()
     }
 }
 
+/// The `match` / `not_match` examples declared by one `prefix_rule` call, together with the
+/// rules that call produced.
+#[derive(Clone, Debug)]
+pub struct RuleExamples {
+    pub source: Option<RuleSource>,
+    pub rules: Vec<RuleRef>,
+    pub matches: Vec<RuleExample>,
+    pub not_matches: Vec<RuleExample>,
+}
+
 #[derive(Debug, ProvidesStaticType)]
 struct PolicyBuilder {
     rules_by_program: MultiMap<String, RuleRef>,
+    rule_sources: Vec<(RuleRef, RuleSource)>,
+    examples: Option<Vec<RuleExamples>>,
 }
 
 impl PolicyBuilder {
-    fn new() -> Self {
+    fn new(collect_examples: bool) -> Self {
         Self {
             rules_by_program: MultiMap::new(),
+            rule_sources: Vec::new(),
+            examples: collect_examples.then(Vec::new),
         }
     }
 
-    fn add_rule(&mut self, rule: RuleRef) {
+    fn add_rule(&mut self, rule: RuleRef, source: Option<RuleSource>) {
+        if let Some(source) = source {
+            self.rule_sources.push((rule.clone(), source));
+        }
         self.rules_by_program
             .insert(rule.program().to_string(), rule);
     }
 
-    fn build(self) -> crate::policy::Policy {
-        crate::policy::Policy::new(self.rules_by_program)
+    fn build(self) -> (crate::policy::Policy, Vec<RuleExamples>) {
+        (
+            crate::policy::Policy::with_sources(self.rules_by_program, self.rule_sources),
+            self.examples.unwrap_or_default(),
+        )
     }
 }
 
@@ -106,6 +162,12 @@ fn parse_pattern<'v>(pattern: UnpackList<Value<'v>>) -> Result<Vec<PatternToken>
 fn parse_pattern_token<'v>(value: Value<'v>) -> Result<PatternToken> {
     if let Some(s) = value.unpack_str() {
         Ok(PatternToken::Single(s.to_string()))
+    } else if let Some(matcher) = value.downcast_ref::<TokenMatcher>() {
+        match matcher {
+            TokenMatcher::Glob(glob) => Ok(PatternToken::Glob(GlobPattern::new(glob))),
+            TokenMatcher::Regex(regex) => RegexPattern::new(regex).map(PatternToken::Regex),
+            TokenMatcher::AnyArgs => Ok(PatternToken::AnyArgs),
+        }
     } else if let Some(list) = ListRef::from_value(value) {
         let tokens: Vec<String> = list
             .content()
@@ -132,24 +194,181 @@ fn parse_pattern_token<'v>(value: Value<'v>) -> Result<PatternToken> {
         }
     } else {
         Err(Error::InvalidPattern(format!(
-            "pattern element must be a string or list of strings (got {})",
+            "pattern element must be a string, list of strings, glob(), regex() or any_args() (got {})",
             value.get_type()
         )))
     }
 }
 
-fn parse_examples<'v>(examples: UnpackList<Value<'v>>) -> Result<Vec<Vec<String>>> {
+fn parse_string_list<'v>(values: UnpackList<Value<'v>>, describe: &str) -> Result<Vec<String>> {
+    values
+        .items
+        .into_iter()
+        .map(|value| {
+            value.unpack_str().map(str::to_string).ok_or_else(|| {
+                Error::InvalidConstraint(format!(
+                    "{describe} must be strings (got {})",
+                    value.get_type()
+                ))
+            })
+        })
+        .collect()
+}
+
+fn parse_constraints<'v>(
+    forbidden_flags: Option<UnpackList<Value<'v>>>,
+    paths_within: Option<UnpackList<Value<'v>>>,
+) -> Result<ArgConstraints> {
+    let forbidden_flags = forbidden_flags
+        .map(|flags| parse_string_list(flags, "forbidden_flags entries"))
+        .transpose()?
+        .unwrap_or_default();
+    if let Some(flag) = forbidden_flags.iter().find(|flag| !flag.starts_with('-')) {
+        return Err(Error::InvalidConstraint(format!(
+            "forbidden_flags entries must start with `-` (got {flag})"
+        )));
+    }
+
+    let paths_within = paths_within
+        .map(|roots| parse_string_list(roots, "paths_within entries"))
+        .transpose()?
+        .unwrap_or_default()
+        .iter()
+        .map(|root| PathRoot::parse(root))
+        .collect::<Result<_>>()?;
+
+    Ok(ArgConstraints {
+        forbidden_flags,
+        paths_within,
+    })
+}
+
+fn parse_conditions<'v>(
+    cwd: Option<UnpackList<Value<'v>>>,
+    not_cwd: Option<UnpackList<Value<'v>>>,
+    env: Option<Value<'v>>,
+    sandbox: Option<UnpackList<Value<'v>>>,
+) -> Result<RuleConditions> {
+    let parse_cwd_patterns = |patterns: Option<UnpackList<Value<'v>>>, describe: &str| {
+        patterns
+            .map(|patterns| parse_string_list(patterns, describe))
+            .transpose()
+            .map(|patterns| {
+                patterns
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|pattern| CwdPattern::new(pattern))
+                    .collect::<Vec<_>>()
+            })
+    };
+    let cwd = parse_cwd_patterns(cwd, "cwd entries")?;
+    let not_cwd = parse_cwd_patterns(not_cwd, "not_cwd entries")?;
+
+    let env = match env {
+        None => Vec::new(),
+        Some(value) => {
+            let dict = DictRef::from_value(value).ok_or_else(|| {
+                Error::InvalidConstraint(format!(
+                    "env must be a dict of variable names to globs (got {})",
+                    value.get_type()
+                ))
+            })?;
+            dict.iter()
+                .map(
+                    |(name, glob)| match (name.unpack_str(), glob.unpack_str()) {
+                        (Some(name), Some(glob)) => Ok((name.to_string(), GlobPattern::new(glob))),
+                        _ => Err(Error::InvalidConstraint(format!(
+                            "env entries must map strings to strings (got {name}: {glob})"
+                        ))),
+                    },
+                )
+                .collect::<Result<_>>()?
+        }
+    };
+
+    let sandbox = sandbox
+        .map(|modes| parse_string_list(modes, "sandbox entries"))
+        .transpose()?
+        .unwrap_or_default()
+        .iter()
+        .map(|mode| SandboxMode::parse(mode))
+        .collect::<Result<_>>()?;
+
+    Ok(RuleConditions {
+        cwd,
+        not_cwd,
+        env,
+        sandbox,
+    })
+}
+
+fn parse_examples<'v>(examples: UnpackList<Value<'v>>) -> Result<Vec<RuleExample>> {
     examples.items.into_iter().map(parse_example).collect()
 }
 
-fn parse_example<'v>(value: Value<'v>) -> Result<Vec<String>> {
+fn parse_example<'v>(value: Value<'v>) -> Result<RuleExample> {
+    match DictRef::from_value(value) {
+        Some(dict) => parse_dict_example(&dict),
+        None => parse_example_command(value).map(RuleExample::new),
+    }
+}
+
+/// Parses an example written as `{"command": ..., "cwd": ..., "env": {...}, "sandbox": ...}`,
+/// which is checked against rule conditions in that context.
+fn parse_dict_example(dict: &DictRef) -> Result<RuleExample> {
+    let mut command = None;
+    let mut context = MatchContext::default();
+    for (key, value) in dict.iter() {
+        let as_str = |describe: &str| {
+            value.unpack_str().ok_or_else(|| {
+                Error::InvalidExample(format!(
+                    "example {describe} must be a string (got {})",
+                    value.get_type()
+                ))
+            })
+        };
+        match key.unpack_str() {
+            Some("command") => command = Some(parse_example_command(value)?),
+            Some("cwd") => context.cwd = Some(PathBuf::from(as_str("cwd")?)),
+            Some("sandbox") => context.sandbox_mode = Some(SandboxMode::parse(as_str("sandbox")?)?),
+            Some("env") => {
+                let env = DictRef::from_value(value).ok_or_else(|| {
+                    Error::InvalidExample(format!(
+                        "example env must be a dict of strings (got {})",
+                        value.get_type()
+                    ))
+                })?;
+                context.env = Some(
+                    env.iter()
+                        .map(|(name, value)| match (name.unpack_str(), value.unpack_str()) {
+                            (Some(name), Some(value)) => Ok((name.to_string(), value.to_string())),
+                            _ => Err(Error::InvalidExample(format!(
+                                "example env entries must map strings to strings (got {name}: {value})"
+                            ))),
+                        })
+                        .collect::<Result<HashMap<_, _>>>()?,
+                );
+            }
+            _ => {
+                return Err(Error::InvalidExample(format!(
+                    "unknown example key {key}; expected command, cwd, env, or sandbox"
+                )));
+            }
+        }
+    }
+    let command = command
+        .ok_or_else(|| Error::InvalidExample("example dict must have a command".to_string()))?;
+    Ok(RuleExample { command, context })
+}
+
+fn parse_example_command<'v>(value: Value<'v>) -> Result<Vec<String>> {
     if let Some(raw) = value.unpack_str() {
         parse_string_example(raw)
     } else if let Some(list) = ListRef::from_value(value) {
         parse_list_example(list)
     } else {
         Err(Error::InvalidExample(format!(
-            "example must be a string or list of strings (got {})",
+            "example must be a string, a list of strings, or a dict (got {})",
             value.get_type()
         )))
     }
@@ -205,13 +424,63 @@ fn policy_builder<'v, 'a>(eval: &Evaluator<'v, 'a, '_>) -> RefMut<'a, PolicyBuil
         .borrow_mut()
 }
 
+/// Pattern elements created by the `glob`, `regex` and `any_args` builtins.
+#[derive(Clone, Debug, PartialEq, Eq, ProvidesStaticType, NoSerialize, Allocative)]
+enum TokenMatcher {
+    Glob(String),
+    Regex(String),
+    AnyArgs,
+}
+
+impl fmt::Display for TokenMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Glob(glob) => write!(f, "glob({glob:?})"),
+            Self::Regex(regex) => write!(f, "regex({regex:?})"),
+            Self::AnyArgs => write!(f, "any_args()"),
+        }
+    }
+}
+
+impl<'v> AllocValue<'v> for TokenMatcher {
+    fn alloc_value(self, heap: &'v Heap) -> Value<'v> {
+        heap.alloc_simple(self)
+    }
+}
+
+#[starlark_value(type = "TokenMatcher")]
+impl<'v> StarlarkValue<'v> for TokenMatcher {}
+
 #[starlark_module]
 fn policy_builtins(builder: &mut GlobalsBuilder) {
+    /// Matches a single token against a shell-style glob (`*` and `?`).
+    fn glob(pattern: &str) -> anyhow::Result<TokenMatcher> {
+        Ok(TokenMatcher::Glob(pattern.to_string()))
+    }
+
+    /// Matches a single token against a regular expression anchored at both ends.
+    fn regex(pattern: &str) -> anyhow::Result<TokenMatcher> {
+        RegexPattern::new(pattern)?;
+        Ok(TokenMatcher::Regex(pattern.to_string()))
+    }
+
+    /// Matches any number of tokens, including none.
+    fn any_args() -> anyhow::Result<TokenMatcher> {
+        Ok(TokenMatcher::AnyArgs)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn prefix_rule<'v>(
         pattern: UnpackList<Value<'v>>,
         decision: Option<&'v str>,
         r#match: Option<UnpackList<Value<'v>>>,
         not_match: Option<UnpackList<Value<'v>>>,
+        forbidden_flags: Option<UnpackList<Value<'v>>>,
+        paths_within: Option<UnpackList<Value<'v>>>,
+        cwd: Option<UnpackList<Value<'v>>>,
+        not_cwd: Option<UnpackList<Value<'v>>>,
+        env: Option<Value<'v>>,
+        sandbox: Option<UnpackList<Value<'v>>>,
         eval: &mut Evaluator<'v, '_, '_>,
     ) -> anyhow::Result<NoneType> {
         let decision = match decision {
@@ -220,40 +489,73 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
         };
 
         let pattern_tokens = parse_pattern(pattern)?;
+        let constraints = parse_constraints(forbidden_flags, paths_within)?;
+        let conditions = parse_conditions(cwd, not_cwd, env, sandbox)?;
 
-        let matches: Vec<Vec<String>> =
+        let matches: Vec<RuleExample> =
             r#match.map(parse_examples).transpose()?.unwrap_or_default();
-        let not_matches: Vec<Vec<String>> = not_match
+        let not_matches: Vec<RuleExample> = not_match
             .map(parse_examples)
             .transpose()?
             .unwrap_or_default();
 
+        let source = eval.call_stack_top_location().map(|span| RuleSource {
+            file: span.filename().to_string(),
+            line: span.resolve_span().begin.line + 1,
+        });
         let mut builder = policy_builder(eval);
 
         let (first_token, remaining_tokens) = pattern_tokens
             .split_first()
             .ok_or_else(|| Error::InvalidPattern("pattern cannot be empty".to_string()))?;
 
+        if first_token.alternatives().is_empty() {
+            return Err(Error::InvalidPattern(format!(
+                "first pattern element must name the program (got {pattern_tokens:?})"
+            ))
+            .into());
+        }
+
         let rest: Arc<[PatternToken]> = remaining_tokens.to_vec().into();
 
         let rules: Vec<RuleRef> = first_token
             .alternatives()
             .iter()
             .map(|head| {
-                Arc::new(PrefixRule {
+                let rule = PrefixRule {
                     pattern: PrefixPattern {
                         first: Arc::from(head.as_str()),
                         rest: rest.clone(),
                     },
                     decision,
-                }) as RuleRef
+                };
+                if constraints.is_empty() && conditions.is_empty() {
+                    Arc::new(rule) as RuleRef
+                } else {
+                    Arc::new(ConstrainedPrefixRule {
+                        rule,
+                        constraints: constraints.clone(),
+                        conditions: conditions.clone(),
+                    }) as RuleRef
+                }
             })
             .collect();
 
-        validate_not_match_examples(&rules, &not_matches)?;
-        validate_match_examples(&rules, &matches)?;
+        if let Some(examples) = builder.examples.as_mut() {
+            examples.push(RuleExamples {
+                source: source.clone(),
+                rules: rules.clone(),
+                matches,
+                not_matches,
+            });
+        } else {
+            validate_not_match_examples(&rules, &not_matches)?;
+            validate_match_examples(&rules, &matches)?;
+        }
 
-        rules.into_iter().for_each(|rule| builder.add_rule(rule));
+        rules
+            .into_iter()
+            .for_each(|rule| builder.add_rule(rule, source.clone()));
         Ok(NoneType)
     }
 }
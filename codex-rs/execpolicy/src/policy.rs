@@ -1,3 +1,4 @@
+use crate::context::MatchContext;
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
@@ -6,6 +7,7 @@ use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
 use crate::rule::RuleMatch;
 use crate::rule::RuleRef;
+use crate::rule::RuleSource;
 use multimap::MultiMap;
 use serde::Deserialize;
 use serde::Serialize;
@@ -16,11 +18,22 @@ type HeuristicsFallback<'a> = Option<&'a dyn Fn(&[String]) -> Decision>;
 #[derive(Clone, Debug)]
 pub struct Policy {
     rules_by_program: MultiMap<String, RuleRef>,
+    rule_sources: Vec<(RuleRef, RuleSource)>,
 }
 
 impl Policy {
     pub fn new(rules_by_program: MultiMap<String, RuleRef>) -> Self {
-        Self { rules_by_program }
+        Self::with_sources(rules_by_program, Vec::new())
+    }
+
+    pub(crate) fn with_sources(
+        rules_by_program: MultiMap<String, RuleRef>,
+        rule_sources: Vec<(RuleRef, RuleSource)>,
+    ) -> Self {
+        Self {
+            rules_by_program,
+            rule_sources,
+        }
     }
 
     pub fn empty() -> Self {
@@ -31,6 +44,14 @@ impl Policy {
         &self.rules_by_program
     }
 
+    /// Where `rule` was defined, when it was loaded from a policy file.
+    pub fn rule_source(&self, rule: &RuleRef) -> Option<&RuleSource> {
+        self.rule_sources
+            .iter()
+            .find(|(candidate, _)| Arc::ptr_eq(candidate, rule))
+            .map(|(_, source)| source)
+    }
+
     pub fn add_prefix_rule(&mut self, prefix: &[String], decision: Decision) -> Result<()> {
         let (first_token, rest) = prefix
             .split_first()
@@ -56,7 +77,20 @@ impl Policy {
     where
         F: Fn(&[String]) -> Decision,
     {
-        let matched_rules = self.matches_for_command(cmd, Some(heuristics_fallback));
+        self.check_with_context(cmd, &MatchContext::default(), heuristics_fallback)
+    }
+
+    /// Like [`Policy::check`], resolving path constraints against `context`.
+    pub fn check_with_context<F>(
+        &self,
+        cmd: &[String],
+        context: &MatchContext,
+        heuristics_fallback: &F,
+    ) -> Evaluation
+    where
+        F: Fn(&[String]) -> Decision,
+    {
+        let matched_rules = self.matches_for_command(cmd, context, Some(heuristics_fallback));
         Evaluation::from_matches(matched_rules)
     }
 
@@ -65,6 +99,21 @@ impl Policy {
         commands: Commands,
         heuristics_fallback: &F,
     ) -> Evaluation
+    where
+        Commands: IntoIterator,
+        Commands::Item: AsRef<[String]>,
+        F: Fn(&[String]) -> Decision,
+    {
+        self.check_multiple_with_context(commands, &MatchContext::default(), heuristics_fallback)
+    }
+
+    /// Like [`Policy::check_multiple`], resolving path constraints against `context`.
+    pub fn check_multiple_with_context<Commands, F>(
+        &self,
+        commands: Commands,
+        context: &MatchContext,
+        heuristics_fallback: &F,
+    ) -> Evaluation
     where
         Commands: IntoIterator,
         Commands::Item: AsRef<[String]>,
@@ -73,7 +122,7 @@ impl Policy {
         let matched_rules: Vec<RuleMatch> = commands
             .into_iter()
             .flat_map(|command| {
-                self.matches_for_command(command.as_ref(), Some(heuristics_fallback))
+                self.matches_for_command(command.as_ref(), context, Some(heuristics_fallback))
             })
             .collect();
 
@@ -83,16 +132,14 @@ impl Policy {
     pub fn matches_for_command(
         &self,
         cmd: &[String],
+        context: &MatchContext,
         heuristics_fallback: HeuristicsFallback<'_>,
     ) -> Vec<RuleMatch> {
-        let mut matched_rules: Vec<RuleMatch> = match cmd.first() {
-            Some(first) => self
-                .rules_by_program
-                .get_vec(first)
-                .map(|rules| rules.iter().filter_map(|rule| rule.matches(cmd)).collect())
-                .unwrap_or_default(),
-            None => Vec::new(),
-        };
+        let mut matched_rules: Vec<RuleMatch> = self
+            .matching_rules(cmd, context)
+            .into_iter()
+            .map(|(_, rule_match)| rule_match)
+            .collect();
 
         if let (true, Some(heuristics_fallback)) = (matched_rules.is_empty(), heuristics_fallback) {
             matched_rules.push(RuleMatch::HeuristicsRuleMatch {
@@ -103,6 +150,53 @@ impl Policy {
 
         matched_rules
     }
+
+    /// Rules that match `cmd`, each with the match it produced.
+    pub fn matching_rules(
+        &self,
+        cmd: &[String],
+        context: &MatchContext,
+    ) -> Vec<(&RuleRef, RuleMatch)> {
+        cmd.first()
+            .and_then(|first| self.rules_by_program.get_vec(first))
+            .map(|rules| {
+                rules
+                    .iter()
+                    .filter_map(|rule| {
+                        rule.matches_with_context(cmd, context)
+                            .map(|rule_match| (rule, rule_match))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Every rule defined for `cmd`'s program, in definition order, with the match it
+    /// produced if any.
+    pub fn consulted_rules(&self, cmd: &[String], context: &MatchContext) -> Vec<ConsultedRule> {
+        cmd.first()
+            .and_then(|first| self.rules_by_program.get_vec(first))
+            .map(|rules| {
+                rules
+                    .iter()
+                    .map(|rule| ConsultedRule {
+                        source: self.rule_source(rule).cloned(),
+                        rule_match: rule.matches_with_context(cmd, context),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// A rule checked while evaluating a command.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsultedRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<RuleSource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_match: Option<RuleMatch>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
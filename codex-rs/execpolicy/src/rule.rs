@@ -1,18 +1,29 @@
+use crate::conditions::RuleConditions;
+use crate::constraints::ArgConstraints;
+use crate::context::MatchContext;
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use regex_lite::Regex;
 use serde::Deserialize;
 use serde::Serialize;
 use shlex::try_join;
 use std::any::Any;
+use std::fmt;
 use std::fmt::Debug;
 use std::sync::Arc;
+use wildmatch::WildMatch;
 
-/// Matches a single command token, either a fixed string or one of several allowed alternatives.
+/// Matches command tokens: a fixed string, one of several allowed alternatives, a glob or regex
+/// over a single token, or any number of tokens.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PatternToken {
     Single(String),
     Alts(Vec<String>),
+    Glob(GlobPattern),
+    Regex(RegexPattern),
+    /// Zero or more tokens.
+    AnyArgs,
 }
 
 impl PatternToken {
@@ -20,18 +31,90 @@ impl PatternToken {
         match self {
             Self::Single(expected) => expected == token,
             Self::Alts(alternatives) => alternatives.iter().any(|alt| alt == token),
+            Self::Glob(glob) => glob.matches(token),
+            Self::Regex(regex) => regex.matches(token),
+            Self::AnyArgs => true,
         }
     }
 
+    /// The literal strings this token matches; empty for glob, regex and `AnyArgs` tokens.
     pub fn alternatives(&self) -> &[String] {
         match self {
             Self::Single(expected) => std::slice::from_ref(expected),
             Self::Alts(alternatives) => alternatives,
+            Self::Glob(_) | Self::Regex(_) | Self::AnyArgs => &[],
         }
     }
 }
 
-/// Prefix matcher for commands with support for alternative match tokens.
+/// Shell-style glob (`*` matches any run of characters, `?` a single one) matched against a
+/// whole token.
+#[derive(Clone, Debug)]
+pub struct GlobPattern {
+    source: String,
+    glob: WildMatch,
+}
+
+impl GlobPattern {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            glob: WildMatch::new(source),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub(crate) fn matches(&self, token: &str) -> bool {
+        self.glob.matches(token)
+    }
+}
+
+impl PartialEq for GlobPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for GlobPattern {}
+
+/// Regular expression that must match a whole token.
+#[derive(Clone, Debug)]
+pub struct RegexPattern {
+    source: String,
+    regex: Regex,
+}
+
+impl RegexPattern {
+    pub fn new(source: &str) -> Result<Self> {
+        let regex = Regex::new(&format!("^(?:{source})$"))
+            .map_err(|err| Error::InvalidPattern(format!("invalid regex `{source}`: {err}")))?;
+        Ok(Self {
+            source: source.to_string(),
+            regex,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    fn matches(&self, token: &str) -> bool {
+        self.regex.is_match(token)
+    }
+}
+
+impl PartialEq for RegexPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for RegexPattern {}
+
+/// Prefix matcher for commands with support for alternative, glob, regex and `AnyArgs` tokens.
 /// First token is fixed since we key by the first token in policy.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PrefixPattern {
@@ -41,19 +124,38 @@ pub struct PrefixPattern {
 
 impl PrefixPattern {
     pub fn matches_prefix(&self, cmd: &[String]) -> Option<Vec<String>> {
-        let pattern_length = self.rest.len() + 1;
-        if cmd.len() < pattern_length || cmd[0] != self.first.as_ref() {
+        let (first, args) = cmd.split_first()?;
+        if first != self.first.as_ref() {
             return None;
         }
 
-        for (pattern_token, cmd_token) in self.rest.iter().zip(&cmd[1..pattern_length]) {
-            if !pattern_token.matches(cmd_token) {
-                return None;
-            }
+        let matched_args = match_tokens(&self.rest, args)?;
+        Some(cmd[..=matched_args].to_vec())
+    }
+}
+
+/// Matches `pattern` against the start of `args` and returns how many tokens it consumed.
+/// `AnyArgs` consumes as few tokens as possible, except at the end of the pattern where it
+/// consumes the rest of the command.
+fn match_tokens(pattern: &[PatternToken], args: &[String]) -> Option<usize> {
+    let Some((token, rest)) = pattern.split_first() else {
+        return Some(0);
+    };
+
+    if matches!(token, PatternToken::AnyArgs) {
+        if rest.is_empty() {
+            return Some(args.len());
         }
+        return (0..=args.len()).find_map(|skipped| {
+            match_tokens(rest, &args[skipped..]).map(|matched| skipped + matched)
+        });
+    }
 
-        Some(cmd[..pattern_length].to_vec())
+    let (arg, remaining_args) = args.split_first()?;
+    if !token.matches(arg) {
+        return None;
     }
+    match_tokens(rest, remaining_args).map(|matched| matched + 1)
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -79,6 +181,20 @@ impl RuleMatch {
     }
 }
 
+/// Where a rule was defined: the policy identifier passed to [`crate::PolicyParser::parse`]
+/// and the 1-based line of the rule.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RuleSource {
+    pub file: String,
+    pub line: usize,
+}
+
+impl fmt::Display for RuleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PrefixRule {
     pub pattern: PrefixPattern,
@@ -89,6 +205,11 @@ pub trait Rule: Any + Debug + Send + Sync {
     fn program(&self) -> &str;
 
     fn matches(&self, cmd: &[String]) -> Option<RuleMatch>;
+
+    /// Like [`Rule::matches`], for rules whose outcome depends on where the command runs.
+    fn matches_with_context(&self, cmd: &[String], _context: &MatchContext) -> Option<RuleMatch> {
+        self.matches(cmd)
+    }
 }
 
 pub type RuleRef = Arc<dyn Rule>;
@@ -108,20 +229,66 @@ impl Rule for PrefixRule {
     }
 }
 
-/// Count how many rules match each provided example and error if any example is unmatched.
-pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[Vec<String>]) -> Result<()> {
-    let mut unmatched_examples = Vec::new();
+/// A prefix rule that only applies when the command's arguments satisfy `constraints` and
+/// where it runs satisfies `conditions`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConstrainedPrefixRule {
+    pub rule: PrefixRule,
+    pub constraints: ArgConstraints,
+    pub conditions: RuleConditions,
+}
 
-    for example in matches {
-        if rules.iter().any(|rule| rule.matches(example).is_some()) {
-            continue;
+impl Rule for ConstrainedPrefixRule {
+    fn program(&self) -> &str {
+        self.rule.program()
+    }
+
+    fn matches(&self, cmd: &[String]) -> Option<RuleMatch> {
+        self.matches_with_context(cmd, &MatchContext::default())
+    }
+
+    fn matches_with_context(&self, cmd: &[String], context: &MatchContext) -> Option<RuleMatch> {
+        let rule_match = self.rule.matches(cmd)?;
+        (self.conditions.hold(context) && self.constraints.allows(&cmd[1..], context))
+            .then_some(rule_match)
+    }
+}
+
+/// A `match` / `not_match` example: a command and, for examples written as a dict, where it
+/// runs. Facts the example leaves unset are not checked against rule conditions.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RuleExample {
+    pub command: Vec<String>,
+    pub context: MatchContext,
+}
+
+impl RuleExample {
+    pub fn new(command: Vec<String>) -> Self {
+        Self {
+            command,
+            context: MatchContext::default(),
         }
+    }
+
+    /// Whether `rule` matches the example's command in the example's context.
+    pub fn matched_by(&self, rule: &RuleRef) -> bool {
+        rule.matches_with_context(&self.command, &self.context)
+            .is_some()
+    }
 
-        unmatched_examples.push(
-            try_join(example.iter().map(String::as_str))
-                .unwrap_or_else(|_| "unable to render example".to_string()),
-        );
+    fn render(&self) -> String {
+        try_join(self.command.iter().map(String::as_str))
+            .unwrap_or_else(|_| "unable to render example".to_string())
     }
+}
+
+/// Count how many rules match each provided example and error if any example is unmatched.
+pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[RuleExample]) -> Result<()> {
+    let unmatched_examples: Vec<String> = matches
+        .iter()
+        .filter(|example| !rules.iter().any(|rule| example.matched_by(rule)))
+        .map(RuleExample::render)
+        .collect();
 
     if unmatched_examples.is_empty() {
         Ok(())
@@ -136,14 +303,13 @@ pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[Vec<String>]
 /// Ensure that no rule matches any provided negative example.
 pub(crate) fn validate_not_match_examples(
     rules: &[RuleRef],
-    not_matches: &[Vec<String>],
+    not_matches: &[RuleExample],
 ) -> Result<()> {
     for example in not_matches {
-        if let Some(rule) = rules.iter().find(|rule| rule.matches(example).is_some()) {
+        if let Some(rule) = rules.iter().find(|rule| example.matched_by(rule)) {
             return Err(Error::ExampleDidMatch {
                 rule: format!("{rule:?}"),
-                example: try_join(example.iter().map(String::as_str))
-                    .unwrap_or_else(|_| "unable to render example".to_string()),
+                example: example.render(),
             });
         }
     }
@@ -1,4 +1,6 @@
 use std::any::Any;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Context;
@@ -6,10 +8,16 @@ use anyhow::Result;
 use codex_execpolicy::Decision;
 use codex_execpolicy::Error;
 use codex_execpolicy::Evaluation;
+use codex_execpolicy::MatchContext;
 use codex_execpolicy::Policy;
 use codex_execpolicy::PolicyParser;
 use codex_execpolicy::RuleMatch;
 use codex_execpolicy::RuleRef;
+use codex_execpolicy::SandboxMode;
+use codex_execpolicy::execpolicytest::check_examples;
+use codex_execpolicy::execpolicytest::check_fixtures;
+use codex_execpolicy::execpolicytest::load_policy_dir;
+use codex_execpolicy::execpolicytest::parse_fixtures;
 use codex_execpolicy::rule::PatternToken;
 use codex_execpolicy::rule::PrefixPattern;
 use codex_execpolicy::rule::PrefixRule;
@@ -452,3 +460,444 @@ fn heuristics_match_is_returned_when_no_policy_matches() {
         evaluation
     );
 }
+
+#[test]
+fn glob_regex_and_any_args_tokens() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["git", any_args(), "push"],
+    match = ["git push", "git -C repo push origin", "git -c core.pager=cat push"],
+    not_match = ["git status", "git -C push-dir status"],
+)
+prefix_rule(
+    pattern = ["cargo", "test", "-p", glob("codex-*")],
+    match = ["cargo test -p codex-core"],
+    not_match = ["cargo test -p serde"],
+)
+prefix_rule(
+    pattern = ["git", "checkout", regex("v[0-9]+\\.[0-9]+")],
+    match = ["git checkout v1.2"],
+    not_match = ["git checkout v1.2-rc", "git checkout main"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.codexpolicy", policy_src)?;
+    let policy = parser.build();
+
+    let push = policy.check(
+        &tokens(&["git", "-C", "repo", "push", "--tags"]),
+        &prompt_all,
+    );
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Allow,
+            matched_rules: vec![RuleMatch::PrefixRuleMatch {
+                matched_prefix: tokens(&["git", "-C", "repo", "push"]),
+                decision: Decision::Allow,
+            }],
+        },
+        push
+    );
+
+    let test = policy.check(
+        &tokens(&["cargo", "test", "-p", "codex-execpolicy", "--lib"]),
+        &prompt_all,
+    );
+    assert_eq!(
+        vec![RuleMatch::PrefixRuleMatch {
+            matched_prefix: tokens(&["cargo", "test", "-p", "codex-execpolicy"]),
+            decision: Decision::Allow,
+        }],
+        test.matched_rules
+    );
+    Ok(())
+}
+
+#[test]
+fn trailing_any_args_matches_the_whole_command() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(pattern = ["make", any_args()], decision = "prompt")
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.codexpolicy", policy_src)?;
+    let policy = parser.build();
+
+    let evaluation = policy.check(&tokens(&["make", "-j8", "all"]), &allow_all);
+    assert_eq!(
+        vec![RuleMatch::PrefixRuleMatch {
+            matched_prefix: tokens(&["make", "-j8", "all"]),
+            decision: Decision::Prompt,
+        }],
+        evaluation.matched_rules
+    );
+    Ok(())
+}
+
+#[test]
+fn forbidden_flags_stop_the_rule_from_applying() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["git", "push"],
+    forbidden_flags = ["--force", "-f", "--force-with-lease"],
+    match = ["git push origin main"],
+    not_match = ["git push --force", "git push -fu origin main", "git push --force-with-lease=main"],
+)
+prefix_rule(
+    pattern = ["git", "push", any_args(), ["--force", "-f"]],
+    decision = "forbidden",
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.codexpolicy", policy_src)?;
+    let policy = parser.build();
+
+    let push = policy.check(&tokens(&["git", "push", "origin", "main"]), &prompt_all);
+    assert_eq!(Decision::Allow, push.decision);
+
+    let force = policy.check(
+        &tokens(&["git", "push", "origin", "main", "--force"]),
+        &prompt_all,
+    );
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Forbidden,
+            matched_rules: vec![RuleMatch::PrefixRuleMatch {
+                matched_prefix: tokens(&["git", "push", "origin", "main", "--force"]),
+                decision: Decision::Forbidden,
+            }],
+        },
+        force
+    );
+
+    let lease = policy.check(&tokens(&["git", "push", "--force-with-lease"]), &prompt_all);
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Prompt,
+            matched_rules: vec![RuleMatch::HeuristicsRuleMatch {
+                command: tokens(&["git", "push", "--force-with-lease"]),
+                decision: Decision::Prompt,
+            }],
+        },
+        lease
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn paths_within_resolves_arguments_against_the_context() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["rm"],
+    paths_within = ["workspace"],
+    match = ["rm -rf build"],
+    not_match = ["rm -rf /", "rm -rf ../other"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.codexpolicy", policy_src)?;
+    let policy = parser.build();
+    let workspace = tempfile::tempdir()?;
+    let context = MatchContext::new(workspace.path().to_path_buf());
+
+    let inside = policy.check_with_context(
+        &tokens(&["rm", "-rf", "target", "./node_modules"]),
+        &context,
+        &prompt_all,
+    );
+    assert_eq!(Decision::Allow, inside.decision);
+
+    let outside = workspace.path().join("../elsewhere");
+    let outside = policy.check_with_context(
+        &tokens(&[
+            "rm",
+            "-rf",
+            "target",
+            outside.to_str().context("utf-8 path")?,
+        ]),
+        &context,
+        &prompt_all,
+    );
+    assert_eq!(Decision::Prompt, outside.decision);
+    assert!(!outside.is_match());
+    Ok(())
+}
+
+#[test]
+fn invalid_matchers_and_constraints_are_rejected() {
+    let cases = [
+        r#"prefix_rule(pattern = [glob("*"), "status"])"#,
+        r#"prefix_rule(pattern = ["git", regex("(")])"#,
+        r#"prefix_rule(pattern = ["git", "push"], forbidden_flags = ["force"])"#,
+        r#"prefix_rule(pattern = ["rm"], paths_within = ["build"])"#,
+    ];
+    for policy_src in cases {
+        let mut parser = PolicyParser::new();
+        assert!(
+            parser.parse("test.codexpolicy", policy_src).is_err(),
+            "expected {policy_src} to be rejected"
+        );
+    }
+}
+
+#[test]
+fn rules_record_their_source_location() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(pattern = ["git", "status"])
+
+prefix_rule(
+    pattern = [["npm", "pnpm"], "test"],
+    decision = "prompt",
+)
+"#;
+    let mut parser = PolicyParser::new();
+    parser.parse("team.codexpolicy", policy_src)?;
+    let policy = parser.build();
+
+    let sources: Vec<String> = policy
+        .matching_rules(&tokens(&["pnpm", "test"]), &MatchContext::default())
+        .into_iter()
+        .filter_map(|(rule, _)| policy.rule_source(rule).map(ToString::to_string))
+        .collect();
+    assert_eq!(vec!["team.codexpolicy:4".to_string()], sources);
+    Ok(())
+}
+
+#[test]
+fn policy_test_reports_failing_examples_and_fixtures() -> Result<()> {
+    let policy_dir = tempfile::tempdir()?;
+    std::fs::write(
+        policy_dir.path().join("team.codexpolicy"),
+        r#"
+prefix_rule(
+    pattern = ["rm", any_args()],
+    decision = "allow",
+    match = ["rm foo"],
+    not_match = ["rm -rf /"],
+)
+prefix_rule(pattern = ["git", "push"], decision = "prompt")
+"#,
+    )?;
+    let (policy, examples) = load_policy_dir(policy_dir.path())?;
+    let identifier = policy_dir.path().join("team.codexpolicy");
+    let rule_location = format!("{}:2", identifier.display());
+
+    let results = check_examples(&policy, &examples);
+    assert_eq!(
+        vec![
+            (rule_location.clone(), true),
+            (rule_location.clone(), false)
+        ],
+        results
+            .iter()
+            .map(|result| (result.location.clone(), result.passed))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        format!(
+            "FAIL {rule_location}: rm -rf /: expected no match, got match (matched {rule_location})"
+        ),
+        results[1].to_string()
+    );
+
+    let fixtures = parse_fixtures(
+        "ci.fixtures",
+        "# destructive commands must never be allowed\nrm -rf / -> forbidden\n\n'git' push -> prompt\nls -> unmatched\n",
+    )?;
+    let results = check_fixtures(&policy, &fixtures, &MatchContext::default());
+    assert_eq!(
+        vec![
+            ("ci.fixtures:2", "forbidden", "allow", false),
+            ("ci.fixtures:4", "prompt", "prompt", true),
+            ("ci.fixtures:5", "unmatched", "unmatched", true),
+        ],
+        results
+            .iter()
+            .map(|result| (
+                result.location.as_str(),
+                result.expected.as_str(),
+                result.actual.as_str(),
+                result.passed
+            ))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(vec![rule_location], results[0].matched_rules);
+    Ok(())
+}
+
+#[test]
+fn malformed_fixture_lines_are_rejected() {
+    for contents in ["git push", "git push -> maybe", " -> allow"] {
+        assert!(
+            parse_fixtures("ci.fixtures", contents).is_err(),
+            "expected {contents:?} to be rejected"
+        );
+    }
+}
+
+#[test]
+fn conditions_limit_rules_to_cwd_env_and_sandbox() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["terraform", "apply"],
+    decision = "forbidden",
+    not_cwd = ["infra/sandbox/**"],
+)
+prefix_rule(
+    pattern = ["kubectl"],
+    decision = "prompt",
+    env = {"KUBECONFIG": "*prod*"},
+)
+prefix_rule(
+    pattern = ["npm", "install"],
+    decision = "allow",
+    sandbox = ["workspace-write"],
+)
+"#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.codexpolicy", policy_src)?;
+    let policy = parser.build();
+    let context = |cwd: &str, kubeconfig: &str, sandbox: SandboxMode| MatchContext {
+        cwd: Some(PathBuf::from(cwd)),
+        env: Some(HashMap::from([(
+            "KUBECONFIG".to_string(),
+            kubeconfig.to_string(),
+        )])),
+        sandbox_mode: Some(sandbox),
+        ..Default::default()
+    };
+    let decision = |cmd: &[&str], context: &MatchContext| {
+        policy
+            .check_with_context(&tokens(cmd), context, &allow_all)
+            .matched_rules
+            .into_iter()
+            .find(|rule_match| !matches!(rule_match, RuleMatch::HeuristicsRuleMatch { .. }))
+            .map(|rule_match| rule_match.decision())
+    };
+
+    let app = context("/repo/app", "~/.kube/dev", SandboxMode::ReadOnly);
+    let sandbox = context(
+        "/repo/infra/sandbox/eu",
+        "~/.kube/prod",
+        SandboxMode::WorkspaceWrite,
+    );
+    assert_eq!(
+        vec![
+            decision(&["terraform", "apply"], &app),
+            decision(&["terraform", "apply"], &sandbox),
+            decision(&["kubectl", "get", "pods"], &app),
+            decision(&["kubectl", "get", "pods"], &sandbox),
+            decision(&["npm", "install"], &app),
+            decision(&["npm", "install"], &sandbox),
+        ],
+        vec![
+            Some(Decision::Forbidden),
+            None,
+            None,
+            Some(Decision::Prompt),
+            None,
+            Some(Decision::Allow),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_conditions_are_rejected() {
+    let cases = [
+        r#"prefix_rule(pattern = ["npm"], sandbox = ["sandboxed"])"#,
+        r#"prefix_rule(pattern = ["kubectl"], env = ["KUBECONFIG"])"#,
+        r#"prefix_rule(pattern = ["kubectl"], env = {"KUBECONFIG": 1})"#,
+    ];
+    for policy_src in cases {
+        let mut parser = PolicyParser::new();
+        assert!(
+            parser.parse("test.codexpolicy", policy_src).is_err(),
+            "expected {policy_src} to be rejected"
+        );
+    }
+}
+
+#[test]
+fn policy_tests_check_conditions_in_the_context_they_give() -> Result<()> {
+    let policy_dir = tempfile::tempdir()?;
+    std::fs::write(
+        policy_dir.path().join("team.codexpolicy"),
+        r#"
+prefix_rule(
+    pattern = ["kubectl"],
+    decision = "prompt",
+    env = {"KUBECONFIG": "*prod*"},
+    match = [{"command": "kubectl apply", "env": {"KUBECONFIG": "~/.kube/prod"}}],
+    not_match = [{"command": "kubectl apply", "env": {"KUBECONFIG": "~/.kube/dev"}}],
+)
+prefix_rule(
+    pattern = ["terraform", "apply"],
+    cwd = ["infra/sandbox/**"],
+    sandbox = ["workspace-write"],
+    match = [{"command": ["terraform", "apply"], "cwd": "/repo/infra/sandbox/eu", "sandbox": "workspace-write"}],
+    not_match = [{"command": "terraform apply", "cwd": "/repo/infra/prod"}],
+)
+"#,
+    )?;
+    let (policy, examples) = load_policy_dir(policy_dir.path())?;
+    let results = check_examples(&policy, &examples);
+    assert_eq!(
+        vec![true; 4],
+        results
+            .iter()
+            .map(|result| result.passed)
+            .collect::<Vec<_>>()
+    );
+
+    let fixtures = parse_fixtures(
+        "ci.fixtures",
+        "kubectl apply -> unmatched\n\
+         [KUBECONFIG=~/.kube/prod] kubectl apply -> prompt\n\
+         [cwd=infra/sandbox/eu] terraform apply -> allow\n\
+         [cwd=infra/sandbox/eu sandbox=read-only] terraform apply -> unmatched\n\
+         [cwd=infra/prod] terraform apply -> unmatched\n",
+    )?;
+    let context = MatchContext {
+        cwd: Some(PathBuf::from("/repo")),
+        env: Some(HashMap::new()),
+        sandbox_mode: Some(SandboxMode::WorkspaceWrite),
+        ..Default::default()
+    };
+    let results = check_fixtures(&policy, &fixtures, &context);
+    assert_eq!(
+        Vec::<String>::new(),
+        results
+            .iter()
+            .filter(|result| !result.passed)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn malformed_fixture_settings_and_example_dicts_are_rejected() {
+    for contents in [
+        "[cwd=infra kubectl apply -> prompt",
+        "[sandbox=sandboxed] npm install -> allow",
+        "[=value] ls -> unmatched",
+    ] {
+        assert!(
+            parse_fixtures("ci.fixtures", contents).is_err(),
+            "expected {contents:?} to be rejected"
+        );
+    }
+
+    for policy_src in [
+        r#"prefix_rule(pattern = ["npm"], match = [{"cwd": "/repo"}])"#,
+        r#"prefix_rule(pattern = ["npm"], match = [{"command": "npm", "shell": "bash"}])"#,
+        r#"prefix_rule(pattern = ["npm"], match = [{"command": "npm", "env": {"CI": 1}}])"#,
+    ] {
+        let mut parser = PolicyParser::new();
+        assert!(
+            parser.parse("test.codexpolicy", policy_src).is_err(),
+            "expected {policy_src} to be rejected"
+        );
+    }
+}
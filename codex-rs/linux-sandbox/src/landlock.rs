@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -6,6 +7,7 @@ use codex_core::error::CodexErr;
 use codex_core::error::Result;
 use codex_core::error::SandboxErr;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::canonicalize_existing_prefix;
 
 use landlock::ABI;
 use landlock::Access;
@@ -25,47 +27,79 @@ use seccompiler::SeccompRule;
 use seccompiler::TargetArch;
 use seccompiler::apply_filter;
 
+/// System directories that stay readable when a policy limits reads to a set
+/// of roots, so that programs can still be loaded and run.
+const LINUX_SYSTEM_READ_ROOTS: &[&str] = &[
+    "/bin", "/sbin", "/usr", "/lib", "/lib32", "/lib64", "/libx32", "/etc", "/opt", "/nix", "/dev",
+    "/proc", "/sys", "/run",
+];
+
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// With `proxied`, the process has already been moved into a network
+/// namespace where only the codex network proxy is reachable (see
+/// [`crate::proxy_bridge`]), so the child may open TCP connections even
+/// though the policy has no network access.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    proxied: bool,
 ) -> Result<()> {
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        install_network_seccomp_filter_on_current_thread(proxied)?;
     }
 
-    if !sandbox_policy.has_full_disk_write_access() {
+    if !sandbox_policy.has_full_disk_write_access() || !sandbox_policy.has_full_disk_read_access() {
         let writable_roots = sandbox_policy
             .get_writable_roots_with_cwd(cwd)
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        let readable_roots = match sandbox_policy.get_readable_roots_with_cwd(cwd) {
+            Some(mut roots) => {
+                roots.extend(LINUX_SYSTEM_READ_ROOTS.iter().map(PathBuf::from));
+                roots
+            }
+            None => vec![PathBuf::from("/")],
+        };
+        install_filesystem_landlock_rules_on_current_thread(
+            readable_roots,
+            writable_roots,
+            sandbox_policy.get_deny_read_paths(),
+        )?;
     }
 
-    // TODO(ragona): Add appropriate restrictions if
-    // `sandbox_policy.has_full_disk_read_access()` is `false`.
-
     Ok(())
 }
 
 /// Installs Landlock file-system rules on the current thread allowing read
-/// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// access to `readable_roots` and write access to `/dev/null` and the provided
+/// list of `writable_roots`. Nothing under `deny` is accessible.
 ///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
-fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathBuf>) -> Result<()> {
+fn install_filesystem_landlock_rules_on_current_thread(
+    readable_roots: Vec<PathBuf>,
+    writable_roots: Vec<PathBuf>,
+    deny: &[PathBuf],
+) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
 
+    let deny: Vec<PathBuf> = deny
+        .iter()
+        .map(|path| canonicalize_existing_prefix(path))
+        .collect();
+    let readable_roots = exclude_denied_paths(&readable_roots, &deny);
+    let writable_roots = exclude_denied_paths(&writable_roots, &deny);
+
     let mut ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
+        .add_rules(landlock::path_beneath_rules(&readable_roots, access_ro))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
@@ -82,9 +116,63 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
     Ok(())
 }
 
+/// Landlock rules can only grant access, so a denied path below a granted
+/// root is carved out by granting, in every directory from the root down to
+/// the denied path, the entries that exist right now other than the one
+/// leading to it. Roots are canonicalized and missing ones are dropped; `deny`
+/// must already be canonical.
+///
+/// Those directories are never granted as a whole, so their listings and
+/// anything created in them later are inaccessible. A denied path inside the
+/// cwd thus stops sandboxed commands from creating files directly in the cwd,
+/// and a denied path that does not exist yet stays protected once created.
+fn exclude_denied_paths(roots: &[PathBuf], deny: &[PathBuf]) -> Vec<PathBuf> {
+    let mut allowed = Vec::new();
+    let mut visited = HashSet::new();
+    for root in roots {
+        if let Ok(root) = root.canonicalize() {
+            collect_allowed_paths(root, deny, &mut visited, &mut allowed);
+        }
+    }
+    allowed
+}
+
+fn collect_allowed_paths(
+    path: PathBuf,
+    deny: &[PathBuf],
+    visited: &mut HashSet<PathBuf>,
+    allowed: &mut Vec<PathBuf>,
+) {
+    if !visited.insert(path.clone()) || deny.iter().any(|denied| path.starts_with(denied)) {
+        return;
+    }
+    if !deny.iter().any(|denied| denied.starts_with(&path)) {
+        allowed.push(path);
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(&path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // Resolve symlinks so a link pointing at (or above) a denied path
+        // cannot be used to reach it.
+        if let Ok(entry_path) = entry.path().canonicalize() {
+            collect_allowed_paths(entry_path, deny, visited, allowed);
+        }
+    }
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
-fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+///
+/// With `proxied`, the network namespace already limits connections to the
+/// proxy, so connecting and sending are allowed. Only TCP sockets can be
+/// created and TCP Fast Open is refused all the same, keeping the command to
+/// what the proxy bridge relays. `io_uring` is blocked because it bypasses
+/// seccomp.
+fn install_network_seccomp_filter_on_current_thread(
+    proxied: bool,
+) -> std::result::Result<(), SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
@@ -93,25 +181,29 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         rules.insert(nr, vec![]); // empty rule vec = unconditional match
     };
 
-    deny_syscall(libc::SYS_connect);
     deny_syscall(libc::SYS_accept);
     deny_syscall(libc::SYS_accept4);
     deny_syscall(libc::SYS_bind);
     deny_syscall(libc::SYS_listen);
-    deny_syscall(libc::SYS_getpeername);
-    deny_syscall(libc::SYS_getsockname);
-    deny_syscall(libc::SYS_shutdown);
-    deny_syscall(libc::SYS_sendto);
-    deny_syscall(libc::SYS_sendmsg);
-    deny_syscall(libc::SYS_sendmmsg);
-    // NOTE: allowing recvfrom allows some tools like: `cargo clippy` to run
-    // with their socketpair + child processes for sub-proc management
-    // deny_syscall(libc::SYS_recvfrom);
-    deny_syscall(libc::SYS_recvmsg);
-    deny_syscall(libc::SYS_recvmmsg);
-    deny_syscall(libc::SYS_getsockopt);
-    deny_syscall(libc::SYS_setsockopt);
     deny_syscall(libc::SYS_ptrace);
+    if proxied {
+        deny_syscall(libc::SYS_io_uring_setup);
+    } else {
+        deny_syscall(libc::SYS_connect);
+        deny_syscall(libc::SYS_getpeername);
+        deny_syscall(libc::SYS_getsockname);
+        deny_syscall(libc::SYS_shutdown);
+        deny_syscall(libc::SYS_sendto);
+        deny_syscall(libc::SYS_sendmsg);
+        deny_syscall(libc::SYS_sendmmsg);
+        // NOTE: allowing recvfrom allows some tools like: `cargo clippy` to run
+        // with their socketpair + child processes for sub-proc management
+        // deny_syscall(libc::SYS_recvfrom);
+        deny_syscall(libc::SYS_recvmsg);
+        deny_syscall(libc::SYS_recvmmsg);
+        deny_syscall(libc::SYS_getsockopt);
+        deny_syscall(libc::SYS_setsockopt);
+    }
 
     // For `socket` we allow AF_UNIX (arg0 == AF_UNIX) and deny everything else.
     let unix_only_rule = SeccompRule::new(vec![SeccompCondition::new(
@@ -121,7 +213,22 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         libc::AF_UNIX as u64,
     )?])?;
 
-    rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    if proxied {
+        rules.insert(libc::SYS_socket, tcp_only_socket_rules()?);
+        let fast_open = |flags_arg: u8| -> std::result::Result<SeccompRule, SandboxErr> {
+            Ok(SeccompRule::new(vec![SeccompCondition::new(
+                flags_arg,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::MaskedEq(libc::MSG_FASTOPEN as u64),
+                libc::MSG_FASTOPEN as u64,
+            )?])?)
+        };
+        rules.insert(libc::SYS_sendto, vec![fast_open(3)?]);
+        rules.insert(libc::SYS_sendmsg, vec![fast_open(2)?]);
+        rules.insert(libc::SYS_sendmmsg, vec![fast_open(3)?]);
+    } else {
+        rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    }
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
     let filter = SeccompFilter::new(
@@ -143,3 +250,44 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
 
     Ok(())
 }
+
+/// Rules matching every `socket` call except plain IPv4/IPv6 TCP sockets.
+fn tcp_only_socket_rules() -> std::result::Result<Vec<SeccompRule>, SandboxErr> {
+    let mut rules = vec![
+        SeccompRule::new(vec![
+            SeccompCondition::new(
+                0,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::AF_INET as u64,
+            )?,
+            SeccompCondition::new(
+                0,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::AF_INET6 as u64,
+            )?,
+        ])?,
+        SeccompRule::new(vec![
+            SeccompCondition::new(2, SeccompCmpArgLen::Dword, SeccompCmpOp::Ne, 0)?,
+            SeccompCondition::new(
+                2,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::IPPROTO_TCP as u64,
+            )?,
+        ])?,
+    ];
+    // The low bits of the type argument hold the socket type; the rest are
+    // flags such as SOCK_CLOEXEC.
+    const SOCK_TYPE_MASK: u64 = 0xf;
+    for socket_type in (0..=SOCK_TYPE_MASK).filter(|ty| *ty != libc::SOCK_STREAM as u64) {
+        rules.push(SeccompRule::new(vec![SeccompCondition::new(
+            1,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::MaskedEq(SOCK_TYPE_MASK),
+            socket_type,
+        )?])?);
+    }
+    Ok(rules)
+}
@@ -3,6 +3,7 @@ use std::ffi::CString;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::proxy_bridge::isolate_network_except_proxy;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
@@ -14,6 +15,12 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: codex_core::protocol::SandboxPolicy,
 
+    /// Port of the codex network proxy on localhost. When set and the policy
+    /// has no network access, the command can still reach the proxy at
+    /// `127.0.0.1` on this port, and nothing else.
+    #[arg(long = "network-proxy-port")]
+    pub network_proxy_port: Option<u16>,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,10 +30,18 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        network_proxy_port,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    // If the namespace cannot be set up, for example because user namespaces
+    // are disabled, the network stays blocked altogether.
+    let proxied = !sandbox_policy.has_full_network_access()
+        && network_proxy_port.is_some_and(|port| isolate_network_except_proxy(port).is_ok());
+
+    if let Err(e) =
+        apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd, proxied)
+    {
         panic!("error running landlock: {e:?}");
     }
 
@@ -0,0 +1,210 @@
+//! Network isolation for commands that may only reach the codex network
+//! proxy.
+//!
+//! The command runs in a fresh network namespace (inside a fresh user
+//! namespace unless we are root) whose only interface is its own loopback. A
+//! listener on `127.0.0.1:<port>` inside the namespace is handed to a
+//! forwarder process that stays in the original namespace and relays every
+//! connection to the proxy on the same port, so the proxy is the only
+//! endpoint outside the namespace the command can reach.
+
+use std::io;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+
+/// Moves the current process into a network namespace in which
+/// `127.0.0.1:port` leads to the proxy and nothing else is reachable. Must be
+/// called while the process is still single-threaded and before the sandbox
+/// policy forbids `bind`.
+///
+/// On error the forwarder exits on its own. The process may already have
+/// entered the namespace, so callers must treat the network as blocked.
+pub(crate) fn isolate_network_except_proxy(port: u16) -> io::Result<()> {
+    let (command_end, forwarder_end) = socket_pair()?;
+    let parent = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => return Err(io::Error::last_os_error()),
+        0 => {
+            drop(command_end);
+            run_forwarder(parent, forwarder_end, port);
+        }
+        _ => drop(forwarder_end),
+    }
+
+    enter_network_namespace()?;
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    send_fd(&command_end, listener.as_raw_fd())
+}
+
+fn enter_network_namespace() -> io::Result<()> {
+    let uid = unsafe { libc::geteuid() };
+    let gid = unsafe { libc::getegid() };
+    // Root can create the network namespace directly. Going through a user
+    // namespace would cost it access to files owned by other users.
+    let flags = if uid == 0 {
+        libc::CLONE_NEWNET
+    } else {
+        libc::CLONE_NEWUSER | libc::CLONE_NEWNET
+    };
+    if unsafe { libc::unshare(flags) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if uid != 0 {
+        // Map the user onto itself so files keep their owners.
+        std::fs::write("/proc/self/setgroups", "deny")?;
+        std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+        std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    }
+    bring_up_loopback()
+}
+
+/// A new network namespace starts with its loopback interface down.
+fn bring_up_loopback() -> io::Result<()> {
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(socket) };
+
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCGIFFLAGS as _, &mut request) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe { request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short };
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS as _, &request) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Body of the forwarder process, which never returns. It dies with the
+/// command (its parent) and exits early if the listener never arrives.
+fn run_forwarder(parent: libc::pid_t, channel: OwnedFd, port: u16) -> ! {
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+        if libc::getppid() != parent {
+            libc::_exit(0);
+        }
+    }
+    // Keep whoever reads the command's output from also waiting on us.
+    if let Ok(dev_null) = std::fs::File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+    {
+        for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+            unsafe { libc::dup2(dev_null.as_raw_fd(), fd) };
+        }
+    }
+
+    let Ok(listener) = recv_fd(&channel) else {
+        unsafe { libc::_exit(0) };
+    };
+    drop(channel);
+    for inbound in TcpListener::from(listener).incoming().flatten() {
+        std::thread::spawn(move || relay(inbound, port));
+    }
+    unsafe { libc::_exit(0) }
+}
+
+/// Copies bytes both ways between a connection from the command and a new
+/// connection to the proxy until both sides are done.
+fn relay(inbound: TcpStream, port: u16) {
+    let Ok(outbound) = TcpStream::connect((Ipv4Addr::LOCALHOST, port)) else {
+        return;
+    };
+    let (Ok(inbound_reader), Ok(outbound_writer)) = (inbound.try_clone(), outbound.try_clone())
+    else {
+        return;
+    };
+    let upload = std::thread::spawn(move || copy_then_shutdown(inbound_reader, outbound_writer));
+    copy_then_shutdown(outbound, inbound);
+    let _ = upload.join();
+}
+
+fn copy_then_shutdown(mut from: TcpStream, mut to: TcpStream) {
+    let _ = io::copy(&mut from, &mut to);
+    let _ = to.shutdown(Shutdown::Write);
+}
+
+fn socket_pair() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0 as RawFd; 2];
+    if unsafe {
+        libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
+            0,
+            fds.as_mut_ptr(),
+        )
+    } != 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+}
+
+/// Space for a control message carrying a single file descriptor.
+const FD_CMSG_SPACE: usize =
+    unsafe { libc::CMSG_SPACE(std::mem::size_of::<RawFd>() as libc::c_uint) } as usize;
+
+fn send_fd(channel: &OwnedFd, fd: RawFd) -> io::Result<()> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control = [0u8; FD_CMSG_SPACE];
+    let mut message: libc::msghdr = unsafe { std::mem::zeroed() };
+    message.msg_iov = &mut iov;
+    message.msg_iovlen = 1;
+    message.msg_control = control.as_mut_ptr().cast();
+    message.msg_controllen = control.len() as _;
+    unsafe {
+        let header = libc::CMSG_FIRSTHDR(&message);
+        (*header).cmsg_level = libc::SOL_SOCKET;
+        (*header).cmsg_type = libc::SCM_RIGHTS;
+        (*header).cmsg_len = libc::CMSG_LEN(std::mem::size_of::<RawFd>() as libc::c_uint) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(header).cast::<RawFd>(), fd);
+        if libc::sendmsg(channel.as_raw_fd(), &message, 0) != 1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+fn recv_fd(channel: &OwnedFd) -> io::Result<OwnedFd> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control = [0u8; FD_CMSG_SPACE];
+    let mut message: libc::msghdr = unsafe { std::mem::zeroed() };
+    message.msg_iov = &mut iov;
+    message.msg_iovlen = 1;
+    message.msg_control = control.as_mut_ptr().cast();
+    message.msg_controllen = control.len() as _;
+    unsafe {
+        if libc::recvmsg(channel.as_raw_fd(), &mut message, libc::MSG_CMSG_CLOEXEC) != 1 {
+            return Err(io::Error::last_os_error());
+        }
+        let header = libc::CMSG_FIRSTHDR(&message);
+        if header.is_null()
+            || (*header).cmsg_level != libc::SOL_SOCKET
+            || (*header).cmsg_type != libc::SCM_RIGHTS
+        {
+            return Err(io::Error::other("no file descriptor received"));
+        }
+        let fd = std::ptr::read_unaligned(libc::CMSG_DATA(header).cast::<RawFd>());
+        Ok(OwnedFd::from_raw_fd(fd))
+    }
+}
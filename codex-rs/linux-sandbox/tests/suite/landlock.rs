@@ -7,6 +7,7 @@ use codex_core::exec::process_exec_tool_call;
 use codex_core::exec_env::create_env;
 use codex_core::protocol::SandboxPolicy;
 use std::collections::HashMap;
+use std::net::TcpListener;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
@@ -32,8 +33,23 @@ fn create_env_from_core_vars() -> HashMap<String, String> {
     create_env(&policy)
 }
 
-#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
 async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: writable_roots.to_vec(),
+        network_access: false,
+        // Exclude tmp-related folders from writable roots because we need a
+        // folder that is writable by tests but that we intentionally disallow
+        // writing to in the sandbox.
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        readable_roots: Vec::new(),
+        deny_read: Vec::new(),
+    };
+    run_cmd_with_policy(cmd, &sandbox_policy, timeout_ms).await;
+}
+
+#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
+async fn run_cmd_with_policy(cmd: &[&str], sandbox_policy: &SandboxPolicy, timeout_ms: u64) {
     let cwd = std::env::current_dir().expect("cwd should exist");
     let sandbox_cwd = cwd.clone();
     let params = ExecParams {
@@ -46,20 +62,11 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
         arg0: None,
     };
 
-    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
-        writable_roots: writable_roots.to_vec(),
-        network_access: false,
-        // Exclude tmp-related folders from writable roots because we need a
-        // folder that is writable by tests but that we intentionally disallow
-        // writing to in the sandbox.
-        exclude_tmpdir_env_var: true,
-        exclude_slash_tmp: true,
-    };
     let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
     let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
     let res = process_exec_tool_call(
         params,
-        &sandbox_policy,
+        sandbox_policy,
         sandbox_cwd.as_path(),
         &codex_linux_sandbox_exe,
         None,
@@ -122,6 +129,149 @@ async fn test_writable_root() {
     .await;
 }
 
+fn deny_read_policy(deny_read: Vec<PathBuf>) -> SandboxPolicy {
+    let mut sandbox_policy = SandboxPolicy::new_read_only_policy();
+    sandbox_policy.restrict_reads(&[], &deny_read);
+    sandbox_policy
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_deny_read_file() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secret = tmpdir.path().join("secret");
+    std::fs::write(&secret, "hunter2").unwrap();
+    run_cmd_with_policy(
+        &["cat", &secret.to_string_lossy()],
+        &deny_read_policy(vec![secret.clone()]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deny_read_keeps_siblings_readable() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secret = tmpdir.path().join("secret");
+    let public = tmpdir.path().join("public");
+    std::fs::write(&secret, "hunter2").unwrap();
+    std::fs::write(&public, "hello").unwrap();
+    run_cmd_with_policy(
+        &["cat", &public.to_string_lossy()],
+        &deny_read_policy(vec![secret]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_deny_read_path_created_after_setup() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secret = tmpdir.path().join("secret");
+    let mut sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![tmpdir.path().to_path_buf()],
+        network_access: false,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        readable_roots: Vec::new(),
+        deny_read: Vec::new(),
+    };
+    sandbox_policy.restrict_reads(&[], std::slice::from_ref(&secret));
+    let script = format!("echo hunter2 > {0} && cat {0}", secret.to_string_lossy());
+    run_cmd_with_policy(&["bash", "-c", &script], &sandbox_policy, LONG_TIMEOUT_MS).await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_read_outside_readable_roots() {
+    let readable = tempfile::tempdir().unwrap();
+    let other = tempfile::tempdir().unwrap();
+    let file_path = other.path().join("file");
+    std::fs::write(&file_path, "hello").unwrap();
+    let mut sandbox_policy = SandboxPolicy::new_read_only_policy();
+    sandbox_policy.restrict_reads(&[readable.path().to_path_buf()], &[]);
+    run_cmd_with_policy(
+        &["cat", &file_path.to_string_lossy()],
+        &sandbox_policy,
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_read_inside_readable_roots() {
+    let readable = tempfile::tempdir().unwrap();
+    let file_path = readable.path().join("file");
+    std::fs::write(&file_path, "hello").unwrap();
+    let mut sandbox_policy = SandboxPolicy::new_read_only_policy();
+    sandbox_policy.restrict_reads(&[readable.path().to_path_buf()], &[]);
+    run_cmd_with_policy(
+        &["cat", &file_path.to_string_lossy()],
+        &sandbox_policy,
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+/// Runs `bash -c script` under the sandbox helper with a read-only policy
+/// that lets it reach `network_proxy_port`, returning whether it succeeded.
+#[expect(clippy::expect_used)]
+fn run_with_network_proxy_port(script: &str, network_proxy_port: u16) -> bool {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    std::process::Command::new(env!("CARGO_BIN_EXE_codex-linux-sandbox"))
+        .arg("--sandbox-policy-cwd")
+        .arg(&cwd)
+        .args(["--sandbox-policy", r#"{"type":"read-only"}"#])
+        .args(["--network-proxy-port", &network_proxy_port.to_string()])
+        .args(["--", "bash", "-c", script])
+        .status()
+        .expect("sandbox helper should run")
+        .success()
+}
+
+#[test]
+fn test_network_proxy_port_is_reachable() {
+    let proxy = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = proxy.local_addr().unwrap().port();
+    assert!(run_with_network_proxy_port(
+        &format!("echo hi > /dev/tcp/127.0.0.1/{port}"),
+        port
+    ));
+}
+
+#[test]
+fn test_network_proxy_port_does_not_open_other_ports() {
+    let proxy = TcpListener::bind("127.0.0.1:0").unwrap();
+    let other = TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy_port = proxy.local_addr().unwrap().port();
+    let other_port = other.local_addr().unwrap().port();
+    assert!(!run_with_network_proxy_port(
+        &format!("echo hi > /dev/tcp/127.0.0.1/{other_port}"),
+        proxy_port
+    ));
+    assert!(!run_with_network_proxy_port(
+        &format!("echo hi > /dev/udp/127.0.0.1/{proxy_port}"),
+        proxy_port
+    ));
+}
+
+#[test]
+fn test_network_proxy_port_is_only_reachable_on_the_proxy_address() {
+    // Listens on every local address, like another host serving the proxy
+    // port would.
+    let proxy = TcpListener::bind("0.0.0.0:0").unwrap();
+    let port = proxy.local_addr().unwrap().port();
+    assert!(run_with_network_proxy_port(
+        &format!("echo hi > /dev/tcp/127.0.0.1/{port}"),
+        port
+    ));
+    assert!(!run_with_network_proxy_port(
+        &format!("echo hi > /dev/tcp/127.0.0.2/{port}"),
+        port
+    ));
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
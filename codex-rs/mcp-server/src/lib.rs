@@ -25,6 +25,7 @@ mod codex_tool_runner;
 mod error_code;
 mod exec_approval;
 pub(crate) mod message_processor;
+mod network_approval;
 mod outgoing_message;
 mod patch_approval;
 
@@ -36,6 +37,8 @@ pub use crate::codex_tool_config::CodexToolCallParam;
 pub use crate::codex_tool_config::CodexToolCallReplyParam;
 pub use crate::exec_approval::ExecApprovalElicitRequestParams;
 pub use crate::exec_approval::ExecApprovalResponse;
+pub use crate::network_approval::NetworkApprovalElicitRequestParams;
+pub use crate::network_approval::NetworkApprovalResponse;
 pub use crate::patch_approval::PatchApprovalElicitRequestParams;
 pub use crate::patch_approval::PatchApprovalResponse;
 
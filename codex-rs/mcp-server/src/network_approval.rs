@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use codex_core::CodexConversation;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use mcp_types::ElicitRequest;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ModelContextProtocolRequest;
+use mcp_types::RequestId;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use tracing::error;
+
+use crate::codex_tool_runner::INVALID_PARAMS_ERROR_CODE;
+use crate::outgoing_message::OutgoingMessageSender;
+
+#[derive(Debug, Serialize)]
+pub struct NetworkApprovalElicitRequestParams {
+    pub message: String,
+    #[serde(rename = "requestedSchema")]
+    pub requested_schema: ElicitRequestParamsRequestedSchema,
+    pub codex_elicitation: String,
+    pub codex_mcp_tool_call_id: String,
+    pub codex_event_id: String,
+    pub codex_call_id: String,
+    pub codex_host: String,
+    pub codex_port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codex_reason: Option<String>,
+}
+
+/// `denied` blocks the host for the rest of the session, `abort` only refuses
+/// this connection, and `approved_for_session` allows the host from now on.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NetworkApprovalResponse {
+    pub decision: ReviewDecision,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn handle_network_approval_request(
+    call_id: String,
+    host: String,
+    port: u16,
+    reason: Option<String>,
+    outgoing: Arc<OutgoingMessageSender>,
+    codex: Arc<CodexConversation>,
+    request_id: RequestId,
+    tool_call_id: String,
+    event_id: String,
+) {
+    let mut message_lines = Vec::new();
+    if let Some(r) = &reason {
+        message_lines.push(r.clone());
+    }
+    message_lines.push(format!(
+        "Allow a sandboxed command to connect to {host}:{port}?"
+    ));
+
+    let params = NetworkApprovalElicitRequestParams {
+        message: message_lines.join("\n"),
+        requested_schema: ElicitRequestParamsRequestedSchema {
+            r#type: "object".to_string(),
+            properties: json!({}),
+            required: None,
+        },
+        codex_elicitation: "network-approval".to_string(),
+        codex_mcp_tool_call_id: tool_call_id,
+        codex_event_id: event_id,
+        codex_call_id: call_id.clone(),
+        codex_host: host,
+        codex_port: port,
+        codex_reason: reason,
+    };
+    let params_json = match serde_json::to_value(&params) {
+        Ok(value) => value,
+        Err(err) => {
+            let message = format!("Failed to serialize NetworkApprovalElicitRequestParams: {err}");
+            error!("{message}");
+
+            outgoing
+                .send_error(
+                    request_id.clone(),
+                    JSONRPCErrorError {
+                        code: INVALID_PARAMS_ERROR_CODE,
+                        message,
+                        data: None,
+                    },
+                )
+                .await;
+
+            return;
+        }
+    };
+
+    let on_response = outgoing
+        .send_request(ElicitRequest::METHOD, Some(params_json))
+        .await;
+
+    // Listen for the response on a separate task so we don't block the main agent loop.
+    tokio::spawn(async move {
+        on_network_approval_response(call_id, on_response, codex).await;
+    });
+}
+
+pub(crate) async fn on_network_approval_response(
+    call_id: String,
+    receiver: tokio::sync::oneshot::Receiver<mcp_types::Result>,
+    codex: Arc<CodexConversation>,
+) {
+    // Without an answer, only this connection is refused.
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<NetworkApprovalResponse>(value)
+            .map(|response| response.decision)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize NetworkApprovalResponse: {err}");
+                ReviewDecision::Abort
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Abort
+        }
+    };
+
+    if let Err(err) = codex
+        .submit(Op::NetworkApproval {
+            id: call_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit NetworkApproval: {err}");
+    }
+}
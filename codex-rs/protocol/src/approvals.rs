@@ -86,6 +86,20 @@ pub struct ExecApprovalRequestEvent {
     pub parsed_cmd: Vec<ParsedCommand>,
 }
 
+/// A sandboxed command tried to reach a host outside
+/// `network_proxy.allowed_domains`. Answered with `Op::NetworkApproval`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct NetworkApprovalRequestEvent {
+    /// Identifier to answer the request with.
+    pub call_id: String,
+    /// Turn ID of the command that made the request.
+    pub turn_id: String,
+    pub host: String,
+    pub port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ElicitationRequestEvent {
     pub server_name: String,
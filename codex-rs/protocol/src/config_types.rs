@@ -46,6 +46,27 @@ pub enum Verbosity {
     High,
 }
 
+/// How conversation history is compacted, both automatically near the
+/// context limit and on `/compact`.
+#[derive(
+    Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum CompactionStrategy {
+    /// Ask the model to summarize the conversation.
+    #[default]
+    Summarize,
+    /// Replace long tool outputs outside the most recent turns with a
+    /// placeholder.
+    ElideToolOutputs,
+    /// Keep only the most recent turns and drop the older ones.
+    KeepRecent,
+    /// Summarize older turns locally from the requests, commands and file
+    /// changes they contain, without a model call.
+    Heuristic,
+}
+
 #[derive(
     Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize, Display, JsonSchema, TS,
 )]
@@ -12,6 +12,7 @@ use std::time::Duration;
 
 use crate::ConversationId;
 use crate::approvals::ElicitationRequestEvent;
+use crate::config_types::CompactionStrategy;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use crate::custom_prompts::CustomPrompt;
 use crate::items::TurnItem;
@@ -40,6 +41,7 @@ pub use crate::approvals::ApplyPatchApprovalRequestEvent;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecPolicyAmendment;
+pub use crate::approvals::NetworkApprovalRequestEvent;
 pub use crate::approvals::SandboxCommandAssessment;
 pub use crate::approvals::SandboxRiskLevel;
 
@@ -157,6 +159,14 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Allow or deny a connection to a host that is not allowlisted
+    NetworkApproval {
+        /// The `call_id` of the [`NetworkApprovalRequestEvent`]
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
     /// Resolve an MCP elicitation request.
     ResolveElicitation {
         /// Name of the MCP server that issued the request.
@@ -186,10 +196,19 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
-    /// Request the agent to summarize the current conversation context.
-    /// The agent will use its existing context (either conversation history or previous response id)
-    /// to generate a summary which will be returned as an AgentMessage event.
-    Compact,
+    /// Request an estimate of what the next prompt is made of.
+    /// Reply is delivered via `EventMsg::ContextBreakdown`.
+    GetContextBreakdown,
+
+    /// Request the agent to compact the current conversation context.
+    /// With the summarize strategy the agent will use its existing context (either conversation
+    /// history or previous response id) to generate a summary which will be returned as an
+    /// AgentMessage event.
+    Compact {
+        /// Strategy for this compaction; defaults to the configured one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        strategy: Option<CompactionStrategy>,
+    },
 
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
@@ -212,6 +231,19 @@ pub enum Op {
 
     /// Request the list of available models.
     ListModels,
+
+    /// Record a change to the session's title, tags or starred flag in its
+    /// rollout.
+    UpdateSessionLabels { labels: SessionLabelsItem },
+
+    /// Pin a note to the session. Pinned notes are re-injected verbatim into
+    /// the conversation after every compaction.
+    /// Reply is delivered via `EventMsg::PinnedContextUpdated`.
+    PinContext { text: String },
+
+    /// Remove the pinned note with the given id.
+    /// Reply is delivered via `EventMsg::PinnedContextUpdated`.
+    UnpinContext { id: u32 },
 }
 
 /// Determines the conditions under which the user is consulted to approve
@@ -264,9 +296,20 @@ pub enum SandboxPolicy {
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
 
-    /// Read-only access to the entire file-system.
+    /// Read-only access to the file-system.
     #[serde(rename = "read-only")]
-    ReadOnly,
+    ReadOnly {
+        /// When non-empty, reads are limited to these folders, the current
+        /// working directory and the platform's system directories. Empty
+        /// means the entire file-system is readable.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        readable_roots: Vec<PathBuf>,
+
+        /// Paths that must not be readable from within the sandbox, even when
+        /// they fall under a readable or writable root.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny_read: Vec<PathBuf>,
+    },
 
     /// Same as `ReadOnly` but additionally grants write access to the current
     /// working directory ("workspace").
@@ -292,6 +335,17 @@ pub enum SandboxPolicy {
         /// writable roots on UNIX. Defaults to `false`.
         #[serde(default)]
         exclude_slash_tmp: bool,
+
+        /// When non-empty, reads are limited to these folders, the writable
+        /// roots and the platform's system directories. Empty means the
+        /// entire file-system is readable.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        readable_roots: Vec<PathBuf>,
+
+        /// Paths that must be neither readable nor writable from within the
+        /// sandbox, even when they fall under a readable or writable root.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny_read: Vec<PathBuf>,
     },
 }
 
@@ -337,7 +391,10 @@ impl FromStr for SandboxPolicy {
 impl SandboxPolicy {
     /// Returns a policy with read-only disk access and no network.
     pub fn new_read_only_policy() -> Self {
-        SandboxPolicy::ReadOnly
+        SandboxPolicy::ReadOnly {
+            readable_roots: vec![],
+            deny_read: vec![],
+        }
     }
 
     /// Returns a policy that can read the entire disk, but can only write to
@@ -349,18 +406,122 @@ impl SandboxPolicy {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            readable_roots: vec![],
+            deny_read: vec![],
         }
     }
 
-    /// Always returns `true`; restricting read access is not supported.
+    /// Returns `false` when the policy limits reads to a set of roots or
+    /// denies reading any path.
     pub fn has_full_disk_read_access(&self) -> bool {
-        true
+        match self {
+            SandboxPolicy::DangerFullAccess => true,
+            SandboxPolicy::ReadOnly {
+                readable_roots,
+                deny_read,
+            }
+            | SandboxPolicy::WorkspaceWrite {
+                readable_roots,
+                deny_read,
+                ..
+            } => readable_roots.is_empty() && deny_read.is_empty(),
+        }
+    }
+
+    /// Returns the folders reads are limited to, or `None` when reads are not
+    /// limited to specific roots (paths in [`Self::get_deny_read_paths`] may
+    /// still be excluded). The result includes `cwd` and every writable root
+    /// but not the platform's system directories, which each sandbox
+    /// implementation adds on its own.
+    pub fn get_readable_roots_with_cwd(&self, cwd: &Path) -> Option<Vec<PathBuf>> {
+        let readable_roots = match self {
+            SandboxPolicy::DangerFullAccess => return None,
+            SandboxPolicy::ReadOnly { readable_roots, .. }
+            | SandboxPolicy::WorkspaceWrite { readable_roots, .. } => readable_roots,
+        };
+        if readable_roots.is_empty() {
+            return None;
+        }
+
+        let mut roots = readable_roots.clone();
+        roots.push(cwd.to_path_buf());
+        for writable_root in self.get_writable_roots_with_cwd(cwd) {
+            if !roots.contains(&writable_root.root) {
+                roots.push(writable_root.root);
+            }
+        }
+        Some(roots)
+    }
+
+    /// Paths that must stay unreadable (and unwritable) inside the sandbox.
+    pub fn get_deny_read_paths(&self) -> &[PathBuf] {
+        match self {
+            SandboxPolicy::DangerFullAccess => &[],
+            SandboxPolicy::ReadOnly { deny_read, .. }
+            | SandboxPolicy::WorkspaceWrite { deny_read, .. } => deny_read,
+        }
+    }
+
+    /// Whether `path` may be read under this policy. Used by tools that read
+    /// files in-process rather than through a sandboxed command.
+    pub fn is_path_readable(&self, path: &Path, cwd: &Path) -> bool {
+        let path = canonicalize_existing_prefix(path);
+        if self
+            .get_deny_read_paths()
+            .iter()
+            .any(|denied| path.starts_with(canonicalize_existing_prefix(denied)))
+        {
+            return false;
+        }
+        match self.get_readable_roots_with_cwd(cwd) {
+            None => true,
+            Some(roots) => roots
+                .iter()
+                .any(|root| path.starts_with(canonicalize_existing_prefix(root))),
+        }
+    }
+
+    /// Limits reads to `readable_roots` unless the policy already names its
+    /// own, and adds `deny_read` to the deny-list. Has no effect on
+    /// `DangerFullAccess`.
+    pub fn restrict_reads(&mut self, readable_roots: &[PathBuf], deny_read: &[PathBuf]) {
+        let (SandboxPolicy::ReadOnly {
+            readable_roots: current_roots,
+            deny_read: current_deny,
+        }
+        | SandboxPolicy::WorkspaceWrite {
+            readable_roots: current_roots,
+            deny_read: current_deny,
+            ..
+        }) = self
+        else {
+            return;
+        };
+        if current_roots.is_empty() {
+            current_roots.extend_from_slice(readable_roots);
+        }
+        for path in deny_read {
+            if !current_deny.contains(path) {
+                current_deny.push(path.clone());
+            }
+        }
+    }
+
+    /// Applies the read restrictions of `other` to this policy, so switching
+    /// sandbox modes cannot widen what the agent may read.
+    pub fn inherit_read_restrictions(&mut self, other: &SandboxPolicy) {
+        let readable_roots = match other {
+            SandboxPolicy::DangerFullAccess => return,
+            SandboxPolicy::ReadOnly { readable_roots, .. }
+            | SandboxPolicy::WorkspaceWrite { readable_roots, .. } => readable_roots,
+        };
+        self.restrict_reads(readable_roots, other.get_deny_read_paths());
     }
 
     pub fn has_full_disk_write_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { .. } => false,
         }
     }
@@ -368,7 +529,7 @@ impl SandboxPolicy {
     pub fn has_full_network_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { network_access, .. } => *network_access,
         }
     }
@@ -379,12 +540,12 @@ impl SandboxPolicy {
     pub fn get_writable_roots_with_cwd(&self, cwd: &Path) -> Vec<WritableRoot> {
         match self {
             SandboxPolicy::DangerFullAccess => Vec::new(),
-            SandboxPolicy::ReadOnly => Vec::new(),
+            SandboxPolicy::ReadOnly { .. } => Vec::new(),
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
-                network_access: _,
+                ..
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -436,6 +597,29 @@ impl SandboxPolicy {
     }
 }
 
+/// Canonicalizes the longest existing ancestor of `path` and appends the
+/// remaining components, so symlinks above a path that does not exist yet are
+/// still resolved and it compares equal to where it would be created.
+pub fn canonicalize_existing_prefix(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(canonical) = current.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |resolved, name| resolved.join(name));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                current = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
 /// Event Queue Entry - events from agent
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Event {
@@ -459,6 +643,11 @@ pub enum EventMsg {
     /// indicates the task continued but the user should still be notified.
     Warning(WarningEvent),
 
+    /// A configured turn or session budget crossed its warning threshold or
+    /// was exhausted. Exhausting a budget aborts the turn with
+    /// [`TurnAbortReason::BudgetExceeded`].
+    BudgetWarning(BudgetWarningEvent),
+
     /// Conversation history was compacted (either automatically or manually).
     ContextCompacted(ContextCompactedEvent),
 
@@ -523,12 +712,27 @@ pub enum EventMsg {
     /// Notification that the agent attached a local image via the view_image tool.
     ViewImageToolCall(ViewImageToolCallEvent),
 
+    /// A sub-agent spawned by the `spawn_agent` tool started working.
+    SubAgentBegin(SubAgentBeginEvent),
+
+    /// An event emitted by a running sub-agent, nested under the tool call
+    /// that spawned it.
+    SubAgentActivity(SubAgentActivityEvent),
+
+    /// A sub-agent spawned by the `spawn_agent` tool finished.
+    SubAgentEnd(SubAgentEndEvent),
+
+    /// The notes pinned to the session changed.
+    PinnedContextUpdated(PinnedContextEvent),
+
     ExecApprovalRequest(ExecApprovalRequestEvent),
 
     ElicitationRequest(ElicitationRequestEvent),
 
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    NetworkApprovalRequest(NetworkApprovalRequestEvent),
+
     /// Notification advising the user that something they are using has been
     /// deprecated and should be phased out.
     DeprecationNotice(DeprecationNoticeEvent),
@@ -561,6 +765,9 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Estimated size of the next prompt, by category.
+    ContextBreakdown(ContextBreakdownEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -746,6 +953,66 @@ pub struct WarningEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct BudgetWarningEvent {
+    pub scope: BudgetScope,
+    pub limit: BudgetLimit,
+    /// Amount consumed so far, in the unit of `limit`.
+    pub used: f64,
+    /// Configured hard limit, in the unit of `limit`.
+    pub max: f64,
+    /// True when the hard limit was reached and the turn is being aborted.
+    pub exceeded: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    Turn,
+    Session,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLimit {
+    /// Total tokens reported by the model provider.
+    Tokens,
+    ToolCalls,
+    /// Wall-clock seconds.
+    Duration,
+    /// Estimated cost in US dollars.
+    Cost,
+}
+
+impl fmt::Display for BudgetWarningEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = match self.scope {
+            BudgetScope::Turn => "Turn",
+            BudgetScope::Session => "Session",
+        };
+        let (used, max) = match self.limit {
+            BudgetLimit::Tokens => (
+                format!("{} tokens", self.used as i64),
+                format!("{}", self.max as i64),
+            ),
+            BudgetLimit::ToolCalls => (
+                format!("{} tool calls", self.used as i64),
+                format!("{}", self.max as i64),
+            ),
+            BudgetLimit::Duration => (
+                format!("{}s", self.used.round() as i64),
+                format!("{}s", self.max.round() as i64),
+            ),
+            BudgetLimit::Cost => (format!("${:.2}", self.used), format!("${:.2}", self.max)),
+        };
+        if self.exceeded {
+            write!(f, "{scope} budget exhausted: {used} used of {max}")
+        } else {
+            write!(f, "{scope} budget running low: {used} used of {max}")
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ContextCompactedEvent;
 
@@ -759,7 +1026,7 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<i64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema, TS)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default, JsonSchema, TS)]
 pub struct TokenUsage {
     #[ts(type = "number")]
     pub input_tokens: i64,
@@ -1209,6 +1476,8 @@ pub enum RolloutItem {
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
     EventMsg(EventMsg),
+    SessionLabels(SessionLabelsItem),
+    ExecApprovalDecision(ExecApprovalDecisionItem),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
@@ -1230,6 +1499,37 @@ impl From<CompactedItem> for ResponseItem {
     }
 }
 
+/// A change to the user-set title, tags or starred flag of a session. Fields
+/// left unset keep the value recorded earlier in the rollout.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema, TS)]
+pub struct SessionLabelsItem {
+    /// New title for the session; an empty string clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Replaces all tags of the session, before `add_tags` and `remove_tags`
+    /// are applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Tags to add, keeping the tags other edits added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub add_tags: Option<Vec<String>>,
+    /// Tags to remove, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove_tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starred: Option<bool>,
+}
+
+/// The user's answer to an [`ExecApprovalRequestEvent`]. Recorded so that
+/// `codex execpolicy suggest` can turn repeated decisions into rules.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, TS)]
+pub struct ExecApprovalDecisionItem {
+    pub call_id: String,
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    pub decision: ReviewDecision,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
 pub struct TurnContextItem {
     pub cwd: PathBuf,
@@ -1434,6 +1734,51 @@ pub struct ViewImageToolCallEvent {
     pub path: PathBuf,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentBeginEvent {
+    /// Identifier for the `spawn_agent` tool call.
+    pub call_id: String,
+    /// Task delegated to the sub-agent.
+    pub task: String,
+    /// Model used by the sub-agent.
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentActivityEvent {
+    /// Identifier for the `spawn_agent` tool call that owns the sub-agent.
+    pub call_id: String,
+    /// Event emitted by the sub-agent.
+    pub msg: Box<EventMsg>,
+}
+
+/// A note pinned to the session, either by the user or by the model through
+/// the `remember` tool.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct PinnedNote {
+    /// Identifier used to unpin the note; unique within the session.
+    pub id: u32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct PinnedContextEvent {
+    /// All notes currently pinned, oldest first.
+    pub notes: Vec<PinnedNote>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentEndEvent {
+    /// Identifier for the `spawn_agent` tool call.
+    pub call_id: String,
+    /// Task delegated to the sub-agent (mirrors SubAgentBeginEvent::task).
+    pub task: String,
+    /// Model used by the sub-agent (mirrors SubAgentBeginEvent::model).
+    pub model: String,
+    /// Final message produced by the sub-agent, if it finished its turn.
+    pub last_agent_message: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecOutputStream {
@@ -1612,6 +1957,53 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::GetContextBreakdown`. Token counts are the same
+/// byte-based estimates used to track context usage, not tokenizer counts.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ContextBreakdownEvent {
+    #[ts(type = "number | null")]
+    pub model_context_window: Option<i64>,
+    /// Sum of every category below.
+    #[ts(type = "number")]
+    pub total_tokens: i64,
+    /// Base and developer instructions.
+    #[ts(type = "number")]
+    pub base_instructions: i64,
+    /// AGENTS.md and other project docs.
+    #[ts(type = "number")]
+    pub project_docs: i64,
+    /// The skills section appended to the project docs.
+    #[ts(type = "number")]
+    pub skills: i64,
+    /// Specs of the built-in tools.
+    #[ts(type = "number")]
+    pub builtin_tools: i64,
+    /// Specs of MCP tools, per server.
+    pub mcp_servers: Vec<McpServerContextUsage>,
+    /// User and assistant messages, including environment context and summaries.
+    #[ts(type = "number")]
+    pub messages: i64,
+    #[ts(type = "number")]
+    pub reasoning: i64,
+    /// Tool calls made by the model.
+    #[ts(type = "number")]
+    pub tool_calls: i64,
+    #[ts(type = "number")]
+    pub tool_outputs: i64,
+    /// Images attached to messages or returned by tools.
+    #[ts(type = "number")]
+    pub images: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpServerContextUsage {
+    pub server: String,
+    /// Number of tools the server exposes to the model.
+    pub tool_count: usize,
+    #[ts(type = "number")]
+    pub tokens: i64,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SessionConfiguredEvent {
     /// Name left as session_id instead of conversation_id for backwards compatibility.
@@ -1713,6 +2105,8 @@ pub enum TurnAbortReason {
     Interrupted,
     Replaced,
     ReviewEnded,
+    /// A configured turn or session budget was exhausted.
+    BudgetExceeded,
 }
 
 #[cfg(test)]
@@ -1768,7 +2162,7 @@ mod tests {
                 model: "codex-mini-latest".to_string(),
                 model_provider_id: "openai".to_string(),
                 approval_policy: AskForApproval::Never,
-                sandbox_policy: SandboxPolicy::ReadOnly,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 cwd: PathBuf::from("/home/user/project"),
                 reasoning_effort: Some(ReasoningEffortConfig::default()),
                 history_log_id: 0,
@@ -1860,4 +2254,65 @@ mod tests {
         assert_eq!(value["msg"]["cancelled"][0], "c");
         Ok(())
     }
+
+    #[test]
+    fn read_only_policy_without_read_fields_deserializes() -> Result<()> {
+        let policy: SandboxPolicy = serde_json::from_str(r#"{"type":"read-only"}"#)?;
+        assert_eq!(policy, SandboxPolicy::new_read_only_policy());
+        assert!(policy.has_full_disk_read_access());
+        assert_eq!(serde_json::to_string(&policy)?, r#"{"type":"read-only"}"#);
+        Ok(())
+    }
+
+    #[test]
+    fn read_restrictions_limit_readable_paths() {
+        let cwd = PathBuf::from("/work/project");
+        let mut policy = SandboxPolicy::new_workspace_write_policy();
+        policy.restrict_reads(
+            &[PathBuf::from("/data")],
+            &[PathBuf::from("/work/project/.env")],
+        );
+
+        assert!(!policy.has_full_disk_read_access());
+        assert!(policy.is_path_readable(Path::new("/work/project/src/main.rs"), &cwd));
+        assert!(policy.is_path_readable(Path::new("/data/input.csv"), &cwd));
+        assert!(!policy.is_path_readable(Path::new("/work/project/.env"), &cwd));
+        assert!(!policy.is_path_readable(Path::new("/etc/passwd"), &cwd));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn denied_path_behind_symlink_stays_denied_once_created() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let real = dir.path().join("real");
+        std::fs::create_dir(&real)?;
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&real, &link)?;
+
+        let mut policy = SandboxPolicy::new_read_only_policy();
+        policy.restrict_reads(&[], &[link.join("secret")]);
+
+        std::fs::write(real.join("secret"), "hunter2")?;
+        assert!(!policy.is_path_readable(&real.join("secret"), dir.path()));
+        assert!(!policy.is_path_readable(&link.join("secret"), dir.path()));
+        assert!(policy.is_path_readable(&real.join("public"), dir.path()));
+        Ok(())
+    }
+
+    #[test]
+    fn switching_modes_keeps_read_restrictions() {
+        let mut configured = SandboxPolicy::new_workspace_write_policy();
+        configured.restrict_reads(&[], &[PathBuf::from("/home/user/.ssh")]);
+
+        let mut switched = SandboxPolicy::new_read_only_policy();
+        switched.inherit_read_restrictions(&configured);
+
+        assert_eq!(
+            switched,
+            SandboxPolicy::ReadOnly {
+                readable_roots: Vec::new(),
+                deny_read: vec![PathBuf::from("/home/user/.ssh")],
+            }
+        );
+    }
 }
@@ -355,6 +355,31 @@ impl App {
                     resumed.session_configured,
                 )
             }
+            ResumeSelection::Fork {
+                path,
+                point,
+                restore_snapshot,
+            } => {
+                let forked = conversation_manager
+                    .fork_conversation_at(&point, config.clone(), path.clone(), restore_snapshot)
+                    .await
+                    .wrap_err_with(|| format!("Failed to fork session from {}", path.display()))?;
+                let init = crate::chatwidget::ChatWidgetInit {
+                    config: config.clone(),
+                    frame_requester: tui.frame_requester(),
+                    app_event_tx: app_event_tx.clone(),
+                    initial_prompt: initial_prompt.clone(),
+                    initial_images: initial_images.clone(),
+                    enhanced_keys_supported,
+                    auth_manager: auth_manager.clone(),
+                    models_manager: conversation_manager.get_models_manager(),
+                    feedback: feedback.clone(),
+                    skills: skills.clone(),
+                    is_first_run,
+                    model_family,
+                };
+                ChatWidget::new_from_existing(init, forked.conversation, forked.session_configured)
+            }
         };
 
         chat_widget.maybe_prompt_windows_sandbox_enable();
@@ -392,7 +417,7 @@ impl App {
                 && matches!(
                     app.config.sandbox_policy,
                     codex_core::protocol::SandboxPolicy::WorkspaceWrite { .. }
-                        | codex_core::protocol::SandboxPolicy::ReadOnly
+                        | codex_core::protocol::SandboxPolicy::ReadOnly { .. }
                 )
                 && !app
                     .config
@@ -529,6 +554,7 @@ impl App {
                     &self.config.codex_home,
                     &self.config.model_provider_id,
                     false,
+                    None,
                 )
                 .await?
                 {
@@ -588,7 +614,9 @@ impl App {
                             }
                         }
                     }
-                    ResumeSelection::Exit | ResumeSelection::StartFresh => {}
+                    ResumeSelection::Exit
+                    | ResumeSelection::StartFresh
+                    | ResumeSelection::Fork { .. } => {}
                 }
 
                 // Leaving alt-screen may blank the inline viewport; force a redraw either way.
@@ -700,6 +728,9 @@ impl App {
             AppEvent::OpenReasoningPopup { model } => {
                 self.chat_widget.open_reasoning_popup(model);
             }
+            AppEvent::OpenExportOptionsPopup { format } => {
+                self.chat_widget.open_export_options_popup(format);
+            }
             AppEvent::OpenFullAccessConfirmation { preset } => {
                 self.chat_widget.open_full_access_confirmation(preset);
             }
@@ -838,12 +869,12 @@ impl App {
                 let policy_is_workspace_write_or_ro = matches!(
                     policy,
                     codex_core::protocol::SandboxPolicy::WorkspaceWrite { .. }
-                        | codex_core::protocol::SandboxPolicy::ReadOnly
+                        | codex_core::protocol::SandboxPolicy::ReadOnly { .. }
                 );
 
                 self.config.sandbox_policy = policy.clone();
                 #[cfg(target_os = "windows")]
-                if !matches!(policy, codex_core::protocol::SandboxPolicy::ReadOnly)
+                if !matches!(policy, codex_core::protocol::SandboxPolicy::ReadOnly { .. })
                     || codex_core::get_platform_sandbox().is_some()
                 {
                     self.config.forced_auto_mode_downgraded_on_windows = false;
@@ -961,6 +992,12 @@ impl App {
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::OpenPinNotePrompt => {
+                self.chat_widget.show_pin_note_prompt();
+            }
+            AppEvent::ExplainExecApproval { command, cwd } => {
+                self.chat_widget.explain_exec_approval(command, cwd);
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -996,6 +1033,23 @@ impl App {
                         "E L I C I T A T I O N".to_string(),
                     ));
                 }
+                ApprovalRequest::Network {
+                    host, port, reason, ..
+                } => {
+                    let _ = tui.enter_alt_screen();
+                    let mut lines = vec![Line::from(vec![
+                        "Host: ".into(),
+                        format!("{host}:{port}").bold(),
+                    ])];
+                    if let Some(reason) = reason {
+                        lines.extend([Line::from(""), Line::from(reason)]);
+                    }
+                    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+                    self.overlay = Some(Overlay::new_static_with_renderables(
+                        vec![Box::new(paragraph)],
+                        "N E T W O R K".to_string(),
+                    ));
+                }
             },
         }
         Ok(true)
@@ -1326,7 +1380,7 @@ mod tests {
                 model: "gpt-test".to_string(),
                 model_provider_id: "test-provider".to_string(),
                 approval_policy: AskForApproval::Never,
-                sandbox_policy: SandboxPolicy::ReadOnly,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 cwd: PathBuf::from("/home/user/project"),
                 reasoning_effort: None,
                 history_log_id: 0,
@@ -1380,7 +1434,7 @@ mod tests {
             model: "gpt-test".to_string(),
             model_provider_id: "test-provider".to_string(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             cwd: PathBuf::from("/home/user/project"),
             reasoning_effort: None,
             history_log_id: 0,
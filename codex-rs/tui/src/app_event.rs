@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use codex_common::approval_presets::ApprovalPreset;
+use codex_core::export::ExportFormat;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::RateLimitSnapshot;
@@ -74,6 +75,11 @@ pub(crate) enum AppEvent {
         model: ModelPreset,
     },
 
+    /// Open the content selection popup after picking an export format.
+    OpenExportOptionsPopup {
+        format: ExportFormat,
+    },
+
     /// Open the confirmation prompt before enabling full access mode.
     OpenFullAccessConfirmation {
         preset: ApprovalPreset,
@@ -156,9 +162,18 @@ pub(crate) enum AppEvent {
     /// Open the custom prompt option from the review popup.
     OpenReviewCustomPrompt,
 
+    /// Open the prompt for writing a note from the `/pin` popup.
+    OpenPinNotePrompt,
+
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
+    /// Explain how execpolicy and the safety heuristics decided that a command needs approval.
+    ExplainExecApproval {
+        command: Vec<String>,
+        cwd: PathBuf,
+    },
+
     /// Open the feedback note entry overlay after the user selects a category.
     OpenFeedbackNote {
         category: FeedbackCategory,
@@ -44,6 +44,7 @@ pub(crate) enum ApprovalRequest {
     Exec {
         id: String,
         command: Vec<String>,
+        cwd: PathBuf,
         reason: Option<String>,
         risk: Option<SandboxCommandAssessment>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
@@ -59,6 +60,12 @@ pub(crate) enum ApprovalRequest {
         request_id: RequestId,
         message: String,
     },
+    Network {
+        id: String,
+        host: String,
+        port: u16,
+        reason: Option<String>,
+    },
 }
 
 /// Modal overlay asking the user to approve or deny one or more requests.
@@ -126,6 +133,10 @@ impl ApprovalOverlay {
                 elicitation_options(),
                 format!("{server_name} needs your approval."),
             ),
+            ApprovalVariant::Network { host, port, .. } => (
+                network_options(host),
+                format!("Would you like to allow a connection to {host}:{port}?"),
+            ),
         };
 
         let header = Box::new(ColumnRenderable::with([
@@ -134,6 +145,18 @@ impl ApprovalOverlay {
             header,
         ]));
 
+        let mut footer_hint = vec!["Press ".into(), key_hint::plain(KeyCode::Enter).into()];
+        if matches!(variant, ApprovalVariant::Exec { .. }) {
+            footer_hint.extend([
+                " to confirm, ".into(),
+                key_hint::plain(KeyCode::Char('e')).into(),
+                " to explain, or ".into(),
+            ]);
+        } else {
+            footer_hint.push(" to confirm or ".into());
+        }
+        footer_hint.extend([key_hint::plain(KeyCode::Esc).into(), " to cancel".into()]);
+
         let items = options
             .iter()
             .map(|opt| SelectionItem {
@@ -147,13 +170,7 @@ impl ApprovalOverlay {
             .collect();
 
         let params = SelectionViewParams {
-            footer_hint: Some(Line::from(vec![
-                "Press ".into(),
-                key_hint::plain(KeyCode::Enter).into(),
-                " to confirm or ".into(),
-                key_hint::plain(KeyCode::Esc).into(),
-                " to cancel".into(),
-            ])),
+            footer_hint: Some(Line::from(footer_hint)),
             items,
             header,
             ..Default::default()
@@ -186,6 +203,9 @@ impl ApprovalOverlay {
                 ) => {
                     self.handle_elicitation_decision(server_name, request_id, *decision);
                 }
+                (ApprovalVariant::Network { id, .. }, ApprovalDecision::Review(decision)) => {
+                    self.handle_network_decision(id, decision.clone());
+                }
                 _ => {}
             }
         }
@@ -210,6 +230,13 @@ impl ApprovalOverlay {
         }));
     }
 
+    fn handle_network_decision(&self, id: &str, decision: ReviewDecision) {
+        self.app_event_tx.send(AppEvent::CodexOp(Op::NetworkApproval {
+            id: id.to_string(),
+            decision,
+        }));
+    }
+
     fn handle_elicitation_decision(
         &self,
         server_name: &str,
@@ -248,6 +275,22 @@ impl ApprovalOverlay {
                     false
                 }
             }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if !self.current_complete
+                && matches!(self.current_variant, Some(ApprovalVariant::Exec { .. })) =>
+            {
+                if let Some(ApprovalVariant::Exec { command, cwd, .. }) = &self.current_variant {
+                    self.app_event_tx.send(AppEvent::ExplainExecApproval {
+                        command: command.clone(),
+                        cwd: cwd.clone(),
+                    });
+                }
+                true
+            }
             e => {
                 if let Some(idx) = self
                     .options
@@ -299,6 +342,9 @@ impl BottomPaneView for ApprovalOverlay {
                         ElicitationAction::Cancel,
                     );
                 }
+                ApprovalVariant::Network { id, .. } => {
+                    self.handle_network_decision(id, ReviewDecision::Abort);
+                }
             }
         }
         self.queue.clear();
@@ -344,6 +390,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
             ApprovalRequest::Exec {
                 id,
                 command,
+                cwd,
                 reason,
                 risk,
                 proposed_execpolicy_amendment,
@@ -369,6 +416,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     variant: ApprovalVariant::Exec {
                         id,
                         command,
+                        cwd,
                         proposed_execpolicy_amendment,
                     },
                     header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
@@ -415,6 +463,21 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     header: Box::new(header),
                 }
             }
+            ApprovalRequest::Network {
+                id,
+                host,
+                port,
+                reason,
+            } => {
+                let mut header = Vec::new();
+                if let Some(reason) = reason.filter(|reason| !reason.is_empty()) {
+                    header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
+                }
+                Self {
+                    variant: ApprovalVariant::Network { id, host, port },
+                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                }
+            }
         }
     }
 }
@@ -446,6 +509,7 @@ enum ApprovalVariant {
     Exec {
         id: String,
         command: Vec<String>,
+        cwd: PathBuf,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     },
     ApplyPatch {
@@ -455,6 +519,11 @@ enum ApprovalVariant {
         server_name: String,
         request_id: RequestId,
     },
+    Network {
+        id: String,
+        host: String,
+        port: u16,
+    },
 }
 
 #[derive(Clone)]
@@ -535,6 +604,29 @@ fn patch_options() -> Vec<ApprovalOption> {
     ]
 }
 
+fn network_options(host: &str) -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, allow this connection".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
+        ApprovalOption {
+            label: format!("Yes, and allow {host} for this session"),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: format!("No, block {host} for this session"),
+            decision: ApprovalDecision::Review(ReviewDecision::Denied),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        },
+    ]
+}
+
 fn elicitation_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
@@ -569,6 +661,7 @@ mod tests {
         ApprovalRequest::Exec {
             id: "test".to_string(),
             command: vec!["echo".to_string(), "hi".to_string()],
+            cwd: PathBuf::from("/workspace"),
             reason: Some("reason".to_string()),
             risk: None,
             proposed_execpolicy_amendment: None,
@@ -612,6 +705,7 @@ mod tests {
             ApprovalRequest::Exec {
                 id: "test".to_string(),
                 command: vec!["echo".to_string()],
+                cwd: PathBuf::from("/workspace"),
                 reason: None,
                 risk: None,
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
@@ -651,6 +745,7 @@ mod tests {
             ApprovalRequest::Exec {
                 id: "test".to_string(),
                 command: vec!["echo".to_string()],
+                cwd: PathBuf::from("/workspace"),
                 reason: None,
                 risk: None,
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
@@ -670,6 +765,38 @@ mod tests {
         assert!(rx.try_recv().is_err());
     }
 
+    #[test]
+    fn network_request_answers_with_a_network_approval() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(
+            ApprovalRequest::Network {
+                id: "network-1".to_string(),
+                host: "pypi.org".to_string(),
+                port: 443,
+                reason: None,
+            },
+            tx,
+            Features::with_defaults(),
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+        let mut saw_op = false;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(op) = ev {
+                assert_eq!(
+                    op,
+                    Op::NetworkApproval {
+                        id: "network-1".to_string(),
+                        decision: ReviewDecision::ApprovedForSession,
+                    }
+                );
+                saw_op = true;
+            }
+        }
+        assert!(saw_op, "expected the decision to emit a network approval");
+        assert!(view.is_complete());
+    }
+
     #[test]
     fn header_includes_command_snippet() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -678,6 +805,7 @@ mod tests {
         let exec_request = ApprovalRequest::Exec {
             id: "test".into(),
             command,
+            cwd: PathBuf::from("/workspace"),
             reason: None,
             risk: None,
             proposed_execpolicy_amendment: None,
@@ -729,6 +857,24 @@ mod tests {
         assert_eq!(rendered, expected);
     }
 
+    #[test]
+    fn explain_shortcut_requests_explanation_without_deciding() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+
+        assert!(!view.is_complete());
+        match rx.try_recv() {
+            Ok(AppEvent::ExplainExecApproval { command, cwd }) => {
+                assert_eq!(command, vec!["echo".to_string(), "hi".to_string()]);
+                assert_eq!(cwd, PathBuf::from("/workspace"));
+            }
+            other => panic!("expected an explain request, got {other:?}"),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
     #[test]
     fn enter_sets_last_selected_index_without_dismissing() {
         let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
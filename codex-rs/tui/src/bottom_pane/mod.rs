@@ -569,6 +569,7 @@ mod tests {
         ApprovalRequest::Exec {
             id: "1".to_string(),
             command: vec!["echo".into(), "ok".into()],
+            cwd: PathBuf::from("/workspace"),
             reason: None,
             risk: None,
             proposed_execpolicy_amendment: None,
@@ -9,8 +9,14 @@ use codex_app_server_protocol::AuthMode;
 use codex_backend_client::Client as BackendClient;
 use codex_core::config::Config;
 use codex_core::config::types::Notifications;
+use codex_core::exec_env::create_env;
+use codex_core::explain_command;
+use codex_core::export::ExportFormat;
+use codex_core::export::ExportOptions;
+use codex_core::export::export_rollout;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
+use codex_core::load_exec_policy_for_features;
 use codex_core::openai_models::model_family::ModelFamily;
 use codex_core::openai_models::models_manager::ModelsManager;
 use codex_core::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
@@ -39,12 +45,19 @@ use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::NetworkApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PinnedContextEvent;
+use codex_core::protocol::PinnedNote;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
+use codex_core::protocol::SessionLabelsItem;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentActivityEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
@@ -57,10 +70,12 @@ use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::sandboxing::SandboxPermissions;
 use codex_core::skills::model::SkillMetadata;
 use codex_protocol::ConversationId;
 use codex_protocol::account::PlanType;
 use codex_protocol::approvals::ElicitationRequestEvent;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
 use crossterm::event::KeyCode;
@@ -75,6 +90,7 @@ use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Wrap;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
 use tracing::debug;
@@ -324,6 +340,13 @@ pub(crate) struct ChatWidget {
     pre_review_token_info: Option<Option<TokenUsageInfo>>,
     // Whether to add a final message separator after the last message
     needs_final_message_separator: bool,
+    // Display numbers for sub-agents spawned in this session, keyed by call id.
+    sub_agents: HashMap<String, usize>,
+    // Notes currently pinned to the session, as last reported by core.
+    pinned_notes: Vec<PinnedNote>,
+    // Latest user message and final agent reply, offered by `/pin`.
+    last_user_message: Option<String>,
+    last_agent_message: Option<String>,
 
     last_rendered_width: std::cell::Cell<Option<usize>>,
     // Feedback sink for /feedback
@@ -511,6 +534,11 @@ impl ChatWidget {
         self.running_commands.clear();
         self.suppressed_exec_calls.clear();
         self.last_unified_wait = None;
+        if let Some(message) = last_agent_message.as_ref()
+            && !message.trim().is_empty()
+        {
+            self.last_agent_message = Some(message.clone());
+        }
         self.request_redraw();
 
         // If there is a queued user message, send exactly one now to begin the next turn.
@@ -794,6 +822,14 @@ impl ChatWidget {
         );
     }
 
+    fn on_network_approval_request(&mut self, ev: NetworkApprovalRequestEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_network_approval(ev),
+            |s| s.handle_network_approval_now(ev2),
+        );
+    }
+
     fn on_elicitation_request(&mut self, ev: ElicitationRequestEvent) {
         let ev2 = ev.clone();
         self.defer_or_handle(
@@ -831,6 +867,41 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_sub_agent_begin(&mut self, event: SubAgentBeginEvent) {
+        self.flush_answer_stream_with_separator();
+        let number = self.sub_agents.len() + 1;
+        self.sub_agents.insert(event.call_id, number);
+        self.add_to_history(history_cell::new_sub_agent_begin(
+            number,
+            &event.task,
+            &event.model,
+        ));
+        self.request_redraw();
+    }
+
+    fn on_sub_agent_activity(&mut self, event: SubAgentActivityEvent) {
+        let Some(number) = self.sub_agents.get(&event.call_id).copied() else {
+            return;
+        };
+        let Some(summary) = history_cell::sub_agent_activity_summary(&event.msg, &self.config.cwd)
+        else {
+            return;
+        };
+        self.add_to_history(history_cell::new_sub_agent_activity(number, summary));
+        self.request_redraw();
+    }
+
+    fn on_sub_agent_end(&mut self, event: SubAgentEndEvent) {
+        let Some(number) = self.sub_agents.get(&event.call_id).copied() else {
+            return;
+        };
+        self.add_to_history(history_cell::new_sub_agent_end(
+            number,
+            event.last_agent_message.as_deref(),
+        ));
+        self.request_redraw();
+    }
+
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
         let ev2 = event.clone();
         self.defer_or_handle(
@@ -1083,6 +1154,7 @@ impl ChatWidget {
         let request = ApprovalRequest::Exec {
             id,
             command: ev.command,
+            cwd: ev.cwd,
             reason: ev.reason,
             risk: ev.risk,
             proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
@@ -1092,6 +1164,38 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    /// Explain the pending exec approval in the history, using the policy files on disk and the
+    /// session's approval and sandbox settings.
+    pub(crate) fn explain_exec_approval(&self, command: Vec<String>, cwd: PathBuf) {
+        let tx = self.app_event_tx.clone();
+        let features = self.config.features.clone();
+        let codex_home = self.config.codex_home.clone();
+        let approval_policy = self.config.approval_policy;
+        let sandbox_policy = self.config.sandbox_policy.clone();
+        let env = create_env(&self.config.shell_environment_policy);
+        tokio::spawn(async move {
+            let cell = match load_exec_policy_for_features(&features, &codex_home).await {
+                Ok(policy) => {
+                    let explanation = explain_command(
+                        &policy,
+                        &features,
+                        &command,
+                        &cwd,
+                        &env,
+                        approval_policy,
+                        &sandbox_policy,
+                        SandboxPermissions::UseDefault,
+                    );
+                    history_cell::new_exec_policy_explanation(&explanation)
+                }
+                Err(err) => {
+                    history_cell::new_error_event(format!("Failed to load execpolicy: {err}"))
+                }
+            };
+            tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+        });
+    }
+
     pub(crate) fn handle_apply_patch_approval_now(
         &mut self,
         id: String,
@@ -1131,6 +1235,20 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    pub(crate) fn handle_network_approval_now(&mut self, ev: NetworkApprovalRequestEvent) {
+        self.flush_answer_stream_with_separator();
+
+        let request = ApprovalRequest::Network {
+            id: ev.call_id,
+            host: ev.host,
+            port: ev.port,
+            reason: ev.reason,
+        };
+        self.bottom_pane
+            .push_approval_request(request, &self.config.features);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_exec_begin_now(&mut self, ev: ExecCommandBeginEvent) {
         // Ensure the status indicator is visible while the command runs.
         self.running_commands.insert(
@@ -1308,6 +1426,10 @@ impl ChatWidget {
             is_review_mode: false,
             pre_review_token_info: None,
             needs_final_message_separator: false,
+            sub_agents: HashMap::new(),
+            pinned_notes: Vec::new(),
+            last_user_message: None,
+            last_agent_message: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
             current_rollout_path: None,
@@ -1393,6 +1515,10 @@ impl ChatWidget {
             is_review_mode: false,
             pre_review_token_info: None,
             needs_final_message_separator: false,
+            sub_agents: HashMap::new(),
+            pinned_notes: Vec::new(),
+            last_user_message: None,
+            last_agent_message: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
             current_rollout_path: None,
@@ -1532,7 +1658,7 @@ impl ChatWidget {
             }
             SlashCommand::Compact => {
                 self.clear_token_usage();
-                self.app_event_tx.send(AppEvent::CodexOp(Op::Compact));
+                self.open_compact_popup();
             }
             SlashCommand::Review => {
                 self.open_review_popup();
@@ -1584,9 +1710,21 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Context => {
+                self.submit_op(Op::GetContextBreakdown);
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Export => {
+                self.open_export_popup();
+            }
+            SlashCommand::Rename => {
+                self.show_rename_prompt();
+            }
+            SlashCommand::Pin => {
+                self.open_pin_popup();
+            }
             SlashCommand::Rollout => {
                 if let Some(path) = self.rollout_path() {
                     self.add_info_message(
@@ -1717,6 +1855,7 @@ impl ChatWidget {
 
         if !text.is_empty() {
             items.push(UserInput::Text { text: text.clone() });
+            self.last_user_message = Some(text.clone());
         }
 
         for path in image_paths {
@@ -1805,6 +1944,7 @@ impl ChatWidget {
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
+            EventMsg::BudgetWarning(ev) => self.on_warning(ev.to_string()),
             EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
@@ -1818,6 +1958,9 @@ impl ChatWidget {
                 TurnAbortReason::ReviewEnded => {
                     self.on_interrupted_turn(ev.reason);
                 }
+                TurnAbortReason::BudgetExceeded => {
+                    self.on_error("Turn aborted: budget exhausted".to_owned())
+                }
             },
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
             EventMsg::ExecApprovalRequest(ev) => {
@@ -1830,12 +1973,17 @@ impl ChatWidget {
             EventMsg::ElicitationRequest(ev) => {
                 self.on_elicitation_request(ev);
             }
+            EventMsg::NetworkApprovalRequest(ev) => self.on_network_approval_request(ev),
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
             EventMsg::PatchApplyEnd(ev) => self.on_patch_apply_end(ev),
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
+            EventMsg::SubAgentBegin(ev) => self.on_sub_agent_begin(ev),
+            EventMsg::SubAgentActivity(ev) => self.on_sub_agent_activity(ev),
+            EventMsg::SubAgentEnd(ev) => self.on_sub_agent_end(ev),
+            EventMsg::PinnedContextUpdated(ev) => self.on_pinned_context_updated(ev, from_replay),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
@@ -1843,6 +1991,9 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ContextBreakdown(ev) => {
+                self.add_to_history(history_cell::new_context_breakdown_output(&ev));
+            }
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
@@ -1932,6 +2083,7 @@ impl ChatWidget {
     fn on_user_message_event(&mut self, event: UserMessageEvent) {
         let message = event.message.trim();
         if !message.is_empty() {
+            self.last_user_message = Some(message.to_string());
             self.add_to_history(history_cell::new_user_prompt(message.to_string()));
         }
     }
@@ -2511,15 +2663,16 @@ impl ChatWidget {
         }
         matches!(
             (&preset.sandbox, current_sandbox),
-            (SandboxPolicy::ReadOnly, SandboxPolicy::ReadOnly)
-                | (
-                    SandboxPolicy::DangerFullAccess,
-                    SandboxPolicy::DangerFullAccess
-                )
-                | (
-                    SandboxPolicy::WorkspaceWrite { .. },
-                    SandboxPolicy::WorkspaceWrite { .. }
-                )
+            (
+                SandboxPolicy::ReadOnly { .. },
+                SandboxPolicy::ReadOnly { .. }
+            ) | (
+                SandboxPolicy::DangerFullAccess,
+                SandboxPolicy::DangerFullAccess
+            ) | (
+                SandboxPolicy::WorkspaceWrite { .. },
+                SandboxPolicy::WorkspaceWrite { .. }
+            )
         )
     }
 
@@ -2632,7 +2785,7 @@ impl ChatWidget {
         let mut header_children: Vec<Box<dyn Renderable>> = Vec::new();
         let describe_policy = |policy: &SandboxPolicy| match policy {
             SandboxPolicy::WorkspaceWrite { .. } => "Agent mode",
-            SandboxPolicy::ReadOnly => "Read-Only mode",
+            SandboxPolicy::ReadOnly { .. } => "Read-Only mode",
             _ => "Agent mode",
         };
         let mode_label = preset
@@ -2811,7 +2964,7 @@ impl ChatWidget {
     /// Set the sandbox policy in the widget's config copy.
     pub(crate) fn set_sandbox_policy(&mut self, policy: SandboxPolicy) {
         #[cfg(target_os = "windows")]
-        let should_clear_downgrade = !matches!(policy, SandboxPolicy::ReadOnly)
+        let should_clear_downgrade = !matches!(policy, SandboxPolicy::ReadOnly { .. })
             || codex_core::get_platform_sandbox().is_some();
 
         self.config.sandbox_policy = policy;
@@ -2872,6 +3025,137 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    /// Open a popup to choose the transcript format for `/export`.
+    pub(crate) fn open_export_popup(&mut self) {
+        if self.rollout_path().is_none() || self.conversation_id.is_none() {
+            self.add_info_message("Session is not recorded yet.".to_string(), None);
+            return;
+        }
+
+        let formats = [
+            (ExportFormat::Markdown, "Markdown"),
+            (ExportFormat::Html, "HTML"),
+            (ExportFormat::Json, "JSON"),
+        ];
+        let items = formats
+            .into_iter()
+            .map(|(format, name)| {
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenExportOptionsPopup { format });
+                })];
+                SelectionItem {
+                    name: name.to_string(),
+                    description: Some(format!("Write a .{} file", format.extension())),
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Export session".to_string()),
+            subtitle: Some("Choose a transcript format.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Open a popup to choose what the `/export` transcript includes. The
+    /// transcript is written to a new file under `~/.codex/exports`.
+    pub(crate) fn open_export_options_popup(&mut self, format: ExportFormat) {
+        let (Some(rollout_path), Some(conversation_id)) =
+            (self.rollout_path(), self.conversation_id)
+        else {
+            self.add_info_message("Session is not recorded yet.".to_string(), None);
+            return;
+        };
+        let exports_dir = self.config.codex_home.join("exports");
+
+        let choices = [
+            (
+                "Full transcript",
+                "Messages, reasoning and tool output",
+                true,
+                true,
+            ),
+            (
+                "Without reasoning",
+                "Leave out reasoning summaries",
+                false,
+                true,
+            ),
+            (
+                "Without tool output",
+                "Keep tool calls but not their output",
+                true,
+                false,
+            ),
+            (
+                "Messages only",
+                "Leave out reasoning and tool output",
+                false,
+                false,
+            ),
+        ];
+        let items = choices
+            .into_iter()
+            .map(
+                |(name, description, include_reasoning, include_tool_output)| {
+                    let options = ExportOptions {
+                        include_reasoning,
+                        include_tool_output,
+                    };
+                    let rollout_path = rollout_path.clone();
+                    let exports_dir = exports_dir.clone();
+                    let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                        let tx = tx.clone();
+                        let rollout_path = rollout_path.clone();
+                        let output_path = exports_dir.join(format!(
+                            "codex-{conversation_id}-{}.{}",
+                            Local::now().format("%Y%m%d-%H%M%S"),
+                            format.extension()
+                        ));
+                        tokio::spawn(async move {
+                            let result = match export_rollout(&rollout_path, format, options).await
+                            {
+                                Ok(transcript) => write_new_file(&output_path, &transcript).await,
+                                Err(err) => Err(err),
+                            };
+                            let cell = match result {
+                                Ok(()) => history_cell::new_info_event(
+                                    format!("Exported session to {}", output_path.display()),
+                                    None,
+                                ),
+                                Err(err) => history_cell::new_error_event(format!(
+                                    "Failed to export session to {}: {err}",
+                                    output_path.display()
+                                )),
+                            };
+                            tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+                        });
+                    })];
+                    SelectionItem {
+                        name: name.to_string(),
+                        description: Some(description.to_string()),
+                        actions,
+                        dismiss_on_select: true,
+                        ..Default::default()
+                    }
+                },
+            )
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Export session".to_string()),
+            subtitle: Some(format!("Written to {}.", exports_dir.display())),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     pub(crate) fn add_mcp_output(&mut self) {
         if self.config.mcp_servers.is_empty() {
             self.add_to_history(history_cell::empty_mcp_output());
@@ -3112,6 +3396,178 @@ impl ChatWidget {
         self.bottom_pane.show_view(Box::new(view));
     }
 
+    pub(crate) fn show_rename_prompt(&mut self) {
+        if self.conversation_id.is_none() {
+            self.add_info_message("Session is not recorded yet.".to_string(), None);
+            return;
+        }
+        let tx = self.app_event_tx.clone();
+        let view = CustomPromptView::new(
+            "Rename session".to_string(),
+            "Type a title and press Enter".to_string(),
+            None,
+            Box::new(move |title: String| {
+                let title = title.trim().to_string();
+                if title.is_empty() {
+                    return;
+                }
+                tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_info_event(format!("Session renamed to \"{title}\""), None),
+                )));
+                tx.send(AppEvent::CodexOp(Op::UpdateSessionLabels {
+                    labels: SessionLabelsItem {
+                        title: Some(title),
+                        ..Default::default()
+                    },
+                }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    /// Open a popup to pin a note, or unpin an existing one, for `/pin`.
+    pub(crate) fn open_compact_popup(&mut self) {
+        let configured = self.config.compaction.strategy;
+        let strategies = [
+            (
+                CompactionStrategy::Summarize,
+                "Summarize",
+                "Ask the model to summarize the conversation",
+            ),
+            (
+                CompactionStrategy::ElideToolOutputs,
+                "Elide tool outputs",
+                "Drop long tool outputs from older turns",
+            ),
+            (
+                CompactionStrategy::KeepRecent,
+                "Keep recent turns",
+                "Keep only the most recent turns verbatim",
+            ),
+            (
+                CompactionStrategy::Heuristic,
+                "Heuristic",
+                "Summarize older turns locally, without calling the model",
+            ),
+        ];
+        let items = strategies
+            .into_iter()
+            .map(|(strategy, name, description)| {
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::Compact {
+                        strategy: Some(strategy),
+                    }));
+                })];
+                SelectionItem {
+                    name: name.to_string(),
+                    description: Some(description.to_string()),
+                    is_current: strategy == configured,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Compact conversation".to_string()),
+            subtitle: Some("Choose how to shrink the conversation history".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_pin_popup(&mut self) {
+        let mut items = vec![SelectionItem {
+            name: "Write a note…".to_string(),
+            description: Some("Type the text to pin".to_string()),
+            actions: vec![Box::new(|tx| tx.send(AppEvent::OpenPinNotePrompt))],
+            dismiss_on_select: true,
+            ..Default::default()
+        }];
+        let candidates = [
+            ("Pin your last message", self.last_user_message.clone()),
+            ("Pin Codex's last reply", self.last_agent_message.clone()),
+        ];
+        for (name, text) in candidates {
+            let Some(text) = text else {
+                continue;
+            };
+            let description = Some(pin_preview(&text));
+            let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                tx.send(AppEvent::CodexOp(Op::PinContext { text: text.clone() }));
+            })];
+            items.push(SelectionItem {
+                name: name.to_string(),
+                description,
+                actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+        for note in &self.pinned_notes {
+            let id = note.id;
+            let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                tx.send(AppEvent::CodexOp(Op::UnpinContext { id }));
+            })];
+            items.push(SelectionItem {
+                name: format!("Unpin #{id}"),
+                description: Some(pin_preview(&note.text)),
+                actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Pinned context".to_string()),
+            subtitle: Some(
+                "Pinned notes are kept verbatim when the conversation is compacted".to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn show_pin_note_prompt(&mut self) {
+        let tx = self.app_event_tx.clone();
+        let view = CustomPromptView::new(
+            "Pin a note".to_string(),
+            "Type a note to keep across compaction and press Enter".to_string(),
+            None,
+            Box::new(move |text: String| {
+                let text = text.trim().to_string();
+                if text.is_empty() {
+                    return;
+                }
+                tx.send(AppEvent::CodexOp(Op::PinContext { text }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    fn on_pinned_context_updated(&mut self, event: PinnedContextEvent, from_replay: bool) {
+        let previous = std::mem::replace(&mut self.pinned_notes, event.notes);
+        if from_replay {
+            return;
+        }
+        let pinned = self
+            .pinned_notes
+            .iter()
+            .filter(|note| !previous.iter().any(|old| old.id == note.id))
+            .map(|note| format!("Pinned #{}: {}", note.id, pin_preview(&note.text)));
+        let unpinned = previous
+            .iter()
+            .filter(|note| !self.pinned_notes.iter().any(|new| new.id == note.id))
+            .map(|note| format!("Unpinned #{}: {}", note.id, pin_preview(&note.text)));
+        let messages: Vec<String> = pinned.chain(unpinned).collect();
+        for message in messages {
+            self.add_info_message(message, None);
+        }
+    }
+
     pub(crate) fn token_usage(&self) -> TokenUsage {
         self.token_info
             .as_ref()
@@ -3251,6 +3707,29 @@ const EXAMPLE_PROMPTS: [&str; 6] = [
     "Improve documentation in @filename",
 ];
 
+// Write `contents` to `path`, creating its parent directory. Fails rather
+// than replace a file that already exists.
+async fn write_new_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .await?;
+    file.write_all(contents.as_bytes()).await?;
+    file.flush().await
+}
+
+const PIN_PREVIEW_GRAPHEMES: usize = 60;
+
+// One-line preview of a pinned note for popups and info messages.
+fn pin_preview(text: &str) -> String {
+    let first_line = text.trim().lines().next().unwrap_or_default();
+    truncate_text(first_line, PIN_PREVIEW_GRAPHEMES)
+}
+
 // Extract the first bold (Markdown) element in the form **...** from `s`.
 // Returns the inner text if found; otherwise `None`.
 fn extract_first_bold(s: &str) -> Option<String> {
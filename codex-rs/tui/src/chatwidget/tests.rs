@@ -49,6 +49,7 @@ use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_protocol::ConversationId;
 use codex_protocol::account::PlanType;
+use codex_protocol::config_types::CompactionStrategy;
 use codex_protocol::openai_models::ModelPreset;
 use codex_protocol::openai_models::ReasoningEffortPreset;
 use codex_protocol::parse_command::ParsedCommand;
@@ -107,7 +108,7 @@ fn resumed_initial_messages_render_history() {
         model: "test-model".to_string(),
         model_provider_id: "test-provider".to_string(),
         approval_policy: AskForApproval::Never,
-        sandbox_policy: SandboxPolicy::ReadOnly,
+        sandbox_policy: SandboxPolicy::new_read_only_policy(),
         cwd: PathBuf::from("/home/user/project"),
         reasoning_effort: Some(ReasoningEffortConfig::default()),
         history_log_id: 0,
@@ -428,6 +429,10 @@ fn make_chatwidget_manual() -> (
         is_review_mode: false,
         pre_review_token_info: None,
         needs_final_message_separator: false,
+        sub_agents: HashMap::new(),
+        pinned_notes: Vec::new(),
+        last_user_message: None,
+        last_agent_message: None,
         last_rendered_width: std::cell::Cell::new(None),
         feedback: codex_feedback::CodexFeedback::new(),
         current_rollout_path: None,
@@ -1541,6 +1546,49 @@ fn view_image_tool_call_adds_history_cell() {
     assert_snapshot!("local_image_attachment_history_snapshot", combined);
 }
 
+#[test]
+fn sub_agent_events_render_nested_history_snapshot() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "sub-agent".into(),
+        msg: EventMsg::SubAgentBegin(SubAgentBeginEvent {
+            call_id: "call-agent".into(),
+            task: "Investigate why test_parse fails".into(),
+            model: "gpt-5.1-codex".into(),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "sub-agent".into(),
+        msg: EventMsg::SubAgentActivity(SubAgentActivityEvent {
+            call_id: "call-agent".into(),
+            msg: Box::new(EventMsg::WebSearchEnd(WebSearchEndEvent {
+                call_id: "call-search".into(),
+                query: "serde untagged enum error".into(),
+            })),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "sub-agent".into(),
+        msg: EventMsg::SubAgentEnd(SubAgentEndEvent {
+            call_id: "call-agent".into(),
+            task: "Investigate why test_parse fails".into(),
+            model: "gpt-5.1-codex".into(),
+            last_agent_message: Some(
+                "The fixture has a trailing comma.\nRemoving it fixes the test.".into(),
+            ),
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 3, "expected begin, activity and end cells");
+    let combined = cells
+        .iter()
+        .map(|cell| lines_to_single_string(cell))
+        .collect::<String>();
+    assert_snapshot!("sub_agent_nested_history", combined);
+}
+
 // Snapshot test: interrupting a running exec finalizes the active cell with a red ✗
 // marker (replacing the spinner) and flushes it into history.
 #[test]
@@ -1763,6 +1811,8 @@ fn preset_matching_ignores_extra_writable_roots() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        readable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
 
     assert!(
@@ -1932,6 +1982,167 @@ fn feedback_selection_popup_snapshot() {
     assert_snapshot!("feedback_selection_popup", popup);
 }
 
+#[test]
+fn export_popup_lists_transcript_formats() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.conversation_id = Some(ConversationId::new());
+    chat.current_rollout_path = Some(PathBuf::from("/tmp/rollout.jsonl"));
+
+    chat.dispatch_command(SlashCommand::Export);
+
+    let popup = render_bottom_popup(&chat, 120);
+    for name in ["Export session", "Markdown", "HTML", "JSON"] {
+        assert!(popup.contains(name), "expected {name} in popup:\n{popup}");
+    }
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let mut format = None;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::OpenExportOptionsPopup { format: selected } = event {
+            format = Some(selected);
+        }
+    }
+    assert_eq!(format, Some(ExportFormat::Markdown));
+}
+
+#[test]
+fn export_options_popup_offers_to_omit_reasoning_and_tool_output() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.conversation_id = Some(ConversationId::new());
+    chat.current_rollout_path = Some(PathBuf::from("/tmp/rollout.jsonl"));
+
+    chat.open_export_options_popup(ExportFormat::Html);
+
+    let popup = render_bottom_popup(&chat, 120);
+    let exports_dir = chat.config.codex_home.join("exports");
+    for name in [
+        "Full transcript",
+        "Without reasoning",
+        "Without tool output",
+        "Messages only",
+        &exports_dir.display().to_string(),
+    ] {
+        assert!(popup.contains(name), "expected {name} in popup:\n{popup}");
+    }
+}
+
+#[tokio::test]
+async fn export_never_overwrites_an_existing_file() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("exports").join("session.md");
+
+    write_new_file(&path, "first")
+        .await
+        .expect("write new file");
+    let err = write_new_file(&path, "second")
+        .await
+        .expect_err("existing file must not be replaced");
+
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+}
+
+#[test]
+fn rename_prompt_submits_session_title() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.conversation_id = Some(ConversationId::new());
+
+    chat.dispatch_command(SlashCommand::Rename);
+    chat.handle_paste("  release prep  ".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let mut title = None;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::CodexOp(Op::UpdateSessionLabels { labels }) = event {
+            title = labels.title;
+        }
+    }
+    assert_eq!(title.as_deref(), Some("release prep"));
+}
+
+#[test]
+fn pin_popup_offers_last_reply_and_unpins_notes() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: Some("Use the staging database for tests.".to_string()),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "pin-1".into(),
+        msg: EventMsg::PinnedContextUpdated(PinnedContextEvent {
+            notes: vec![PinnedNote {
+                id: 3,
+                text: "Never edit files under migrations/.".to_string(),
+            }],
+        }),
+    });
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        history.contains("Pinned #3: Never edit files under migrations/."),
+        "expected pin info message, got:\n{history}"
+    );
+
+    chat.dispatch_command(SlashCommand::Pin);
+    let popup = render_bottom_popup(&chat, 120);
+    for name in [
+        "Pinned context",
+        "Write a note…",
+        "Pin Codex's last reply",
+        "Use the staging database for tests.",
+        "Unpin #3",
+    ] {
+        assert!(popup.contains(name), "expected {name} in popup:\n{popup}");
+    }
+    assert!(
+        !popup.contains("Pin your last message"),
+        "no user message was sent yet:\n{popup}"
+    );
+
+    // Select "Unpin #3", the last item.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let mut unpinned = None;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::CodexOp(Op::UnpinContext { id }) = event {
+            unpinned = Some(id);
+        }
+    }
+    assert_eq!(unpinned, Some(3));
+}
+
+#[test]
+fn compact_popup_preselects_configured_strategy() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.compaction.strategy = CompactionStrategy::KeepRecent;
+
+    chat.dispatch_command(SlashCommand::Compact);
+    let popup = render_bottom_popup(&chat, 120);
+    for name in [
+        "Compact conversation",
+        "Summarize",
+        "Elide tool outputs",
+        "Keep recent turns",
+        "Heuristic",
+    ] {
+        assert!(popup.contains(name), "expected {name} in popup:\n{popup}");
+    }
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let mut requested = None;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::CodexOp(Op::Compact { strategy }) = event {
+            requested = strategy;
+        }
+    }
+    assert_eq!(requested, Some(CompactionStrategy::Heuristic));
+}
+
 #[test]
 fn feedback_upload_consent_popup_snapshot() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
@@ -2,6 +2,7 @@ use clap::Parser;
 use clap::ValueHint;
 use codex_common::ApprovalModeCliArg;
 use codex_common::CliConfigOverrides;
+use codex_core::ForkPoint;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -32,6 +33,23 @@ pub struct Cli {
     #[clap(skip)]
     pub resume_show_all: bool,
 
+    /// Internal: only consider sessions carrying this tag when resuming.
+    #[clap(skip)]
+    pub resume_tag: Option<String>,
+
+    /// Internal: fork this recorded session (id or rollout path). Set by the
+    /// top-level `codex fork` wrapper.
+    #[clap(skip)]
+    pub fork_session: Option<String>,
+
+    /// Internal: where `codex fork` cuts the session.
+    #[clap(skip)]
+    pub fork_point: Option<ForkPoint>,
+
+    /// Internal: restore the ghost snapshot at the fork point.
+    #[clap(skip)]
+    pub fork_restore_snapshot: bool,
+
     /// Model the agent should use.
     #[arg(long, short = 'm')]
     pub model: Option<String>,
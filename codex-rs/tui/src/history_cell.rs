@@ -12,6 +12,7 @@ use crate::render::line_utils::line_to_static;
 use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
 use crate::render::renderable::Renderable;
+use crate::status::format_tokens_compact;
 use crate::style::user_message_style;
 use crate::text_formatting::format_and_truncate_tool_result;
 use crate::text_formatting::truncate_text;
@@ -24,9 +25,12 @@ use crate::wrapping::word_wrap_line;
 use crate::wrapping::word_wrap_lines;
 use base64::Engine;
 use codex_common::format_env_display::format_env_display;
+use codex_core::CommandExplanation;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::config::types::ReasoningSummaryFormat;
+use codex_core::protocol::ContextBreakdownEvent;
+use codex_core::protocol::EventMsg;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
@@ -1121,6 +1125,83 @@ pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
 
+/// Render the estimated size of the next prompt, one line per category.
+pub(crate) fn new_context_breakdown_output(breakdown: &ContextBreakdownEvent) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/context".magenta().into(),
+        "".into(),
+        vec!["🧮  ".into(), "Context usage".bold()].into(),
+        "".into(),
+    ];
+
+    let total = breakdown.total_tokens;
+    let mut summary = vec![
+        "  ~".into(),
+        format_tokens_compact(total).bold(),
+        " tokens".into(),
+    ];
+    if let Some(window) = breakdown.model_context_window.filter(|window| *window > 0) {
+        let percent = total.saturating_mul(100) / window;
+        summary.push(format!(" of {} ({percent}%)", format_tokens_compact(window)).into());
+    }
+    summary.push(" in the next prompt (estimated)".dim());
+    lines.push(summary.into());
+    lines.push("".into());
+
+    let mut rows: Vec<(String, i64)> = vec![
+        ("Base instructions".to_string(), breakdown.base_instructions),
+        (
+            "AGENTS.md and project docs".to_string(),
+            breakdown.project_docs,
+        ),
+        ("Skills".to_string(), breakdown.skills),
+        ("Built-in tools".to_string(), breakdown.builtin_tools),
+    ];
+    rows.extend(breakdown.mcp_servers.iter().map(|usage| {
+        let noun = if usage.tool_count == 1 {
+            "tool"
+        } else {
+            "tools"
+        };
+        (
+            format!("MCP {} ({} {noun})", usage.server, usage.tool_count),
+            usage.tokens,
+        )
+    }));
+    rows.extend([
+        ("Messages".to_string(), breakdown.messages),
+        ("Reasoning".to_string(), breakdown.reasoning),
+        ("Tool calls".to_string(), breakdown.tool_calls),
+        ("Tool outputs".to_string(), breakdown.tool_outputs),
+        ("Images".to_string(), breakdown.images),
+    ]);
+
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| UnicodeWidthStr::width(label.as_str()))
+        .max()
+        .unwrap_or(0);
+    for (label, tokens) in rows {
+        let percent = if total > 0 {
+            tokens.saturating_mul(100) / total
+        } else {
+            0
+        };
+        let padding = " ".repeat(label_width - UnicodeWidthStr::width(label.as_str()));
+        let amount = format!("{:>6}", format_tokens_compact(tokens));
+        let mut line: Vec<Span<'static>> = vec![format!("  • {label}{padding}  ").into()];
+        if tokens == 0 {
+            line.push(amount.dim());
+        } else {
+            line.push(amount.into());
+            line.push(format!("  {percent:>3}%").dim());
+        }
+        lines.push(line.into());
+    }
+
+    PlainHistoryCell { lines }
+}
+
 /// Render MCP tools grouped by connection using the fully-qualified tool names.
 pub(crate) fn new_mcp_tools_output(
     config: &Config,
@@ -1284,6 +1365,20 @@ pub(crate) fn new_mcp_tools_output(
 
     PlainHistoryCell { lines }
 }
+/// Render the rules and heuristics behind a pending exec approval, as printed by
+/// `codex execpolicy explain`.
+pub(crate) fn new_exec_policy_explanation(explanation: &CommandExplanation) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> =
+        vec![vec!["• ".dim(), "Why this command needs approval".bold()].into()];
+    lines.extend(
+        explanation
+            .to_string()
+            .lines()
+            .map(|line| Line::from(format!("  {line}"))),
+    );
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_info_event(message: String, hint: Option<String>) -> PlainHistoryCell {
     let mut line = vec!["• ".dim(), message.into()];
     if let Some(hint) = hint {
@@ -1418,6 +1513,116 @@ pub(crate) fn new_view_image_tool_call(path: PathBuf, cwd: &Path) -> PlainHistor
     PlainHistoryCell { lines }
 }
 
+/// Maximum graphemes shown for a sub-agent task or activity line.
+const SUB_AGENT_LINE_MAX_GRAPHEMES: usize = 120;
+/// Maximum lines of a sub-agent's final message shown in the transcript.
+const SUB_AGENT_RESULT_MAX_LINES: usize = 3;
+
+pub(crate) fn new_sub_agent_begin(number: usize, task: &str, model: &str) -> PlainHistoryCell {
+    let task = task.lines().next().unwrap_or_default();
+    let lines: Vec<Line<'static>> = vec![
+        vec![
+            "• ".dim(),
+            format!("Spawned agent {number}").bold(),
+            format!(" ({model})").dim(),
+        ]
+        .into(),
+        vec![
+            "  └ ".dim(),
+            truncate_text(task, SUB_AGENT_LINE_MAX_GRAPHEMES).into(),
+        ]
+        .into(),
+    ];
+    PlainHistoryCell { lines }
+}
+
+/// One-line description of a sub-agent event, or `None` for events that are
+/// not worth surfacing in the transcript.
+pub(crate) fn sub_agent_activity_summary(msg: &EventMsg, cwd: &Path) -> Option<String> {
+    let summary = match msg {
+        EventMsg::ExecCommandEnd(ev) => {
+            format!("Ran {}", strip_bash_lc_and_escape(&ev.command))
+        }
+        EventMsg::PatchApplyEnd(ev) if ev.success => {
+            let mut paths: Vec<String> = ev
+                .changes
+                .keys()
+                .map(|path| display_path_for(path, cwd))
+                .collect();
+            paths.sort();
+            format!("Edited {}", paths.join(", "))
+        }
+        EventMsg::PatchApplyEnd(_) => "Failed to apply patch".to_string(),
+        EventMsg::McpToolCallEnd(ev) => {
+            format!("Called {}.{}", ev.invocation.server, ev.invocation.tool)
+        }
+        EventMsg::WebSearchEnd(ev) => format!("Searched: {}", ev.query),
+        EventMsg::ViewImageToolCall(ev) => {
+            format!("Viewed {}", display_path_for(&ev.path, cwd))
+        }
+        EventMsg::Warning(ev) => format!("Warning: {}", ev.message),
+        EventMsg::BudgetWarning(ev) => format!("Warning: {ev}"),
+        EventMsg::Error(ev) => format!("Error: {}", ev.message),
+        _ => return None,
+    };
+    Some(summary)
+}
+
+pub(crate) fn new_sub_agent_activity(number: usize, summary: String) -> PlainHistoryCell {
+    let lines: Vec<Line<'static>> = vec![
+        vec![
+            "  ↳ ".dim(),
+            format!("agent {number}: ").dim(),
+            truncate_text(&summary, SUB_AGENT_LINE_MAX_GRAPHEMES).dim(),
+        ]
+        .into(),
+    ];
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_sub_agent_end(
+    number: usize,
+    last_agent_message: Option<&str>,
+) -> PlainHistoryCell {
+    let Some(message) = last_agent_message else {
+        return PlainHistoryCell {
+            lines: vec![
+                vec![
+                    "• ".dim(),
+                    format!("Agent {number} stopped").bold(),
+                    " without a result".dim(),
+                ]
+                .into(),
+            ],
+        };
+    };
+
+    let mut lines: Vec<Line<'static>> =
+        vec![vec!["• ".dim(), format!("Agent {number} finished").bold()].into()];
+    let message_lines: Vec<&str> = message
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    for (idx, line) in message_lines
+        .iter()
+        .take(SUB_AGENT_RESULT_MAX_LINES)
+        .enumerate()
+    {
+        let prefix = if idx == 0 { "  └ " } else { "    " };
+        lines.push(
+            vec![
+                prefix.dim(),
+                truncate_text(line, SUB_AGENT_LINE_MAX_GRAPHEMES).dim(),
+            ]
+            .into(),
+        );
+    }
+    if message_lines.len() > SUB_AGENT_RESULT_MAX_LINES {
+        lines.push(vec!["    …".dim()].into());
+    }
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_reasoning_summary_block(
     full_reasoning_buffer: String,
     reasoning_summary_format: ReasoningSummaryFormat,
@@ -1519,6 +1724,7 @@ mod tests {
     use codex_core::config::types::McpServerTransportConfig;
     use codex_core::openai_models::models_manager::ModelsManager;
     use codex_core::protocol::McpAuthStatus;
+    use codex_core::protocol::McpServerContextUsage;
     use codex_protocol::parse_command::ParsedCommand;
     use dirs::home_dir;
     use pretty_assertions::assert_eq;
@@ -1642,6 +1848,32 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn context_breakdown_snapshot() {
+        let breakdown = ContextBreakdownEvent {
+            model_context_window: Some(272_000),
+            total_tokens: 61_450,
+            base_instructions: 4_100,
+            project_docs: 1_250,
+            skills: 300,
+            builtin_tools: 3_000,
+            mcp_servers: vec![McpServerContextUsage {
+                server: "github".to_string(),
+                tool_count: 40,
+                tokens: 8_900,
+            }],
+            messages: 2_400,
+            reasoning: 0,
+            tool_calls: 1_500,
+            tool_outputs: 40_000,
+            images: 0,
+        };
+        let cell = new_context_breakdown_output(&breakdown);
+        let rendered = render_lines(&cell.display_lines(120)).join("\n");
+
+        insta::assert_snapshot!(rendered);
+    }
+
     #[test]
     fn empty_agent_message_cell_transcript() {
         let cell = AgentMessageCell::new(vec![Line::default()], false);
@@ -11,6 +11,7 @@ use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
 use codex_core::AuthManager;
 use codex_core::CodexAuth;
+use codex_core::ForkPoint;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::RolloutRecorder;
 use codex_core::auth::enforce_login_restrictions;
@@ -220,6 +221,7 @@ pub async fn run_main(
         tools_web_search_request: None,
         experimental_sandbox_command_assessment: None,
         additional_writable_roots: additional_dirs,
+        model_cassette: None,
     };
 
     let config = load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await;
@@ -430,8 +432,39 @@ async fn run_ratatui_app(
         initial_config
     };
 
-    // Determine resume behavior: explicit id, then resume last, then picker.
-    let resume_selection = if let Some(id_str) = cli.resume_session_id.as_deref() {
+    // Determine resume behavior: fork, explicit id, then resume last, then picker.
+    let resume_selection = if let Some(session) = cli.fork_session.as_deref() {
+        let path = PathBuf::from(session);
+        let path = if path.is_file() {
+            Some(path)
+        } else {
+            find_conversation_path_by_id_str(&config.codex_home, session).await?
+        };
+        match path {
+            Some(path) => resume_picker::ResumeSelection::Fork {
+                path,
+                point: cli.fork_point.clone().unwrap_or(ForkPoint::Latest),
+                restore_snapshot: cli.fork_restore_snapshot,
+            },
+            None => {
+                error!("Error finding conversation path: {session}");
+                restore();
+                session_log::log_session_end();
+                let _ = tui.terminal.clear();
+                if let Err(err) = writeln!(
+                    std::io::stdout(),
+                    "No saved session found with ID {session}. Run `codex resume` to choose from existing sessions."
+                ) {
+                    error!("Failed to write fork error message: {err}");
+                }
+                return Ok(AppExitInfo {
+                    token_usage: codex_core::protocol::TokenUsage::default(),
+                    conversation_id: None,
+                    update_action: None,
+                });
+            }
+        }
+    } else if let Some(id_str) = cli.resume_session_id.as_deref() {
         match find_conversation_path_by_id_str(&config.codex_home, id_str).await? {
             Some(path) => resume_picker::ResumeSelection::Resume(path),
             None => {
@@ -453,23 +486,9 @@ async fn run_ratatui_app(
             }
         }
     } else if cli.resume_last {
-        let provider_filter = vec![config.model_provider_id.clone()];
-        match RolloutRecorder::list_conversations(
-            &config.codex_home,
-            1,
-            None,
-            INTERACTIVE_SESSION_SOURCES,
-            Some(provider_filter.as_slice()),
-            &config.model_provider_id,
-        )
-        .await
-        {
-            Ok(page) => page
-                .items
-                .first()
-                .map(|it| resume_picker::ResumeSelection::Resume(it.path.clone()))
-                .unwrap_or(resume_picker::ResumeSelection::StartFresh),
-            Err(_) => resume_picker::ResumeSelection::StartFresh,
+        match find_latest_session(&config, cli.resume_tag.as_deref()).await {
+            Ok(Some(path)) => resume_picker::ResumeSelection::Resume(path),
+            Ok(None) | Err(_) => resume_picker::ResumeSelection::StartFresh,
         }
     } else if cli.resume_picker {
         match resume_picker::run_resume_picker(
@@ -477,6 +496,7 @@ async fn run_ratatui_app(
             &config.codex_home,
             &config.model_provider_id,
             cli.resume_show_all,
+            cli.resume_tag.clone(),
         )
         .await?
         {
@@ -517,6 +537,42 @@ async fn run_ratatui_app(
     app_result
 }
 
+/// Returns the most recent interactive session, or the most recent one
+/// carrying `tag` when it is set.
+async fn find_latest_session(
+    config: &Config,
+    tag: Option<&str>,
+) -> std::io::Result<Option<PathBuf>> {
+    let provider_filter = vec![config.model_provider_id.clone()];
+    let page_size = if tag.is_some() { 25 } else { 1 };
+    let mut cursor = None;
+    loop {
+        let page = RolloutRecorder::list_conversations(
+            &config.codex_home,
+            page_size,
+            cursor.as_ref(),
+            INTERACTIVE_SESSION_SOURCES,
+            Some(provider_filter.as_slice()),
+            &config.model_provider_id,
+        )
+        .await?;
+        let found = page
+            .items
+            .iter()
+            .find(|item| tag.is_none_or(|tag| item.labels.has_tag(tag)));
+        if let Some(item) = found {
+            return Ok(Some(item.path.clone()));
+        }
+        if tag.is_none() || page.reached_scan_cap {
+            return Ok(None);
+        }
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(None),
+        }
+    }
+}
+
 #[expect(
     clippy::print_stderr,
     reason = "TUI should no longer be displayed, so we can write to stderr."
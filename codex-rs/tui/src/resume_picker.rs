@@ -2,14 +2,19 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::DateTime;
 use chrono::Utc;
 use codex_core::ConversationItem;
 use codex_core::ConversationsPage;
 use codex_core::Cursor;
+use codex_core::ForkPoint;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::RolloutRecorder;
+use codex_core::labels::SessionLabels;
+use codex_core::search::SessionSearchHit;
+use codex_core::search::SessionSearcher;
 use codex_protocol::items::TurnItem;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
@@ -22,6 +27,7 @@ use ratatui::style::Stylize as _;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use tokio::sync::mpsc;
+use tokio::sync::watch;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use unicode_width::UnicodeWidthStr;
@@ -36,12 +42,21 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::SessionMetaLine;
 
 const PAGE_SIZE: usize = 25;
+const FULL_TEXT_RESULT_LIMIT: usize = 200;
+/// How long typing must pause before the full-text search runs.
+const FULL_TEXT_DEBOUNCE: Duration = Duration::from_millis(200);
 const LOAD_NEAR_THRESHOLD: usize = 5;
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
     StartFresh,
     Resume(PathBuf),
+    /// Start a new session from part of the recorded session at `path`.
+    Fork {
+        path: PathBuf,
+        point: ForkPoint,
+        restore_snapshot: bool,
+    },
     Exit,
 }
 
@@ -56,22 +71,36 @@ struct PageLoadRequest {
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
 
+#[derive(Clone)]
+struct FullTextSearchRequest {
+    query: String,
+}
+
+type FullTextSearcher = Arc<dyn Fn(FullTextSearchRequest) + Send + Sync>;
+
 enum BackgroundEvent {
     PageLoaded {
         request_token: usize,
         search_token: Option<usize>,
         page: std::io::Result<ConversationsPage>,
     },
+    FullTextResults {
+        query: String,
+        hits: std::io::Result<Vec<SessionSearchHit>>,
+    },
 }
 
-/// Interactive session picker that lists recorded rollout files with simple
-/// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
+/// Interactive session picker that lists recorded rollout files with search
+/// and pagination. Shows the first user input as the preview, relative time
+/// (e.g., "5 seconds ago"), and the absolute path. Typing filters loaded rows
+/// by title, tags and preview and also runs a full-text search over all
+/// recorded sessions. When `tag` is set only sessions carrying it are listed.
 pub async fn run_resume_picker(
     tui: &mut Tui,
     codex_home: &Path,
     default_provider: &str,
     show_all: bool,
+    tag: Option<String>,
 ) -> Result<ResumeSelection> {
     let alt = AltScreenGuard::enter(tui);
     let (bg_tx, bg_rx) = mpsc::unbounded_channel();
@@ -105,6 +134,16 @@ pub async fn run_resume_picker(
         });
     });
 
+    let (query_tx, query_rx) = watch::channel(String::new());
+    tokio::spawn(run_full_text_searches(
+        codex_home.to_path_buf(),
+        query_rx,
+        bg_tx.clone(),
+    ));
+    let full_text_searcher: FullTextSearcher = Arc::new(move |request: FullTextSearchRequest| {
+        let _ = query_tx.send(request.query);
+    });
+
     let mut state = PickerState::new(
         codex_home.to_path_buf(),
         alt.tui.frame_requester(),
@@ -113,6 +152,8 @@ pub async fn run_resume_picker(
         show_all,
         filter_cwd,
     );
+    state.full_text_searcher = Some(full_text_searcher);
+    state.tag_filter = tag;
     state.start_initial_load();
     state.request_frame();
 
@@ -154,6 +195,37 @@ pub async fn run_resume_picker(
 }
 
 /// RAII guard that ensures we leave the alt-screen on scope exit.
+/// Runs the picker's full-text searches one at a time against a single
+/// in-memory index. Only the latest query is searched, once typing has paused
+/// for [`FULL_TEXT_DEBOUNCE`]; queries replaced while waiting are dropped.
+async fn run_full_text_searches(
+    codex_home: PathBuf,
+    mut queries: watch::Receiver<String>,
+    tx: mpsc::UnboundedSender<BackgroundEvent>,
+) {
+    let mut searcher = SessionSearcher::new(codex_home);
+    while queries.changed().await.is_ok() {
+        loop {
+            tokio::select! {
+                changed = queries.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+                _ = tokio::time::sleep(FULL_TEXT_DEBOUNCE) => break,
+            }
+        }
+        let query = queries.borrow_and_update().clone();
+        let hits = searcher.search(&query, FULL_TEXT_RESULT_LIMIT).await;
+        if tx
+            .send(BackgroundEvent::FullTextResults { query, hits })
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
 struct AltScreenGuard<'a> {
     tui: &'a mut Tui,
 }
@@ -189,6 +261,12 @@ struct PickerState {
     default_provider: String,
     show_all: bool,
     filter_cwd: Option<PathBuf>,
+    /// Only list sessions carrying this tag (`codex resume --tag`).
+    tag_filter: Option<String>,
+    full_text_searcher: Option<FullTextSearcher>,
+    /// Rows matched by the full-text search for the current query.
+    full_text_matches: HashSet<PathBuf>,
+    full_text_pending: bool,
 }
 
 struct PaginationState {
@@ -248,6 +326,28 @@ struct Row {
     updated_at: Option<DateTime<Utc>>,
     cwd: Option<PathBuf>,
     git_branch: Option<String>,
+    labels: SessionLabels,
+}
+
+impl Row {
+    /// The user-set title when there is one, otherwise the first message.
+    fn display_text(&self) -> &str {
+        self.labels.title.as_deref().unwrap_or(&self.preview)
+    }
+
+    fn matches_query(&self, query: &str) -> bool {
+        self.preview.to_lowercase().contains(query)
+            || self
+                .labels
+                .title
+                .as_ref()
+                .is_some_and(|title| title.to_lowercase().contains(query))
+            || self
+                .labels
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(query))
+    }
 }
 
 impl PickerState {
@@ -282,6 +382,10 @@ impl PickerState {
             default_provider,
             show_all,
             filter_cwd,
+            tag_filter: None,
+            full_text_searcher: None,
+            full_text_matches: HashSet::new(),
+            full_text_pending: false,
         }
     }
 
@@ -403,10 +507,43 @@ impl PickerState {
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
+            BackgroundEvent::FullTextResults { query, hits } => {
+                if query != self.query {
+                    return Ok(());
+                }
+                self.full_text_pending = false;
+                match hits {
+                    Ok(hits) => self.ingest_full_text_hits(hits),
+                    Err(err) => {
+                        tracing::warn!("full-text session search failed: {err}");
+                        self.request_frame();
+                    }
+                }
+            }
         }
         Ok(())
     }
 
+    fn ingest_full_text_hits(&mut self, hits: Vec<SessionSearchHit>) {
+        for hit in hits {
+            if !INTERACTIVE_SESSION_SOURCES.contains(&hit.source) {
+                continue;
+            }
+            let provider = hit
+                .model_provider
+                .as_deref()
+                .unwrap_or(self.default_provider.as_str());
+            if provider != self.default_provider {
+                continue;
+            }
+            self.full_text_matches.insert(hit.path.clone());
+            if self.seen_paths.insert(hit.path.clone()) {
+                self.all_rows.push(hit_to_row(hit));
+            }
+        }
+        self.apply_filter();
+    }
+
     fn reset_pagination(&mut self) {
         self.pagination.next_cursor = None;
         self.pagination.num_scanned_files = 0;
@@ -448,7 +585,7 @@ impl PickerState {
         } else {
             let q = self.query.to_lowercase();
             self.filtered_rows = base_iter
-                .filter(|r| r.preview.to_lowercase().contains(&q))
+                .filter(|r| r.matches_query(&q) || self.full_text_matches.contains(&r.path))
                 .cloned()
                 .collect();
         }
@@ -463,6 +600,11 @@ impl PickerState {
     }
 
     fn row_matches_filter(&self, row: &Row) -> bool {
+        if let Some(tag) = self.tag_filter.as_deref()
+            && !row.labels.has_tag(tag)
+        {
+            return false;
+        }
         if self.show_all {
             return true;
         }
@@ -481,6 +623,8 @@ impl PickerState {
         }
         self.query = new_query;
         self.selected = 0;
+        self.full_text_matches.clear();
+        self.start_full_text_search();
         self.apply_filter();
         if self.query.is_empty() {
             self.search_state = SearchState::Idle;
@@ -499,6 +643,20 @@ impl PickerState {
         self.load_more_if_needed(LoadTrigger::Search { token });
     }
 
+    fn start_full_text_search(&mut self) {
+        self.full_text_pending = false;
+        if self.query.trim().is_empty() {
+            return;
+        }
+        let Some(searcher) = self.full_text_searcher.as_ref() else {
+            return;
+        };
+        self.full_text_pending = true;
+        searcher(FullTextSearchRequest {
+            query: self.query.clone(),
+        });
+    }
+
     fn continue_search_if_needed(&mut self) {
         let Some(token) = self.search_state.active_token() else {
             return;
@@ -655,6 +813,25 @@ fn head_to_row(item: &ConversationItem) -> Row {
         updated_at,
         cwd,
         git_branch,
+        labels: item.labels.clone(),
+    }
+}
+
+fn hit_to_row(hit: SessionSearchHit) -> Row {
+    let created_at = parse_timestamp_str(&hit.created_at);
+    let preview = hit.preview.trim().to_string();
+    Row {
+        path: hit.path,
+        preview: if preview.is_empty() {
+            String::from("(no message yet)")
+        } else {
+            preview
+        },
+        created_at,
+        updated_at: created_at,
+        cwd: Some(hit.cwd),
+        git_branch: None,
+        labels: hit.labels,
     }
 }
 
@@ -838,7 +1015,23 @@ fn render_list(
         if add_leading_gap {
             preview_width = preview_width.saturating_sub(2);
         }
-        let preview = truncate_text(&row.preview, preview_width);
+        let tags = row
+            .labels
+            .tags
+            .iter()
+            .map(|tag| format!("#{tag}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let tags_width = if tags.is_empty() { 0 } else { tags.width() + 2 };
+        if row.labels.starred {
+            preview_width = preview_width.saturating_sub(2);
+        }
+        // Keep at least some of the title visible before making room for tags.
+        let show_tags = tags_width > 0 && preview_width > tags_width + 10;
+        if show_tags {
+            preview_width -= tags_width;
+        }
+        let preview = truncate_text(row.display_text(), preview_width);
         let mut spans: Vec<Span> = vec![marker];
         if let Some(updated) = updated_span {
             spans.push(updated);
@@ -855,7 +1048,18 @@ fn render_list(
         if add_leading_gap {
             spans.push("  ".into());
         }
-        spans.push(preview.into());
+        if row.labels.starred {
+            spans.push("★ ".cyan());
+        }
+        if row.labels.title.is_some() {
+            spans.push(preview.bold());
+        } else {
+            spans.push(preview.into());
+        }
+        if show_tags {
+            spans.push("  ".into());
+            spans.push(Span::from(tags).dim());
+        }
 
         let line: Line = spans.into();
         let rect = Rect::new(area.x, y, area.width, 1);
@@ -873,6 +1077,7 @@ fn render_list(
 fn render_empty_state_line(state: &PickerState) -> Line<'static> {
     if !state.query.is_empty() {
         if state.search_state.is_active()
+            || state.full_text_pending
             || (state.pagination.loading.is_pending() && state.pagination.next_cursor.is_some())
         {
             return vec!["Searching…".italic().dim()].into();
@@ -1050,6 +1255,7 @@ fn calculate_column_metrics(rows: &[Row], include_cwd: bool) -> ColumnMetrics {
 mod tests {
     use super::*;
     use chrono::Duration;
+    use codex_protocol::protocol::SessionSource;
     use crossterm::event::KeyCode;
     use crossterm::event::KeyEvent;
     use crossterm::event::KeyModifiers;
@@ -1080,6 +1286,7 @@ mod tests {
             head: head_with_ts_and_user_text(ts, &[preview]),
             created_at: Some(ts.to_string()),
             updated_at: Some(ts.to_string()),
+            labels: Default::default(),
         }
     }
 
@@ -1154,12 +1361,14 @@ mod tests {
             head: head_with_ts_and_user_text("2025-01-01T00:00:00Z", &["A"]),
             created_at: Some("2025-01-01T00:00:00Z".into()),
             updated_at: Some("2025-01-01T00:00:00Z".into()),
+            labels: Default::default(),
         };
         let b = ConversationItem {
             path: PathBuf::from("/tmp/b.jsonl"),
             head: head_with_ts_and_user_text("2025-01-02T00:00:00Z", &["B"]),
             created_at: Some("2025-01-02T00:00:00Z".into()),
             updated_at: Some("2025-01-02T00:00:00Z".into()),
+            labels: Default::default(),
         };
         let rows = rows_from_items(vec![a, b]);
         assert_eq!(rows.len(), 2);
@@ -1168,6 +1377,46 @@ mod tests {
         assert!(rows[1].preview.contains('B'));
     }
 
+    #[test]
+    fn tag_filter_and_query_use_session_labels() {
+        let loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            String::from("openai"),
+            true,
+            None,
+        );
+        state.tag_filter = Some("Release".to_string());
+
+        let mut release = make_item("/tmp/a.jsonl", "2025-01-01T00:00:00Z", "bump versions");
+        release.labels = SessionLabels {
+            title: Some("release prep".to_string()),
+            tags: vec!["release".to_string()],
+            starred: true,
+        };
+        let other = make_item("/tmp/b.jsonl", "2025-01-02T00:00:00Z", "release notes");
+        state.reset_pagination();
+        state.ingest_page(page(vec![release, other], None, 2, false));
+        let paths: Vec<_> = state
+            .filtered_rows
+            .iter()
+            .map(|row| row.path.clone())
+            .collect();
+        assert_eq!(paths, vec![PathBuf::from("/tmp/a.jsonl")]);
+        assert_eq!(state.filtered_rows[0].display_text(), "release prep");
+
+        state.tag_filter = None;
+        state.set_query("prep".to_string());
+        let paths: Vec<_> = state
+            .filtered_rows
+            .iter()
+            .map(|row| row.path.clone())
+            .collect();
+        assert_eq!(paths, vec![PathBuf::from("/tmp/a.jsonl")]);
+    }
+
     #[test]
     fn row_uses_tail_timestamp_for_updated_at() {
         let head = head_with_ts_and_user_text("2025-01-01T00:00:00Z", &["Hello"]);
@@ -1176,6 +1425,7 @@ mod tests {
             head,
             created_at: Some("2025-01-01T00:00:00Z".into()),
             updated_at: Some("2025-01-01T01:00:00Z".into()),
+            labels: Default::default(),
         };
 
         let row = head_to_row(&item);
@@ -1216,6 +1466,7 @@ mod tests {
                 updated_at: Some(now - Duration::seconds(42)),
                 cwd: None,
                 git_branch: None,
+                labels: SessionLabels::default(),
             },
             Row {
                 path: PathBuf::from("/tmp/b.jsonl"),
@@ -1224,6 +1475,7 @@ mod tests {
                 updated_at: Some(now - Duration::minutes(35)),
                 cwd: None,
                 git_branch: None,
+                labels: SessionLabels::default(),
             },
             Row {
                 path: PathBuf::from("/tmp/c.jsonl"),
@@ -1232,6 +1484,7 @@ mod tests {
                 updated_at: Some(now - Duration::hours(2)),
                 cwd: None,
                 git_branch: None,
+                labels: SessionLabels::default(),
             },
         ];
         state.all_rows = rows.clone();
@@ -1725,4 +1978,111 @@ mod tests {
         assert!(!state.search_state.is_active());
         assert!(state.pagination.reached_scan_cap);
     }
+
+    #[test]
+    fn full_text_hits_extend_filtered_rows() {
+        let loader: PageLoader = Arc::new(|_| {});
+        let searches: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let search_sink = searches.clone();
+        let searcher: FullTextSearcher = Arc::new(move |req: FullTextSearchRequest| {
+            search_sink.lock().unwrap().push(req.query);
+        });
+
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            String::from("openai"),
+            true,
+            None,
+        );
+        state.full_text_searcher = Some(searcher);
+        state.reset_pagination();
+        state.ingest_page(page(
+            vec![
+                make_item("/tmp/a.jsonl", "2025-01-02T00:00:00Z", "fix tests"),
+                make_item("/tmp/b.jsonl", "2025-01-01T00:00:00Z", "update docs"),
+            ],
+            None,
+            2,
+            false,
+        ));
+
+        state.set_query("migration".to_string());
+        assert_eq!(searches.lock().unwrap().as_slice(), ["migration"]);
+        assert!(state.filtered_rows.is_empty());
+        assert!(state.full_text_pending);
+
+        let hit = |path: &str, source: SessionSource| SessionSearchHit {
+            id: codex_protocol::ConversationId::new(),
+            path: PathBuf::from(path),
+            created_at: "2024-12-01T00:00:00Z".to_string(),
+            cwd: PathBuf::from("/repo"),
+            source,
+            model_provider: None,
+            preview: "older session".to_string(),
+            score: 1,
+            matches: Vec::new(),
+            labels: Default::default(),
+        };
+        state
+            .handle_background_event(BackgroundEvent::FullTextResults {
+                query: "migration".to_string(),
+                hits: Ok(vec![
+                    hit("/tmp/b.jsonl", SessionSource::Cli),
+                    hit("/tmp/c.jsonl", SessionSource::Cli),
+                    hit("/tmp/d.jsonl", SessionSource::Exec),
+                ]),
+            })
+            .unwrap();
+
+        assert!(!state.full_text_pending);
+        let paths: Vec<_> = state
+            .filtered_rows
+            .iter()
+            .map(|row| row.path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("/tmp/b.jsonl"), PathBuf::from("/tmp/c.jsonl")]
+        );
+
+        // Results for a stale query are ignored.
+        state.set_query("migrations".to_string());
+        state
+            .handle_background_event(BackgroundEvent::FullTextResults {
+                query: "migration".to_string(),
+                hits: Ok(vec![hit("/tmp/a.jsonl", SessionSource::Cli)]),
+            })
+            .unwrap();
+        assert!(state.filtered_rows.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn full_text_search_runs_only_the_latest_query() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let (query_tx, query_rx) = watch::channel(String::new());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(run_full_text_searches(
+            codex_home.path().to_path_buf(),
+            query_rx,
+            tx,
+        ));
+
+        query_tx.send("mig".to_string()).unwrap();
+        tokio::time::sleep(FULL_TEXT_DEBOUNCE / 2).await;
+        query_tx.send("migration".to_string()).unwrap();
+        tokio::time::sleep(FULL_TEXT_DEBOUNCE * 2).await;
+        drop(query_tx);
+        task.await.unwrap();
+
+        let mut queries = Vec::new();
+        while let Some(event) = rx.recv().await {
+            if let BackgroundEvent::FullTextResults { query, hits } = event {
+                assert!(hits.unwrap().is_empty());
+                queries.push(query);
+            }
+        }
+        assert_eq!(queries, vec!["migration".to_string()]);
+    }
 }
@@ -20,11 +20,15 @@ pub enum SlashCommand {
     Resume,
     Init,
     Compact,
+    Pin,
     Undo,
     Diff,
     Mention,
     Status,
+    Context,
     Mcp,
+    Export,
+    Rename,
     Logout,
     Quit,
     Exit,
@@ -40,18 +44,22 @@ impl SlashCommand {
             SlashCommand::Feedback => "send logs to maintainers",
             SlashCommand::New => "start a new chat during a conversation",
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
-            SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Compact => "compact conversation to prevent hitting the context limit",
             SlashCommand::Review => "review my current changes and find issues",
             SlashCommand::Resume => "resume a saved chat",
+            SlashCommand::Pin => "pin a note so it survives compaction",
             SlashCommand::Undo => "ask Codex to undo a turn",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Context => "show what is taking up the context window",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
             SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Export => "export this session as Markdown, HTML or JSON",
+            SlashCommand::Rename => "set a title for this session",
             SlashCommand::Logout => "log out of Codex",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
@@ -80,7 +88,11 @@ impl SlashCommand {
             | SlashCommand::Mention
             | SlashCommand::Skills
             | SlashCommand::Status
+            | SlashCommand::Context
             | SlashCommand::Mcp
+            | SlashCommand::Export
+            | SlashCommand::Rename
+            | SlashCommand::Pin
             | SlashCommand::Feedback
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
@@ -91,6 +91,8 @@ fn status_snapshot_includes_reasoning_details() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        readable_roots: Vec::new(),
+        deny_read: Vec::new(),
     };
 
     config.cwd = PathBuf::from("/workspace/tests");
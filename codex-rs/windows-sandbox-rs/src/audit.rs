@@ -5,9 +5,9 @@ use crate::logging::log_note;
 use crate::policy::SandboxPolicy;
 use crate::token::convert_string_sid_to_sid;
 use crate::token::world_sid;
-use anyhow::anyhow;
 use crate::winutil::to_wide;
 use anyhow::Result;
+use anyhow::anyhow;
 use std::collections::HashSet;
 use std::ffi::c_void;
 use std::path::Path;
@@ -28,10 +28,10 @@ use windows_sys::Win32::Security::Authorization::GetNamedSecurityInfoW;
 use windows_sys::Win32::Security::Authorization::GetSecurityInfo;
 use windows_sys::Win32::Security::DACL_SECURITY_INFORMATION;
 use windows_sys::Win32::Security::EqualSid;
+use windows_sys::Win32::Security::GENERIC_MAPPING;
 use windows_sys::Win32::Security::GetAce;
 use windows_sys::Win32::Security::GetAclInformation;
 use windows_sys::Win32::Security::MapGenericMask;
-use windows_sys::Win32::Security::GENERIC_MAPPING;
 use windows_sys::Win32::Storage::FileSystem::CreateFileW;
 use windows_sys::Win32::Storage::FileSystem::FILE_ALL_ACCESS;
 use windows_sys::Win32::Storage::FileSystem::FILE_APPEND_DATA;
@@ -351,10 +351,9 @@ pub fn apply_capability_denies_for_world_writable(
             }
             (sid, roots)
         }
-        SandboxPolicy::ReadOnly => (
-            unsafe { convert_string_sid_to_sid(&caps.readonly) }.ok_or_else(|| {
-                anyhow!("ConvertStringSidToSidW failed for readonly capability")
-            })?,
+        SandboxPolicy::ReadOnly { .. } => (
+            unsafe { convert_string_sid_to_sid(&caps.readonly) }
+                .ok_or_else(|| anyhow!("ConvertStringSidToSidW failed for readonly capability"))?,
             Vec::new(),
         ),
         SandboxPolicy::DangerFullAccess => {